@@ -12,7 +12,7 @@ use binius_core::{
 };
 use binius_field::{
 	BinaryField128b, BinaryField128bPolyval, BinaryField1b, BinaryField8b, ExtensionField, Field,
-	PackedField, TowerField,
+	PackedBinaryField128x1b, PackedField, TowerField,
 };
 use binius_hash::GroestlHasher;
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
@@ -23,6 +23,10 @@ fn sumcheck_128b_over_1b(c: &mut Criterion) {
 	sumcheck_128b_with_switchover::<BinaryField1b>(c, "Sumcheck 128b over 1b", 8)
 }
 
+fn sumcheck_128b_over_packed_1b(c: &mut Criterion) {
+	sumcheck_128b_with_switchover::<PackedBinaryField128x1b>(c, "Sumcheck 128b over packed 1b", 8)
+}
+
 fn sumcheck_128b_over_8b(c: &mut Criterion) {
 	sumcheck_128b_with_switchover::<BinaryField8b>(c, "Sumcheck 128b over 8b", 7)
 }
@@ -287,6 +291,7 @@ criterion_group!(
 	sumcheck_128b_monomial_basis,
 	sumcheck_128b_monomial_basis_with_arc,
 	sumcheck_128b_over_1b,
+	sumcheck_128b_over_packed_1b,
 	sumcheck_128b_over_8b
 );
 criterion_main!(sumcheck);
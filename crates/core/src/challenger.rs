@@ -1,5 +1,6 @@
 // Copyright 2023 Ulvetanna Inc.
 // Copyright (c) 2022-2023 The Plonky3 Authors
+use crate::merkle_tree::MerkleCap;
 use binius_field::{ExtensionField, Field, PackedExtensionField, PackedField};
 use binius_hash::Hasher;
 use bytemuck::{bytes_of, AnyBitPattern, Pod};
@@ -89,6 +90,19 @@ where
 	}
 }
 
+impl<F, H, PE> CanObserve<MerkleCap<PE>> for HashChallenger<F, H>
+where
+	F: Field,
+	H: Hasher<F>,
+	H::Digest: PackedField<Scalar = F>,
+	PE: PackedExtensionField<F>,
+	PE::Scalar: ExtensionField<F>,
+{
+	fn observe(&mut self, cap: MerkleCap<PE>) {
+		CanObserve::<PE>::observe_slice(self, &cap.0);
+	}
+}
+
 impl<F, H, FE> CanSample<FE> for HashChallenger<F, H>
 where
 	F: Field,
@@ -0,0 +1,1269 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{ConstraintComposition, Error, Expr};
+use crate::{
+	oracle::{
+		BatchId, CommittedBatchSpec, CommittedId, CompositePolyOracle, MultilinearOracleSet,
+		MultilinearPolyOracle, OracleId, ProjectionVariant, RoundId, ShiftVariant,
+	},
+	polynomial::{
+		arith_expr::constant_tower_level,
+		composition::{empty_mix_composition, MixComposition},
+		transparent::select_row::SelectRow,
+		CompositionPoly, MultilinearExtension, MultivariatePoly,
+	},
+	protocols::zerocheck::{check_compositions, UnsatisfiedConstraint, ZerocheckClaim},
+	witness::MultilinearWitnessIndex,
+};
+use binius_field::{BinaryField1b, ExtensionField, PackedField, TowerField};
+use std::{collections::HashMap, iter, sync::Arc};
+
+/// Handle to a column declared in a [`ConstraintSystemBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Column(pub(super) usize);
+
+impl Column {
+	/// The position of the column in declaration order.
+	pub fn index(&self) -> usize {
+		self.0
+	}
+}
+
+/// The mix of all constraints of a [`ConstraintSystem`], over its zerocheck columns.
+pub type ConstraintMixComposition<P, F> = MixComposition<P, (Vec<ConstraintComposition<F>>, ())>;
+
+#[derive(Debug, Clone)]
+enum ColumnDef<F: TowerField> {
+	Committed {
+		round_id: RoundId,
+		n_vars: usize,
+		tower_level: usize,
+	},
+	Transparent {
+		poly: Arc<dyn MultivariatePoly<F>>,
+		tower_level: usize,
+	},
+	Shifted {
+		inner: Column,
+		offset: usize,
+		block_bits: usize,
+		variant: ShiftVariant,
+	},
+	LinearCombination {
+		n_vars: usize,
+		offset: F,
+		inner: Vec<(Column, F)>,
+	},
+	Projected {
+		inner: Column,
+		values: Vec<F>,
+		variant: ProjectionVariant,
+	},
+	Repeating {
+		inner: Column,
+		log_count: usize,
+	},
+}
+
+/// Declares the columns and constraints of a constraint system.
+///
+/// Committed columns are grouped into one batch per distinct `(round_id, n_vars, tower_level)`,
+/// in order of first declaration, and keep their declaration order within the batch. Derived
+/// columns may only refer to previously declared columns.
+#[derive(Debug, Clone)]
+pub struct ConstraintSystemBuilder<F: TowerField> {
+	names: Vec<String>,
+	columns: Vec<ColumnDef<F>>,
+	n_vars: Vec<usize>,
+	constraints: Vec<(String, Expr<F>)>,
+	boundaries: Vec<Boundary>,
+	intermediates: Vec<IntermediateColumn<F>>,
+}
+
+impl<F: TowerField> ConstraintSystemBuilder<F> {
+	#[allow(clippy::new_without_default)]
+	pub fn new() -> Self {
+		Self {
+			names: Vec::new(),
+			columns: Vec::new(),
+			n_vars: Vec::new(),
+			constraints: Vec::new(),
+			boundaries: Vec::new(),
+			intermediates: Vec::new(),
+		}
+	}
+
+	fn check_name(&self, name: &str) -> Result<(), Error> {
+		let taken = self.names.iter().any(|other| other == name)
+			|| self.constraints.iter().any(|(other, _)| other == name)
+			|| self.boundaries.iter().any(|other| other.name == name);
+		if taken {
+			return Err(Error::DuplicateName {
+				name: name.to_string(),
+			});
+		}
+		Ok(())
+	}
+
+	fn column_n_vars(&self, column: Column) -> Result<usize, Error> {
+		self.n_vars
+			.get(column.0)
+			.copied()
+			.ok_or(Error::InvalidColumn(column.0))
+	}
+
+	/// The highest tower level of the values of a column.
+	fn column_tower_level(&self, column: Column) -> usize {
+		match &self.columns[column.0] {
+			ColumnDef::Committed { tower_level, .. }
+			| ColumnDef::Transparent { tower_level, .. } => *tower_level,
+			ColumnDef::Shifted { inner, .. } | ColumnDef::Repeating { inner, .. } => {
+				self.column_tower_level(*inner)
+			}
+			ColumnDef::LinearCombination { offset, inner, .. } => inner
+				.iter()
+				.map(|&(column, coeff)| {
+					let coeff_level = constant_tower_level(coeff);
+					self.column_tower_level(column).max(coeff_level)
+				})
+				.fold(constant_tower_level(*offset), usize::max),
+			ColumnDef::Projected { inner, values, .. } => values
+				.iter()
+				.map(|&value| constant_tower_level(value))
+				.fold(self.column_tower_level(*inner), usize::max),
+		}
+	}
+
+	fn add(
+		&mut self,
+		name: impl ToString,
+		column: ColumnDef<F>,
+		n_vars: usize,
+	) -> Result<Column, Error> {
+		let name = name.to_string();
+		self.check_name(&name)?;
+		self.names.push(name);
+		self.columns.push(column);
+		self.n_vars.push(n_vars);
+		Ok(Column(self.columns.len() - 1))
+	}
+
+	pub fn add_committed(
+		&mut self,
+		name: impl ToString,
+		round_id: RoundId,
+		n_vars: usize,
+		tower_level: usize,
+	) -> Result<Column, Error> {
+		let column = ColumnDef::Committed {
+			round_id,
+			n_vars,
+			tower_level,
+		};
+		self.add(name, column, n_vars)
+	}
+
+	pub fn add_transparent(
+		&mut self,
+		name: impl ToString,
+		poly: Arc<dyn MultivariatePoly<F>>,
+		tower_level: usize,
+	) -> Result<Column, Error> {
+		let n_vars = poly.n_vars();
+		self.add(name, ColumnDef::Transparent { poly, tower_level }, n_vars)
+	}
+
+	/// Adds a column shifting `inner` by `offset` within blocks of `2^block_bits` rows.
+	///
+	/// The offset must be non-zero and smaller than the block size, which must not exceed the
+	/// inner column.
+	pub fn add_shifted(
+		&mut self,
+		name: impl ToString,
+		inner: Column,
+		offset: usize,
+		block_bits: usize,
+		variant: ShiftVariant,
+	) -> Result<Column, Error> {
+		let n_vars = self.column_n_vars(inner)?;
+		if block_bits > n_vars || offset == 0 || offset >= 1 << block_bits {
+			return Err(Error::InvalidShift {
+				name: name.to_string(),
+				offset,
+				block_bits,
+				n_vars,
+			});
+		}
+		let column = ColumnDef::Shifted {
+			inner,
+			offset,
+			block_bits,
+			variant,
+		};
+		self.add(name, column, n_vars)
+	}
+
+	pub fn add_linear_combination(
+		&mut self,
+		name: impl ToString,
+		n_vars: usize,
+		offset: F,
+		inner: impl IntoIterator<Item = (Column, F)>,
+	) -> Result<Column, Error> {
+		let inner = inner.into_iter().collect::<Vec<_>>();
+		for &(column, _) in inner.iter() {
+			self.column_n_vars(column)?;
+		}
+		let column = ColumnDef::LinearCombination {
+			n_vars,
+			offset,
+			inner,
+		};
+		self.add(name, column, n_vars)
+	}
+
+	/// Adds a column fixing variables of `inner` to `values`, which must leave at least one
+	/// variable free.
+	pub fn add_projected(
+		&mut self,
+		name: impl ToString,
+		inner: Column,
+		values: Vec<F>,
+		variant: ProjectionVariant,
+	) -> Result<Column, Error> {
+		let inner_n_vars = self.column_n_vars(inner)?;
+		if values.len() >= inner_n_vars {
+			return Err(Error::InvalidProjection {
+				name: name.to_string(),
+				values_len: values.len(),
+				n_vars: inner_n_vars,
+			});
+		}
+		let n_vars = inner_n_vars - values.len();
+		let column = ColumnDef::Projected {
+			inner,
+			values,
+			variant,
+		};
+		self.add(name, column, n_vars)
+	}
+
+	pub fn add_repeating(
+		&mut self,
+		name: impl ToString,
+		inner: Column,
+		log_count: usize,
+	) -> Result<Column, Error> {
+		let n_vars = self.column_n_vars(inner)? + log_count;
+		self.add(name, ColumnDef::Repeating { inner, log_count }, n_vars)
+	}
+
+	/// Adds a constraint asserting that `expr` vanishes on every row of the hypercube.
+	pub fn add_constraint(&mut self, name: impl ToString, expr: Expr<F>) -> Result<(), Error> {
+		let name = name.to_string();
+		self.check_name(&name)?;
+
+		let mut columns = Vec::new();
+		expr.collect_vars(&mut columns);
+		let (&first, rest) = columns
+			.split_first()
+			.ok_or_else(|| Error::ConstantConstraint { name: name.clone() })?;
+		let n_vars = self.column_n_vars(Column(first))?;
+		for &index in rest {
+			let column_n_vars = self.column_n_vars(Column(index))?;
+			if column_n_vars != n_vars {
+				return Err(Error::ConstraintNumberOfVariablesMismatch {
+					name,
+					n_vars: column_n_vars,
+					expected: n_vars,
+				});
+			}
+		}
+
+		self.constraints.push((name, expr));
+		Ok(())
+	}
+
+	/// Adds a boundary constraint asserting that `column` takes a public value on hypercube row
+	/// `row`.
+	///
+	/// The values of all boundary constraints are given to the prover and the verifier in
+	/// declaration order, and are bound to the proof.
+	pub fn add_boundary(
+		&mut self,
+		name: impl ToString,
+		column: Column,
+		row: usize,
+	) -> Result<(), Error> {
+		let name = name.to_string();
+		self.check_name(&name)?;
+
+		let n_vars = self.column_n_vars(column)?;
+		if row >= 1 << n_vars {
+			return Err(Error::BoundaryRowOutOfRange { name, row });
+		}
+
+		self.boundaries.push(Boundary { name, column, row });
+		Ok(())
+	}
+
+	/// Reduces every constraint to degree at most `max_degree` by committing intermediate columns.
+	///
+	/// Wherever a product would exceed `max_degree`, its factor of highest degree is replaced by a
+	/// new committed column in round `round_id`, with a constraint equating the column and the
+	/// factor. Equal factors share a column. The prover computes the values of the intermediate
+	/// columns that the [`Witness`](super::Witness) does not set.
+	///
+	/// Every extra column is paid for in commitment size, while every sumcheck round of the
+	/// zerocheck gets cheaper with the degree; the returned report compares both costs.
+	pub fn reduce_degree(
+		&mut self,
+		max_degree: usize,
+		round_id: RoundId,
+	) -> Result<DegreeReductionReport, Error> {
+		if max_degree < 2 {
+			return Err(Error::DegreeTargetTooLow { max_degree });
+		}
+
+		let degree_before = self.max_degree();
+		let sumcheck_evals_per_row_before = self.sumcheck_evals_per_row(degree_before);
+		let n_intermediates_before = self.intermediates.len();
+
+		// Definitions of intermediate columns are appended to the constraints and already have
+		// degree at most `max_degree`
+		for index in 0..self.constraints.len() {
+			if self.constraints[index].1.degree() <= max_degree {
+				continue;
+			}
+			let (name, expr) = self.constraints[index].clone();
+			let mut vars = Vec::new();
+			expr.collect_vars(&mut vars);
+			let n_vars = self.column_n_vars(Column(vars[0]))?;
+
+			let context = ReductionContext {
+				constraint_name: &name,
+				max_degree,
+				round_id,
+				n_vars,
+			};
+			self.constraints[index].1 = self.reduce_expr(expr, &context)?;
+		}
+
+		let degree_after = self.max_degree();
+		let intermediate_columns = self.intermediates[n_intermediates_before..]
+			.iter()
+			.map(|intermediate| intermediate.column)
+			.collect::<Vec<_>>();
+		let committed_bits_per_row = intermediate_columns
+			.iter()
+			.map(|&column| 1 << self.column_tower_level(column))
+			.sum();
+		Ok(DegreeReductionReport {
+			degree_before,
+			degree_after,
+			intermediate_columns,
+			committed_bits_per_row,
+			sumcheck_evals_per_row_before,
+			sumcheck_evals_per_row_after: self.sumcheck_evals_per_row(degree_after),
+		})
+	}
+
+	fn reduce_expr(&mut self, expr: Expr<F>, context: &ReductionContext) -> Result<Expr<F>, Error> {
+		match expr {
+			Expr::Add(left, right) => {
+				let left = self.reduce_expr(*left, context)?;
+				let right = self.reduce_expr(*right, context)?;
+				Ok(Expr::Add(Box::new(left), Box::new(right)))
+			}
+			Expr::Mul(left, right) => {
+				let mut left = self.reduce_expr(*left, context)?;
+				let mut right = self.reduce_expr(*right, context)?;
+				// Each replacement leaves a factor of degree 1, so this takes at most two steps
+				while left.degree() + right.degree() > context.max_degree {
+					let factor = if left.degree() >= right.degree() {
+						&mut left
+					} else {
+						&mut right
+					};
+					let column = self.add_intermediate(factor.clone(), context)?;
+					*factor = Expr::from(column);
+				}
+				Ok(Expr::Mul(Box::new(left), Box::new(right)))
+			}
+			// Trivial powers are unwrapped, so that the factors left by splitting a power compare
+			// equal to their bases when committed
+			Expr::Pow(_, 0) => Ok(Expr::Const(F::ONE)),
+			Expr::Pow(base, 1) => self.reduce_expr(*base, context),
+			Expr::Pow(base, exp) => {
+				let base = self.reduce_expr(*base, context)?;
+				if base.degree() * exp as usize <= context.max_degree {
+					return Ok(base.pow(exp));
+				}
+				// Split the power into a product, whose factors are then reduced as above
+				let half = exp / 2;
+				self.reduce_expr(base.clone().pow(exp - half) * base.pow(half), context)
+			}
+			expr => Ok(expr),
+		}
+	}
+
+	/// Returns a committed column constrained to equal `expr`, reusing an earlier one if possible.
+	fn add_intermediate(
+		&mut self,
+		expr: Expr<F>,
+		context: &ReductionContext,
+	) -> Result<Column, Error> {
+		if let Some(intermediate) = self
+			.intermediates
+			.iter()
+			.find(|intermediate| intermediate.expr == expr)
+		{
+			return Ok(intermediate.column);
+		}
+
+		let mut vars = Vec::new();
+		expr.collect_vars(&mut vars);
+		let tower_level = vars
+			.into_iter()
+			.map(|index| self.column_tower_level(Column(index)))
+			.fold(expr.binary_tower_level(), usize::max);
+
+		let name = format!("{}_intermediate_{}", context.constraint_name, self.intermediates.len());
+		let column = self.add_committed(&name, context.round_id, context.n_vars, tower_level)?;
+		self.add_constraint(format!("{name}_definition"), Expr::from(column) - expr.clone())?;
+		self.intermediates.push(IntermediateColumn { column, expr });
+		Ok(column)
+	}
+
+	/// The degree of the mix of all constraints, including the degree 2 boundary constraints.
+	fn max_degree(&self) -> usize {
+		let boundary_degree = if self.boundaries.is_empty() { 0 } else { 2 };
+		self.constraints
+			.iter()
+			.map(|(_, expr)| expr.degree())
+			.fold(boundary_degree, usize::max)
+	}
+
+	/// Multilinear evaluations per hypercube row in a zerocheck sumcheck round of the given
+	/// degree, which evaluates every multilinear of the composition at `degree + 1` points.
+	fn sumcheck_evals_per_row(&self, degree: usize) -> usize {
+		let mut columns = Vec::new();
+		for (_, expr) in self.constraints.iter() {
+			expr.collect_vars(&mut columns);
+		}
+		for boundary in self.boundaries.iter() {
+			if !columns.contains(&boundary.column.0) {
+				columns.push(boundary.column.0);
+			}
+		}
+		(degree + 1) * (columns.len() + self.boundaries.len())
+	}
+
+	/// Lays out the oracle set and assembles the constraints.
+	///
+	/// All constraints, including boundary constraints, must be over columns with the same number
+	/// of variables, since they are proven with a single zerocheck.
+	pub fn build(self) -> Result<ConstraintSystem<F>, Error> {
+		let Self {
+			names,
+			columns,
+			n_vars: columns_n_vars,
+			constraints,
+			boundaries,
+			intermediates,
+		} = self;
+
+		let mut oracles = MultilinearOracleSet::new();
+
+		// Group the committed columns into batches
+		let mut batch_specs = Vec::<CommittedBatchSpec>::new();
+		let mut committed_ids = HashMap::new();
+		for (index, column) in columns.iter().enumerate() {
+			if let ColumnDef::Committed {
+				round_id,
+				n_vars,
+				tower_level,
+			} = *column
+			{
+				let batch_id = batch_specs
+					.iter()
+					.position(|spec| {
+						spec.round_id == round_id
+							&& spec.n_vars == n_vars && spec.tower_level == tower_level
+					})
+					.unwrap_or_else(|| {
+						batch_specs.push(CommittedBatchSpec {
+							round_id,
+							n_vars,
+							tower_level,
+							n_polys: 0,
+						});
+						batch_specs.len() - 1
+					});
+				let index_in_batch = batch_specs[batch_id].n_polys;
+				batch_specs[batch_id].n_polys += 1;
+				committed_ids.insert(
+					index,
+					CommittedId {
+						batch_id,
+						index: index_in_batch,
+					},
+				);
+			}
+		}
+		for spec in batch_specs {
+			oracles.add_committed_batch(spec);
+		}
+
+		let mut oracle_ids = Vec::<OracleId>::with_capacity(columns.len());
+		for (index, column) in columns.into_iter().enumerate() {
+			let oracle_id = match column {
+				ColumnDef::Committed { .. } => oracles.committed_oracle_id(committed_ids[&index]),
+				ColumnDef::Transparent { poly, tower_level } => {
+					oracles.add_transparent(poly, tower_level)?
+				}
+				ColumnDef::Shifted {
+					inner,
+					offset,
+					block_bits,
+					variant,
+				} => oracles.add_shifted(oracle_ids[inner.0], offset, block_bits, variant)?,
+				ColumnDef::LinearCombination {
+					n_vars,
+					offset,
+					inner,
+				} => oracles.add_linear_combination(
+					n_vars,
+					offset,
+					inner
+						.into_iter()
+						.map(|(column, coeff)| (oracle_ids[column.0], coeff)),
+				)?,
+				ColumnDef::Projected {
+					inner,
+					values,
+					variant,
+				} => oracles.add_projected(oracle_ids[inner.0], values, variant)?,
+				ColumnDef::Repeating { inner, log_count } => {
+					oracles.add_repeating(oracle_ids[inner.0], log_count)?
+				}
+			};
+			oracle_ids.push(oracle_id);
+		}
+
+		// The zerocheck columns are all columns referenced by any constraint, in order of first use
+		let mut zerocheck_columns = Vec::new();
+		for (_, expr) in constraints.iter() {
+			expr.collect_vars(&mut zerocheck_columns);
+		}
+		for boundary in boundaries.iter() {
+			if !zerocheck_columns.contains(&boundary.column.0) {
+				zerocheck_columns.push(boundary.column.0);
+			}
+		}
+		let n_vars = zerocheck_columns
+			.first()
+			.map(|&index| columns_n_vars[index])
+			.ok_or(Error::NoConstraints)?;
+
+		// Every boundary constraint is gated by a selector of its row, which follows the zerocheck
+		// columns in the composition query
+		let mut boundary_selector_ids = Vec::with_capacity(boundaries.len());
+		for boundary in boundaries.iter() {
+			let boundary_n_vars = columns_n_vars[boundary.column.0];
+			if boundary_n_vars != n_vars {
+				return Err(Error::ConstraintNumberOfVariablesMismatch {
+					name: boundary.name.clone(),
+					n_vars: boundary_n_vars,
+					expected: n_vars,
+				});
+			}
+			let selector = SelectRow::new(n_vars, boundary.row)?;
+			boundary_selector_ids.push(oracles.add_transparent(Arc::new(selector), 0)?);
+		}
+		let n_query_vars = zerocheck_columns.len() + boundaries.len();
+
+		let constraints = constraints
+			.into_iter()
+			.map(|(name, expr)| {
+				let mut vars = Vec::new();
+				expr.collect_vars(&mut vars);
+				let constraint_n_vars = columns_n_vars[vars[0]];
+				if constraint_n_vars != n_vars {
+					return Err(Error::ConstraintNumberOfVariablesMismatch {
+						name,
+						n_vars: constraint_n_vars,
+						expected: n_vars,
+					});
+				}
+
+				let expr = expr.map_vars(&|index| {
+					zerocheck_columns
+						.iter()
+						.position(|&column| column == index)
+						.expect("zerocheck columns contain all constraint columns")
+				});
+				let composition = ConstraintComposition::with_n_vars(n_query_vars, expr)?;
+				Ok(Constraint { name, composition })
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let names = names
+			.into_iter()
+			.enumerate()
+			.map(|(index, name)| (name, Column(index)))
+			.collect();
+
+		Ok(ConstraintSystem {
+			oracles,
+			oracle_ids,
+			names,
+			n_vars,
+			zerocheck_columns: zerocheck_columns.into_iter().map(Column).collect(),
+			constraints,
+			boundaries,
+			boundary_selector_ids,
+			intermediates,
+		})
+	}
+}
+
+struct ReductionContext<'a> {
+	constraint_name: &'a str,
+	max_degree: usize,
+	round_id: RoundId,
+	n_vars: usize,
+}
+
+/// The costs of a constraint system before and after [`ConstraintSystemBuilder::reduce_degree`].
+///
+/// Costs are given per hypercube row, so that the commitment and sumcheck costs of the new
+/// columns can be compared independently of the number of variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DegreeReductionReport {
+	/// Degree of the constraint mix before the reduction
+	pub degree_before: usize,
+	/// Degree of the constraint mix after the reduction
+	pub degree_after: usize,
+	/// The committed intermediate columns added by the reduction, in declaration order
+	pub intermediate_columns: Vec<Column>,
+	/// Bits committed per row for the intermediate columns, by their tower levels
+	pub committed_bits_per_row: usize,
+	/// Multilinear evaluations per row in each zerocheck sumcheck round before the reduction
+	pub sumcheck_evals_per_row_before: usize,
+	/// Multilinear evaluations per row in each zerocheck sumcheck round after the reduction
+	pub sumcheck_evals_per_row_after: usize,
+}
+
+/// A committed column introduced by [`ConstraintSystemBuilder::reduce_degree`].
+#[derive(Debug, Clone)]
+pub struct IntermediateColumn<F: TowerField> {
+	pub column: Column,
+	/// The expression over columns that the values of the column equal on every row
+	pub expr: Expr<F>,
+}
+
+/// A named constraint of a [`ConstraintSystem`].
+#[derive(Debug, Clone)]
+pub struct Constraint<F: TowerField> {
+	pub name: String,
+	/// The constraint polynomial, over the zerocheck columns of the constraint system
+	pub composition: ConstraintComposition<F>,
+}
+
+/// A named boundary constraint of a [`ConstraintSystem`], whose value is public.
+#[derive(Debug, Clone)]
+pub struct Boundary {
+	pub name: String,
+	pub column: Column,
+	/// The hypercube row on which the column takes the public value
+	pub row: usize,
+}
+
+/// A constraint system produced by a [`ConstraintSystemBuilder`].
+#[derive(Debug, Clone)]
+pub struct ConstraintSystem<F: TowerField> {
+	oracles: MultilinearOracleSet<F>,
+	oracle_ids: Vec<OracleId>,
+	names: HashMap<String, Column>,
+	n_vars: usize,
+	zerocheck_columns: Vec<Column>,
+	constraints: Vec<Constraint<F>>,
+	boundaries: Vec<Boundary>,
+	boundary_selector_ids: Vec<OracleId>,
+	intermediates: Vec<IntermediateColumn<F>>,
+}
+
+impl<F: TowerField> ConstraintSystem<F> {
+	pub fn oracles(&self) -> &MultilinearOracleSet<F> {
+		&self.oracles
+	}
+
+	/// Specifications of the committed batches, in batch ID order.
+	pub fn batch_specs(&self) -> Vec<CommittedBatchSpec> {
+		self.oracles
+			.committed_batches()
+			.into_iter()
+			.map(|batch| CommittedBatchSpec {
+				round_id: batch.round_id,
+				n_vars: batch.n_vars,
+				n_polys: batch.n_polys,
+				tower_level: batch.tower_level,
+			})
+			.collect()
+	}
+
+	/// The batch of fixed columns in the precommitment round 0, if there is one.
+	///
+	/// Its columns are committed once by [`setup`](crate::setup::setup), and the resulting keys
+	/// are passed to [`prove`](super::prove) and [`verify`](super::verify).
+	pub fn fixed_batch_id(&self) -> Option<BatchId> {
+		self.oracles
+			.committed_batches_in_round(0)
+			.first()
+			.map(|batch| batch.id)
+	}
+
+	/// Number of variables of the constrained columns.
+	pub fn n_vars(&self) -> usize {
+		self.n_vars
+	}
+
+	pub fn column(&self, name: &str) -> Option<Column> {
+		self.names.get(name).copied()
+	}
+
+	/// All columns, in declaration order.
+	pub fn columns(&self) -> impl Iterator<Item = Column> {
+		(0..self.oracle_ids.len()).map(Column)
+	}
+
+	pub fn column_name(&self, column: Column) -> &str {
+		self.names
+			.iter()
+			.find_map(|(name, &other)| (other == column).then_some(name.as_str()))
+			.expect("every column has a name")
+	}
+
+	/// The committed columns of each batch, in batch ID order and in their order within the batch.
+	pub fn batch_columns(&self) -> Vec<Vec<Column>> {
+		let mut batch_columns = vec![Vec::new(); self.oracles.committed_batches().len()];
+		for column in self.columns() {
+			if let MultilinearPolyOracle::Committed { id, .. } =
+				self.oracles.oracle(self.oracle_id(column))
+			{
+				debug_assert_eq!(batch_columns[id.batch_id].len(), id.index);
+				batch_columns[id.batch_id].push(column);
+			}
+		}
+		batch_columns
+	}
+
+	/// The transparent columns, whose witnesses are not derived from other columns.
+	pub fn transparent_columns(&self) -> Vec<Column> {
+		self.columns()
+			.filter(|&column| {
+				matches!(
+					self.oracles.oracle(self.oracle_id(column)),
+					MultilinearPolyOracle::Transparent(..)
+				)
+			})
+			.collect()
+	}
+
+	pub fn oracle_id(&self, column: Column) -> OracleId {
+		self.oracle_ids[column.0]
+	}
+
+	pub fn constraints(&self) -> &[Constraint<F>] {
+		&self.constraints
+	}
+
+	/// The boundary constraints, in declaration order.
+	pub fn boundaries(&self) -> &[Boundary] {
+		&self.boundaries
+	}
+
+	/// The intermediate columns added by degree reduction, in declaration order.
+	pub fn intermediate_columns(&self) -> &[IntermediateColumn<F>] {
+		&self.intermediates
+	}
+
+	/// The oracles of the columns referenced by constraints, followed by the row selectors of the
+	/// boundary constraints, in the order of the composition query.
+	pub fn zerocheck_oracle_ids(&self) -> Vec<OracleId> {
+		self.zerocheck_columns
+			.iter()
+			.map(|&column| self.oracle_id(column))
+			.chain(self.boundary_selector_ids.iter().copied())
+			.collect()
+	}
+
+	/// Adds the witnesses of the boundary row selectors to a witness index.
+	///
+	/// The selectors are transparent oracles that are not columns, so they are never part of a
+	/// [`Witness`](super::Witness).
+	pub fn set_boundary_selector_witnesses<PW>(
+		&self,
+		witness_index: &mut MultilinearWitnessIndex<PW>,
+	) -> Result<(), Error>
+	where
+		PW: PackedField,
+		PW::Scalar: ExtensionField<BinaryField1b>,
+	{
+		for (boundary, &selector_id) in iter::zip(&self.boundaries, &self.boundary_selector_ids) {
+			let values = SelectRow::new(self.n_vars, boundary.row)?
+				.multilinear_extension::<BinaryField1b>()?
+				.evals()
+				.to_vec();
+			let selector = MultilinearExtension::from_values(values)?;
+			witness_index.set(selector_id, selector.specialize_arc_dyn());
+		}
+		Ok(())
+	}
+
+	/// The compositions of the boundary constraints, asserting $s_i \cdot (c_i - v_i) = 0$ for the
+	/// row selector $s_i$ of each boundary, its column $c_i$ and its public value $v_i$.
+	pub fn boundary_compositions(
+		&self,
+		boundary_values: &[F],
+	) -> Result<Vec<ConstraintComposition<F>>, Error> {
+		if boundary_values.len() != self.boundaries.len() {
+			return Err(Error::IncorrectNumberOfBoundaryValues {
+				expected: self.boundaries.len(),
+			});
+		}
+
+		let n_query_vars = self.zerocheck_columns.len() + self.boundaries.len();
+		iter::zip(&self.boundaries, boundary_values)
+			.enumerate()
+			.map(|(index, (boundary, &value))| {
+				let column_index = self
+					.zerocheck_columns
+					.iter()
+					.position(|&column| column == boundary.column)
+					.expect("zerocheck columns contain all boundary columns");
+				let selector = Expr::Var(self.zerocheck_columns.len() + index);
+				let expr = selector * (Expr::Var(column_index) - Expr::Const(value));
+				Ok(ConstraintComposition::with_n_vars(n_query_vars, expr)?)
+			})
+			.collect()
+	}
+
+	/// Mixes all constraints, including the boundary constraints with their public values, into
+	/// one composition with powers of `challenge`.
+	///
+	/// The prover calls this with the witness packed field, and the verifier with `F`, both using
+	/// the same sampled challenge.
+	pub fn mix_composition<P>(
+		&self,
+		challenge: P::Scalar,
+		boundary_values: &[F],
+	) -> Result<ConstraintMixComposition<P, F>, Error>
+	where
+		P: PackedField,
+		P::Scalar: ExtensionField<F>,
+	{
+		let n_query_vars = self.zerocheck_columns.len() + self.boundaries.len();
+		let compositions = self
+			.constraints
+			.iter()
+			.map(|constraint| constraint.composition.clone())
+			.chain(self.boundary_compositions(boundary_values)?);
+		let mix = empty_mix_composition::<P>(n_query_vars, challenge).include(compositions)?;
+		Ok(mix)
+	}
+
+	/// The zerocheck claim that all constraints hold, mixed with `mixing_challenge`.
+	pub fn zerocheck_claim(
+		&self,
+		mixing_challenge: F,
+		boundary_values: &[F],
+	) -> Result<ZerocheckClaim<F>, Error> {
+		let poly = CompositePolyOracle::new(
+			self.n_vars,
+			self.zerocheck_oracle_ids()
+				.into_iter()
+				.map(|id| self.oracles.oracle(id))
+				.collect(),
+			self.mix_composition::<F>(mixing_challenge, boundary_values)?,
+		)?;
+		Ok(ZerocheckClaim { poly })
+	}
+
+	/// Evaluates every constraint on every row of the witness and reports the failing rows.
+	///
+	/// Boundary constraints are checked against `boundary_values`, and the witness index must hold
+	/// the boundary row selectors, see [`Self::set_boundary_selector_witnesses`]. Reported rows
+	/// list the values of the oracles referenced by the failing constraint. This is a debugging
+	/// aid to run before proving; it returns an empty list if all constraints hold.
+	pub fn check_witness<PW>(
+		&self,
+		witness_index: &MultilinearWitnessIndex<PW>,
+		boundary_values: &[F],
+	) -> Result<Vec<UnsatisfiedConstraint<F>>, Error>
+	where
+		PW: PackedField,
+		F: From<PW::Scalar>,
+	{
+		let boundary_compositions = self.boundary_compositions(boundary_values)?;
+		let named_compositions = self
+			.constraints
+			.iter()
+			.map(|constraint| (constraint.name.as_str(), &constraint.composition))
+			.chain(
+				self.boundaries
+					.iter()
+					.map(|boundary| boundary.name.as_str())
+					.zip(&boundary_compositions),
+			)
+			.collect::<Vec<_>>();
+		let compositions = named_compositions
+			.iter()
+			.map(|&(name, composition)| (Some(name), composition as &dyn CompositionPoly<F>))
+			.collect::<Vec<_>>();
+		let oracle_ids = self.zerocheck_oracle_ids();
+		let mut unsatisfied =
+			check_compositions(self.n_vars, &oracle_ids, &compositions, witness_index)?;
+
+		for constraint in unsatisfied.iter_mut() {
+			let mut vars = Vec::new();
+			named_compositions[constraint.index]
+				.1
+				.expr()
+				.collect_vars(&mut vars);
+			let column_ids = vars
+				.into_iter()
+				.map(|index| oracle_ids[index])
+				.collect::<Vec<_>>();
+			for row in constraint.rows.iter_mut() {
+				row.values.retain(|(id, _)| column_ids.contains(id));
+			}
+		}
+		Ok(unsatisfied)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::polynomial::{transparent::step_down::StepDown, MultilinearExtension};
+	use assert_matches::assert_matches;
+	use binius_field::{BinaryField128b, BinaryField8b, Field};
+	use rand::{rngs::StdRng, Rng, SeedableRng};
+
+	type F = BinaryField128b;
+
+	const N_VARS: usize = 4;
+
+	#[test]
+	fn test_build_oracle_layout() {
+		let mut builder = ConstraintSystemBuilder::<F>::new();
+		let a = builder.add_committed("a", 1, N_VARS, 0).unwrap();
+		let wide = builder.add_committed("wide", 1, N_VARS, 3).unwrap();
+		let b = builder.add_committed("b", 1, N_VARS, 0).unwrap();
+		let a_shift = builder
+			.add_shifted("a_shift", a, 1, N_VARS, ShiftVariant::LogicalLeft)
+			.unwrap();
+		let sum = builder
+			.add_linear_combination("sum", N_VARS, F::ZERO, [(a, F::ONE), (b, F::ONE)])
+			.unwrap();
+		builder
+			.add_constraint("and", Expr::from(a) * a_shift - b)
+			.unwrap();
+		builder
+			.add_constraint("sum_wide", Expr::from(sum) - wide)
+			.unwrap();
+
+		let system = builder.build().unwrap();
+		assert_eq!(
+			system.batch_specs(),
+			vec![
+				CommittedBatchSpec {
+					round_id: 1,
+					n_vars: N_VARS,
+					n_polys: 2,
+					tower_level: 0,
+				},
+				CommittedBatchSpec {
+					round_id: 1,
+					n_vars: N_VARS,
+					n_polys: 1,
+					tower_level: 3,
+				},
+			]
+		);
+
+		let oracles = system.oracles();
+		let b_id = CommittedId {
+			batch_id: 0,
+			index: 1,
+		};
+		assert_eq!(system.oracle_id(b), oracles.committed_oracle_id(b_id));
+		assert_eq!(system.column("a_shift"), Some(a_shift));
+		assert_eq!(
+			system.zerocheck_oracle_ids(),
+			[a, a_shift, b, sum, wide].map(|column| system.oracle_id(column))
+		);
+		assert_eq!(system.constraints()[0].composition.expr().degree(), 2);
+
+		let claim = system.zerocheck_claim(F::new(0x1234), &[]).unwrap();
+		assert_eq!(claim.poly.n_vars(), N_VARS);
+		assert_eq!(claim.poly.max_individual_degree(), 2);
+		assert_eq!(claim.poly.n_multilinears(), 5);
+	}
+
+	#[test]
+	fn test_mix_composition_vanishes_on_satisfying_rows() {
+		let mut rng = StdRng::seed_from_u64(0);
+		let mut builder = ConstraintSystemBuilder::<F>::new();
+		let a = builder.add_committed("a", 1, N_VARS, 0).unwrap();
+		let b = builder.add_committed("b", 1, N_VARS, 0).unwrap();
+		let c = builder.add_committed("c", 1, N_VARS, 0).unwrap();
+		let selector = builder
+			.add_transparent("selector", Arc::new(StepDown::new(N_VARS, 3).unwrap()), 0)
+			.unwrap();
+		let constant = Expr::Const(F::from(BinaryField8b::new(0x5a)));
+		builder
+			.add_constraint("and", Expr::from(a) * b - c)
+			.unwrap();
+		builder
+			.add_constraint(
+				"scaled",
+				(Expr::from(c) * constant.clone() - Expr::from(c) * constant) * selector,
+			)
+			.unwrap();
+		let system = builder.build().unwrap();
+
+		let composition = system
+			.mix_composition::<F>(F::random(&mut rng), &[])
+			.unwrap();
+		assert_eq!(CompositionPoly::<F>::n_vars(&composition), 4);
+		assert_eq!(
+			system.constraints()[1]
+				.composition
+				.expr()
+				.binary_tower_level(),
+			3
+		);
+
+		for (a, b) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+			let a = F::new(a);
+			let b = F::new(b);
+			let s = F::random(&mut rng);
+			assert_eq!(composition.evaluate(&[a, b, a * b, s]).unwrap(), F::ZERO);
+			assert_ne!(composition.evaluate(&[a, b, a * b + F::ONE, s]).unwrap(), F::ZERO);
+		}
+	}
+
+	#[test]
+	fn test_check_witness_reports_failing_rows() {
+		let mut rng = StdRng::seed_from_u64(0);
+		let mut builder = ConstraintSystemBuilder::<F>::new();
+		let a = builder.add_committed("a", 1, N_VARS, 0).unwrap();
+		let b = builder.add_committed("b", 1, N_VARS, 0).unwrap();
+		let c = builder.add_committed("c", 1, N_VARS, 0).unwrap();
+		let d = builder.add_committed("d", 1, N_VARS, 0).unwrap();
+		builder
+			.add_constraint("and", Expr::from(a) * b - c)
+			.unwrap();
+		builder
+			.add_constraint("bit", Expr::from(d) * d - d)
+			.unwrap();
+		let system = builder.build().unwrap();
+
+		let mut random_bits = || {
+			(0..1 << N_VARS)
+				.map(|_| F::new(rng.gen::<bool>() as u128))
+				.collect::<Vec<_>>()
+		};
+		let a_values = random_bits();
+		let b_values = random_bits();
+		let d_values = random_bits();
+		let mut c_values = a_values
+			.iter()
+			.zip(b_values.iter())
+			.map(|(&a, &b)| a * b)
+			.collect::<Vec<_>>();
+
+		let mut witness_index = MultilinearWitnessIndex::<F>::new();
+		for (column, values) in [(a, &a_values), (b, &b_values), (d, &d_values)] {
+			witness_index.set(
+				system.oracle_id(column),
+				MultilinearExtension::from_values(values.clone())
+					.unwrap()
+					.specialize_arc_dyn(),
+			);
+		}
+		let set_c = |witness_index: &mut MultilinearWitnessIndex<F>, values: &[F]| {
+			witness_index.set(
+				system.oracle_id(c),
+				MultilinearExtension::from_values(values.to_vec())
+					.unwrap()
+					.specialize_arc_dyn(),
+			);
+		};
+
+		set_c(&mut witness_index, &c_values);
+		assert!(system
+			.check_witness(&witness_index, &[])
+			.unwrap()
+			.is_empty());
+
+		const BAD_ROW: usize = 5;
+		c_values[BAD_ROW] += F::ONE;
+		set_c(&mut witness_index, &c_values);
+		let unsatisfied = system.check_witness(&witness_index, &[]).unwrap();
+		assert_eq!(unsatisfied.len(), 1);
+		assert_eq!(unsatisfied[0].index, 0);
+		assert_eq!(unsatisfied[0].name.as_deref(), Some("and"));
+		assert_eq!(unsatisfied[0].n_failing_rows, 1);
+		assert_eq!(unsatisfied[0].rows[0].row, BAD_ROW);
+		assert_eq!(unsatisfied[0].rows[0].eval, F::ONE);
+		assert_eq!(
+			unsatisfied[0].rows[0].values,
+			vec![
+				(system.oracle_id(a), a_values[BAD_ROW]),
+				(system.oracle_id(b), b_values[BAD_ROW]),
+				(system.oracle_id(c), c_values[BAD_ROW]),
+			]
+		);
+	}
+
+	#[test]
+	fn test_reduce_degree() {
+		let mut builder = ConstraintSystemBuilder::<F>::new();
+		let a = builder.add_committed("a", 1, N_VARS, 0).unwrap();
+		let b = builder.add_committed("b", 1, N_VARS, 0).unwrap();
+		let c = builder.add_committed("c", 1, N_VARS, 3).unwrap();
+		let d = builder.add_committed("d", 1, N_VARS, 0).unwrap();
+		builder
+			.add_constraint("quartic", Expr::from(a) * b * c * d - a)
+			.unwrap();
+		builder
+			.add_constraint("cubic", Expr::from(a) * b * d)
+			.unwrap();
+
+		assert_matches!(
+			builder.reduce_degree(1, 2),
+			Err(Error::DegreeTargetTooLow { max_degree: 1 })
+		);
+
+		// a * b is shared by both constraints, and a * b * c is in the tower level of c
+		let report = builder.reduce_degree(2, 2).unwrap();
+		assert_eq!(report.degree_before, 4);
+		assert_eq!(report.degree_after, 2);
+		assert_eq!(report.intermediate_columns.len(), 2);
+		assert_eq!(report.committed_bits_per_row, 1 + 8);
+		assert_eq!(report.sumcheck_evals_per_row_before, 5 * 4);
+		assert_eq!(report.sumcheck_evals_per_row_after, 3 * 6);
+
+		let system = builder.build().unwrap();
+		assert!(system
+			.constraints()
+			.iter()
+			.all(|constraint| constraint.composition.expr().degree() <= 2));
+		assert_eq!(system.constraints().len(), 4);
+
+		let intermediates = system.intermediate_columns();
+		assert_eq!(
+			intermediates
+				.iter()
+				.map(|intermediate| intermediate.column)
+				.collect::<Vec<_>>(),
+			report.intermediate_columns
+		);
+		assert_eq!(intermediates[0].expr, Expr::from(a) * b);
+		assert_eq!(intermediates[1].expr, Expr::from(intermediates[0].column) * c);
+		assert_eq!(system.column("quartic_intermediate_0"), Some(intermediates[0].column));
+		assert_eq!(
+			system.batch_specs()[2..],
+			[0, 3].map(|tower_level| CommittedBatchSpec {
+				round_id: 2,
+				n_vars: N_VARS,
+				n_polys: 1,
+				tower_level,
+			})
+		);
+	}
+
+	#[test]
+	fn test_reduce_degree_power() {
+		let mut builder = ConstraintSystemBuilder::<F>::new();
+		let a = builder.add_committed("a", 1, N_VARS, 0).unwrap();
+		let b = builder.add_committed("b", 1, N_VARS, 0).unwrap();
+		builder
+			.add_constraint("power", (Expr::from(a) * b).pow(3) - a)
+			.unwrap();
+
+		// (a * b)^3 is split into (a * b)^2 * (a * b), committing a * b and its square
+		let report = builder.reduce_degree(2, 2).unwrap();
+		assert_eq!(report.degree_before, 6);
+		assert_eq!(report.degree_after, 2);
+		assert_eq!(report.intermediate_columns.len(), 2);
+
+		let system = builder.build().unwrap();
+		let intermediates = system.intermediate_columns();
+		assert_eq!(intermediates[0].expr, Expr::from(a) * b);
+		assert_eq!(
+			intermediates[1].expr,
+			Expr::from(intermediates[0].column) * intermediates[0].column
+		);
+	}
+
+	#[test]
+	fn test_builder_errors() {
+		let mut builder = ConstraintSystemBuilder::<F>::new();
+		let a = builder.add_committed("a", 1, N_VARS, 0).unwrap();
+		let short = builder.add_committed("short", 1, N_VARS - 1, 0).unwrap();
+
+		assert_matches!(
+			builder.add_committed("a", 1, N_VARS, 0),
+			Err(Error::DuplicateName { name }) if name == "a"
+		);
+		assert_matches!(builder.add_repeating("r", Column(7), 1), Err(Error::InvalidColumn(7)));
+		assert_matches!(
+			builder.add_constraint("const", Expr::Const(F::ONE)),
+			Err(Error::ConstantConstraint { .. })
+		);
+		assert_matches!(
+			builder.add_constraint("mixed", Expr::from(a) * short),
+			Err(Error::ConstraintNumberOfVariablesMismatch { .. })
+		);
+		assert_matches!(
+			builder.add_shifted("a_shift", a, 1, N_VARS + 1, ShiftVariant::CircularLeft),
+			Err(Error::InvalidShift { name, .. }) if name == "a_shift"
+		);
+		assert_matches!(
+			builder.add_shifted("a_shift", a, 1 << 3, 3, ShiftVariant::LogicalLeft),
+			Err(Error::InvalidShift { offset: 8, .. })
+		);
+		assert_matches!(
+			builder.add_shifted("a_shift", a, 0, 3, ShiftVariant::LogicalLeft),
+			Err(Error::InvalidShift { offset: 0, .. })
+		);
+		assert_matches!(
+			builder.add_projected(
+				"a_proj",
+				a,
+				vec![F::ONE; N_VARS],
+				ProjectionVariant::FirstVars
+			),
+			Err(Error::InvalidProjection { name, values_len: N_VARS, n_vars: N_VARS })
+				if name == "a_proj"
+		);
+		assert_matches!(
+			builder.add_boundary("a_oob", a, 1 << N_VARS),
+			Err(Error::BoundaryRowOutOfRange { row, .. }) if row == 1 << N_VARS
+		);
+
+		builder
+			.add_constraint("short_zero", Expr::from(short))
+			.unwrap();
+		builder.add_constraint("a_zero", Expr::from(a)).unwrap();
+		assert_matches!(
+			builder.build(),
+			Err(Error::ConstraintNumberOfVariablesMismatch { name, .. }) if name == "a_zero"
+		);
+	}
+}
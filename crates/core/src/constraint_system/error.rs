@@ -0,0 +1,73 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use crate::{
+	oracle::Error as OracleError,
+	poly_commit::Error as PolyCommitError,
+	polynomial::Error as PolynomialError,
+	protocols::{
+		greedy_evalcheck::Error as GreedyEvalcheckError, sumcheck::Error as SumcheckError,
+		zerocheck::Error as ZerocheckError,
+	},
+	witness::Error as WitnessError,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("the name {name} is already used by another column or constraint")]
+	DuplicateName { name: String },
+	#[error("column {0} was not declared by this builder")]
+	InvalidColumn(usize),
+	#[error("constraint {name} does not reference any columns")]
+	ConstantConstraint { name: String },
+	#[error("constraint {name} has {n_vars} variables, expected {expected}")]
+	ConstraintNumberOfVariablesMismatch {
+		name: String,
+		n_vars: usize,
+		expected: usize,
+	},
+	#[error("shifted column {name} has an invalid offset {offset} or block size {block_bits}")]
+	InvalidShift {
+		name: String,
+		offset: usize,
+		block_bits: usize,
+		n_vars: usize,
+	},
+	#[error("projected column {name} fixes {values_len} of the {n_vars} inner variables")]
+	InvalidProjection {
+		name: String,
+		values_len: usize,
+		n_vars: usize,
+	},
+	#[error("boundary {name} is on row {row}, which is outside of its column")]
+	BoundaryRowOutOfRange { name: String, row: usize },
+	#[error("the constraint system has no constraints")]
+	NoConstraints,
+	#[error("constraints cannot be reduced below degree 2, got a target of {max_degree}")]
+	DegreeTargetTooLow { max_degree: usize },
+	#[error("the values of intermediate column {name} are not in the committed field")]
+	IntermediateOutOfField { name: String },
+	#[error("no witness is given for column {name}")]
+	MissingWitness { name: String },
+	#[error("expected {expected} boundary values, one per boundary constraint")]
+	IncorrectNumberOfBoundaryValues { expected: usize },
+	#[error(
+		"the constraint system has fixed columns in round 0, which require the keys from setup"
+	)]
+	MissingSetupKey,
+	#[error("the proof must have commitments for {expected} commitment rounds")]
+	IncorrectNumberOfCommitmentRounds { expected: usize },
+	#[error("oracle error: {0}")]
+	Oracle(#[from] OracleError),
+	#[error("polynomial error: {0}")]
+	Polynomial(#[from] PolynomialError),
+	#[error("zerocheck error: {0}")]
+	Zerocheck(#[from] ZerocheckError),
+	#[error("sumcheck error: {0}")]
+	Sumcheck(#[from] SumcheckError),
+	#[error("greedy evalcheck error: {0}")]
+	GreedyEvalcheck(#[from] GreedyEvalcheckError),
+	#[error("polynomial commitment error: {0}")]
+	PolyCommit(#[from] PolyCommitError),
+	#[error("witness error: {0}")]
+	Witness(#[from] WitnessError),
+}
@@ -0,0 +1,23 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::Column;
+use crate::polynomial::{composition::ArithCircuitPoly, ArithExpr};
+use binius_field::Field;
+
+/// An arithmetic expression over columns, used to write constraints.
+///
+/// Expressions are built from columns and constants with the `+`, `-` and `*` operators and
+/// [`ArithExpr::pow`], e.g. `Expr::from(a) * b - c`. Within a
+/// [`ConstraintSystemBuilder`](super::ConstraintSystemBuilder), variable indices refer to
+/// declared columns; within a [`ConstraintComposition`] they refer to positions in the
+/// composition query.
+pub type Expr<F> = ArithExpr<F>;
+
+/// A constraint polynomial, compiled from an [`Expr`] over the query variables.
+pub type ConstraintComposition<F> = ArithCircuitPoly<F>;
+
+impl<F: Field> From<Column> for Expr<F> {
+	fn from(column: Column) -> Self {
+		Self::Var(column.index())
+	}
+}
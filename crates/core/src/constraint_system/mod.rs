@@ -0,0 +1,30 @@
+// Copyright 2024 Ulvetanna Inc.
+
+//! A high-level builder for constraint systems.
+//!
+//! The [`ConstraintSystemBuilder`] declares named committed and derived columns and named
+//! constraints over them. It assigns the committed columns to batches and lays out the
+//! [`MultilinearOracleSet`](crate::oracle::MultilinearOracleSet), so that circuits do not have
+//! to track committed indices and mix compositions by hand.
+//!
+//! A built [`ConstraintSystem`] is proven end to end with [`prove`] and checked with [`verify`].
+//! Fixed columns, declared as committed columns of round 0, are committed once by
+//! [`setup`](crate::setup::setup), and the resulting keys are passed to both.
+
+mod builder;
+mod error;
+mod expr;
+mod proof;
+mod prove;
+#[cfg(test)]
+mod tests;
+mod verify;
+mod witness;
+
+pub use builder::*;
+pub use error::Error;
+pub use expr::*;
+pub use proof::Proof;
+pub use prove::prove;
+pub use verify::verify;
+pub use witness::Witness;
@@ -0,0 +1,19 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use crate::protocols::{
+	greedy_evalcheck::GreedyEvalcheckProof, sumcheck::SumcheckProof, zerocheck::ZerocheckProof,
+};
+use binius_field::Field;
+
+/// A proof that a witness satisfies a [`ConstraintSystem`](super::ConstraintSystem).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof<F: Field, PCSComm, PCSProof> {
+	/// Batch commitments of every commitment round that has committed batches, in round order,
+	/// except for the fixed columns of round 0, which are committed in the verifying key
+	pub commitments: Vec<Vec<PCSComm>>,
+	pub zerocheck_proof: ZerocheckProof,
+	pub sumcheck_proof: SumcheckProof<F>,
+	pub evalcheck_proof: GreedyEvalcheckProof<F>,
+	/// Opening proofs of the committed batches, in commitment round order
+	pub opening_proofs: Vec<PCSProof>,
+}
@@ -0,0 +1,328 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	Column, ConstraintComposition, ConstraintSystem, Error, IntermediateColumn, Proof, Witness,
+};
+use crate::{
+	challenger::{CanObserve, CanSample, CanSampleBits},
+	oracle::{MultilinearOracleSet, RoundId},
+	poly_commit::{MultiRoundProver, PolyCommitScheme},
+	polynomial::{CompositionPoly, EvaluationDomain, MultilinearComposite, MultilinearExtension},
+	protocols::{
+		greedy_evalcheck::{self, GreedyEvalcheckProveOutput},
+		sumcheck::{self, SumcheckProveOutput},
+		zerocheck::{self, Error as ZerocheckError, ZerocheckProveOutput},
+	},
+	setup::ProvingKey,
+	witness::{
+		derive_witnesses, derive_witnesses_of, Error as WitnessError, MultilinearWitnessIndex,
+	},
+};
+use binius_field::{ExtensionField, Field, PackedField, PackedFieldIndexable, TowerField};
+use rayon::prelude::*;
+use std::{collections::HashMap, iter, iter::Step};
+
+/// Proves that a witness satisfies all constraints of a constraint system.
+///
+/// The public values of the boundary constraints, in declaration order, are observed by the
+/// challenger before anything else. If the constraint system has fixed columns in commitment round
+/// 0, they are taken from `proving_key`, whose precommitment is observed next. The committed
+/// columns of the later rounds are then committed round by round, computing the intermediate
+/// columns of degree reduction that the witness does not set. The
+/// constraints, mixed with a random challenge, are then reduced by zerocheck and sumcheck to an
+/// evaluation claim, which greedy evalcheck reduces to one opening per committed batch. The prover
+/// runs over the witness packed field `PW`, whose scalars must extend `F`, and all committed
+/// batches are opened with `pcs`.
+pub fn prove<F, P, PW, PCS, CH>(
+	constraint_system: &ConstraintSystem<F>,
+	witness: &Witness<'_, P, PW>,
+	boundary_values: &[F],
+	pcs: &PCS,
+	proving_key: Option<&ProvingKey<P, F, PCS>>,
+	mut challenger: CH,
+) -> Result<Proof<F, PCS::Commitment, PCS::Proof>, Error>
+where
+	F: TowerField + Step + From<PW::Scalar> + ExtensionField<P::Scalar>,
+	P: PackedField,
+	PW: PackedFieldIndexable<Scalar: TowerField + ExtensionField<F> + ExtensionField<P::Scalar>>,
+	PCS: PolyCommitScheme<P, F>,
+	CH: CanObserve<F> + CanObserve<PCS::Commitment> + CanSample<F> + CanSampleBits<usize>,
+{
+	let n_boundaries = constraint_system.boundaries().len();
+	if boundary_values.len() != n_boundaries {
+		return Err(Error::IncorrectNumberOfBoundaryValues {
+			expected: n_boundaries,
+		});
+	}
+	challenger.observe_slice(boundary_values);
+
+	let mut oracles = constraint_system.oracles().clone();
+	let mut pcs_prover = match proving_key {
+		Some(proving_key) => {
+			MultiRoundProver::with_proving_key(pcs, &oracles, proving_key, &mut challenger)?
+		}
+		None if constraint_system.fixed_batch_id().is_some() => return Err(Error::MissingSetupKey),
+		None => MultiRoundProver::new(pcs, &oracles),
+	};
+	let fixed_columns = proving_key.map_or_else(Vec::new, |proving_key| {
+		let fixed_batch_id = proving_key.verifying_key().fixed_batch_id;
+		iter::zip(
+			constraint_system
+				.batch_columns()
+				.swap_remove(fixed_batch_id),
+			proving_key.fixed_columns(),
+		)
+		.collect()
+	});
+
+	let missing_witness = |column| Error::MissingWitness {
+		name: constraint_system.column_name(column).to_string(),
+	};
+
+	// Intermediate columns of degree reduction are computed below if they are not given
+	let intermediate_columns = constraint_system
+		.intermediate_columns()
+		.iter()
+		.map(|intermediate| intermediate.column)
+		.collect::<Vec<_>>();
+	let is_fixed = |column| fixed_columns.iter().any(|&(fixed, _)| fixed == column);
+	if let Some(column) = constraint_system
+		.batch_columns()
+		.into_iter()
+		.flatten()
+		.find(|&column| {
+			witness.committed(column).is_none()
+				&& !intermediate_columns.contains(&column)
+				&& !is_fixed(column)
+		}) {
+		return Err(missing_witness(column));
+	}
+
+	// Index the witnesses of all oracles
+	let mut witness_index = MultilinearWitnessIndex::<PW>::new();
+	for &(column, poly) in fixed_columns.iter() {
+		let oracle_id = constraint_system.oracle_id(column);
+		witness_index.set(oracle_id, poly.to_ref().specialize_arc_dyn());
+	}
+	for column in constraint_system
+		.columns()
+		.filter(|&column| !is_fixed(column))
+	{
+		let oracle_id = constraint_system.oracle_id(column);
+		if let Some(poly) = witness.committed(column) {
+			witness_index.set(oracle_id, poly.to_ref().specialize_arc_dyn());
+		} else if let Some(poly) = witness.get(column) {
+			witness_index.set(oracle_id, poly.clone());
+		}
+	}
+	if let Some(column) = constraint_system
+		.transparent_columns()
+		.into_iter()
+		.find(|&column| witness.get(column).is_none())
+	{
+		return Err(missing_witness(column));
+	}
+	constraint_system.set_boundary_selector_witnesses(&mut witness_index)?;
+	let intermediate_polys =
+		compute_intermediate_witnesses(constraint_system, witness, &mut witness_index)?;
+	derive_witnesses(&oracles, &mut witness_index)?;
+
+	// Commit the committed columns of each round after the precommitment
+	let batch_polys = constraint_system
+		.batch_columns()
+		.into_iter()
+		.map(|columns| {
+			columns
+				.into_iter()
+				.filter(|&column| !is_fixed(column))
+				.map(|column| {
+					witness
+						.committed(column)
+						.or_else(|| intermediate_polys.get(&column))
+						.ok_or_else(|| missing_witness(column))
+				})
+				.collect::<Result<Vec<_>, _>>()
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let mut commitments = Vec::new();
+	for round_id in committed_rounds(&oracles) {
+		let round_polys = oracles
+			.committed_batches_in_round(round_id)
+			.into_iter()
+			.map(|batch| {
+				batch_polys[batch.id]
+					.iter()
+					.map(|poly| poly.to_ref())
+					.collect()
+			})
+			.collect();
+		commitments.push(pcs_prover.commit_round(&mut challenger, round_id, round_polys)?);
+	}
+
+	// Reduce the mixed constraints to an evaluation claim
+	let n_vars = constraint_system.n_vars();
+	let mixing_challenge: F = challenger.sample();
+	let zerocheck_challenges = challenger.sample_vec(n_vars.saturating_sub(1));
+
+	let zerocheck_claim = constraint_system.zerocheck_claim(mixing_challenge, boundary_values)?;
+	let multilinears = constraint_system
+		.zerocheck_oracle_ids()
+		.into_iter()
+		.map(|id| {
+			witness_index
+				.get(id)
+				.cloned()
+				.ok_or(ZerocheckError::MissingWitness(id))
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	let zerocheck_witness = MultilinearComposite::new(
+		n_vars,
+		constraint_system.mix_composition::<PW>(mixing_challenge.into(), boundary_values)?,
+		multilinears,
+	)?;
+
+	let ZerocheckProveOutput {
+		sumcheck_claim,
+		sumcheck_witness,
+		zerocheck_proof,
+	} = zerocheck::prove(&zerocheck_claim, zerocheck_witness, zerocheck_challenges)?;
+
+	let sumcheck_domain = EvaluationDomain::<PW::Scalar>::new_isomorphic::<F>(
+		sumcheck_claim.poly.max_individual_degree() + 1,
+	)?;
+	let SumcheckProveOutput {
+		evalcheck_claim,
+		sumcheck_proof,
+	} = sumcheck::prove(
+		&sumcheck_claim,
+		sumcheck_witness,
+		&sumcheck_domain,
+		&mut challenger,
+		sumcheck::default_switchover_round,
+	)?;
+
+	// Reduce the evaluation claim to openings of the committed batches
+	let GreedyEvalcheckProveOutput {
+		same_query_claims,
+		proof: evalcheck_proof,
+	} = greedy_evalcheck::prove(
+		&mut oracles,
+		&mut witness_index,
+		[evalcheck_claim],
+		sumcheck::default_switchover_round,
+		&mut challenger,
+	)?;
+
+	let opening_proofs = pcs_prover.prove_openings(&mut challenger, &same_query_claims)?;
+
+	Ok(Proof {
+		commitments,
+		zerocheck_proof,
+		sumcheck_proof,
+		evalcheck_proof,
+		opening_proofs,
+	})
+}
+
+/// Computes the values of the intermediate columns that the witness does not set, and adds them to
+/// the witness index.
+///
+/// Intermediate columns are computed in declaration order, so that each may refer to earlier ones.
+/// The derived columns declared before an intermediate column are derived first, as its expression
+/// may refer to them, while derived columns may in turn refer to earlier intermediate columns.
+/// The values are computed over `PW::Scalar` and must lie in the subfield `P::Scalar` to be
+/// committed, and the columns must have at least `P::LOG_WIDTH` variables to be packed.
+fn compute_intermediate_witnesses<F, P, PW>(
+	constraint_system: &ConstraintSystem<F>,
+	witness: &Witness<'_, P, PW>,
+	witness_index: &mut MultilinearWitnessIndex<'_, PW>,
+) -> Result<HashMap<Column, MultilinearExtension<'static, P>>, Error>
+where
+	F: TowerField,
+	P: PackedField,
+	PW: PackedField<Scalar: TowerField + ExtensionField<F> + ExtensionField<P::Scalar>>,
+{
+	let n_vars = constraint_system.n_vars();
+	let mut polys = HashMap::new();
+	let mut n_derived_columns = 0;
+	for IntermediateColumn { column, expr } in constraint_system.intermediate_columns() {
+		let derived_ids = (n_derived_columns..column.0)
+			.map(|index| constraint_system.oracle_id(Column(index)))
+			.filter(|&id| witness_index.get(id).is_none())
+			.collect::<Vec<_>>();
+		derive_witnesses_of(constraint_system.oracles(), derived_ids, witness_index)?;
+		n_derived_columns = column.0;
+
+		if witness.committed(*column).is_some() {
+			continue;
+		}
+		let oracle_id = constraint_system.oracle_id(*column);
+		if n_vars < P::LOG_WIDTH {
+			return Err(WitnessError::NotEnoughVarsForWitness {
+				id: oracle_id,
+				n_vars,
+			}
+			.into());
+		}
+		let out_of_field = || Error::IntermediateOutOfField {
+			name: constraint_system.column_name(*column).to_string(),
+		};
+
+		let mut vars = Vec::new();
+		expr.collect_vars(&mut vars);
+		let inputs = vars
+			.iter()
+			.map(|&index| {
+				witness_index
+					.get(constraint_system.oracle_id(Column(index)))
+					.cloned()
+					.ok_or_else(|| Error::MissingWitness {
+						name: constraint_system.column_name(Column(index)).to_string(),
+					})
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		let composition = ConstraintComposition::with_n_vars(
+			vars.len(),
+			expr.map_vars(&|index| {
+				vars.iter()
+					.position(|&var| var == index)
+					.expect("vars contains every variable of the expression")
+			}),
+		)?;
+
+		let values = (0..1 << n_vars)
+			.into_par_iter()
+			.map(|row| {
+				let query = inputs
+					.iter()
+					.map(|input| input.evaluate_on_hypercube(row))
+					.collect::<Result<Vec<_>, _>>()?;
+				let value = CompositionPoly::<PW::Scalar>::evaluate(&composition, &query)?;
+				let mut bases = ExtensionField::<P::Scalar>::iter_bases(&value);
+				let base = bases.next().expect("an extension has a non-empty basis");
+				if bases.any(|coeff| coeff != P::Scalar::ZERO) {
+					return Err(out_of_field());
+				}
+				Ok(base)
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		let packed_values = values
+			.chunks_exact(P::WIDTH)
+			.map(|chunk| P::from_fn(|i| chunk[i]))
+			.collect();
+		let poly = MultilinearExtension::from_values(packed_values)?;
+
+		witness_index.set(oracle_id, poly.clone().specialize_arc_dyn());
+		polys.insert(*column, poly);
+	}
+	Ok(polys)
+}
+
+/// The commitment rounds after the precommitment round 0 that have committed batches, in
+/// increasing order.
+pub(super) fn committed_rounds<F: TowerField>(oracles: &MultilinearOracleSet<F>) -> Vec<RoundId> {
+	(1..oracles.n_rounds())
+		.filter(|&round_id| !oracles.committed_batches_in_round(round_id).is_empty())
+		.collect()
+}
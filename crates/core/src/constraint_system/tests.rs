@@ -0,0 +1,355 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::*;
+use crate::{
+	challenger::HashChallenger,
+	oracle::ShiftVariant,
+	poly_commit::FRIPCS,
+	polynomial::{transparent::step_down::StepDown, MultilinearExtension},
+	setup::setup,
+};
+use assert_matches::assert_matches;
+use binius_field::{
+	BinaryField128b, BinaryField16b, BinaryField1b, Field, PackedBinaryField1x128b, PackedField,
+	TowerField,
+};
+use binius_hash::{GroestlDigestCompression, GroestlHasher};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{iter, iter::repeat_with, sync::Arc};
+
+type F = BinaryField128b;
+type P = PackedBinaryField1x128b;
+type TestPCS = FRIPCS<P, BinaryField16b, F, GroestlHasher<F>, GroestlDigestCompression>;
+
+const N_VARS: usize = 6;
+
+struct TestColumns {
+	a: Column,
+	b: Column,
+	c: Column,
+	d: Column,
+	selector: Column,
+}
+
+/// A system with `c = a * b` on every row and `d` equal to the next row of `a` on all rows but
+/// the last, where `d` is committed in a later round than `a`, `b` and `c`.
+fn make_builder() -> (ConstraintSystemBuilder<F>, TestColumns) {
+	let mut builder = ConstraintSystemBuilder::<F>::new();
+	let a = builder
+		.add_committed("a", 1, N_VARS, F::TOWER_LEVEL)
+		.unwrap();
+	let b = builder
+		.add_committed("b", 1, N_VARS, F::TOWER_LEVEL)
+		.unwrap();
+	let c = builder
+		.add_committed("c", 1, N_VARS, F::TOWER_LEVEL)
+		.unwrap();
+	let d = builder
+		.add_committed("d", 2, N_VARS, F::TOWER_LEVEL)
+		.unwrap();
+	let a_next = builder
+		.add_shifted("a_next", a, 1, N_VARS, ShiftVariant::LogicalRight)
+		.unwrap();
+	let step_down = StepDown::new(N_VARS, (1 << N_VARS) - 1).unwrap();
+	let selector = builder
+		.add_transparent("selector", Arc::new(step_down), 0)
+		.unwrap();
+	builder
+		.add_constraint("and", Expr::from(a) * b - c)
+		.unwrap();
+	builder
+		.add_constraint("next", (Expr::from(a_next) - d) * selector)
+		.unwrap();
+
+	let columns = TestColumns {
+		a,
+		b,
+		c,
+		d,
+		selector,
+	};
+	(builder, columns)
+}
+
+fn make_constraint_system() -> (ConstraintSystem<F>, TestColumns) {
+	let (builder, columns) = make_builder();
+	(builder.build().unwrap(), columns)
+}
+
+/// The system of [`make_builder`] with the first row of `a` and the last row of `c` public.
+fn make_constraint_system_with_boundaries() -> (ConstraintSystem<F>, TestColumns) {
+	let (mut builder, columns) = make_builder();
+	builder.add_boundary("a_first", columns.a, 0).unwrap();
+	builder
+		.add_boundary("c_last", columns.c, (1 << N_VARS) - 1)
+		.unwrap();
+	(builder.build().unwrap(), columns)
+}
+
+fn make_witness<'a>(
+	columns: &TestColumns,
+	selector: &'a MultilinearExtension<'static, BinaryField1b>,
+	rng: &mut StdRng,
+) -> Witness<'a, P, F> {
+	let mut random_values = || {
+		repeat_with(|| <F as Field>::random(&mut *rng))
+			.take(1 << N_VARS)
+			.collect::<Vec<_>>()
+	};
+	let a = random_values();
+	let b = random_values();
+	let c = a.iter().zip(b.iter()).map(|(&a, &b)| a * b).collect();
+	let mut d = random_values();
+	d[..(1 << N_VARS) - 1].copy_from_slice(&a[1..]);
+
+	let mut witness = Witness::new();
+	for (column, values) in [
+		(columns.a, a),
+		(columns.b, b),
+		(columns.c, c),
+		(columns.d, d),
+	] {
+		let values = values.into_iter().map(P::broadcast).collect();
+		witness.set_committed(column, MultilinearExtension::from_values(values).unwrap());
+	}
+	witness.set(columns.selector, selector.to_ref().specialize_arc_dyn());
+	witness
+}
+
+fn make_selector() -> MultilinearExtension<'static, BinaryField1b> {
+	let values = StepDown::new(N_VARS, (1 << N_VARS) - 1)
+		.unwrap()
+		.multilinear_extension::<BinaryField1b>()
+		.unwrap()
+		.evals()
+		.to_vec();
+	MultilinearExtension::from_values(values).unwrap()
+}
+
+#[test]
+fn test_prove_verify() {
+	let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+	let mut rng = StdRng::seed_from_u64(0);
+	let (system, columns) = make_constraint_system();
+	let selector = make_selector();
+	let witness = make_witness(&columns, &selector, &mut rng);
+
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let proof = prove(&system, &witness, &[], &pcs, None, challenger.clone()).unwrap();
+	assert_eq!(proof.commitments.len(), 2);
+	assert_eq!(proof.opening_proofs.len(), 2);
+
+	verify(&system, &[], &pcs, None, proof, challenger).unwrap();
+}
+
+#[test]
+fn test_prove_verify_boundaries() {
+	let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+	let mut rng = StdRng::seed_from_u64(0);
+	let (system, columns) = make_constraint_system_with_boundaries();
+	let selector = make_selector();
+	let witness = make_witness(&columns, &selector, &mut rng);
+
+	let value = |column, row| {
+		witness
+			.committed(column)
+			.unwrap()
+			.evaluate_on_hypercube(row)
+			.unwrap()
+	};
+	let boundary_values = [value(columns.a, 0), value(columns.c, (1 << N_VARS) - 1)];
+
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let make_proof =
+		|| prove(&system, &witness, &boundary_values, &pcs, None, challenger.clone()).unwrap();
+	verify(&system, &boundary_values, &pcs, None, make_proof(), challenger.clone()).unwrap();
+
+	// The proof does not verify against other public values
+	let wrong_values = [boundary_values[0] + F::ONE, boundary_values[1]];
+	assert!(verify(&system, &wrong_values, &pcs, None, make_proof(), challenger.clone()).is_err());
+	assert_matches!(
+		verify(&system, &boundary_values[..1], &pcs, None, make_proof(), challenger),
+		Err(Error::IncorrectNumberOfBoundaryValues { expected: 2 })
+	);
+}
+
+#[test]
+fn test_prove_verify_reduced_degree() {
+	let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+	let mut rng = StdRng::seed_from_u64(0);
+	let (mut builder, columns) = make_builder();
+	let e = builder
+		.add_committed("e", 1, N_VARS, F::TOWER_LEVEL)
+		.unwrap();
+	builder
+		.add_constraint(
+			"scaled_and",
+			Expr::from(columns.a) * columns.b * e - Expr::from(columns.c) * e,
+		)
+		.unwrap();
+	let report = builder.reduce_degree(2, 1).unwrap();
+	assert_eq!(report.degree_before, 3);
+	assert_eq!(report.intermediate_columns.len(), 1);
+	let system = builder.build().unwrap();
+
+	let selector = make_selector();
+	let mut witness = make_witness(&columns, &selector, &mut rng);
+	let e_values = repeat_with(|| P::random(&mut rng))
+		.take(1 << N_VARS)
+		.collect();
+	witness.set_committed(e, MultilinearExtension::from_values(e_values).unwrap());
+
+	// The witness of the intermediate column is computed by the prover
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let proof = prove(&system, &witness, &[], &pcs, None, challenger.clone()).unwrap();
+	verify(&system, &[], &pcs, None, proof, challenger).unwrap();
+}
+
+#[test]
+fn test_prove_verify_shifted_intermediate() {
+	let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+	let mut rng = StdRng::seed_from_u64(0);
+	let (mut builder, columns) = make_builder();
+	let e = builder
+		.add_committed("e", 1, N_VARS, F::TOWER_LEVEL)
+		.unwrap();
+	builder
+		.add_constraint(
+			"scaled_and",
+			Expr::from(columns.a) * columns.b * e - Expr::from(columns.c) * e,
+		)
+		.unwrap();
+	let report = builder.reduce_degree(2, 1).unwrap();
+
+	// The intermediate column is `a * b`, which equals `c`, and so do their shifts
+	let product = report.intermediate_columns[0];
+	let product_next = builder
+		.add_shifted("product_next", product, 1, N_VARS, ShiftVariant::LogicalRight)
+		.unwrap();
+	let c_next = builder
+		.add_shifted("c_next", columns.c, 1, N_VARS, ShiftVariant::LogicalRight)
+		.unwrap();
+	builder
+		.add_constraint("shifted_product", Expr::from(product_next) - c_next)
+		.unwrap();
+	let system = builder.build().unwrap();
+	assert_eq!(system.intermediate_columns()[0].expr, Expr::from(columns.a) * columns.b);
+
+	let selector = make_selector();
+	let mut witness = make_witness(&columns, &selector, &mut rng);
+	let e_values = repeat_with(|| P::random(&mut rng))
+		.take(1 << N_VARS)
+		.collect();
+	witness.set_committed(e, MultilinearExtension::from_values(e_values).unwrap());
+
+	// The shifted column is derived from the intermediate column computed by the prover
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let proof = prove(&system, &witness, &[], &pcs, None, challenger.clone()).unwrap();
+	verify(&system, &[], &pcs, None, proof, challenger).unwrap();
+}
+
+#[test]
+fn test_prove_missing_witness() {
+	let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+	let mut rng = StdRng::seed_from_u64(0);
+	let (system, columns) = make_constraint_system();
+	let selector = make_selector();
+	let witness = make_witness(&columns, &selector, &mut rng);
+
+	let mut missing_committed = Witness::<P, F>::new();
+	for column in [columns.a, columns.b, columns.c] {
+		let poly = witness.committed(column).unwrap().to_ref();
+		missing_committed.set_committed(column, poly);
+	}
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	assert_matches!(
+		prove(&system, &missing_committed, &[], &pcs, None, challenger.clone()),
+		Err(Error::MissingWitness { name }) if name == "d"
+	);
+
+	let d = witness.committed(columns.d).unwrap().to_ref();
+	missing_committed.set_committed(columns.d, d);
+	assert_matches!(
+		prove(&system, &missing_committed, &[], &pcs, None, challenger),
+		Err(Error::MissingWitness { name }) if name == "selector"
+	);
+}
+
+#[test]
+fn test_verify_rejects_missing_commitments() {
+	let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+	let mut rng = StdRng::seed_from_u64(0);
+	let (system, columns) = make_constraint_system();
+	let selector = make_selector();
+	let witness = make_witness(&columns, &selector, &mut rng);
+
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let mut proof = prove(&system, &witness, &[], &pcs, None, challenger.clone()).unwrap();
+	proof.commitments.pop();
+	assert_matches!(
+		verify(&system, &[], &pcs, None, proof, challenger),
+		Err(Error::IncorrectNumberOfCommitmentRounds { expected: 2 })
+	);
+}
+
+/// The system of [`make_builder`] with a fixed column `k` in round 0 and `e = k * a` in round 1.
+fn make_constraint_system_with_fixed() -> (ConstraintSystem<F>, TestColumns, Column, Column) {
+	let (mut builder, columns) = make_builder();
+	let k = builder
+		.add_committed("k", 0, N_VARS, F::TOWER_LEVEL)
+		.unwrap();
+	let e = builder
+		.add_committed("e", 1, N_VARS, F::TOWER_LEVEL)
+		.unwrap();
+	builder
+		.add_constraint("scaled", Expr::from(e) - Expr::from(k) * columns.a)
+		.unwrap();
+	(builder.build().unwrap(), columns, k, e)
+}
+
+#[test]
+fn test_prove_verify_with_setup_keys() {
+	let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+	let mut rng = StdRng::seed_from_u64(0);
+	let (system, columns, k, e) = make_constraint_system_with_fixed();
+
+	let k_values = repeat_with(|| P::random(&mut rng))
+		.take(1 << N_VARS)
+		.collect::<Vec<_>>();
+	let fixed_batch_id = system.fixed_batch_id().unwrap();
+	let (proving_key, verifying_key) = setup(
+		&pcs,
+		system.oracles(),
+		fixed_batch_id,
+		vec![MultilinearExtension::from_values(k_values.clone()).unwrap()],
+	)
+	.unwrap();
+
+	// The fixed column is taken from the proving key, not from the witness
+	let selector = make_selector();
+	let mut witness = make_witness(&columns, &selector, &mut rng);
+	let a_values = witness.committed(columns.a).unwrap().evals();
+	let e_values = iter::zip(&k_values, a_values)
+		.map(|(&k, &a)| k * a)
+		.collect();
+	witness.set_committed(e, MultilinearExtension::from_values(e_values).unwrap());
+	assert!(witness.committed(k).is_none());
+
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let make_proof =
+		|| prove(&system, &witness, &[], &pcs, Some(&proving_key), challenger.clone()).unwrap();
+	let proof = make_proof();
+	assert_eq!(proof.commitments.len(), 2);
+	assert_eq!(proof.opening_proofs.len(), 3);
+	verify(&system, &[], &pcs, Some(&verifying_key), proof, challenger.clone()).unwrap();
+
+	// The commitment to the fixed column cannot be chosen by the prover
+	assert_matches!(
+		prove(&system, &witness, &[], &pcs, None, challenger.clone()),
+		Err(Error::MissingSetupKey)
+	);
+	assert_matches!(
+		verify(&system, &[], &pcs, None, make_proof(), challenger),
+		Err(Error::MissingSetupKey)
+	);
+}
@@ -0,0 +1,90 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{prove::committed_rounds, ConstraintSystem, Error, Proof};
+use crate::{
+	challenger::{CanObserve, CanSample, CanSampleBits},
+	poly_commit::{MultiRoundVerifier, PolyCommitScheme},
+	protocols::{
+		greedy_evalcheck, sumcheck,
+		zerocheck::{self, Error as ZerocheckError},
+	},
+	setup::VerifyingKey,
+};
+use binius_field::{ExtensionField, PackedField, TowerField};
+
+/// Verifies a proof that all constraints of a constraint system are satisfied.
+///
+/// Counterpart of [`prove`](super::prove). The challenger must be in the same state as the
+/// prover's challenger was, and `boundary_values` must be the public values the proof is checked
+/// against. If the constraint system has fixed columns in commitment round 0, their commitment is
+/// taken from `verifying_key` rather than from the proof.
+pub fn verify<F, P, PCS, CH>(
+	constraint_system: &ConstraintSystem<F>,
+	boundary_values: &[F],
+	pcs: &PCS,
+	verifying_key: Option<&VerifyingKey<PCS::Commitment>>,
+	proof: Proof<F, PCS::Commitment, PCS::Proof>,
+	mut challenger: CH,
+) -> Result<(), Error>
+where
+	F: TowerField + ExtensionField<P::Scalar>,
+	P: PackedField,
+	PCS: PolyCommitScheme<P, F>,
+	CH: CanObserve<F> + CanObserve<PCS::Commitment> + CanSample<F> + CanSampleBits<usize>,
+{
+	let n_boundaries = constraint_system.boundaries().len();
+	if boundary_values.len() != n_boundaries {
+		return Err(Error::IncorrectNumberOfBoundaryValues {
+			expected: n_boundaries,
+		});
+	}
+	challenger.observe_slice(boundary_values);
+
+	let mut oracles = constraint_system.oracles().clone();
+	let Proof {
+		commitments,
+		zerocheck_proof,
+		sumcheck_proof,
+		evalcheck_proof,
+		opening_proofs,
+	} = proof;
+
+	// Observe the commitments of each round
+	let rounds = committed_rounds(&oracles);
+	if commitments.len() != rounds.len() {
+		return Err(Error::IncorrectNumberOfCommitmentRounds {
+			expected: rounds.len(),
+		});
+	}
+	let mut pcs_verifier = match verifying_key {
+		Some(verifying_key) => {
+			MultiRoundVerifier::with_verifying_key(pcs, &oracles, verifying_key, &mut challenger)?
+		}
+		None if constraint_system.fixed_batch_id().is_some() => return Err(Error::MissingSetupKey),
+		None => MultiRoundVerifier::new(pcs, &oracles),
+	};
+	for (round_id, round_commitments) in rounds.into_iter().zip(commitments) {
+		pcs_verifier.observe_round(&mut challenger, round_id, round_commitments)?;
+	}
+
+	// Reduce the mixed constraints to an evaluation claim
+	let n_vars = constraint_system.n_vars();
+	let mixing_challenge: F = challenger.sample();
+	let zerocheck_challenges = challenger.sample_vec(n_vars.saturating_sub(1));
+
+	let zerocheck_claim = constraint_system.zerocheck_claim(mixing_challenge, boundary_values)?;
+	let sumcheck_claim = zerocheck::verify(&zerocheck_claim, zerocheck_proof, zerocheck_challenges)
+		.map_err(ZerocheckError::from)?;
+	let evalcheck_claim = sumcheck::verify(&sumcheck_claim, sumcheck_proof, &mut challenger)?;
+
+	// Reduce the evaluation claim to openings of the committed batches
+	let same_query_claims = greedy_evalcheck::verify(
+		&mut oracles,
+		[evalcheck_claim],
+		evalcheck_proof,
+		&mut challenger,
+	)?;
+
+	pcs_verifier.verify_openings(&mut challenger, &same_query_claims, opening_proofs)?;
+	Ok(())
+}
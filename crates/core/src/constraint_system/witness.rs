@@ -0,0 +1,47 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::Column;
+use crate::{polynomial::MultilinearExtension, witness::MultilinearWitness};
+use binius_field::PackedField;
+use std::collections::HashMap;
+
+/// The column values of a [`ConstraintSystem`](super::ConstraintSystem) passed to the prover.
+///
+/// Committed columns are given over the packed field `P` of the polynomial commitment scheme,
+/// except for intermediate columns added by degree reduction, which the prover computes if unset,
+/// and fixed columns of round 0, which the prover takes from its proving key.
+/// Transparent columns, and derived columns that the prover should not recompute, are given as
+/// multilinear witnesses over the packed field `PW` that the prover runs in. All other derived
+/// columns are computed by [`derive_witnesses`](crate::witness::derive_witnesses).
+pub struct Witness<'a, P: PackedField, PW: PackedField> {
+	committed: HashMap<Column, MultilinearExtension<'a, P>>,
+	columns: HashMap<Column, MultilinearWitness<'a, PW>>,
+}
+
+impl<'a, P: PackedField, PW: PackedField> Witness<'a, P, PW> {
+	#[allow(clippy::new_without_default)]
+	pub fn new() -> Self {
+		Self {
+			committed: HashMap::new(),
+			columns: HashMap::new(),
+		}
+	}
+
+	/// Sets the values of a committed column.
+	pub fn set_committed(&mut self, column: Column, poly: MultilinearExtension<'a, P>) {
+		self.committed.insert(column, poly);
+	}
+
+	/// Sets the witness of a transparent or derived column.
+	pub fn set(&mut self, column: Column, witness: MultilinearWitness<'a, PW>) {
+		self.columns.insert(column, witness);
+	}
+
+	pub fn committed(&self, column: Column) -> Option<&MultilinearExtension<'a, P>> {
+		self.committed.get(&column)
+	}
+
+	pub fn get(&self, column: Column) -> Option<&MultilinearWitness<'a, PW>> {
+		self.columns.get(&column)
+	}
+}
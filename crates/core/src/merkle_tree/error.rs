@@ -6,6 +6,10 @@ pub enum Error {
 	IncorrectVectorLen { expected: usize },
 	#[error("Index exceeds Merkle tree base size: {max}")]
 	IndexOutOfRange { max: usize },
+	#[error("Merkle cap height exceeds the tree height {max}")]
+	CapHeightTooLarge { max: usize },
+	#[error("Merkle tree was committed with a different cap height, expected {expected}")]
+	CapHeightMismatch { expected: usize },
 	#[error("Verification error: {0}")]
 	Verification(#[from] VerificationError),
 }
@@ -14,6 +18,14 @@ pub enum Error {
 pub enum VerificationError {
 	#[error("Merkle tree branch is expected to be {expected}")]
 	IncorrectBranchLength { expected: usize },
+	#[error("Merkle multi-proof is expected to have {expected} nodes")]
+	IncorrectMultiProofLength { expected: usize },
+	#[error("Expected {expected} opened leaves, one per index")]
+	IncorrectNumberOfOpenings { expected: usize },
+	#[error("Opened leaves at repeated index {index} do not match")]
+	ConflictingOpenings { index: usize },
+	#[error("Merkle cap is expected to have {expected} nodes")]
+	IncorrectCapLength { expected: usize },
 	#[error("Computed Merkle root does not match commitment")]
 	MerkleRootMismatch,
 }
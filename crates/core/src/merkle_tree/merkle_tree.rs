@@ -16,16 +16,44 @@ use binius_hash::Hasher;
 ///
 /// The vector entries at each index in a batch are hashed together into leaf digests. Then a
 /// Merkle tree is constructed over the  implementation requires that the number of
-#[derive(Debug, Clone)]
+///
+/// The tree is only built up to its cap, the $2^k$ nodes at depth $k$ for a cap height $k$. A cap
+/// height of 0 gives the usual tree with a single root.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MerkleTree<D> {
 	/// Base-2 logarithm of the number of leaves
 	pub log_len: usize,
+	/// Base-2 logarithm of the number of cap nodes
+	pub cap_height: usize,
 	/// Number of vectors that are committed in this batch
 	pub batch_size: usize,
-	/// The inner nodes, arranged as a flattened array of layers with the root at the end
+	/// The inner nodes, arranged as a flattened array of layers with the cap at the end
 	pub inner_nodes: Vec<D>,
 }
 
+/// The nodes of a Merkle tree at a fixed depth, which commit to the tree in place of its root.
+///
+/// Authentication paths stop at the cap, so a cap of height $k$ shortens every path by $k$ nodes
+/// at the cost of a commitment of $2^k$ nodes. A cap of height 0 is the Merkle root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleCap<D>(pub Vec<D>);
+
+impl<D: Copy> MerkleCap<D> {
+	/// Returns the Merkle root if the cap has height 0.
+	pub fn root(&self) -> Option<D> {
+		match self.0[..] {
+			[root] => Some(root),
+			_ => None,
+		}
+	}
+}
+
+impl<D> From<D> for MerkleCap<D> {
+	fn from(root: D) -> Self {
+		Self(vec![root])
+	}
+}
+
 impl<D> MerkleTree<D>
 where
 	D: Copy + Default + Send + Sync,
@@ -33,6 +61,7 @@ where
 	pub fn build<P, H, C>(
 		compression: &C,
 		log_len: usize,
+		cap_height: usize,
 		leaves: impl Iterator<Item = impl AsRef<[P]>>,
 	) -> Result<Self, Error>
 	where
@@ -40,14 +69,18 @@ where
 		H: Hasher<P, Digest = D> + Send,
 		C: PseudoCompressionFunction<D, 2> + Sync,
 	{
+		if cap_height > log_len {
+			return Err(Error::CapHeightTooLarge { max: log_len });
+		}
+
 		let len = 1 << log_len;
 
-		let mut inner_nodes = vec![H::Digest::default(); 2 * len - 1];
+		let mut inner_nodes = vec![H::Digest::default(); 2 * len - (1 << cap_height)];
 		let batch_size = Self::hash_leaves::<_, H>(leaves, &mut inner_nodes[..len])?;
 
 		{
 			let (mut prev_layer, mut remaining) = inner_nodes.split_at_mut(len);
-			for i in 1..log_len + 1 {
+			for i in 1..log_len - cap_height + 1 {
 				let (next_layer, next_remaining) = remaining.split_at_mut(1 << (log_len - i));
 				Self::compress_layer(compression, prev_layer, next_layer);
 				(prev_layer, remaining) = (next_layer, next_remaining);
@@ -56,20 +89,18 @@ where
 
 		Ok(Self {
 			log_len,
+			cap_height,
 			batch_size,
 			inner_nodes,
 		})
 	}
 
-	/// Get the Merkle root
-	pub fn root(&self) -> D {
-		*self
-			.inner_nodes
-			.last()
-			.expect("Merkle tree length is at least 1")
+	/// Get the Merkle cap
+	pub fn cap(&self) -> &[D] {
+		&self.inner_nodes[self.inner_nodes.len() - (1 << self.cap_height)..]
 	}
 
-	/// Get a Merkle branch for the given index
+	/// Get a Merkle branch for the given index, up to but excluding the cap
 	///
 	/// Throws if the index is out of range
 	pub fn branch(&self, index: usize) -> Result<Vec<D>, Error> {
@@ -79,7 +110,7 @@ where
 			});
 		}
 
-		let branch = (0..self.log_len)
+		let branch = (0..self.log_len - self.cap_height)
 			.map(|j| {
 				let node_index = (((1 << j) - 1) << (self.log_len + 1 - j)) | (index >> j) ^ 1;
 				self.inner_nodes[node_index]
@@ -89,6 +120,25 @@ where
 		Ok(branch)
 	}
 
+	/// Get the sibling nodes that authenticate the leaves at all of the given indices
+	///
+	/// Nodes shared between the branches, or computable from the opened leaves, are omitted. The
+	/// indices must be sorted and deduplicated. Throws if any index is out of range.
+	pub fn multi_branch(&self, indices: &[usize]) -> Result<Vec<D>, Error> {
+		if indices.iter().any(|&index| index >= 1 << self.log_len) {
+			return Err(Error::IndexOutOfRange {
+				max: 1 << self.log_len,
+			});
+		}
+
+		let nodes = multi_branch_node_indices(self.log_len, self.cap_height, indices)
+			.into_iter()
+			.map(|node_index| self.inner_nodes[node_index])
+			.collect();
+
+		Ok(nodes)
+	}
+
 	fn hash_leaves<P, H>(
 		leaves: impl Iterator<Item = impl AsRef<[P]>>,
 		digests: &mut [D],
@@ -142,10 +192,35 @@ where
 	}
 }
 
+/// Indices into the flattened inner nodes of the siblings required by a multi-opening proof.
+///
+/// Walks the tree bottom-up to the cap over the sorted, deduplicated leaf `indices`. On each
+/// layer, a node whose sibling is also known needs no proof node; otherwise its sibling is
+/// required. The result is ordered by layer, then by index, which is the order the verifier
+/// consumes them in.
+fn multi_branch_node_indices(log_len: usize, cap_height: usize, indices: &[usize]) -> Vec<usize> {
+	let mut node_indices = Vec::new();
+	let mut layer = indices.to_vec();
+	for j in 0..log_len - cap_height {
+		let layer_offset = ((1 << j) - 1) << (log_len + 1 - j);
+		let mut layer_iter = layer.iter().copied().peekable();
+		while let Some(index) = layer_iter.next() {
+			if layer_iter.next_if_eq(&(index ^ 1)).is_none() {
+				node_indices.push(layer_offset | (index ^ 1));
+			}
+		}
+
+		layer.iter_mut().for_each(|index| *index >>= 1);
+		layer.dedup();
+	}
+	node_indices
+}
+
 /// [`VectorCommitScheme`] implementation using a binary Merkle tree.
 #[derive(Copy, Clone)]
 pub struct MerkleTreeVCS<P, D, H, C> {
 	log_len: usize,
+	cap_height: usize,
 	compression: C,
 	_p_marker: PhantomData<P>,
 	_d_marker: PhantomData<D>,
@@ -153,15 +228,38 @@ pub struct MerkleTreeVCS<P, D, H, C> {
 }
 
 impl<P, D, H, C> MerkleTreeVCS<P, D, H, C> {
+	/// Construct a vector commitment scheme that commits to the Merkle root.
 	pub fn new(log_len: usize, compression: C) -> Self {
 		Self {
 			log_len,
+			cap_height: 0,
 			compression,
 			_p_marker: PhantomData,
 			_d_marker: PhantomData,
 			_h_marker: PhantomData,
 		}
 	}
+
+	/// Construct a vector commitment scheme that commits to the Merkle cap of the given height.
+	///
+	/// Throws if the cap height exceeds `log_len`.
+	pub fn with_cap_height(
+		log_len: usize,
+		cap_height: usize,
+		compression: C,
+	) -> Result<Self, Error> {
+		if cap_height > log_len {
+			return Err(Error::CapHeightTooLarge { max: log_len });
+		}
+		Ok(Self {
+			cap_height,
+			..Self::new(log_len, compression)
+		})
+	}
+
+	pub fn cap_height(&self) -> usize {
+		self.cap_height
+	}
 }
 
 impl<P, D, H, C> VectorCommitScheme<P> for MerkleTreeVCS<P, D, H, C>
@@ -171,9 +269,10 @@ where
 	H: Hasher<P, Digest = D> + Send,
 	C: PseudoCompressionFunction<D, 2> + Sync,
 {
-	type Commitment = D;
+	type Commitment = MerkleCap<D>;
 	type Committed = MerkleTree<D>;
 	type Proof = Vec<D>;
+	type MultiProof = Vec<D>;
 	type Error = Error;
 
 	fn vector_len(&self) -> usize {
@@ -184,8 +283,9 @@ where
 		&self,
 		vecs: impl Iterator<Item = impl AsRef<[P]>>,
 	) -> Result<(Self::Commitment, Self::Committed), Self::Error> {
-		let tree = MerkleTree::build::<_, H, _>(&self.compression, self.log_len, vecs)?;
-		Ok((tree.root(), tree))
+		let tree =
+			MerkleTree::build::<_, H, _>(&self.compression, self.log_len, self.cap_height, vecs)?;
+		Ok((MerkleCap(tree.cap().to_vec()), tree))
 	}
 
 	fn prove_batch_opening(
@@ -193,11 +293,7 @@ where
 		committed: &Self::Committed,
 		index: usize,
 	) -> Result<Self::Proof, Self::Error> {
-		if committed.log_len != self.log_len {
-			return Err(Error::IncorrectVectorLen {
-				expected: 1 << self.log_len,
-			});
-		}
+		self.check_committed(committed)?;
 		committed.branch(index)
 	}
 
@@ -208,9 +304,10 @@ where
 		proof: Self::Proof,
 		values: impl Iterator<Item = P>,
 	) -> Result<(), Self::Error> {
-		if proof.len() != self.log_len {
+		self.check_cap(commitment)?;
+		if proof.len() != self.log_len - self.cap_height {
 			return Err(VerificationError::IncorrectBranchLength {
-				expected: self.log_len,
+				expected: self.log_len - self.cap_height,
 			}
 			.into());
 		}
@@ -220,11 +317,9 @@ where
 			});
 		}
 
-		let leaf_digest = values
-			.fold(H::new(), |hasher, value| hasher.chain_update(slice::from_ref(&value)))
-			.finalize();
+		let leaf_digest = Self::hash_leaf(values);
 
-		let root = proof.into_iter().fold(leaf_digest, |node, branch_node| {
+		let cap_node = proof.into_iter().fold(leaf_digest, |node, branch_node| {
 			let next_node = if index & 1 == 0 {
 				self.compression.compress([node, branch_node])
 			} else {
@@ -234,7 +329,94 @@ where
 			next_node
 		});
 
-		if root.ct_eq(commitment).into() {
+		if cap_node.ct_eq(&commitment.0[index]).into() {
+			Ok(())
+		} else {
+			Err(VerificationError::MerkleRootMismatch.into())
+		}
+	}
+
+	fn prove_multi_opening(
+		&self,
+		committed: &Self::Committed,
+		indices: &[usize],
+	) -> Result<Self::MultiProof, Self::Error> {
+		self.check_committed(committed)?;
+
+		let mut indices = indices.to_vec();
+		indices.sort_unstable();
+		indices.dedup();
+		committed.multi_branch(&indices)
+	}
+
+	fn verify_multi_opening(
+		&self,
+		commitment: &Self::Commitment,
+		indices: &[usize],
+		proof: Self::MultiProof,
+		values: impl Iterator<Item = impl Iterator<Item = P>>,
+	) -> Result<(), Self::Error> {
+		self.check_cap(commitment)?;
+		if indices.iter().any(|&index| index >= 1 << self.log_len) {
+			return Err(Error::IndexOutOfRange {
+				max: 1 << self.log_len,
+			});
+		}
+
+		let leaf_digests = values.map(Self::hash_leaf).collect::<Vec<_>>();
+		if leaf_digests.len() != indices.len() {
+			return Err(VerificationError::IncorrectNumberOfOpenings {
+				expected: indices.len(),
+			}
+			.into());
+		}
+
+		let mut layer = indices
+			.iter()
+			.copied()
+			.zip(leaf_digests)
+			.collect::<Vec<_>>();
+		layer.sort_unstable_by_key(|&(index, _)| index);
+		if let Some(pair) = layer
+			.windows(2)
+			.find(|pair| pair[0].0 == pair[1].0 && pair[0].1 != pair[1].1)
+		{
+			return Err(VerificationError::ConflictingOpenings { index: pair[0].0 }.into());
+		}
+		layer.dedup_by_key(|&mut (index, _)| index);
+
+		let unique_indices = layer.iter().map(|&(index, _)| index).collect::<Vec<_>>();
+		let expected =
+			multi_branch_node_indices(self.log_len, self.cap_height, &unique_indices).len();
+		if proof.len() != expected {
+			return Err(VerificationError::IncorrectMultiProofLength { expected }.into());
+		}
+
+		let mut proof_nodes = proof.into_iter();
+		for _ in 0..self.log_len - self.cap_height {
+			let mut next_layer = Vec::with_capacity(layer.len());
+			let mut layer_iter = layer.into_iter().peekable();
+			while let Some((index, node)) = layer_iter.next() {
+				let sibling = match layer_iter.next_if(|&(next_index, _)| next_index == index ^ 1) {
+					Some((_, sibling)) => sibling,
+					None => proof_nodes
+						.next()
+						.expect("proof length is checked against the required node count"),
+				};
+				let next_node = if index & 1 == 0 {
+					self.compression.compress([node, sibling])
+				} else {
+					self.compression.compress([sibling, node])
+				};
+				next_layer.push((index >> 1, next_node));
+			}
+			layer = next_layer;
+		}
+
+		let cap_matches = layer
+			.iter()
+			.all(|(index, node)| node.ct_eq(&commitment.0[*index]).into());
+		if cap_matches {
 			Ok(())
 		} else {
 			Err(VerificationError::MerkleRootMismatch.into())
@@ -242,7 +424,51 @@ where
 	}
 
 	fn proof_size(&self, _n_vecs: usize) -> usize {
-		self.log_len * mem::size_of::<D>()
+		(self.log_len - self.cap_height) * mem::size_of::<D>()
+	}
+
+	fn multi_proof_size(&self, _n_vecs: usize, n_indices: usize) -> usize {
+		// Layer j has 2^(log_len - j) nodes, of which at most half need to be provided as siblings.
+		let n_nodes = (0..self.log_len - self.cap_height)
+			.map(|j| n_indices.min(1 << (self.log_len - j - 1)))
+			.sum::<usize>();
+		n_nodes * mem::size_of::<D>()
+	}
+}
+
+impl<P, D, H, C> MerkleTreeVCS<P, D, H, C>
+where
+	P: PackedField,
+	H: Hasher<P, Digest = D>,
+{
+	fn check_committed(&self, committed: &MerkleTree<D>) -> Result<(), Error> {
+		if committed.log_len != self.log_len {
+			return Err(Error::IncorrectVectorLen {
+				expected: 1 << self.log_len,
+			});
+		}
+		if committed.cap_height != self.cap_height {
+			return Err(Error::CapHeightMismatch {
+				expected: self.cap_height,
+			});
+		}
+		Ok(())
+	}
+
+	fn check_cap(&self, commitment: &MerkleCap<D>) -> Result<(), Error> {
+		if commitment.0.len() != 1 << self.cap_height {
+			return Err(VerificationError::IncorrectCapLength {
+				expected: 1 << self.cap_height,
+			}
+			.into());
+		}
+		Ok(())
+	}
+
+	fn hash_leaf(values: impl Iterator<Item = P>) -> D {
+		values
+			.fold(H::new(), |hasher, value| hasher.chain_update(slice::from_ref(&value)))
+			.finalize()
 	}
 }
 
@@ -266,11 +492,56 @@ mod tests {
 		.take(7);
 
 		let tree =
-			MerkleTree::build::<_, GroestlHasher<_>, _>(&GroestlDigestCompression, 8, leaves)
+			MerkleTree::build::<_, GroestlHasher<_>, _>(&GroestlDigestCompression, 8, 0, leaves)
 				.unwrap();
 		assert_eq!(tree.log_len, 8);
 	}
 
+	#[test]
+	fn test_merkle_tree_cap() {
+		let mut rng = StdRng::seed_from_u64(0);
+
+		let leaves = repeat_with(|| {
+			repeat_with(|| Field::random(&mut rng))
+				.take(16)
+				.collect::<Vec<BinaryField16b>>()
+		})
+		.take(2)
+		.collect::<Vec<_>>();
+
+		let full_tree = MerkleTree::build::<_, GroestlHasher<_>, _>(
+			&GroestlDigestCompression,
+			4,
+			0,
+			leaves.iter(),
+		)
+		.unwrap();
+		let capped_tree = MerkleTree::build::<_, GroestlHasher<_>, _>(
+			&GroestlDigestCompression,
+			4,
+			2,
+			leaves.iter(),
+		)
+		.unwrap();
+
+		// The cap is the layer of the full tree four nodes below the root.
+		assert_eq!(capped_tree.cap().len(), 4);
+		assert_eq!(capped_tree.inner_nodes, full_tree.inner_nodes[..16 + 8 + 4]);
+		assert_eq!(full_tree.cap(), [*full_tree.inner_nodes.last().unwrap()]);
+
+		assert_eq!(capped_tree.branch(5).unwrap(), full_tree.branch(5).unwrap()[..2]);
+
+		assert_matches!(
+			MerkleTree::build::<_, GroestlHasher<_>, _>(
+				&GroestlDigestCompression,
+				4,
+				5,
+				leaves.iter(),
+			),
+			Err(Error::CapHeightTooLarge { max: 4 })
+		);
+	}
+
 	#[test]
 	fn test_merkle_vcs_commit_prove_open_correctly() {
 		let mut rng = StdRng::seed_from_u64(0);
@@ -286,7 +557,7 @@ mod tests {
 		.collect::<Vec<_>>();
 
 		let (commitment, tree) = vcs.commit_batch(vecs.iter()).unwrap();
-		assert_eq!(commitment, tree.root());
+		assert_eq!(commitment.0, tree.cap());
 
 		for i in 0..16 {
 			let proof = vcs.prove_batch_opening(&tree, i).unwrap();
@@ -311,7 +582,7 @@ mod tests {
 		.collect::<Vec<_>>();
 
 		let (commitment, tree) = vcs.commit_batch(vecs.iter()).unwrap();
-		assert_eq!(commitment, tree.root());
+		assert_eq!(commitment.0, tree.cap());
 
 		let proof = vcs.prove_batch_opening(&tree, 6).unwrap();
 		let values = vecs.iter().map(|vec| vec[6]);
@@ -356,6 +627,215 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_merkle_vcs_multi_opening() {
+		let mut rng = StdRng::seed_from_u64(0);
+
+		let vcs = <MerkleTreeVCS<_, _, GroestlHasher<_>, _>>::new(4, GroestlDigestCompression);
+
+		let vecs = repeat_with(|| {
+			repeat_with(|| Field::random(&mut rng))
+				.take(16)
+				.collect::<Vec<BinaryField16b>>()
+		})
+		.take(3)
+		.collect::<Vec<_>>();
+
+		let (commitment, tree) = vcs.commit_batch(vecs.iter()).unwrap();
+
+		let indices = [9, 2, 3, 9, 14];
+		let proof = vcs.prove_multi_opening(&tree, &indices).unwrap();
+
+		// The separate branches for the distinct indices would have 4 * 4 nodes.
+		assert!(proof.len() < 4 * 4);
+		assert!(proof.len() * 32 <= vcs.multi_proof_size(3, indices.len()));
+
+		let values = indices.iter().map(|&i| vecs.iter().map(move |vec| vec[i]));
+		vcs.verify_multi_opening(&commitment, &indices, proof, values)
+			.unwrap();
+
+		// Opening every leaf needs no sibling nodes at all.
+		let all_indices = (0..16).collect::<Vec<_>>();
+		let proof = vcs.prove_multi_opening(&tree, &all_indices).unwrap();
+		assert!(proof.is_empty());
+		let values = all_indices
+			.iter()
+			.map(|&i| vecs.iter().map(move |vec| vec[i]));
+		vcs.verify_multi_opening(&commitment, &all_indices, proof, values)
+			.unwrap();
+	}
+
+	fn leaf_values(
+		values: &[Vec<BinaryField16b>],
+	) -> impl Iterator<Item = impl Iterator<Item = BinaryField16b> + '_> {
+		values.iter().map(|leaf| leaf.iter().copied())
+	}
+
+	#[test]
+	fn test_merkle_vcs_incorrect_multi_opening() {
+		let mut rng = StdRng::seed_from_u64(0);
+
+		let vcs = <MerkleTreeVCS<_, _, GroestlHasher<_>, _>>::new(4, GroestlDigestCompression);
+
+		let vecs = repeat_with(|| {
+			repeat_with(|| Field::random(&mut rng))
+				.take(16)
+				.collect::<Vec<BinaryField16b>>()
+		})
+		.take(3)
+		.collect::<Vec<_>>();
+
+		let (commitment, tree) = vcs.commit_batch(vecs.iter()).unwrap();
+
+		let indices = [1, 6, 6, 12];
+		let proof = vcs.prove_multi_opening(&tree, &indices).unwrap();
+		let values = indices
+			.iter()
+			.map(|&i| vecs.iter().map(|vec| vec[i]).collect::<Vec<_>>())
+			.collect::<Vec<_>>();
+		assert!(vcs
+			.verify_multi_opening(&commitment, &indices, proof.clone(), leaf_values(&values))
+			.is_ok());
+
+		// Case: index out of range
+		assert_matches!(
+			vcs.verify_multi_opening(
+				&commitment,
+				&[1, 6, 6, 16],
+				proof.clone(),
+				leaf_values(&values)
+			),
+			Err(Error::IndexOutOfRange { .. })
+		);
+
+		// Case: missing leaf values
+		assert_matches!(
+			vcs.verify_multi_opening(
+				&commitment,
+				&indices,
+				proof.clone(),
+				leaf_values(&values[1..])
+			),
+			Err(Error::Verification(VerificationError::IncorrectNumberOfOpenings { .. }))
+		);
+
+		// Case: corrupted proof
+		let mut corrupted_proof = proof.clone();
+		corrupted_proof[1] = corrupted_proof[0];
+		assert_matches!(
+			vcs.verify_multi_opening(&commitment, &indices, corrupted_proof, leaf_values(&values)),
+			Err(Error::Verification(VerificationError::MerkleRootMismatch))
+		);
+
+		// Case: corrupted leaf values
+		let mut corrupted_values = values.clone();
+		corrupted_values[0][0] += BinaryField16b::ONE;
+		assert_matches!(
+			vcs.verify_multi_opening(
+				&commitment,
+				&indices,
+				proof.clone(),
+				leaf_values(&corrupted_values)
+			),
+			Err(Error::Verification(VerificationError::MerkleRootMismatch))
+		);
+
+		// Case: different values opened at a repeated index
+		let mut corrupted_values = values.clone();
+		corrupted_values[2][0] += BinaryField16b::ONE;
+		assert_matches!(
+			vcs.verify_multi_opening(
+				&commitment,
+				&indices,
+				proof.clone(),
+				leaf_values(&corrupted_values)
+			),
+			Err(Error::Verification(VerificationError::ConflictingOpenings { index: 6 }))
+		);
+
+		// Case: incorrect proof length
+		let mut corrupted_proof = proof.clone();
+		corrupted_proof.push(Default::default());
+		assert_matches!(
+			vcs.verify_multi_opening(&commitment, &indices, corrupted_proof, leaf_values(&values)),
+			Err(Error::Verification(VerificationError::IncorrectMultiProofLength { .. }))
+		);
+	}
+
+	#[test]
+	fn test_merkle_vcs_cap_openings() {
+		let mut rng = StdRng::seed_from_u64(0);
+
+		let vcs = <MerkleTreeVCS<_, _, GroestlHasher<_>, _>>::with_cap_height(
+			4,
+			2,
+			GroestlDigestCompression,
+		)
+		.unwrap();
+
+		let vecs = repeat_with(|| {
+			repeat_with(|| Field::random(&mut rng))
+				.take(16)
+				.collect::<Vec<BinaryField16b>>()
+		})
+		.take(3)
+		.collect::<Vec<_>>();
+
+		let (commitment, tree) = vcs.commit_batch(vecs.iter()).unwrap();
+		assert_eq!(commitment.0.len(), 4);
+		assert_eq!(commitment.root(), None);
+
+		for i in 0..16 {
+			let proof = vcs.prove_batch_opening(&tree, i).unwrap();
+			assert_eq!(proof.len(), 2);
+			let values = vecs.iter().map(|vec| vec[i]);
+			vcs.verify_batch_opening(&commitment, i, proof, values)
+				.unwrap();
+		}
+
+		let indices = [0, 7, 13];
+		let proof = vcs.prove_multi_opening(&tree, &indices).unwrap();
+		let values = indices
+			.iter()
+			.map(|&i| vecs.iter().map(|vec| vec[i]).collect::<Vec<_>>())
+			.collect::<Vec<_>>();
+		vcs.verify_multi_opening(&commitment, &indices, proof.clone(), leaf_values(&values))
+			.unwrap();
+
+		// Case: a cap node that does not match the opened leaf
+		let mut corrupted_commitment = commitment.clone();
+		corrupted_commitment.0[1] = corrupted_commitment.0[0];
+		assert_matches!(
+			vcs.verify_multi_opening(
+				&corrupted_commitment,
+				&indices,
+				proof.clone(),
+				leaf_values(&values)
+			),
+			Err(Error::Verification(VerificationError::MerkleRootMismatch))
+		);
+
+		// Case: a cap of the wrong height
+		assert_matches!(
+			vcs.verify_multi_opening(
+				&MerkleCap::from(commitment.0[0]),
+				&indices,
+				proof,
+				leaf_values(&values)
+			),
+			Err(Error::Verification(VerificationError::IncorrectCapLength { expected: 4 }))
+		);
+
+		// Case: a tree committed with a different cap height
+		let other_vcs =
+			<MerkleTreeVCS<_, _, GroestlHasher<_>, _>>::new(4, GroestlDigestCompression);
+		let (_, other_tree) = other_vcs.commit_batch(vecs.iter()).unwrap();
+		assert_matches!(
+			vcs.prove_batch_opening(&other_tree, 0),
+			Err(Error::CapHeightMismatch { expected: 2 })
+		);
+	}
+
 	#[test]
 	fn test_proof_size() {
 		let vcs = <MerkleTreeVCS<BinaryField16b, _, GroestlHasher<_>, _>>::new(
@@ -364,5 +844,19 @@ mod tests {
 		);
 		assert_eq!(vcs.proof_size(1), 4 * 32);
 		assert_eq!(vcs.proof_size(2), 4 * 32);
+
+		// Bounded by the size of a separate branch per index, and by the full tree for many indices.
+		assert_eq!(vcs.multi_proof_size(1, 1), 4 * 32);
+		assert_eq!(vcs.multi_proof_size(1, 3), (3 + 3 + 2 + 1) * 32);
+		assert_eq!(vcs.multi_proof_size(1, 100), 15 * 32);
+
+		let vcs = <MerkleTreeVCS<BinaryField16b, _, GroestlHasher<_>, _>>::with_cap_height(
+			4,
+			2,
+			GroestlDigestCompression,
+		)
+		.unwrap();
+		assert_eq!(vcs.proof_size(1), 2 * 32);
+		assert_eq!(vcs.multi_proof_size(1, 3), (3 + 3) * 32);
 	}
 }
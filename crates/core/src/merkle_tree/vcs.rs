@@ -7,6 +7,7 @@ pub trait VectorCommitScheme<T> {
 	type Commitment: Clone;
 	type Committed;
 	type Proof;
+	type MultiProof;
 	type Error: std::error::Error + Send + Sync + 'static;
 
 	/// Returns the length of the vectors that can be committed.
@@ -34,6 +35,29 @@ pub trait VectorCommitScheme<T> {
 		values: impl Iterator<Item = T>,
 	) -> Result<(), Self::Error>;
 
+	/// Generate a single opening proof for all vectors in a batch commitment at several indices.
+	///
+	/// The indices may be given in any order and may repeat.
+	fn prove_multi_opening(
+		&self,
+		committed: &Self::Committed,
+		indices: &[usize],
+	) -> Result<Self::MultiProof, Self::Error>;
+
+	/// Verify a multi-opening proof for all vectors in a batch commitment at several indices.
+	///
+	/// `values` yields, for each index in order, the values of all vectors at that index.
+	fn verify_multi_opening(
+		&self,
+		commitment: &Self::Commitment,
+		indices: &[usize],
+		proof: Self::MultiProof,
+		values: impl Iterator<Item = impl Iterator<Item = T>>,
+	) -> Result<(), Self::Error>;
+
 	/// Returns the byte-size of a proof.
 	fn proof_size(&self, n_vecs: usize) -> usize;
+
+	/// Returns an upper bound on the byte-size of a multi-opening proof at `n_indices` indices.
+	fn multi_proof_size(&self, n_vecs: usize, n_indices: usize) -> usize;
 }
@@ -3,6 +3,7 @@
 use crate::{
 	oracle::{
 		BatchId, CommittedBatch, CommittedBatchSpec, CommittedId, CompositePolyOracle, Error,
+		RoundId,
 	},
 	polynomial::{Error as PolynomialError, IdentityCompositionPoly, MultivariatePoly},
 };
@@ -54,6 +55,10 @@ enum MultilinearOracleMeta<F: TowerField> {
 		offset: F,
 		inner: Vec<(OracleId, F)>,
 	},
+	Packed {
+		inner_id: OracleId,
+		log_degree: usize,
+	},
 }
 
 /// An ordered set of multilinear polynomial oracles.
@@ -244,6 +249,35 @@ impl<F: TowerField> MultilinearOracleSet<F> {
 		Ok(id)
 	}
 
+	/// Adds an oracle that packs each $2^\kappa$ consecutive evaluations of an inner oracle into one
+	/// element of the tower field $\kappa$ levels higher, where $\kappa$ is `log_degree`.
+	pub fn add_packed(&mut self, id: OracleId, log_degree: usize) -> Result<OracleId, Error> {
+		if id >= self.oracles.len() {
+			return Err(Error::InvalidOracleId(id));
+		}
+
+		let n_vars = self.n_vars(id);
+		if log_degree > n_vars {
+			return Err(Error::NotEnoughVarsForPacking { n_vars, log_degree });
+		}
+
+		let tower_level = self.tower_level(id) + log_degree;
+		if tower_level > F::TOWER_LEVEL {
+			return Err(Error::TowerLevelTooHigh { tower_level });
+		}
+
+		let id = self.add(MultilinearOracleMeta::Packed {
+			inner_id: id,
+			log_degree,
+		});
+		Ok(id)
+	}
+
+	/// The number of oracles in the set. Oracle IDs range from 0 to `size() - 1`.
+	pub fn size(&self) -> usize {
+		self.oracles.len()
+	}
+
 	pub fn committed_batch(&self, id: BatchId) -> CommittedBatch {
 		let batch = &self.batches[id].spec;
 		CommittedBatch {
@@ -272,6 +306,23 @@ impl<F: TowerField> MultilinearOracleSet<F> {
 			.collect()
 	}
 
+	/// Returns the committed batches of one commitment round, in batch ID order.
+	pub fn committed_batches_in_round(&self, round_id: RoundId) -> Vec<CommittedBatch> {
+		self.committed_batches()
+			.into_iter()
+			.filter(|batch| batch.round_id == round_id)
+			.collect()
+	}
+
+	/// The number of commitment rounds, which is one more than the highest round ID of any batch.
+	pub fn n_rounds(&self) -> usize {
+		self.batches
+			.iter()
+			.map(|batch| batch.spec.round_id + 1)
+			.max()
+			.unwrap_or(0)
+	}
+
 	pub fn committed_oracle_id(&self, id: CommittedId) -> OracleId {
 		let CommittedId { batch_id, index } = id;
 		let batch = &self.batches[batch_id];
@@ -357,6 +408,16 @@ impl<F: TowerField> MultilinearOracleSet<F> {
 				)
 				.expect("linear combination parameters validated by add_linear_combination"),
 			),
+			MultilinearOracleMeta::Packed {
+				inner_id,
+				log_degree,
+			} => MultilinearPolyOracle::Packed(
+				id,
+				Packed {
+					inner: Box::new(self.oracle(*inner_id)),
+					log_degree: *log_degree,
+				},
+			),
 		}
 	}
 
@@ -376,6 +437,10 @@ impl<F: TowerField> MultilinearOracleSet<F> {
 				inner_id, values, ..
 			} => self.n_vars(*inner_id) - values.len(),
 			LinearCombination { n_vars, .. } => *n_vars,
+			Packed {
+				inner_id,
+				log_degree,
+			} => self.n_vars(*inner_id) - log_degree,
 		}
 	}
 
@@ -396,6 +461,10 @@ impl<F: TowerField> MultilinearOracleSet<F> {
 			// TODO: We can derive this more tightly by inspecting the coefficients and inner
 			// polynomials.
 			LinearCombination { .. } => F::TOWER_LEVEL,
+			Packed {
+				inner_id,
+				log_degree,
+			} => self.tower_level(*inner_id) + log_degree,
 		}
 	}
 }
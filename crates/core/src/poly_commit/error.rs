@@ -1,6 +1,10 @@
 // Copyright 2023 Ulvetanna Inc.
 
-use crate::polynomial;
+use crate::{
+	oracle::{BatchId, RoundId},
+	polynomial,
+	serialization::Error as SerializationError,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -14,6 +18,8 @@ pub enum Error {
 	ExtensionDegreePowerOfTwoRequired,
 	#[error("cannot commit unaligned message")]
 	UnalignedMessage,
+	#[error("the committed matrices must have {n_rows} rows and {n_cols} columns")]
+	CommittedMatrixShape { n_rows: usize, n_cols: usize },
 	#[error("packing width must divide code dimension")]
 	PackingWidthMustDivideCodeDimension,
 	#[error("packing width must divide the number of rows")]
@@ -30,8 +36,31 @@ pub enum Error {
 	VectorCommit(#[source] Box<dyn std::error::Error + Send + Sync>),
 	#[error("transpose error: {0}")]
 	Transpose(#[from] binius_field::transpose::Error),
+	#[error("NTT error: {0}")]
+	NTT(#[from] binius_ntt::Error),
+	#[error("the polynomial commitment scheme requires at least one variable")]
+	ZeroVariables,
+	#[error("polynomial commitment scheme error: {0}")]
+	PolyCommit(#[source] Box<dyn std::error::Error + Send + Sync>),
+	#[error("commitment round {round_id} is out of order, the next round is {next_round}")]
+	RoundOutOfOrder {
+		round_id: RoundId,
+		next_round: RoundId,
+	},
+	#[error("commitment round {round_id} has committed batches but was skipped")]
+	SkippedRound { round_id: RoundId },
+	#[error("commitment round {round_id} has {expected} committed batches")]
+	IncorrectNumberOfBatches { round_id: RoundId, expected: usize },
+	#[error("batch {batch_id} has not been committed")]
+	UncommittedBatch { batch_id: BatchId },
+	#[error("the fixed batch {batch_id} must be the only batch of the precommitment round")]
+	InvalidFixedBatch { batch_id: BatchId },
+	#[error("the committed batches do not match the verifying key")]
+	VerifyingKeyMismatch,
 	#[error("verification failure: {0}")]
 	Verification(#[from] VerificationError),
+	#[error("transcript error: {0}")]
+	Transcript(#[from] SerializationError),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -51,4 +80,26 @@ pub enum VerificationError {
 	IncorrectPartialEvaluation,
 	#[error("partial evaluation (t') is the wrong size")]
 	PartialEvaluationSize,
+	#[error("incorrect number of fold rounds, expected {expected}")]
+	NumberOfFoldRounds { expected: usize },
+	#[error("incorrect number of round commitments, expected {expected}")]
+	NumberOfRoundCommitments { expected: usize },
+	#[error("sumcheck round {round} is inconsistent with the claimed sum")]
+	IncorrectSumcheckRound { round: usize },
+	#[error(
+		"opened values in round {round} have incorrect size, got {actual} expected {expected}"
+	)]
+	OpenedValuesSize {
+		round: usize,
+		expected: usize,
+		actual: usize,
+	},
+	#[error("query is inconsistent with the folded codeword in round {round}")]
+	IncorrectFold { round: usize },
+	#[error("incorrect number of sumcheck rounds, expected {expected}")]
+	NumberOfSumcheckRounds { expected: usize },
+	#[error(
+		"partial evaluations of poly_index {poly_index} have incorrect size, expected {expected}"
+	)]
+	PartialEvaluationsSize { poly_index: usize, expected: usize },
 }
@@ -0,0 +1,851 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::error::{Error, VerificationError};
+use crate::{
+	merkle_tree::{MerkleCap, MerkleTree, MerkleTreeVCS, VectorCommitScheme},
+	poly_commit::PolyCommitScheme,
+	polynomial::{
+		multilinear_query::MultilinearQuery, Error as PolynomialError, MultilinearExtension,
+	},
+};
+use binius_field::{
+	packed::iter_packed_slice, util::inner_product_unchecked, BinaryField, BinaryField8b,
+	ExtensionField, Field, PackedExtensionField, PackedField,
+};
+use binius_hash::{GroestlDigestCompression, GroestlHasher, Hasher};
+use binius_ntt::{AdditiveNTT, AdditiveNTTWithOTFCompute};
+use p3_challenger::{CanObserve, CanSample, CanSampleBits};
+use p3_symmetric::PseudoCompressionFunction;
+use p3_util::log2_ceil_usize;
+use rayon::prelude::*;
+use std::{
+	iter::{self, repeat_with},
+	marker::PhantomData,
+	mem,
+};
+
+/// Evaluation proof data for the `FRIPCS` polynomial commitment scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof<F, Digest> {
+	/// Number of distinct multilinear polynomials in the batch opening proof
+	pub n_polys: usize,
+	/// The sumcheck round messages $(g_i(0), g_i(1))$, one for each variable
+	///
+	/// The round polynomial $g_i$ is the linear factor of the sumcheck round polynomial remaining
+	/// after dividing out the equality indicator of the $i$-th query coordinate.
+	pub sumcheck_rounds: Vec<(F, F)>,
+	/// Vector commitments to the folded codewords of all rounds but the last
+	pub round_commitments: Vec<Digest>,
+	/// The constant value of the codeword after all variables have been folded
+	pub final_value: F,
+	/// Opening proofs for the test queries
+	///
+	/// Each query has one opening per round. An opening is a tuple of the pair of adjacent
+	/// codeword values at the queried position, for every committed polynomial in the first
+	/// round, and the Merkle branch authenticating them.
+	pub query_proofs: Vec<Vec<(Vec<F>, Vec<Digest>)>>,
+}
+
+/// A multilinear polynomial commitment scheme based on FRI over binary fields, following [DP24].
+///
+/// The evaluations of a multilinear polynomial over the hypercube are interpreted as the
+/// coefficients of a univariate polynomial in the novel polynomial basis of [LCH14] and encoded
+/// with a Reed–Solomon code using the additive NTT. In this basis, a FRI fold with challenge $r$
+/// of the codeword encodes the partial evaluation of the multilinear at $r$ in its lowest
+/// variable. An evaluation is then proven with a sumcheck that shares its challenges with the
+/// FRI folding rounds, so the proof size is polylogarithmic in the size of the polynomial.
+///
+/// The committed polynomials are lifted into the extension field before encoding.
+///
+/// # Type Parameters
+///
+/// * `P`: The packed base field type of committed elements.
+/// * `FA`: The field that the additive NTT is defined over.
+/// * `FE`: The extension field type used for cryptographic challenges and codeword symbols.
+/// * `H`: The hash function used for the Merkle tree leaves.
+/// * `C`: The compression function used for the Merkle tree inner nodes.
+///
+/// [DP24]: <https://eprint.iacr.org/2024/504>
+/// [LCH14]: <https://arxiv.org/abs/1404.3458>
+#[derive(Debug)]
+pub struct FRIPCS<P, FA, FE, H, C>
+where
+	FA: BinaryField,
+{
+	n_vars: usize,
+	log_inv_rate: usize,
+	n_test_queries: usize,
+	ntt: AdditiveNTTWithOTFCompute<FA>,
+	compression: C,
+	_p_marker: PhantomData<P>,
+	_fe_marker: PhantomData<FE>,
+	_h_marker: PhantomData<H>,
+}
+
+impl<F, P, FA, FE, H, C> FRIPCS<P, FA, FE, H, C>
+where
+	F: Field,
+	P: PackedField<Scalar = F>,
+	FA: BinaryField,
+	FE: BinaryField + ExtensionField<F> + ExtensionField<FA>,
+{
+	/// Construct a [`FRIPCS`].
+	///
+	/// Throws if the number of variables is zero.
+	/// Throws if the NTT field is too small for the codeword length.
+	pub fn new(
+		n_vars: usize,
+		log_inv_rate: usize,
+		n_test_queries: usize,
+		compression: C,
+	) -> Result<Self, Error> {
+		if n_vars == 0 {
+			return Err(Error::ZeroVariables);
+		}
+		if !<FE as ExtensionField<FA>>::DEGREE.is_power_of_two() {
+			return Err(Error::ExtensionDegreePowerOfTwoRequired);
+		}
+
+		let ntt = AdditiveNTTWithOTFCompute::new(n_vars + log_inv_rate)?;
+		Ok(Self {
+			n_vars,
+			log_inv_rate,
+			n_test_queries,
+			ntt,
+			compression,
+			_p_marker: PhantomData,
+			_fe_marker: PhantomData,
+			_h_marker: PhantomData,
+		})
+	}
+
+	/// The base-2 logarithm of the reciprocal of the Reed–Solomon code rate.
+	pub fn log_inv_rate(&self) -> usize {
+		self.log_inv_rate
+	}
+
+	/// The number of codeword positions tested by the verifier.
+	pub fn n_test_queries(&self) -> usize {
+		self.n_test_queries
+	}
+
+	/// The base-2 logarithm of the length of the committed codewords.
+	fn log_code_len(&self) -> usize {
+		self.n_vars + self.log_inv_rate
+	}
+}
+
+impl<P, FA, FE> FRIPCS<P, FA, FE, GroestlHasher<FE>, GroestlDigestCompression>
+where
+	P: PackedField,
+	FA: BinaryField,
+	FE: BinaryField
+		+ ExtensionField<P::Scalar>
+		+ ExtensionField<FA>
+		+ PackedExtensionField<BinaryField8b>
+		+ ExtensionField<BinaryField8b>,
+{
+	pub fn new_using_groestl_merkle_tree(
+		n_vars: usize,
+		log_inv_rate: usize,
+		n_test_queries: usize,
+	) -> Result<Self, Error> {
+		Self::new(n_vars, log_inv_rate, n_test_queries, GroestlDigestCompression)
+	}
+}
+
+impl<F, P, FA, FE, H, C> PolyCommitScheme<P, FE> for FRIPCS<P, FA, FE, H, C>
+where
+	F: Field,
+	P: PackedField<Scalar = F>,
+	FA: BinaryField,
+	FE: BinaryField
+		+ ExtensionField<F>
+		+ ExtensionField<FA>
+		+ ExtensionField<<H::Digest as PackedField>::Scalar>
+		+ PackedExtensionField<FA>,
+	H: Hasher<FE> + Send,
+	H::Digest: PackedField + Send + Sync,
+	C: PseudoCompressionFunction<H::Digest, 2> + Clone + Sync,
+{
+	type Commitment = H::Digest;
+	type Committed = (Vec<Vec<FE>>, MerkleTree<H::Digest>);
+	type Proof = Proof<FE, H::Digest>;
+	type Error = Error;
+
+	fn n_vars(&self) -> usize {
+		self.n_vars
+	}
+
+	fn commit(
+		&self,
+		polys: &[MultilinearExtension<P>],
+	) -> Result<(Self::Commitment, Self::Committed), Error> {
+		for poly in polys {
+			if poly.n_vars() != self.n_vars {
+				return Err(Error::IncorrectPolynomialSize {
+					expected: self.n_vars,
+				});
+			}
+		}
+
+		let codewords = polys
+			.iter()
+			.map(|poly| {
+				let message = iter_packed_slice(poly.evals())
+					.map(FE::from)
+					.collect::<Vec<_>>();
+				self.encode(&message)
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let (cap, tree) = self
+			.round_vcs(0)
+			.commit_batch(codewords.iter().flat_map(|codeword| deinterleave(codeword)))
+			.map_err(|err| Error::VectorCommit(Box::new(err)))?;
+		let commitment = cap.root().expect("round trees are built without a cap");
+		Ok((commitment, (codewords, tree)))
+	}
+
+	/// Generate an evaluation proof at a *random* challenge point.
+	///
+	/// Follows Protocol 4.1 in [DP24], folding one variable per round.
+	///
+	/// Precondition: The queried point must already be observed by the challenger.
+	///
+	/// [DP24]: https://eprint.iacr.org/2024/504
+	fn prove_evaluation<CH>(
+		&self,
+		challenger: &mut CH,
+		committed: &Self::Committed,
+		polys: &[MultilinearExtension<P>],
+		query: &[FE],
+	) -> Result<Self::Proof, Error>
+	where
+		CH: CanObserve<FE> + CanSample<FE> + CanSampleBits<usize>,
+	{
+		let n_polys = polys.len();
+		let n_challenges = log2_ceil_usize(n_polys);
+		let mixing_challenges = challenger.sample_vec(n_challenges);
+		let mixing_coefficients = &MultilinearQuery::<FE>::with_full_query(&mixing_challenges)?
+			.into_expansion()[..n_polys];
+
+		let (codewords, batch_tree) = committed;
+		if codewords.len() != n_polys {
+			return Err(Error::NumBatchedMismatchError {
+				err_str: format!("In prove_evaluation: number of polynomials {} must match number of committed codewords {}", n_polys, codewords.len()),
+			});
+		}
+
+		if query.len() != self.n_vars {
+			return Err(PolynomialError::IncorrectQuerySize {
+				expected: self.n_vars,
+			}
+			.into());
+		}
+
+		// Batch the polynomials by mixing their messages and codewords with the same coefficients
+		let mut message = vec![FE::ZERO; 1 << self.n_vars];
+		for (poly, &coeff) in polys.iter().zip(mixing_coefficients) {
+			for (m_i, eval) in message.iter_mut().zip(iter_packed_slice(poly.evals())) {
+				*m_i += coeff * eval;
+			}
+		}
+
+		let mut codeword = vec![FE::ZERO; 1 << self.log_code_len()];
+		for (codeword_k, &coeff) in codewords.iter().zip(mixing_coefficients) {
+			codeword
+				.par_iter_mut()
+				.zip(codeword_k.par_iter())
+				.for_each(|(c_i, &c_k_i)| *c_i += coeff * c_k_i);
+		}
+
+		let mut sumcheck_rounds = Vec::with_capacity(self.n_vars);
+		let mut round_commitments = Vec::with_capacity(self.n_vars - 1);
+		let mut round_oracles = Vec::with_capacity(self.n_vars - 1);
+		for round in 0..self.n_vars {
+			let eq_ind =
+				MultilinearQuery::<FE>::with_full_query(&query[round + 1..])?.into_expansion();
+			let (eval_0, eval_1) = message
+				.par_chunks_exact(2)
+				.zip(eq_ind.par_iter())
+				.map(|(pair, &eq)| (pair[0] * eq, pair[1] * eq))
+				.reduce(|| (FE::ZERO, FE::ZERO), |(a0, b0), (a1, b1)| (a0 + a1, b0 + b1));
+
+			challenger.observe_slice(&[eval_0, eval_1]);
+			sumcheck_rounds.push((eval_0, eval_1));
+
+			let challenge: FE = challenger.sample();
+			message = fold_message(&message, challenge);
+			codeword = self.fold_codeword(round, &codeword, challenge);
+
+			if round + 1 < self.n_vars {
+				let (cap, tree) = self
+					.round_vcs(round + 1)
+					.commit_batch(deinterleave(&codeword).into_iter())
+					.map_err(|err| Error::VectorCommit(Box::new(err)))?;
+				let commitment = cap.root().expect("round trees are built without a cap");
+				observe_digest(challenger, &commitment);
+				round_commitments.push(commitment);
+				round_oracles.push((codeword.clone(), tree));
+			}
+		}
+
+		debug_assert_eq!(message.len(), 1);
+		let final_value = message[0];
+		challenger.observe(final_value);
+
+		let query_proofs = repeat_with(|| challenger.sample_bits(self.log_code_len() - 1))
+			.take(self.n_test_queries)
+			.map(|mut index| {
+				let mut round_proofs = Vec::with_capacity(self.n_vars);
+
+				let vcs_proof = self
+					.round_vcs(0)
+					.prove_batch_opening(batch_tree, index)
+					.map_err(|err| Error::VectorCommit(Box::new(err)))?;
+				let values = codewords
+					.iter()
+					.flat_map(|codeword| [codeword[2 * index], codeword[2 * index + 1]])
+					.collect();
+				round_proofs.push((values, vcs_proof));
+
+				for (round, (codeword, tree)) in round_oracles.iter().enumerate() {
+					index >>= 1;
+					let vcs_proof = self
+						.round_vcs(round + 1)
+						.prove_batch_opening(tree, index)
+						.map_err(|err| Error::VectorCommit(Box::new(err)))?;
+					let values = vec![codeword[2 * index], codeword[2 * index + 1]];
+					round_proofs.push((values, vcs_proof));
+				}
+
+				Ok(round_proofs)
+			})
+			.collect::<Result<_, Error>>()?;
+
+		Ok(Proof {
+			n_polys,
+			sumcheck_rounds,
+			round_commitments,
+			final_value,
+			query_proofs,
+		})
+	}
+
+	/// Verify an evaluation proof at a *random* challenge point.
+	///
+	/// Follows Protocol 4.1 in [DP24], folding one variable per round.
+	///
+	/// Precondition: The queried point must already be observed by the challenger.
+	///
+	/// [DP24]: https://eprint.iacr.org/2024/504
+	fn verify_evaluation<CH>(
+		&self,
+		challenger: &mut CH,
+		commitment: &Self::Commitment,
+		query: &[FE],
+		proof: Self::Proof,
+		values: &[FE],
+	) -> Result<(), Error>
+	where
+		CH: CanObserve<FE> + CanSample<FE> + CanSampleBits<usize>,
+	{
+		if values.len() != proof.n_polys {
+			return Err(Error::NumBatchedMismatchError {
+				err_str:
+					format!("In verify_evaluation: proof number of polynomials {} must match number of opened values {}", proof.n_polys, values.len()),
+			});
+		}
+
+		let n_challenges = log2_ceil_usize(proof.n_polys);
+		let mixing_challenges = challenger.sample_vec(n_challenges);
+		let mixing_coefficients = &MultilinearQuery::<FE>::with_full_query(&mixing_challenges)?
+			.into_expansion()[..proof.n_polys];
+
+		if query.len() != self.n_vars {
+			return Err(PolynomialError::IncorrectQuerySize {
+				expected: self.n_vars,
+			}
+			.into());
+		}
+
+		self.check_proof_shape(&proof)?;
+
+		let Proof {
+			sumcheck_rounds,
+			round_commitments,
+			final_value,
+			query_proofs,
+			..
+		} = proof;
+
+		// The sum is normalized by the equality indicator factors of the variables folded so far
+		let mut sum =
+			inner_product_unchecked(values.iter().copied(), mixing_coefficients.iter().copied());
+		let mut challenges = Vec::with_capacity(self.n_vars);
+		for (round, (eval_0, eval_1)) in sumcheck_rounds.into_iter().enumerate() {
+			if eval_0 + (eval_1 - eval_0) * query[round] != sum {
+				return Err(VerificationError::IncorrectSumcheckRound { round }.into());
+			}
+
+			challenger.observe_slice(&[eval_0, eval_1]);
+			let challenge: FE = challenger.sample();
+			sum = eval_0 + (eval_1 - eval_0) * challenge;
+			challenges.push(challenge);
+
+			if let Some(round_commitment) = round_commitments.get(round) {
+				observe_digest(challenger, round_commitment);
+			}
+		}
+
+		challenger.observe(final_value);
+		if sum != final_value {
+			return Err(VerificationError::IncorrectEvaluation.into());
+		}
+
+		let round_caps = iter::once(commitment)
+			.chain(&round_commitments)
+			.map(|&digest| MerkleCap::from(digest))
+			.collect::<Vec<_>>();
+		for query_proof in query_proofs {
+			let index = challenger.sample_bits(self.log_code_len() - 1);
+			self.verify_query(
+				&round_caps,
+				&challenges,
+				mixing_coefficients,
+				final_value,
+				index,
+				query_proof,
+			)?;
+		}
+
+		Ok(())
+	}
+
+	fn proof_size(&self, n_polys: usize) -> usize {
+		let fe_size = mem::size_of::<FE>();
+		let sumcheck_size = 2 * self.n_vars * fe_size;
+		let round_commitments_size = (self.n_vars - 1) * mem::size_of::<H::Digest>();
+		let query_size = (0..self.n_vars)
+			.map(|round| {
+				let n_values = if round == 0 { 2 * n_polys } else { 2 };
+				n_values * fe_size + self.round_vcs(round).proof_size(n_values)
+			})
+			.sum::<usize>();
+		sumcheck_size + round_commitments_size + fe_size + query_size * self.n_test_queries
+	}
+}
+
+// Helper functions for PolyCommitScheme implementation.
+impl<F, P, FA, FE, H, C> FRIPCS<P, FA, FE, H, C>
+where
+	F: Field,
+	P: PackedField<Scalar = F>,
+	FA: BinaryField,
+	FE: BinaryField
+		+ ExtensionField<F>
+		+ ExtensionField<FA>
+		+ ExtensionField<<H::Digest as PackedField>::Scalar>
+		+ PackedExtensionField<FA>,
+	H: Hasher<FE> + Send,
+	H::Digest: PackedField + Send + Sync,
+	C: PseudoCompressionFunction<H::Digest, 2> + Clone + Sync,
+{
+	/// Returns the vector commitment scheme for the pairs of values of the round's codeword.
+	fn round_vcs(&self, round: usize) -> MerkleTreeVCS<FE, H::Digest, H, C> {
+		MerkleTreeVCS::new(self.log_code_len() - round - 1, self.compression.clone())
+	}
+
+	/// Encodes a message with the Reed–Solomon code, one coset of the evaluation domain at a time.
+	fn encode(&self, message: &[FE]) -> Result<Vec<FE>, Error> {
+		let mut codeword = Vec::with_capacity(message.len() << self.log_inv_rate);
+		for _ in 0..1 << self.log_inv_rate {
+			codeword.extend_from_slice(message);
+		}
+		codeword
+			.par_chunks_exact_mut(message.len())
+			.enumerate()
+			.try_for_each(|(coset, chunk)| {
+				AdditiveNTT::<FA>::forward_transform_ext(&self.ntt, chunk, coset as u32)
+			})?;
+		Ok(codeword)
+	}
+
+	/// Returns the twiddle factors of the fold in the given round, indexed by the pair position.
+	///
+	/// These are the twiddles of the corresponding layer of the additive NTT over the whole
+	/// evaluation domain.
+	fn fold_twiddles(&self, round: usize) -> Vec<FA> {
+		let log_len = self.log_code_len() - round - 1;
+		let mut twiddles = Vec::with_capacity(1 << log_len);
+		twiddles.push(FA::ZERO);
+		for i in 0..log_len {
+			let subspace_eval = self.ntt.get_subspace_eval(round, i);
+			for j in 0..twiddles.len() {
+				twiddles.push(twiddles[j] + subspace_eval);
+			}
+		}
+		twiddles
+	}
+
+	/// Returns a single twiddle factor of the fold in the given round.
+	fn fold_twiddle(&self, round: usize, index: usize) -> FA {
+		(0..self.log_code_len() - round - 1)
+			.filter(|i| (index >> i) & 1 == 1)
+			.map(|i| self.ntt.get_subspace_eval(round, i))
+			.sum()
+	}
+
+	fn fold_codeword(&self, round: usize, codeword: &[FE], challenge: FE) -> Vec<FE> {
+		let twiddles = self.fold_twiddles(round);
+		codeword
+			.par_chunks_exact(2)
+			.zip(twiddles.par_iter())
+			.map(|(pair, &twiddle)| fold_pair(twiddle, pair[0], pair[1], challenge))
+			.collect()
+	}
+
+	fn check_proof_shape(&self, proof: &Proof<FE, H::Digest>) -> Result<(), Error> {
+		if proof.sumcheck_rounds.len() != self.n_vars {
+			return Err(VerificationError::NumberOfFoldRounds {
+				expected: self.n_vars,
+			}
+			.into());
+		}
+		if proof.round_commitments.len() != self.n_vars - 1 {
+			return Err(VerificationError::NumberOfRoundCommitments {
+				expected: self.n_vars - 1,
+			}
+			.into());
+		}
+		if proof.query_proofs.len() != self.n_test_queries {
+			return Err(VerificationError::NumberOfOpeningProofs {
+				expected: self.n_test_queries,
+			}
+			.into());
+		}
+
+		for query_proof in proof.query_proofs.iter() {
+			if query_proof.len() != self.n_vars {
+				return Err(VerificationError::NumberOfFoldRounds {
+					expected: self.n_vars,
+				}
+				.into());
+			}
+			for (round, (values, _)) in query_proof.iter().enumerate() {
+				let expected = if round == 0 { 2 * proof.n_polys } else { 2 };
+				if values.len() != expected {
+					return Err(VerificationError::OpenedValuesSize {
+						round,
+						expected,
+						actual: values.len(),
+					}
+					.into());
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	fn verify_query(
+		&self,
+		round_caps: &[MerkleCap<H::Digest>],
+		challenges: &[FE],
+		mixing_coefficients: &[FE],
+		final_value: FE,
+		mut index: usize,
+		query_proof: Vec<(Vec<FE>, Vec<H::Digest>)>,
+	) -> Result<(), Error> {
+		let mut folded = FE::ZERO;
+		for (round, (values, vcs_proof)) in query_proof.into_iter().enumerate() {
+			// The value folded in the previous round sits at the position of the previous pair
+			let slot = index & 1;
+			if round > 0 {
+				index >>= 1;
+			}
+
+			self.round_vcs(round)
+				.verify_batch_opening(&round_caps[round], index, vcs_proof, values.iter().copied())
+				.map_err(|err| Error::VectorCommit(Box::new(err)))?;
+
+			let pair = if round == 0 {
+				let mix = |offset: usize| {
+					inner_product_unchecked(
+						values.iter().skip(offset).step_by(2).copied(),
+						mixing_coefficients.iter().copied(),
+					)
+				};
+				[mix(0), mix(1)]
+			} else {
+				if values[slot] != folded {
+					return Err(VerificationError::IncorrectFold { round }.into());
+				}
+				[values[0], values[1]]
+			};
+
+			folded =
+				fold_pair(self.fold_twiddle(round, index), pair[0], pair[1], challenges[round]);
+		}
+
+		if folded != final_value {
+			return Err(VerificationError::IncorrectFold { round: self.n_vars }.into());
+		}
+		Ok(())
+	}
+}
+
+/// Folds a pair of adjacent codeword values with a challenge.
+///
+/// The pair holds the evaluations of a polynomial $f(X) = f_0(q(X)) + X f_1(q(X))$ at two points
+/// $x$ and $x + 1$, where `twiddle` is $x$. Undoing the corresponding additive NTT butterfly
+/// recovers $f_0(q(x))$ and $f_1(q(x))$, which are then combined as
+/// $(1 - r) f_0(q(x)) + r f_1(q(x))$.
+fn fold_pair<FA, FE>(twiddle: FA, u0: FE, u1: FE, challenge: FE) -> FE
+where
+	FA: Field,
+	FE: ExtensionField<FA>,
+{
+	let odd = u0 + u1;
+	let even = u0 + odd * twiddle;
+	even + (odd - even) * challenge
+}
+
+/// Partially evaluates the multilinear with the given evaluations at its lowest variable.
+pub(super) fn fold_message<F: Field>(message: &[F], challenge: F) -> Vec<F> {
+	message
+		.par_chunks_exact(2)
+		.map(|pair| pair[0] + (pair[1] - pair[0]) * challenge)
+		.collect()
+}
+
+/// Splits a codeword into its even and odd positions, so that each vector commitment leaf holds a
+/// pair of values that are folded together.
+fn deinterleave<F: Field>(codeword: &[F]) -> [Vec<F>; 2] {
+	let even = codeword.iter().step_by(2).copied().collect();
+	let odd = codeword.iter().skip(1).step_by(2).copied().collect();
+	[even, odd]
+}
+
+/// Observes a digest as a sequence of extension field elements.
+fn observe_digest<FE, D, CH>(challenger: &mut CH, digest: &D)
+where
+	D: PackedField,
+	FE: ExtensionField<D::Scalar>,
+	CH: CanObserve<FE>,
+{
+	let scalars = digest.iter().collect::<Vec<_>>();
+	for chunk in scalars.chunks(FE::DEGREE) {
+		let elem = FE::from_bases(chunk).expect("chunk length is at most the extension degree");
+		challenger.observe(elem);
+	}
+}
+
+/// Calculates the number of test queries required to reach the security target.
+///
+/// The bound assumes the unique decoding regime, where a query rejects a codeword that is far
+/// from the code with probability at least $(1 - \rho) / 2$. It also checks that the soundness
+/// error of the folding rounds, which is proportional to the codeword length over the field size,
+/// is below the target.
+pub fn calculate_n_test_queries<FE: BinaryField>(
+	security_bits: usize,
+	n_vars: usize,
+	log_inv_rate: usize,
+) -> Result<usize, Error> {
+	if log_inv_rate == 0 {
+		return Err(Error::ParameterError);
+	}
+
+	let folding_err =
+		((n_vars + 1) << (n_vars + log_inv_rate)) as f64 / 2.0_f64.powi(FE::N_BITS as i32);
+	if -folding_err.log2() < security_bits as f64 {
+		return Err(Error::ParameterError);
+	}
+
+	let rate = 2.0_f64.powi(-(log_inv_rate as i32));
+	let per_query_err = (1.0 + rate) / 2.0;
+	Ok((-(security_bits as f64) / per_query_err.log2()).ceil() as usize)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::challenger::HashChallenger;
+	use assert_matches::assert_matches;
+	use binius_field::{
+		BinaryField128b, BinaryField16b, PackedBinaryField128x1b, PackedBinaryField16x8b,
+		PackedBinaryField1x128b,
+	};
+	use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+
+	fn commit_prove_verify<P>(n_vars: usize, batch_size: usize) -> Result<(), Error>
+	where
+		P: PackedField,
+		BinaryField128b: ExtensionField<P::Scalar>,
+	{
+		let pcs =
+			<FRIPCS<P, BinaryField16b, BinaryField128b, _, _>>::new_using_groestl_merkle_tree(
+				n_vars, 1, 32,
+			)
+			.unwrap();
+
+		let mut rng = StdRng::seed_from_u64(0);
+		let polys = repeat_with(|| {
+			let evals = repeat_with(|| P::random(&mut rng))
+				.take((1 << n_vars) / P::WIDTH)
+				.collect::<Vec<_>>();
+			MultilinearExtension::from_values(evals).unwrap()
+		})
+		.take(batch_size)
+		.collect::<Vec<_>>();
+
+		let (commitment, committed) = pcs.commit(&polys).unwrap();
+
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		challenger.observe(commitment);
+		let query = repeat_with(|| challenger.sample())
+			.take(pcs.n_vars())
+			.collect::<Vec<_>>();
+		let multilin_query =
+			MultilinearQuery::<PackedBinaryField1x128b>::with_full_query(&query).unwrap();
+
+		let values = polys
+			.iter()
+			.map(|poly| poly.evaluate(&multilin_query).unwrap())
+			.collect::<Vec<_>>();
+
+		let mut prove_challenger = challenger.clone();
+		let proof = pcs
+			.prove_evaluation(&mut prove_challenger, &committed, &polys, &query)
+			.unwrap();
+
+		let mut verify_challenger = challenger.clone();
+		pcs.verify_evaluation(&mut verify_challenger, &commitment, &query, proof, &values)
+	}
+
+	#[test]
+	fn test_packed_1b_commit_prove_verify_without_error() {
+		let batch_size = thread_rng().gen_range(1..=4);
+		commit_prove_verify::<PackedBinaryField128x1b>(8, batch_size).unwrap();
+	}
+
+	#[test]
+	fn test_packed_8b_commit_prove_verify_without_error() {
+		let batch_size = thread_rng().gen_range(1..=4);
+		commit_prove_verify::<PackedBinaryField16x8b>(6, batch_size).unwrap();
+	}
+
+	#[test]
+	fn test_verify_rejects_incorrect_proofs() {
+		type Packed = PackedBinaryField16x8b;
+
+		let n_vars = 6;
+		let pcs =
+			<FRIPCS<Packed, BinaryField16b, BinaryField128b, _, _>>::new_using_groestl_merkle_tree(
+				n_vars, 1, 16,
+			)
+			.unwrap();
+
+		let mut rng = StdRng::seed_from_u64(0);
+		let polys = repeat_with(|| {
+			let evals = repeat_with(|| Packed::random(&mut rng))
+				.take((1 << n_vars) / Packed::WIDTH)
+				.collect::<Vec<_>>();
+			MultilinearExtension::from_values(evals).unwrap()
+		})
+		.take(2)
+		.collect::<Vec<_>>();
+
+		let (commitment, committed) = pcs.commit(&polys).unwrap();
+
+		let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		let query = repeat_with(|| <BinaryField128b as Field>::random(&mut rng))
+			.take(n_vars)
+			.collect::<Vec<_>>();
+		let multilin_query =
+			MultilinearQuery::<PackedBinaryField1x128b>::with_full_query(&query).unwrap();
+		let values = polys
+			.iter()
+			.map(|poly| poly.evaluate(&multilin_query).unwrap())
+			.collect::<Vec<BinaryField128b>>();
+
+		let proof = pcs
+			.prove_evaluation(&mut challenger.clone(), &committed, &polys, &query)
+			.unwrap();
+
+		// Case: incorrect claimed evaluation
+		let mut incorrect_values = values.clone();
+		incorrect_values[1] += BinaryField128b::ONE;
+		assert_matches!(
+			pcs.verify_evaluation(
+				&mut challenger.clone(),
+				&commitment,
+				&query,
+				proof.clone(),
+				&incorrect_values
+			),
+			Err(Error::Verification(VerificationError::IncorrectSumcheckRound { round: 0 }))
+		);
+
+		// Case: final value inconsistent with the sumcheck
+		let mut corrupted_proof = proof.clone();
+		corrupted_proof.final_value += BinaryField128b::ONE;
+		assert_matches!(
+			pcs.verify_evaluation(
+				&mut challenger.clone(),
+				&commitment,
+				&query,
+				corrupted_proof,
+				&values
+			),
+			Err(Error::Verification(VerificationError::IncorrectEvaluation))
+		);
+
+		// Case: opened values that do not match the round commitment
+		let mut corrupted_proof = proof.clone();
+		corrupted_proof.query_proofs[0][1].0[0] += BinaryField128b::ONE;
+		assert_matches!(
+			pcs.verify_evaluation(
+				&mut challenger.clone(),
+				&commitment,
+				&query,
+				corrupted_proof,
+				&values
+			),
+			Err(Error::VectorCommit(_))
+		);
+
+		// Case: missing query
+		let mut corrupted_proof = proof.clone();
+		corrupted_proof.query_proofs.pop();
+		assert_matches!(
+			pcs.verify_evaluation(
+				&mut challenger.clone(),
+				&commitment,
+				&query,
+				corrupted_proof,
+				&values
+			),
+			Err(Error::Verification(VerificationError::NumberOfOpeningProofs { .. }))
+		);
+
+		pcs.verify_evaluation(&mut challenger.clone(), &commitment, &query, proof, &values)
+			.unwrap();
+	}
+
+	#[test]
+	fn test_calculate_n_test_queries() {
+		let n_test_queries = calculate_n_test_queries::<BinaryField128b>(100, 20, 1).unwrap();
+		assert_eq!(n_test_queries, 241);
+
+		assert_matches!(
+			calculate_n_test_queries::<BinaryField128b>(100, 20, 0),
+			Err(Error::ParameterError)
+		);
+		assert_matches!(
+			calculate_n_test_queries::<BinaryField16b>(100, 10, 1),
+			Err(Error::ParameterError)
+		);
+	}
+}
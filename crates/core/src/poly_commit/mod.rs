@@ -1,9 +1,15 @@
 // Copyright 2023 Ulvetanna Inc.
 
 mod error;
+pub mod fri_pcs;
+mod multi_round;
 mod pcs;
+pub mod ring_switch;
 pub mod tensor_pcs;
 
 pub use error::*;
+pub use fri_pcs::FRIPCS;
+pub use multi_round::*;
 pub use pcs::*;
+pub use ring_switch::RingSwitchPCS;
 pub use tensor_pcs::{BasicTensorPCS, BlockTensorPCS, TensorPCS};
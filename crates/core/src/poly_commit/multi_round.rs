@@ -0,0 +1,528 @@
+// Copyright 2024 Ulvetanna Inc.
+
+//! Commitment of polynomial batches over several rounds of interaction.
+//!
+//! Each committed batch belongs to the commitment round given by its
+//! [`CommittedBatchSpec::round_id`](crate::oracle::CommittedBatchSpec::round_id). The witness of a
+//! later round may depend on challenges sampled after the commitments of all earlier rounds have
+//! been observed, as is the case for lookups and grand products. The prover commits the rounds
+//! in order with a [`MultiRoundProver`], and the verifier observes the commitments in the same
+//! order with a [`MultiRoundVerifier`]. Once the evalcheck reduction yields one same query claim
+//! per batch, both sides run the PCS openings in the order of those claims.
+//!
+//! When the constraint system has fixed columns, round 0 is the precommitment produced by
+//! [`setup`](crate::setup::setup). The prover and verifier are then created from the proving and
+//! verifying keys, which observe the fixed commitment in place of committing round 0.
+
+use super::{error::Error, PolyCommitScheme};
+use crate::{
+	oracle::{BatchId, CommittedBatch, MultilinearOracleSet, RoundId},
+	polynomial::MultilinearExtension,
+	protocols::evalcheck::SameQueryPcsClaim,
+	setup::{ProvingKey, VerifyingKey},
+};
+use binius_field::{ExtensionField, PackedField, TowerField};
+use p3_challenger::{CanObserve, CanSample, CanSampleBits};
+use std::{iter, marker::PhantomData};
+
+/// Prover state for committing batches over several rounds and opening them.
+///
+/// All batches are committed with the same polynomial commitment scheme.
+pub struct MultiRoundProver<'a, 'b, P, FE, PCS>
+where
+	P: PackedField,
+	FE: ExtensionField<P::Scalar>,
+	PCS: PolyCommitScheme<P, FE>,
+{
+	pcs: &'a PCS,
+	batches: Vec<CommittedBatch>,
+	next_round: RoundId,
+	#[allow(clippy::type_complexity)]
+	committed: Vec<Option<(PCS::Committed, Vec<MultilinearExtension<'b, P>>)>>,
+	proving_key: Option<&'a ProvingKey<P, FE, PCS>>,
+	_fe_marker: PhantomData<FE>,
+}
+
+impl<'a, 'b, P, FE, PCS> MultiRoundProver<'a, 'b, P, FE, PCS>
+where
+	P: PackedField,
+	FE: ExtensionField<P::Scalar>,
+	PCS: PolyCommitScheme<P, FE>,
+{
+	pub fn new<F: TowerField>(pcs: &'a PCS, oracles: &MultilinearOracleSet<F>) -> Self {
+		let batches = oracles.committed_batches();
+		let committed = batches.iter().map(|_| None).collect();
+		Self {
+			pcs,
+			batches,
+			next_round: 0,
+			committed,
+			proving_key: None,
+			_fe_marker: PhantomData,
+		}
+	}
+
+	/// Creates a prover whose round 0 is the precommitted fixed batch of a proving key.
+	///
+	/// The fixed commitment is observed, and the next round to commit is round 1.
+	pub fn with_proving_key<F, CH>(
+		pcs: &'a PCS,
+		oracles: &MultilinearOracleSet<F>,
+		proving_key: &'a ProvingKey<P, FE, PCS>,
+		challenger: &mut CH,
+	) -> Result<Self, Error>
+	where
+		F: TowerField,
+		CH: CanObserve<PCS::Commitment>,
+	{
+		let verifying_key = proving_key.verifying_key();
+		verifying_key.check_oracles(oracles)?;
+		challenger.observe(verifying_key.fixed_commitment.clone());
+
+		let mut prover = Self::new(pcs, oracles);
+		prover.next_round = 1;
+		prover.proving_key = Some(proving_key);
+		Ok(prover)
+	}
+
+	/// Commits to all batches of a round and observes the commitments in batch ID order.
+	///
+	/// `batch_polys` holds the polynomials of every batch in the round, in batch ID order. Rounds
+	/// must be committed in increasing order, and only rounds without any batches may be skipped.
+	pub fn commit_round<CH>(
+		&mut self,
+		challenger: &mut CH,
+		round_id: RoundId,
+		batch_polys: Vec<Vec<MultilinearExtension<'b, P>>>,
+	) -> Result<Vec<PCS::Commitment>, Error>
+	where
+		CH: CanObserve<PCS::Commitment>,
+	{
+		let batch_ids =
+			round_batch_ids(&self.batches, self.next_round, round_id, batch_polys.len())?;
+		for (&batch_id, polys) in iter::zip(&batch_ids, &batch_polys) {
+			let n_polys = self.batches[batch_id].n_polys;
+			if polys.len() != n_polys {
+				return Err(Error::NumBatchedMismatchError {
+					err_str: format!(
+						"In commit_round: batch {} has {} polynomials, got {}",
+						batch_id,
+						n_polys,
+						polys.len()
+					),
+				});
+			}
+		}
+
+		let round_committed = batch_polys
+			.iter()
+			.map(|polys| self.pcs.commit(polys))
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(|err| Error::PolyCommit(Box::new(err)))?;
+
+		// The round is only recorded once every batch of it has been committed
+		let mut commitments = Vec::with_capacity(batch_ids.len());
+		for ((batch_id, polys), (commitment, committed)) in
+			iter::zip(iter::zip(batch_ids, batch_polys), round_committed)
+		{
+			challenger.observe(commitment.clone());
+			commitments.push(commitment);
+			self.committed[batch_id] = Some((committed, polys));
+		}
+		self.next_round = round_id + 1;
+		Ok(commitments)
+	}
+
+	/// Proves the openings of the committed batches at their same query claims.
+	///
+	/// The claims should be in commitment round order, as returned by
+	/// [`greedy_evalcheck::prove`](crate::protocols::greedy_evalcheck::prove).
+	pub fn prove_openings<CH>(
+		&self,
+		challenger: &mut CH,
+		same_query_claims: &[(BatchId, SameQueryPcsClaim<FE>)],
+	) -> Result<Vec<PCS::Proof>, Error>
+	where
+		CH: CanObserve<FE> + CanSample<FE> + CanSampleBits<usize>,
+	{
+		same_query_claims
+			.iter()
+			.map(|(batch_id, claim)| {
+				let proof = match self.proving_key {
+					Some(proving_key)
+						if proving_key.verifying_key().fixed_batch_id == *batch_id =>
+					{
+						self.pcs.prove_evaluation(
+							challenger,
+							proving_key.fixed_committed(),
+							proving_key.fixed_columns(),
+							&claim.eval_point,
+						)
+					}
+					_ => {
+						let (committed, polys) = self
+							.committed
+							.get(*batch_id)
+							.and_then(Option::as_ref)
+							.ok_or(Error::UncommittedBatch {
+								batch_id: *batch_id,
+							})?;
+						self.pcs
+							.prove_evaluation(challenger, committed, polys, &claim.eval_point)
+					}
+				};
+				proof.map_err(|err| Error::PolyCommit(Box::new(err)))
+			})
+			.collect()
+	}
+}
+
+/// Verifier state for observing batch commitments over several rounds and checking openings.
+///
+/// Counterpart of [`MultiRoundProver`].
+pub struct MultiRoundVerifier<'a, P, FE, PCS>
+where
+	P: PackedField,
+	FE: ExtensionField<P::Scalar>,
+	PCS: PolyCommitScheme<P, FE>,
+{
+	pcs: &'a PCS,
+	batches: Vec<CommittedBatch>,
+	next_round: RoundId,
+	commitments: Vec<Option<PCS::Commitment>>,
+	_p_marker: PhantomData<P>,
+	_fe_marker: PhantomData<FE>,
+}
+
+impl<'a, P, FE, PCS> MultiRoundVerifier<'a, P, FE, PCS>
+where
+	P: PackedField,
+	FE: ExtensionField<P::Scalar>,
+	PCS: PolyCommitScheme<P, FE>,
+{
+	pub fn new<F: TowerField>(pcs: &'a PCS, oracles: &MultilinearOracleSet<F>) -> Self {
+		let batches = oracles.committed_batches();
+		let commitments = vec![None; batches.len()];
+		Self {
+			pcs,
+			batches,
+			next_round: 0,
+			commitments,
+			_p_marker: PhantomData,
+			_fe_marker: PhantomData,
+		}
+	}
+
+	/// Creates a verifier whose round 0 is the precommitted fixed batch of a verifying key.
+	///
+	/// Counterpart of [`MultiRoundProver::with_proving_key`].
+	pub fn with_verifying_key<F, CH>(
+		pcs: &'a PCS,
+		oracles: &MultilinearOracleSet<F>,
+		verifying_key: &VerifyingKey<PCS::Commitment>,
+		challenger: &mut CH,
+	) -> Result<Self, Error>
+	where
+		F: TowerField,
+		CH: CanObserve<PCS::Commitment>,
+	{
+		verifying_key.check_oracles(oracles)?;
+		challenger.observe(verifying_key.fixed_commitment.clone());
+
+		let mut verifier = Self::new(pcs, oracles);
+		verifier.next_round = 1;
+		verifier.commitments[verifying_key.fixed_batch_id] =
+			Some(verifying_key.fixed_commitment.clone());
+		Ok(verifier)
+	}
+
+	/// Observes the commitments to all batches of a round, in batch ID order.
+	pub fn observe_round<CH>(
+		&mut self,
+		challenger: &mut CH,
+		round_id: RoundId,
+		commitments: Vec<PCS::Commitment>,
+	) -> Result<(), Error>
+	where
+		CH: CanObserve<PCS::Commitment>,
+	{
+		let batch_ids =
+			round_batch_ids(&self.batches, self.next_round, round_id, commitments.len())?;
+
+		for (batch_id, commitment) in batch_ids.into_iter().zip(commitments) {
+			challenger.observe(commitment.clone());
+			self.commitments[batch_id] = Some(commitment);
+		}
+		self.next_round = round_id + 1;
+		Ok(())
+	}
+
+	/// Verifies the openings of the committed batches at their same query claims.
+	pub fn verify_openings<CH>(
+		&self,
+		challenger: &mut CH,
+		same_query_claims: &[(BatchId, SameQueryPcsClaim<FE>)],
+		proofs: Vec<PCS::Proof>,
+	) -> Result<(), Error>
+	where
+		CH: CanObserve<FE> + CanSample<FE> + CanSampleBits<usize>,
+	{
+		if proofs.len() != same_query_claims.len() {
+			return Err(Error::NumBatchedMismatchError {
+				err_str: format!(
+					"In verify_openings: number of opening proofs {} must match number of same \
+					query claims {}",
+					proofs.len(),
+					same_query_claims.len()
+				),
+			});
+		}
+
+		for ((batch_id, claim), proof) in same_query_claims.iter().zip(proofs) {
+			let commitment = self
+				.commitments
+				.get(*batch_id)
+				.and_then(Option::as_ref)
+				.ok_or(Error::UncommittedBatch {
+					batch_id: *batch_id,
+				})?;
+			self.pcs
+				.verify_evaluation(challenger, commitment, &claim.eval_point, proof, &claim.evals)
+				.map_err(|err| Error::PolyCommit(Box::new(err)))?;
+		}
+		Ok(())
+	}
+}
+
+/// Returns the IDs of the batches committed in a round, checking that the round can be committed
+/// next and that the expected number of batches is given.
+fn round_batch_ids(
+	batches: &[CommittedBatch],
+	next_round: RoundId,
+	round_id: RoundId,
+	n_batches: usize,
+) -> Result<Vec<BatchId>, Error> {
+	if round_id < next_round {
+		return Err(Error::RoundOutOfOrder {
+			round_id,
+			next_round,
+		});
+	}
+
+	if let Some(skipped) = batches
+		.iter()
+		.map(|batch| batch.round_id)
+		.filter(|&batch_round_id| (next_round..round_id).contains(&batch_round_id))
+		.min()
+	{
+		return Err(Error::SkippedRound { round_id: skipped });
+	}
+
+	let batch_ids = batches
+		.iter()
+		.filter(|batch| batch.round_id == round_id)
+		.map(|batch| batch.id)
+		.collect::<Vec<_>>();
+	if batch_ids.len() != n_batches {
+		return Err(Error::IncorrectNumberOfBatches {
+			round_id,
+			expected: batch_ids.len(),
+		});
+	}
+	Ok(batch_ids)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		challenger::HashChallenger,
+		oracle::{CommittedBatchSpec, CommittedId, CompositePolyOracle},
+		poly_commit::FRIPCS,
+		polynomial::{composition::BivariateProduct, MultilinearQuery},
+		protocols::{evalcheck::EvalcheckClaim, greedy_evalcheck},
+		witness::MultilinearWitnessIndex,
+	};
+	use assert_matches::assert_matches;
+	use binius_field::{BinaryField128b, BinaryField16b, PackedBinaryField1x128b};
+	use binius_hash::{GroestlDigestCompression, GroestlHasher};
+	use rand::{rngs::StdRng, SeedableRng};
+	use std::iter::repeat_with;
+
+	type F = BinaryField128b;
+	type P = PackedBinaryField1x128b;
+	type TestPCS = FRIPCS<P, BinaryField16b, F, GroestlHasher<F>, GroestlDigestCompression>;
+
+	const N_VARS: usize = 6;
+
+	/// An oracle set with one batch committed in round 0 and one committed in round 1.
+	fn make_oracles() -> (MultilinearOracleSet<F>, BatchId, BatchId) {
+		let mut oracles = MultilinearOracleSet::new();
+		let batch_1 = oracles.add_committed_batch(CommittedBatchSpec {
+			round_id: 1,
+			n_vars: N_VARS,
+			n_polys: 1,
+			tower_level: F::TOWER_LEVEL,
+		});
+		let batch_0 = oracles.add_committed_batch(CommittedBatchSpec {
+			round_id: 0,
+			n_vars: N_VARS,
+			n_polys: 1,
+			tower_level: F::TOWER_LEVEL,
+		});
+		(oracles, batch_0, batch_1)
+	}
+
+	fn make_claim(
+		oracles: &MultilinearOracleSet<F>,
+		batch_0: BatchId,
+		batch_1: BatchId,
+		eval_point: Vec<F>,
+		eval: F,
+	) -> EvalcheckClaim<F> {
+		let f = oracles.committed_oracle(CommittedId {
+			batch_id: batch_0,
+			index: 0,
+		});
+		let g = oracles.committed_oracle(CommittedId {
+			batch_id: batch_1,
+			index: 0,
+		});
+		EvalcheckClaim {
+			poly: CompositePolyOracle::new(N_VARS, vec![f, g], BivariateProduct).unwrap(),
+			eval_point,
+			eval,
+			is_random_point: true,
+		}
+	}
+
+	#[test]
+	fn test_commit_prove_verify_two_rounds() {
+		let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+		let mut rng = StdRng::seed_from_u64(0);
+
+		let (mut oracles, batch_0, batch_1) = make_oracles();
+		assert_eq!(oracles.n_rounds(), 2);
+		assert_eq!(oracles.committed_batches_in_round(0)[0].id, batch_0);
+
+		let f = MultilinearExtension::from_values(
+			repeat_with(|| <P as PackedField>::random(&mut rng))
+				.take(1 << N_VARS)
+				.collect(),
+		)
+		.unwrap();
+
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		let mut prover = MultiRoundProver::new(&pcs, &oracles);
+		let commitments_0 = prover
+			.commit_round(&mut challenger, 0, vec![vec![f.to_ref()]])
+			.unwrap();
+
+		// The second round witness depends on a challenge sampled after the first commitment
+		let alpha: F = challenger.sample();
+		let g = MultilinearExtension::from_values(f.evals().iter().map(|&x| x * alpha).collect())
+			.unwrap();
+		let commitments_1 = prover
+			.commit_round(&mut challenger, 1, vec![vec![g.clone()]])
+			.unwrap();
+
+		let eval_point = challenger.sample_vec(N_VARS);
+		let query = MultilinearQuery::<P>::with_full_query(&eval_point).unwrap();
+		let eval = f.evaluate::<F, _>(&query).unwrap() * g.evaluate::<F, _>(&query).unwrap();
+
+		let mut witness_index = MultilinearWitnessIndex::<F>::new();
+		for (batch_id, poly) in [(batch_0, f.to_ref()), (batch_1, g.clone())] {
+			let oracle_id = oracles.committed_oracle_id(CommittedId { batch_id, index: 0 });
+			witness_index.set(oracle_id, poly.specialize_arc_dyn());
+		}
+
+		let claim = make_claim(&oracles, batch_0, batch_1, eval_point.clone(), eval);
+		let output = greedy_evalcheck::prove(
+			&mut oracles,
+			&mut witness_index,
+			[claim],
+			|_| 1,
+			&mut challenger,
+		)
+		.unwrap();
+
+		// Openings follow the commitment round order, not the batch ID order
+		let opened_batches = output
+			.same_query_claims
+			.iter()
+			.map(|(batch_id, _)| *batch_id)
+			.collect::<Vec<_>>();
+		assert_eq!(opened_batches, vec![batch_0, batch_1]);
+
+		let opening_proofs = prover
+			.prove_openings(&mut challenger, &output.same_query_claims)
+			.unwrap();
+
+		let (mut oracles, batch_0, batch_1) = make_oracles();
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		let mut verifier = MultiRoundVerifier::new(&pcs, &oracles);
+		verifier
+			.observe_round(&mut challenger, 0, commitments_0)
+			.unwrap();
+		let _alpha: F = challenger.sample();
+		verifier
+			.observe_round(&mut challenger, 1, commitments_1)
+			.unwrap();
+
+		let verifier_eval_point = challenger.sample_vec(N_VARS);
+		assert_eq!(verifier_eval_point, eval_point);
+
+		let claim = make_claim(&oracles, batch_0, batch_1, verifier_eval_point, eval);
+		let same_query_claims =
+			greedy_evalcheck::verify(&mut oracles, [claim], output.proof, &mut challenger).unwrap();
+		verifier
+			.verify_openings(&mut challenger, &same_query_claims, opening_proofs)
+			.unwrap();
+	}
+
+	#[test]
+	fn test_rounds_must_be_committed_in_order() {
+		let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+		let (oracles, _, batch_1) = make_oracles();
+		let poly = MultilinearExtension::<P>::zeros(N_VARS).unwrap();
+
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		let mut prover = MultiRoundProver::new(&pcs, &oracles);
+		assert_matches!(
+			prover.commit_round(&mut challenger, 1, vec![vec![poly.clone()]]),
+			Err(Error::SkippedRound { round_id: 0 })
+		);
+		assert_matches!(
+			prover.commit_round(&mut challenger, 0, vec![]),
+			Err(Error::IncorrectNumberOfBatches {
+				round_id: 0,
+				expected: 1
+			})
+		);
+
+		// A round that fails to commit can be committed again
+		assert_matches!(
+			prover.commit_round(&mut challenger, 0, vec![vec![poly.clone(), poly.clone()]]),
+			Err(Error::NumBatchedMismatchError { .. })
+		);
+		prover
+			.commit_round(&mut challenger, 0, vec![vec![poly.clone()]])
+			.unwrap();
+		assert_matches!(
+			prover.commit_round(&mut challenger, 0, vec![vec![poly.clone()]]),
+			Err(Error::RoundOutOfOrder {
+				round_id: 0,
+				next_round: 1
+			})
+		);
+
+		let claim = SameQueryPcsClaim {
+			eval_point: vec![F::ZERO; N_VARS],
+			evals: vec![F::ZERO],
+		};
+		assert_matches!(
+			prover.prove_openings(&mut challenger, &[(batch_1, claim)]),
+			Err(Error::UncommittedBatch { batch_id }) if batch_id == batch_1
+		);
+	}
+}
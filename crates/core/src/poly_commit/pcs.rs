@@ -1,6 +1,10 @@
 // Copyright 2023 Ulvetanna Inc.
 
-use crate::polynomial::MultilinearExtension;
+use crate::{
+	polynomial::MultilinearExtension,
+	serialization::{DeserializeCanonical, Error as SerializationError, SerializeCanonical},
+	transcript::{ProverTranscript, VerifierTranscript},
+};
 use binius_field::{ExtensionField, PackedField};
 use p3_challenger::{CanObserve, CanSample, CanSampleBits};
 
@@ -45,6 +49,45 @@ where
 	where
 		CH: CanObserve<FE> + CanSample<FE> + CanSampleBits<usize>;
 
+	/// Generate an evaluation proof at a *random* challenge point, writing it to a transcript.
+	///
+	/// The challenges are sampled from the transcript as in [`Self::prove_evaluation`], and the
+	/// proof is appended as a decommitment once all of them have been drawn.
+	fn prove_evaluation_with_transcript<CH>(
+		&self,
+		transcript: &mut ProverTranscript<CH>,
+		committed: &Self::Committed,
+		polys: &[MultilinearExtension<P>],
+		query: &[FE],
+	) -> Result<(), Self::Error>
+	where
+		CH: CanObserve<FE> + CanSample<FE> + CanSampleBits<usize>,
+		Self::Proof: SerializeCanonical,
+	{
+		let proof = self.prove_evaluation(transcript, committed, polys, query)?;
+		transcript.write_decommitment(&proof);
+		Ok(())
+	}
+
+	/// Verify an evaluation proof at a *random* challenge point, reading it from a transcript.
+	///
+	/// Counterpart of [`Self::prove_evaluation_with_transcript`].
+	fn verify_evaluation_with_transcript<CH>(
+		&self,
+		transcript: &mut VerifierTranscript<'_, CH>,
+		commitment: &Self::Commitment,
+		query: &[FE],
+		values: &[FE],
+	) -> Result<(), Self::Error>
+	where
+		CH: CanObserve<FE> + CanSample<FE> + CanSampleBits<usize>,
+		Self::Proof: DeserializeCanonical,
+		Self::Error: From<SerializationError>,
+	{
+		let proof = transcript.read_decommitment()?;
+		self.verify_evaluation(transcript, commitment, query, proof, values)
+	}
+
 	/// Return the byte-size of a proof.
 	fn proof_size(&self, n_polys: usize) -> usize;
 }
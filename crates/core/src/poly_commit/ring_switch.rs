@@ -0,0 +1,711 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	error::{Error, VerificationError},
+	fri_pcs::fold_message,
+};
+use crate::{
+	poly_commit::PolyCommitScheme,
+	polynomial::{
+		multilinear_query::MultilinearQuery, Error as PolynomialError, MultilinearExtension,
+	},
+};
+use binius_field::{
+	packed::iter_packed_slice,
+	util::{inner_product_par, inner_product_unchecked},
+	ExtensionField, Field, PackedExtensionField, PackedField,
+};
+use p3_challenger::{CanObserve, CanSample, CanSampleBits};
+use p3_util::{log2_ceil_usize, log2_strict_usize};
+use rayon::prelude::*;
+use std::{marker::PhantomData, mem};
+
+/// Evaluation proof data for the `RingSwitchPCS` polynomial commitment scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof<F, InnerProof> {
+	/// The partial evaluations of every committed polynomial at the high query coordinates
+	///
+	/// For each polynomial there is one value for every assignment of the variables that are
+	/// packed into a single extension field element.
+	pub partial_evals: Vec<Vec<F>>,
+	/// The sumcheck round messages $(g_i(0), g_i(\infty))$, one for each packed variable
+	///
+	/// $g_i(\infty)$ is the leading coefficient of the quadratic round polynomial $g_i$. The
+	/// verifier recovers $g_i(1)$ from the current sum.
+	pub sumcheck_rounds: Vec<(F, F)>,
+	/// The evaluations of the packed polynomials at the sumcheck challenge point
+	pub packed_evals: Vec<F>,
+	/// The evaluation proof of the inner scheme for the packed polynomials
+	pub inner_proof: InnerProof,
+}
+
+/// A polynomial commitment scheme for multilinears over a small tower field, built from a
+/// scheme over an extension field by packing and ring-switching, following Section 3 of [DP24].
+///
+/// Every $2^\kappa$ consecutive hypercube evaluations of a committed polynomial over the small
+/// field $K$ are packed into one element of the extension $L$ of degree $2^\kappa$, and the
+/// resulting multilinear over $L$ with $\kappa$ fewer variables is committed with the inner
+/// scheme. For a polynomial over [`BinaryField1b`](binius_field::BinaryField1b) packed into
+/// [`BinaryField128b`](binius_field::BinaryField128b), this shrinks the committed data by a
+/// factor of 128 compared to committing each bit as an extension field element.
+///
+/// An evaluation claim on the small field polynomial is reduced to an evaluation claim on the
+/// packed polynomial with a sumcheck over the packed variables. The verifier evaluates the
+/// sumcheck's transparent factor in the tensor algebra $L \otimes_K L$.
+///
+/// # Type Parameters
+///
+/// * `P`: The packed small field type of committed elements.
+/// * `PE`: The packed extension field type that the small field elements are packed into. Its
+///   scalar type is also the field used for cryptographic challenges.
+/// * `Inner`: The polynomial commitment scheme for the packed polynomials.
+///
+/// [DP24]: <https://eprint.iacr.org/2024/504>
+#[derive(Debug)]
+pub struct RingSwitchPCS<P, PE, Inner> {
+	inner: Inner,
+	log_degree: usize,
+	_p_marker: PhantomData<P>,
+	_pe_marker: PhantomData<PE>,
+}
+
+impl<F, P, FE, PE, Inner> RingSwitchPCS<P, PE, Inner>
+where
+	F: Field,
+	P: PackedField<Scalar = F>,
+	FE: ExtensionField<F>,
+	PE: PackedExtensionField<P> + PackedField<Scalar = FE>,
+	Inner: PolyCommitScheme<PE, FE>,
+{
+	pub fn new(inner: Inner) -> Result<Self, Error> {
+		let degree = <FE as ExtensionField<F>>::DEGREE;
+		if !degree.is_power_of_two() {
+			return Err(Error::ExtensionDegreePowerOfTwoRequired);
+		}
+
+		Ok(Self {
+			inner,
+			log_degree: log2_strict_usize(degree),
+			_p_marker: PhantomData,
+			_pe_marker: PhantomData,
+		})
+	}
+
+	/// The commitment scheme for the packed polynomials.
+	pub fn inner(&self) -> &Inner {
+		&self.inner
+	}
+
+	/// Reinterprets small field polynomials as multilinears over the extension field.
+	fn pack_polys<'a>(
+		&self,
+		polys: &'a [MultilinearExtension<P>],
+	) -> Result<Vec<MultilinearExtension<'a, PE>>, Error> {
+		polys
+			.iter()
+			.map(|poly| {
+				if poly.n_vars() != self.n_vars() {
+					return Err(Error::IncorrectPolynomialSize {
+						expected: self.n_vars(),
+					});
+				}
+				let packed = PE::try_cast_to_ext(poly.evals()).ok_or(Error::UnalignedMessage)?;
+				Ok(MultilinearExtension::from_values_slice(packed)?)
+			})
+			.collect()
+	}
+}
+
+impl<F, P, FE, PE, Inner> PolyCommitScheme<P, FE> for RingSwitchPCS<P, PE, Inner>
+where
+	F: Field,
+	P: PackedField<Scalar = F>,
+	FE: ExtensionField<F>,
+	PE: PackedExtensionField<P> + PackedField<Scalar = FE>,
+	Inner: PolyCommitScheme<PE, FE>,
+{
+	type Commitment = Inner::Commitment;
+	type Committed = Inner::Committed;
+	type Proof = Proof<FE, Inner::Proof>;
+	type Error = Error;
+
+	fn n_vars(&self) -> usize {
+		self.inner.n_vars() + self.log_degree
+	}
+
+	fn commit(
+		&self,
+		polys: &[MultilinearExtension<P>],
+	) -> Result<(Self::Commitment, Self::Committed), Error> {
+		let packed_polys = self.pack_polys(polys)?;
+		self.inner
+			.commit(&packed_polys)
+			.map_err(|err| Error::PolyCommit(Box::new(err)))
+	}
+
+	/// Generate an evaluation proof at a *random* challenge point.
+	///
+	/// Precondition: The queried point must already be observed by the challenger.
+	fn prove_evaluation<CH>(
+		&self,
+		challenger: &mut CH,
+		committed: &Self::Committed,
+		polys: &[MultilinearExtension<P>],
+		query: &[FE],
+	) -> Result<Self::Proof, Error>
+	where
+		CH: CanObserve<FE> + CanSample<FE> + CanSampleBits<usize>,
+	{
+		if query.len() != self.n_vars() {
+			return Err(PolynomialError::IncorrectQuerySize {
+				expected: self.n_vars(),
+			}
+			.into());
+		}
+
+		let packed_polys = self.pack_polys(polys)?;
+		let packed_messages = packed_polys
+			.iter()
+			.map(|poly| iter_packed_slice(poly.evals()).collect::<Vec<_>>())
+			.collect::<Vec<_>>();
+
+		let (_, query_high) = query.split_at(self.log_degree);
+		let eq_ind_high = MultilinearQuery::<FE>::with_full_query(query_high)?.into_expansion();
+
+		let partial_evals = packed_messages
+			.iter()
+			.map(|message| compute_partial_evals::<F, _>(message, &eq_ind_high))
+			.collect::<Vec<_>>();
+		for evals in partial_evals.iter() {
+			challenger.observe_slice(evals);
+		}
+
+		let (batch_coeffs, mixing_coeffs) =
+			self.sample_batching_coeffs(challenger, packed_polys.len())?;
+
+		// The transparent factor of the sumcheck, batching the components of the equality
+		// indicator over the small field
+		let mut transparent = eq_ind_high
+			.par_iter()
+			.map(|eq| inner_product_unchecked(batch_coeffs.iter().copied(), eq.iter_bases()))
+			.collect::<Vec<_>>();
+
+		let mut mixed_message = vec![FE::ZERO; 1 << self.inner.n_vars()];
+		for (message, &coeff) in packed_messages.iter().zip(mixing_coeffs.iter()) {
+			mixed_message
+				.par_iter_mut()
+				.zip(message.par_iter())
+				.for_each(|(m_i, &t_i)| *m_i += coeff * t_i);
+		}
+
+		let mut sumcheck_rounds = Vec::with_capacity(self.inner.n_vars());
+		let mut challenges = Vec::with_capacity(self.inner.n_vars());
+		for _ in 0..self.inner.n_vars() {
+			let (eval_0, eval_inf) = transparent
+				.par_chunks_exact(2)
+				.zip(mixed_message.par_chunks_exact(2))
+				.map(|(a, t)| (a[0] * t[0], (a[1] - a[0]) * (t[1] - t[0])))
+				.reduce(|| (FE::ZERO, FE::ZERO), |(a0, b0), (a1, b1)| (a0 + a1, b0 + b1));
+
+			challenger.observe_slice(&[eval_0, eval_inf]);
+			sumcheck_rounds.push((eval_0, eval_inf));
+
+			let challenge: FE = challenger.sample();
+			transparent = fold_message(&transparent, challenge);
+			mixed_message = fold_message(&mixed_message, challenge);
+			challenges.push(challenge);
+		}
+
+		let eq_ind_challenges =
+			MultilinearQuery::<FE>::with_full_query(&challenges)?.into_expansion();
+		let packed_evals = packed_messages
+			.iter()
+			.map(|message| inner_product_par(message, &eq_ind_challenges))
+			.collect::<Vec<_>>();
+		challenger.observe_slice(&packed_evals);
+
+		let inner_proof = self
+			.inner
+			.prove_evaluation(challenger, committed, &packed_polys, &challenges)
+			.map_err(|err| Error::PolyCommit(Box::new(err)))?;
+
+		Ok(Proof {
+			partial_evals,
+			sumcheck_rounds,
+			packed_evals,
+			inner_proof,
+		})
+	}
+
+	/// Verify an evaluation proof at a *random* challenge point.
+	///
+	/// Precondition: The queried point must already be observed by the challenger.
+	fn verify_evaluation<CH>(
+		&self,
+		challenger: &mut CH,
+		commitment: &Self::Commitment,
+		query: &[FE],
+		proof: Self::Proof,
+		values: &[FE],
+	) -> Result<(), Error>
+	where
+		CH: CanObserve<FE> + CanSample<FE> + CanSampleBits<usize>,
+	{
+		if query.len() != self.n_vars() {
+			return Err(PolynomialError::IncorrectQuerySize {
+				expected: self.n_vars(),
+			}
+			.into());
+		}
+
+		self.check_proof_shape(&proof, values.len())?;
+
+		let Proof {
+			partial_evals,
+			sumcheck_rounds,
+			packed_evals,
+			inner_proof,
+		} = proof;
+
+		// The partial evaluations must be consistent with the claimed evaluations
+		let (query_low, query_high) = query.split_at(self.log_degree);
+		let eq_ind_low = MultilinearQuery::<FE>::with_full_query(query_low)?.into_expansion();
+		for (evals, &value) in partial_evals.iter().zip(values.iter()) {
+			if inner_product_unchecked(evals.iter().copied(), eq_ind_low.iter().copied()) != value {
+				return Err(VerificationError::IncorrectPartialEvaluation.into());
+			}
+		}
+
+		for evals in partial_evals.iter() {
+			challenger.observe_slice(evals);
+		}
+
+		let (batch_coeffs, mixing_coeffs) =
+			self.sample_batching_coeffs(challenger, partial_evals.len())?;
+
+		let mut sum = partial_evals
+			.iter()
+			.zip(mixing_coeffs.iter())
+			.map(|(evals, &coeff)| coeff * transpose_and_batch::<F, _>(evals, &batch_coeffs))
+			.sum::<FE>();
+
+		let mut challenges = Vec::with_capacity(self.inner.n_vars());
+		for (eval_0, eval_inf) in sumcheck_rounds {
+			let eval_1 = sum - eval_0;
+			challenger.observe_slice(&[eval_0, eval_inf]);
+			let challenge: FE = challenger.sample();
+			sum = eval_0
+				+ (eval_1 - eval_0) * challenge
+				+ eval_inf * challenge * (challenge - FE::ONE);
+			challenges.push(challenge);
+		}
+
+		challenger.observe_slice(&packed_evals);
+
+		let transparent_eval = inner_product_unchecked(
+			batch_coeffs.iter().copied(),
+			tensor_eq_ind::<F, _>(query_high, &challenges).into_iter(),
+		);
+		let mixed_eval =
+			inner_product_unchecked(packed_evals.iter().copied(), mixing_coeffs.iter().copied());
+		if sum != transparent_eval * mixed_eval {
+			return Err(VerificationError::IncorrectEvaluation.into());
+		}
+
+		self.inner
+			.verify_evaluation(challenger, commitment, &challenges, inner_proof, &packed_evals)
+			.map_err(|err| Error::PolyCommit(Box::new(err)))
+	}
+
+	fn proof_size(&self, n_polys: usize) -> usize {
+		let fe_size = mem::size_of::<FE>();
+		let partial_evals_size = n_polys * (1 << self.log_degree) * fe_size;
+		let sumcheck_size = 2 * self.inner.n_vars() * fe_size;
+		partial_evals_size + sumcheck_size + n_polys * fe_size + self.inner.proof_size(n_polys)
+	}
+}
+
+// Helper functions for PolyCommitScheme implementation.
+impl<F, P, FE, PE, Inner> RingSwitchPCS<P, PE, Inner>
+where
+	F: Field,
+	P: PackedField<Scalar = F>,
+	FE: ExtensionField<F>,
+	PE: PackedExtensionField<P> + PackedField<Scalar = FE>,
+	Inner: PolyCommitScheme<PE, FE>,
+{
+	/// Samples the coefficients that batch the tensor algebra columns and the polynomials.
+	fn sample_batching_coeffs<CH>(
+		&self,
+		challenger: &mut CH,
+		n_polys: usize,
+	) -> Result<(Vec<FE>, Vec<FE>), Error>
+	where
+		CH: CanSample<FE>,
+	{
+		let batch_challenges = challenger.sample_vec(self.log_degree);
+		let batch_coeffs =
+			MultilinearQuery::<FE>::with_full_query(&batch_challenges)?.into_expansion();
+
+		let mixing_challenges = challenger.sample_vec(log2_ceil_usize(n_polys));
+		let mut mixing_coeffs =
+			MultilinearQuery::<FE>::with_full_query(&mixing_challenges)?.into_expansion();
+		mixing_coeffs.truncate(n_polys);
+
+		Ok((batch_coeffs, mixing_coeffs))
+	}
+
+	fn check_proof_shape(
+		&self,
+		proof: &Proof<FE, Inner::Proof>,
+		n_polys: usize,
+	) -> Result<(), Error> {
+		if proof.partial_evals.len() != n_polys || proof.packed_evals.len() != n_polys {
+			return Err(Error::NumBatchedMismatchError {
+				err_str: format!(
+					"In verify_evaluation: number of opened values {} must match the number of \
+					partial evaluations {} and packed evaluations {}",
+					n_polys,
+					proof.partial_evals.len(),
+					proof.packed_evals.len()
+				),
+			});
+		}
+
+		for (poly_index, evals) in proof.partial_evals.iter().enumerate() {
+			if evals.len() != 1 << self.log_degree {
+				return Err(VerificationError::PartialEvaluationsSize {
+					poly_index,
+					expected: 1 << self.log_degree,
+				}
+				.into());
+			}
+		}
+
+		if proof.sumcheck_rounds.len() != self.inner.n_vars() {
+			return Err(VerificationError::NumberOfSumcheckRounds {
+				expected: self.inner.n_vars(),
+			}
+			.into());
+		}
+
+		Ok(())
+	}
+}
+
+/// Evaluates a small field multilinear at the high query coordinates, for every assignment of the
+/// low variables, given the packed evaluations over the hypercube.
+fn compute_partial_evals<F, FE>(packed_message: &[FE], eq_ind_high: &[FE]) -> Vec<FE>
+where
+	F: Field,
+	FE: ExtensionField<F>,
+{
+	packed_message
+		.par_iter()
+		.zip(eq_ind_high.par_iter())
+		.fold(
+			|| vec![FE::ZERO; FE::DEGREE],
+			|mut evals, (&packed, &eq)| {
+				for (eval, base) in evals.iter_mut().zip(packed.iter_bases()) {
+					*eval += eq * base;
+				}
+				evals
+			},
+		)
+		.reduce(
+			|| vec![FE::ZERO; FE::DEGREE],
+			|mut lhs, rhs| {
+				for (l, r) in lhs.iter_mut().zip(rhs) {
+					*l += r;
+				}
+				lhs
+			},
+		)
+}
+
+/// Transposes the partial evaluations over the small field and batches the result.
+///
+/// The partial evaluations are the rows of a square matrix over the small field. Each column,
+/// read as an extension field element, is an evaluation of the packed polynomial against one
+/// small field component of the equality indicator. The columns are combined with the batching
+/// coefficients.
+fn transpose_and_batch<F, FE>(partial_evals: &[FE], batch_coeffs: &[FE]) -> FE
+where
+	F: Field,
+	FE: ExtensionField<F>,
+{
+	let rows = partial_evals
+		.iter()
+		.map(|eval| eval.iter_bases().collect::<Vec<F>>())
+		.collect::<Vec<_>>();
+	batch_coeffs
+		.iter()
+		.enumerate()
+		.map(|(j, &coeff)| {
+			let column = rows.iter().map(|row| row[j]).collect::<Vec<_>>();
+			let packed =
+				FE::from_bases(&column).expect("the number of rows equals the extension degree");
+			coeff * packed
+		})
+		.sum()
+}
+
+/// Computes $\sum_u \widetilde{eq}(u, x) \otimes \widetilde{eq}(u, y)$ in the tensor algebra
+/// $L \otimes_K L$.
+///
+/// An element of the tensor algebra is represented by its columns $a_j$ in the decomposition
+/// $\sum_j \beta_j \otimes a_j$, where $\beta_j$ is the basis of $L$ over $K$. The $j$-th column of
+/// the result is the multilinear extension of the $j$-th component of $\widetilde{eq}(\cdot, x)$,
+/// evaluated at $y$.
+fn tensor_eq_ind<F, FE>(x: &[FE], y: &[FE]) -> Vec<FE>
+where
+	F: Field,
+	FE: ExtensionField<F>,
+{
+	debug_assert_eq!(x.len(), y.len());
+
+	let mut columns = FE::ONE.iter_bases().map(FE::from).collect::<Vec<_>>();
+	for (&x_i, &y_i) in x.iter().zip(y.iter()) {
+		let lo = mul_by_simple_tensor::<F, _>(&columns, FE::ONE - x_i, FE::ONE - y_i);
+		let hi = mul_by_simple_tensor::<F, _>(&columns, x_i, y_i);
+		columns = lo.into_iter().zip(hi).map(|(lo, hi)| lo + hi).collect();
+	}
+	columns
+}
+
+/// Multiplies a tensor algebra element, given by its columns, by the simple tensor $a \otimes b$.
+fn mul_by_simple_tensor<F, FE>(columns: &[FE], a: FE, b: FE) -> Vec<FE>
+where
+	F: Field,
+	FE: ExtensionField<F>,
+{
+	let mut result = vec![FE::ZERO; FE::DEGREE];
+	for (j, &column) in columns.iter().enumerate() {
+		let basis_a = FE::basis(j).expect("column index is less than the extension degree") * a;
+		for (res, base) in result.iter_mut().zip(basis_a.iter_bases()) {
+			*res += column * base;
+		}
+	}
+	for res in result.iter_mut() {
+		*res *= b;
+	}
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{challenger::HashChallenger, poly_commit::FRIPCS};
+	use assert_matches::assert_matches;
+	use binius_field::{
+		BinaryField128b, BinaryField16b, BinaryField1b, PackedBinaryField128x1b,
+		PackedBinaryField16x8b, PackedBinaryField1x128b,
+	};
+	use binius_hash::{GroestlDigestCompression, GroestlHasher};
+	use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+	use std::iter::repeat_with;
+
+	type InnerPCS = FRIPCS<
+		PackedBinaryField1x128b,
+		BinaryField16b,
+		BinaryField128b,
+		GroestlHasher<BinaryField128b>,
+		GroestlDigestCompression,
+	>;
+
+	fn make_pcs<P>(n_packed_vars: usize) -> RingSwitchPCS<P, PackedBinaryField1x128b, InnerPCS>
+	where
+		P: PackedField,
+		BinaryField128b: ExtensionField<P::Scalar>,
+		PackedBinaryField1x128b: PackedExtensionField<P>,
+	{
+		let inner = InnerPCS::new_using_groestl_merkle_tree(n_packed_vars, 1, 16).unwrap();
+		RingSwitchPCS::new(inner).unwrap()
+	}
+
+	fn random_polys<P: PackedField>(
+		rng: &mut StdRng,
+		n_vars: usize,
+		batch_size: usize,
+	) -> Vec<MultilinearExtension<'static, P>> {
+		repeat_with(|| {
+			let evals = repeat_with(|| P::random(&mut *rng))
+				.take((1 << n_vars) / P::WIDTH)
+				.collect::<Vec<_>>();
+			MultilinearExtension::from_values(evals).unwrap()
+		})
+		.take(batch_size)
+		.collect()
+	}
+
+	fn commit_prove_verify<P>(n_packed_vars: usize, batch_size: usize) -> Result<(), Error>
+	where
+		P: PackedField,
+		BinaryField128b: ExtensionField<P::Scalar>,
+		PackedBinaryField1x128b: PackedExtensionField<P>,
+	{
+		let pcs = make_pcs::<P>(n_packed_vars);
+		let mut rng = StdRng::seed_from_u64(0);
+		let polys = random_polys::<P>(&mut rng, pcs.n_vars(), batch_size);
+
+		let (commitment, committed) = pcs.commit(&polys).unwrap();
+
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		challenger.observe(commitment);
+		let query = repeat_with(|| challenger.sample())
+			.take(pcs.n_vars())
+			.collect::<Vec<_>>();
+		let multilin_query =
+			MultilinearQuery::<PackedBinaryField1x128b>::with_full_query(&query).unwrap();
+
+		let values = polys
+			.iter()
+			.map(|poly| poly.evaluate(&multilin_query).unwrap())
+			.collect::<Vec<_>>();
+
+		let mut prove_challenger = challenger.clone();
+		let proof = pcs
+			.prove_evaluation(&mut prove_challenger, &committed, &polys, &query)
+			.unwrap();
+
+		let mut verify_challenger = challenger.clone();
+		pcs.verify_evaluation(&mut verify_challenger, &commitment, &query, proof, &values)
+	}
+
+	#[test]
+	fn test_packed_1b_commit_prove_verify_without_error() {
+		let batch_size = thread_rng().gen_range(1..=4);
+		commit_prove_verify::<PackedBinaryField128x1b>(4, batch_size).unwrap();
+	}
+
+	#[test]
+	fn test_packed_8b_commit_prove_verify_without_error() {
+		let batch_size = thread_rng().gen_range(1..=4);
+		commit_prove_verify::<PackedBinaryField16x8b>(4, batch_size).unwrap();
+	}
+
+	#[test]
+	fn test_tensor_eq_ind_columns() {
+		type F = BinaryField128b;
+
+		let mut rng = StdRng::seed_from_u64(0);
+		let x = repeat_with(|| <F as Field>::random(&mut rng))
+			.take(3)
+			.collect::<Vec<_>>();
+		let y = repeat_with(|| <F as Field>::random(&mut rng))
+			.take(3)
+			.collect::<Vec<_>>();
+
+		let eq_x = MultilinearQuery::<F>::with_full_query(&x)
+			.unwrap()
+			.into_expansion();
+		let eq_y = MultilinearQuery::<F>::with_full_query(&y)
+			.unwrap()
+			.into_expansion();
+
+		let columns = tensor_eq_ind::<BinaryField1b, _>(&x, &y);
+		for (j, &column) in columns.iter().enumerate() {
+			let expected = eq_x
+				.iter()
+				.zip(eq_y.iter())
+				.map(|(eq_x_u, &eq_y_u)| {
+					let component = ExtensionField::<BinaryField1b>::iter_bases(eq_x_u)
+						.nth(j)
+						.unwrap();
+					eq_y_u * component
+				})
+				.sum::<F>();
+			assert_eq!(column, expected);
+		}
+	}
+
+	#[test]
+	fn test_verify_rejects_incorrect_proofs() {
+		type Packed = PackedBinaryField128x1b;
+
+		let pcs = make_pcs::<Packed>(4);
+		let mut rng = StdRng::seed_from_u64(0);
+		let polys = random_polys::<Packed>(&mut rng, pcs.n_vars(), 2);
+
+		let (commitment, committed) = pcs.commit(&polys).unwrap();
+
+		let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		let query = repeat_with(|| <BinaryField128b as Field>::random(&mut rng))
+			.take(pcs.n_vars())
+			.collect::<Vec<_>>();
+		let multilin_query =
+			MultilinearQuery::<PackedBinaryField1x128b>::with_full_query(&query).unwrap();
+		let values = polys
+			.iter()
+			.map(|poly| poly.evaluate(&multilin_query).unwrap())
+			.collect::<Vec<BinaryField128b>>();
+
+		let proof = pcs
+			.prove_evaluation(&mut challenger.clone(), &committed, &polys, &query)
+			.unwrap();
+
+		// Case: incorrect claimed evaluation
+		let mut incorrect_values = values.clone();
+		incorrect_values[1] += BinaryField128b::ONE;
+		assert_matches!(
+			pcs.verify_evaluation(
+				&mut challenger.clone(),
+				&commitment,
+				&query,
+				proof.clone(),
+				&incorrect_values
+			),
+			Err(Error::Verification(VerificationError::IncorrectPartialEvaluation))
+		);
+
+		// Case: partial evaluations shifted to keep the claimed evaluation consistent
+		let mut corrupted_proof = proof.clone();
+		let eq_ind_low = MultilinearQuery::<BinaryField128b>::with_full_query(&query[..7])
+			.unwrap()
+			.into_expansion();
+		let delta = BinaryField128b::ONE;
+		corrupted_proof.partial_evals[0][0] += delta * eq_ind_low[1];
+		corrupted_proof.partial_evals[0][1] += delta * eq_ind_low[0];
+		assert_matches!(
+			pcs.verify_evaluation(
+				&mut challenger.clone(),
+				&commitment,
+				&query,
+				corrupted_proof,
+				&values
+			),
+			Err(Error::Verification(VerificationError::IncorrectEvaluation))
+		);
+
+		// Case: packed evaluation inconsistent with the sumcheck
+		let mut corrupted_proof = proof.clone();
+		corrupted_proof.packed_evals[0] += BinaryField128b::ONE;
+		assert_matches!(
+			pcs.verify_evaluation(
+				&mut challenger.clone(),
+				&commitment,
+				&query,
+				corrupted_proof,
+				&values
+			),
+			Err(Error::Verification(VerificationError::IncorrectEvaluation))
+		);
+
+		// Case: missing sumcheck round
+		let mut corrupted_proof = proof.clone();
+		corrupted_proof.sumcheck_rounds.pop();
+		assert_matches!(
+			pcs.verify_evaluation(
+				&mut challenger.clone(),
+				&commitment,
+				&query,
+				corrupted_proof,
+				&values
+			),
+			Err(Error::Verification(VerificationError::NumberOfSumcheckRounds { .. }))
+		);
+
+		pcs.verify_evaluation(&mut challenger.clone(), &commitment, &query, proof, &values)
+			.unwrap();
+	}
+}
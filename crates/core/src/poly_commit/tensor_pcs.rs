@@ -72,7 +72,7 @@ where
 /// * `PI`: The packed intermediate field type.
 /// * `PE`: The packed extension field type.
 /// * `VCSProof`: The vector commitment scheme proof type.
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct Proof<'a, PI, PE, VCSProof>
 where
 	PE: PackedField,
@@ -89,13 +89,14 @@ where
 	/// This value represents the multilinear polynomial such that $\forall v \in \{0, 1\}^{\mu}$,
 	/// $v \rightarrow \sum_{i=0}^{n-1} c_i * t'_i(v)$
 	pub mixed_t_prime: MultilinearExtension<'a, PE>,
-	/// Opening proofs for chosen columns of the encoded matrices
+	/// Chosen columns of the encoded matrices
 	///
 	/// Let $j_1, \ldots, j_k$ be the indices of the columns that are opened.
-	/// The ith element is a tuple of:
-	/// * A vector (size=n_polys) of the $j_i$th columns (one from each polynomial's encoded matrix)
-	/// * A proof that these columns are consistent with the vector commitment
-	pub vcs_proofs: Vec<(Vec<Vec<PI>>, VCSProof)>,
+	/// The ith element is a vector (size=n_polys) of the $j_i$th columns (one from each
+	/// polynomial's encoded matrix).
+	pub opened_columns: Vec<Vec<Vec<PI>>>,
+	/// A single proof that all opened columns are consistent with the vector commitment
+	pub vcs_proof: VCSProof,
 }
 
 /// The multilinear polynomial commitment scheme specified in [DP23].
@@ -150,18 +151,28 @@ where
 		log_rows: usize,
 		code: LC,
 		n_test_queries: usize,
+	) -> Result<Self, Error> {
+		Self::new_using_groestl_merkle_cap(log_rows, code, n_test_queries, 0)
+	}
+
+	/// Construct a [`TensorPCS`] that commits to the Merkle cap of the given height.
+	///
+	/// The commitment has `2^cap_height` digests, and the opening proofs omit the top
+	/// `cap_height` layers of the tree.
+	pub fn new_using_groestl_merkle_cap(
+		log_rows: usize,
+		code: LC,
+		n_test_queries: usize,
+		cap_height: usize,
 	) -> Result<Self, Error> {
 		// Check power of two length because MerkleTreeVCS requires it
 		if !code.len().is_power_of_two() {
 			return Err(Error::CodeLengthPowerOfTwoRequired);
 		}
 		let log_len = log2_strict_usize(code.len());
-		Self::new(
-			log_rows,
-			code,
-			n_test_queries,
-			MerkleTreeVCS::new(log_len, GroestlDigestCompression),
-		)
+		let vcs = MerkleTreeVCS::with_cap_height(log_len, cap_height, GroestlDigestCompression)
+			.map_err(|err| Error::VectorCommit(Box::new(err)))?;
+		Self::new(log_rows, code, n_test_queries, vcs)
 	}
 }
 
@@ -183,7 +194,7 @@ where
 {
 	type Commitment = VCS::Commitment;
 	type Committed = (Vec<RowMajorMatrix<PI>>, VCS::Committed);
-	type Proof = Proof<'static, PI, PE, VCS::Proof>;
+	type Proof = Proof<'static, PI, PE, VCS::MultiProof>;
 	type Error = Error;
 
 	fn n_vars(&self) -> usize {
@@ -280,6 +291,19 @@ where
 			});
 		}
 
+		// The committed state may have been reloaded from storage, so check it matches the scheme
+		let n_rows = 1 << self.log_rows;
+		let shape_matches = col_major_mats.iter().all(|col_major_mat| {
+			col_major_mat.width * PI::WIDTH == n_rows
+				&& col_major_mat.values.len() * PI::WIDTH == n_rows * self.code.len()
+		});
+		if !shape_matches {
+			return Err(Error::CommittedMatrixShape {
+				n_rows,
+				n_cols: self.code.len(),
+			});
+		}
+
 		if query.len() != self.n_vars() {
 			return Err(PolynomialError::IncorrectQuerySize {
 				expected: self.n_vars(),
@@ -300,27 +324,30 @@ where
 		let t_prime = mix_t_primes(log_n_cols, &t_primes, mixing_coefficients)?;
 
 		challenger.observe_slice(PE::unpack_scalars(t_prime.evals()));
-		let merkle_proofs = repeat_with(|| challenger.sample_bits(code_len_bits))
+		let indices = repeat_with(|| challenger.sample_bits(code_len_bits))
 			.take(self.n_test_queries)
-			.map(|index| {
-				let vcs_proof = self
-					.vcs
-					.prove_batch_opening(vcs_committed, index)
-					.map_err(|err| Error::VectorCommit(Box::new(err)))?;
+			.collect::<Vec<_>>();
+
+		let vcs_proof = self
+			.vcs
+			.prove_multi_opening(vcs_committed, &indices)
+			.map_err(|err| Error::VectorCommit(Box::new(err)))?;
 
-				let cols: Vec<_> = col_major_mats
+		let opened_columns = indices
+			.iter()
+			.map(|&index| {
+				col_major_mats
 					.iter()
 					.map(|col_major_mat| col_major_mat.row_slice(index).to_vec())
-					.collect();
-
-				Ok((cols, vcs_proof))
+					.collect()
 			})
-			.collect::<Result<_, Error>>()?;
+			.collect();
 
 		Ok(Proof {
 			n_polys,
 			mixed_t_prime: t_prime,
-			vcs_proofs: merkle_proofs,
+			opened_columns,
+			vcs_proof,
 		})
 	}
 
@@ -399,25 +426,23 @@ where
 		self.encode_ext(proof.mixed_t_prime.evals(), &mut u_prime)?;
 
 		// Check vector commitment openings.
-		let columns = proof
-			.vcs_proofs
-			.into_iter()
-			.map(|(cols, vcs_proof)| {
-				let index = challenger.sample_bits(code_len_bits);
-
-				let leaf_digests = cols.iter().map(hash::<_, H>);
+		let indices = repeat_with(|| challenger.sample_bits(code_len_bits))
+			.take(self.n_test_queries)
+			.collect::<Vec<_>>();
 
-				self.vcs
-					.verify_batch_opening(commitment, index, vcs_proof, leaf_digests)
-					.map_err(|err| Error::VectorCommit(Box::new(err)))?;
+		let leaf_digests = proof
+			.opened_columns
+			.iter()
+			.map(|cols| cols.iter().map(hash::<_, H>));
 
-				Ok((index, cols))
-			})
-			.collect::<Result<Vec<_>, Error>>()?;
+		self.vcs
+			.verify_multi_opening(commitment, &indices, proof.vcs_proof, leaf_digests)
+			.map_err(|err| Error::VectorCommit(Box::new(err)))?;
 
 		// Get the sequence of column tests.
-		let column_tests = columns
+		let column_tests = indices
 			.into_iter()
+			.zip(proof.opened_columns)
 			.flat_map(|(index, cols)| {
 				let mut batched_column_test = (0..block_size)
 					.map(|j| {
@@ -491,7 +516,9 @@ where
 	fn proof_size(&self, n_polys: usize) -> usize {
 		let t_prime_size = (mem::size_of::<PE>() << self.log_cols()) / PE::WIDTH;
 		let column_size = (mem::size_of::<PI>() << self.log_rows()) / PI::WIDTH;
-		t_prime_size + (n_polys * column_size + self.vcs.proof_size(n_polys)) * self.n_test_queries
+		t_prime_size
+			+ n_polys * column_size * self.n_test_queries
+			+ self.vcs.multi_proof_size(n_polys, self.n_test_queries)
 	}
 }
 
@@ -599,19 +626,19 @@ where
 	H::Digest: Copy + Default + Send,
 	VCS: VectorCommitScheme<H::Digest>,
 {
-	fn check_proof_shape(&self, proof: &Proof<PI, PE, VCS::Proof>) -> Result<(), Error> {
+	fn check_proof_shape(&self, proof: &Proof<PI, PE, VCS::MultiProof>) -> Result<(), Error> {
 		let n_rows = 1 << self.log_rows;
 		let log_block_size = log2_strict_usize(<FI as ExtensionField<F>>::DEGREE);
 		let log_n_cols = self.code.dim_bits() + log_block_size;
 		let n_queries = self.n_test_queries;
 
-		if proof.vcs_proofs.len() != n_queries {
+		if proof.opened_columns.len() != n_queries {
 			return Err(VerificationError::NumberOfOpeningProofs {
 				expected: n_queries,
 			}
 			.into());
 		}
-		for (col_idx, (polys_col, _)) in proof.vcs_proofs.iter().enumerate() {
+		for (col_idx, polys_col) in proof.opened_columns.iter().enumerate() {
 			if polys_col.len() != proof.n_polys {
 				return Err(Error::NumBatchedMismatchError {
 					err_str: format!(
@@ -887,7 +914,11 @@ where
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::challenger::HashChallenger;
+	use crate::{
+		challenger::HashChallenger,
+		serialization::{deserialize_versioned, serialize_versioned},
+	};
+	use assert_matches::assert_matches;
 	use binius_field::{
 		BinaryField128b, PackedBinaryField128x1b, PackedBinaryField16x8b, PackedBinaryField1x128b,
 		PackedBinaryField4x32b, PackedBinaryField8x16b,
@@ -1170,6 +1201,118 @@ mod tests {
 			.unwrap();
 	}
 
+	#[test]
+	fn test_merkle_cap_commit_prove_verify_without_error() {
+		let rs_code = ReedSolomonCode::new(5, 2).unwrap();
+		let n_test_queries =
+			calculate_n_test_queries_reed_solomon::<_, BinaryField128b, _>(100, 8, &rs_code)
+				.unwrap();
+		let pcs = <BasicTensorPCS<
+			PackedBinaryField4x32b,
+			PackedBinaryField16x8b,
+			PackedBinaryField1x128b,
+			_,
+			_,
+			_,
+		>>::new_using_groestl_merkle_cap(8, rs_code, n_test_queries, 3)
+		.unwrap();
+
+		// The tree over the 128 code columns stops three layers below the root.
+		assert_eq!(pcs.proof_size(1), 153856);
+
+		let mut rng = StdRng::seed_from_u64(0);
+		let evals = repeat_with(|| PackedBinaryField4x32b::random(&mut rng))
+			.take((1 << pcs.n_vars()) / PackedBinaryField4x32b::WIDTH)
+			.collect::<Vec<_>>();
+		let poly = MultilinearExtension::from_values(evals).unwrap();
+		let polys = [poly.to_ref()];
+
+		let (commitment, committed) = pcs.commit(&polys).unwrap();
+		assert_eq!(commitment.0.len(), 8);
+
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		challenger.observe(commitment.clone());
+		let query = repeat_with(|| challenger.sample())
+			.take(pcs.n_vars())
+			.collect::<Vec<_>>();
+
+		let multilin_query =
+			MultilinearQuery::<PackedBinaryField1x128b>::with_full_query(&query).unwrap();
+		let values = vec![poly.evaluate(&multilin_query).unwrap()];
+
+		let mut prove_challenger = challenger.clone();
+		let proof = pcs
+			.prove_evaluation(&mut prove_challenger, &committed, &polys, &query)
+			.unwrap();
+
+		let mut verify_challenger = challenger.clone();
+		pcs.verify_evaluation(&mut verify_challenger, &commitment, &query, proof, &values)
+			.unwrap();
+	}
+
+	#[test]
+	fn test_prove_with_reloaded_committed_state() {
+		let rs_code = ReedSolomonCode::new(5, 2).unwrap();
+		let n_test_queries =
+			calculate_n_test_queries_reed_solomon::<_, BinaryField128b, _>(100, 8, &rs_code)
+				.unwrap();
+		let pcs = <BasicTensorPCS<
+			PackedBinaryField4x32b,
+			PackedBinaryField16x8b,
+			PackedBinaryField1x128b,
+			_,
+			_,
+			_,
+		>>::new_using_groestl_merkle_tree(8, rs_code, n_test_queries)
+		.unwrap();
+
+		let mut rng = StdRng::seed_from_u64(0);
+		let polys = repeat_with(|| {
+			let evals = repeat_with(|| PackedBinaryField4x32b::random(&mut rng))
+				.take((1 << pcs.n_vars()) / PackedBinaryField4x32b::WIDTH)
+				.collect::<Vec<_>>();
+			MultilinearExtension::from_values(evals).unwrap()
+		})
+		.take(2)
+		.collect::<Vec<_>>();
+
+		let (commitment, committed) = pcs.commit(&polys).unwrap();
+		let bytes = serialize_versioned(&committed);
+		drop(committed);
+		let committed = deserialize_versioned(&bytes).unwrap();
+
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		let query = repeat_with(|| challenger.sample())
+			.take(pcs.n_vars())
+			.collect::<Vec<_>>();
+		let multilin_query =
+			MultilinearQuery::<PackedBinaryField1x128b>::with_full_query(&query).unwrap();
+		let values = polys
+			.iter()
+			.map(|poly| poly.evaluate(&multilin_query).unwrap())
+			.collect::<Vec<_>>();
+
+		let mut prove_challenger = challenger.clone();
+		let proof = pcs
+			.prove_evaluation(&mut prove_challenger, &committed, &polys, &query)
+			.unwrap();
+
+		let mut verify_challenger = challenger.clone();
+		pcs.verify_evaluation(&mut verify_challenger, &commitment, &query, proof, &values)
+			.unwrap();
+
+		// A state committed with other parameters is rejected rather than opened out of bounds
+		let (matrices, tree) = committed;
+		let truncated = matrices
+			.into_iter()
+			.map(|mat| RowMajorMatrix::new(mat.values[..mat.width].to_vec(), mat.width))
+			.collect();
+		assert_matches!(
+			pcs.prove_evaluation(&mut challenger.clone(), &(truncated, tree), &polys, &query),
+			Err(Error::CommittedMatrixShape { .. })
+		);
+	}
+
 	#[test]
 	fn test_proof_size() {
 		let rs_code = ReedSolomonCode::new(5, 2).unwrap();
@@ -1186,8 +1329,8 @@ mod tests {
 		>>::new_using_groestl_merkle_tree(8, rs_code, n_test_queries)
 		.unwrap();
 
-		assert_eq!(pcs.proof_size(1), 182720);
-		assert_eq!(pcs.proof_size(2), 332224);
+		assert_eq!(pcs.proof_size(1), 154080);
+		assert_eq!(pcs.proof_size(2), 303584);
 	}
 
 	#[test]
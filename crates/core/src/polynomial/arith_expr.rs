@@ -0,0 +1,143 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use binius_field::{BinaryField1b, ExtensionField, Field, TowerField};
+use std::{
+	cmp,
+	ops::{Add, Mul, Sub},
+};
+
+/// An arithmetic expression over the variables of a composition polynomial.
+///
+/// Expressions are built at runtime from variables and constants with `+`, `-`, `*` and
+/// [`Self::pow`], and compiled into an [`ArithCircuitPoly`](super::composition::ArithCircuitPoly) for
+/// evaluation. Variable `i` refers to position `i` of the composition query. Subtraction is
+/// represented as the addition of the negation, which is free in characteristic 2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArithExpr<F: Field> {
+	Const(F),
+	Var(usize),
+	Add(Box<ArithExpr<F>>, Box<ArithExpr<F>>),
+	Mul(Box<ArithExpr<F>>, Box<ArithExpr<F>>),
+	Pow(Box<ArithExpr<F>>, u64),
+}
+
+impl<F: Field> ArithExpr<F> {
+	/// The minimum number of query variables, one more than the largest variable index.
+	pub fn n_vars(&self) -> usize {
+		match self {
+			Self::Const(_) => 0,
+			Self::Var(index) => index + 1,
+			Self::Add(left, right) | Self::Mul(left, right) => {
+				cmp::max(left.n_vars(), right.n_vars())
+			}
+			Self::Pow(base, _) => base.n_vars(),
+		}
+	}
+
+	/// Total degree of the expression as a polynomial in its variables.
+	pub fn degree(&self) -> usize {
+		match self {
+			Self::Const(_) => 0,
+			Self::Var(_) => 1,
+			Self::Add(left, right) => cmp::max(left.degree(), right.degree()),
+			Self::Mul(left, right) => left.degree() + right.degree(),
+			Self::Pow(base, exp) => base.degree() * *exp as usize,
+		}
+	}
+
+	pub fn pow(self, exp: u64) -> Self {
+		Self::Pow(Box::new(self), exp)
+	}
+
+	/// Appends the variable indices referenced by the expression, in order of first occurrence.
+	pub fn collect_vars(&self, vars: &mut Vec<usize>) {
+		match self {
+			Self::Const(_) => {}
+			Self::Var(index) => {
+				if !vars.contains(index) {
+					vars.push(*index);
+				}
+			}
+			Self::Add(left, right) | Self::Mul(left, right) => {
+				left.collect_vars(vars);
+				right.collect_vars(vars);
+			}
+			Self::Pow(base, _) => base.collect_vars(vars),
+		}
+	}
+
+	/// Replaces every variable index `i` with `f(i)`.
+	pub fn map_vars(&self, f: &impl Fn(usize) -> usize) -> Self {
+		match self {
+			Self::Const(value) => Self::Const(*value),
+			Self::Var(index) => Self::Var(f(*index)),
+			Self::Add(left, right) => Self::Add(left.map_vars(f).into(), right.map_vars(f).into()),
+			Self::Mul(left, right) => Self::Mul(left.map_vars(f).into(), right.map_vars(f).into()),
+			Self::Pow(base, exp) => Self::Pow(base.map_vars(f).into(), *exp),
+		}
+	}
+
+	/// Converts the constants of the expression into an extension field.
+	pub fn convert_field<FE: ExtensionField<F>>(&self) -> ArithExpr<FE> {
+		match self {
+			Self::Const(value) => ArithExpr::Const((*value).into()),
+			Self::Var(index) => ArithExpr::Var(*index),
+			Self::Add(left, right) => {
+				ArithExpr::Add(Box::new(left.convert_field()), Box::new(right.convert_field()))
+			}
+			Self::Mul(left, right) => {
+				ArithExpr::Mul(Box::new(left.convert_field()), Box::new(right.convert_field()))
+			}
+			Self::Pow(base, exp) => ArithExpr::Pow(Box::new(base.convert_field()), *exp),
+		}
+	}
+
+	/// The maximum binary tower level of any constant in the expression.
+	pub fn binary_tower_level(&self) -> usize
+	where
+		F: TowerField,
+	{
+		match self {
+			Self::Const(value) => constant_tower_level(*value),
+			Self::Var(_) => 0,
+			Self::Add(left, right) | Self::Mul(left, right) => {
+				cmp::max(left.binary_tower_level(), right.binary_tower_level())
+			}
+			Self::Pow(base, _) => base.binary_tower_level(),
+		}
+	}
+}
+
+/// The smallest tower level $\iota$ such that `value` lies in $T_\iota$.
+pub(crate) fn constant_tower_level<F: TowerField>(value: F) -> usize {
+	let n_bits = ExtensionField::<BinaryField1b>::iter_bases(&value)
+		.enumerate()
+		.filter(|(_, bit)| *bit != BinaryField1b::ZERO)
+		.last()
+		.map_or(0, |(last_bit, _)| last_bit + 1);
+	n_bits.next_power_of_two().ilog2() as usize
+}
+
+impl<F: Field, R: Into<ArithExpr<F>>> Add<R> for ArithExpr<F> {
+	type Output = Self;
+
+	fn add(self, rhs: R) -> Self {
+		Self::Add(Box::new(self), Box::new(rhs.into()))
+	}
+}
+
+impl<F: Field, R: Into<ArithExpr<F>>> Sub<R> for ArithExpr<F> {
+	type Output = Self;
+
+	fn sub(self, rhs: R) -> Self {
+		self + Self::Const(-F::ONE) * rhs
+	}
+}
+
+impl<F: Field, R: Into<ArithExpr<F>>> Mul<R> for ArithExpr<F> {
+	type Output = Self;
+
+	fn mul(self, rhs: R) -> Self {
+		Self::Mul(Box::new(self), Box::new(rhs.into()))
+	}
+}
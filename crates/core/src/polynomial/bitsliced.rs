@@ -0,0 +1,162 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+
+/// The maximum degree of the polynomials in a [`BitslicedPoly`].
+pub const BITSLICED_MAX_DEGREE: usize = 7;
+
+/// A vector of 128 univariate polynomials over `BinaryField1b`, stored bit-sliced.
+///
+/// Bit `i` of the `k`-th coefficient word is the coefficient of $X^k$ in the `i`-th polynomial of
+/// the vector. Addition is a word-level XOR and multiplication is a convolution of coefficient
+/// words using AND and XOR, so a composition over `BinaryField1b` can be evaluated at 128 points
+/// with a handful of machine instructions per operation.
+///
+/// The product of two polynomials must not exceed [`BITSLICED_MAX_DEGREE`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BitslicedPoly {
+	coeffs: [u128; BITSLICED_MAX_DEGREE + 1],
+}
+
+impl BitslicedPoly {
+	pub const ZERO: Self = Self {
+		coeffs: [0; BITSLICED_MAX_DEGREE + 1],
+	};
+
+	pub const ONE: Self = Self::constant(u128::MAX);
+
+	/// The vector of constant polynomials with the given bits.
+	pub const fn constant(bits: u128) -> Self {
+		let mut coeffs = [0; BITSLICED_MAX_DEGREE + 1];
+		coeffs[0] = bits;
+		Self { coeffs }
+	}
+
+	/// The vector of linear polynomials `constant + linear * X`.
+	pub const fn linear(constant: u128, linear: u128) -> Self {
+		let mut coeffs = [0; BITSLICED_MAX_DEGREE + 1];
+		coeffs[0] = constant;
+		coeffs[1] = linear;
+		Self { coeffs }
+	}
+
+	/// The bits of the coefficients of $X^k$.
+	pub fn coeff(&self, k: usize) -> u128 {
+		self.coeffs[k]
+	}
+
+	/// The bit-sliced coefficients, in order of increasing degree.
+	pub fn coeffs(&self) -> &[u128] {
+		&self.coeffs
+	}
+}
+
+impl Add for BitslicedPoly {
+	type Output = Self;
+
+	fn add(mut self, rhs: Self) -> Self {
+		self += rhs;
+		self
+	}
+}
+
+impl AddAssign for BitslicedPoly {
+	fn add_assign(&mut self, rhs: Self) {
+		for (lhs, rhs) in self.coeffs.iter_mut().zip(rhs.coeffs) {
+			*lhs ^= rhs;
+		}
+	}
+}
+
+impl Sub for BitslicedPoly {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self {
+		// Characteristic 2
+		self + rhs
+	}
+}
+
+impl SubAssign for BitslicedPoly {
+	fn sub_assign(&mut self, rhs: Self) {
+		*self += rhs;
+	}
+}
+
+impl Mul for BitslicedPoly {
+	type Output = Self;
+
+	fn mul(self, rhs: Self) -> Self {
+		let mut coeffs = [0; BITSLICED_MAX_DEGREE + 1];
+		for (i, &lhs_i) in self.coeffs.iter().enumerate() {
+			if lhs_i == 0 {
+				continue;
+			}
+			for (j, &rhs_j) in rhs.coeffs.iter().enumerate() {
+				match coeffs.get_mut(i + j) {
+					Some(coeff) => *coeff ^= lhs_i & rhs_j,
+					None => debug_assert_eq!(
+						lhs_i & rhs_j,
+						0,
+						"product degree exceeds BITSLICED_MAX_DEGREE"
+					),
+				}
+			}
+		}
+		Self { coeffs }
+	}
+}
+
+impl MulAssign for BitslicedPoly {
+	fn mul_assign(&mut self, rhs: Self) {
+		*self = *self * rhs;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::{rngs::StdRng, Rng, SeedableRng};
+
+	// Evaluates the i-th polynomial of the vector at a point of GF(2) encoded as a bool.
+	fn evaluate_lane(poly: &BitslicedPoly, lane: usize, x: bool) -> bool {
+		poly.coeffs()
+			.iter()
+			.enumerate()
+			.filter(|&(k, _)| k == 0 || x)
+			.fold(false, |acc, (_, word)| acc ^ ((word >> lane) & 1 == 1))
+	}
+
+	#[test]
+	fn test_arithmetic_matches_lanewise() {
+		let mut rng = StdRng::seed_from_u64(0);
+		let a = BitslicedPoly::linear(rng.gen(), rng.gen());
+		let b = BitslicedPoly::linear(rng.gen(), rng.gen());
+		let c = BitslicedPoly::constant(rng.gen());
+
+		let result = a * (BitslicedPoly::ONE - b) + c * a * b;
+		for lane in 0..128 {
+			for x in [false, true] {
+				let (a, b, c) = (
+					evaluate_lane(&a, lane, x),
+					evaluate_lane(&b, lane, x),
+					evaluate_lane(&c, lane, x),
+				);
+				let expected = (a & !b) ^ (c & a & b);
+				assert_eq!(evaluate_lane(&result, lane, x), expected);
+			}
+		}
+	}
+
+	#[test]
+	fn test_mul_convolves_coefficients() {
+		// (1 + X)^3 = 1 + X + X^2 + X^3 over GF(2)
+		let x_plus_one = BitslicedPoly::linear(u128::MAX, u128::MAX);
+		let cube = x_plus_one * x_plus_one * x_plus_one;
+		assert_eq!(&cube.coeffs()[..5], &[u128::MAX, u128::MAX, u128::MAX, u128::MAX, 0]);
+
+		// (1 + X)^2 = 1 + X^2 over GF(2)
+		let square = x_plus_one * x_plus_one;
+		assert_eq!(&square.coeffs()[..3], &[u128::MAX, 0, u128::MAX]);
+	}
+}
@@ -0,0 +1,436 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use crate::polynomial::{ArithExpr, BitslicedPoly, CompositionPoly, Error};
+use binius_field::{ExtensionField, Field, PackedField, TowerField};
+use std::ops::{Add, Mul};
+
+/// Circuits with at most this many steps are evaluated without allocating.
+const MAX_STACK_STEPS: usize = 32;
+
+#[derive(Debug, Clone, Copy)]
+enum CircuitStepArgument<F> {
+	Var(usize),
+	Const(F),
+	Step(usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CircuitStep<F> {
+	Add(CircuitStepArgument<F>, CircuitStepArgument<F>),
+	Mul(CircuitStepArgument<F>, CircuitStepArgument<F>),
+	Square(CircuitStepArgument<F>),
+}
+
+/// A composition polynomial defined by an [`ArithExpr`], compiled to a flat list of steps.
+///
+/// Each step adds, multiplies or squares the results of earlier steps, query variables and
+/// constants. Constant subexpressions are folded and powers are expanded by square-and-multiply
+/// at compile time, so evaluation is a single pass over the steps without recursion.
+#[derive(Debug, Clone)]
+pub struct ArithCircuitPoly<F: Field> {
+	expr: ArithExpr<F>,
+	steps: Vec<CircuitStep<F>>,
+	retval: CircuitStepArgument<F>,
+	n_vars: usize,
+	degree: usize,
+	tower_level: usize,
+}
+
+impl<F: TowerField> ArithCircuitPoly<F> {
+	/// Compiles an expression over the smallest query that contains all of its variables.
+	pub fn new(expr: ArithExpr<F>) -> Self {
+		let n_vars = expr.n_vars();
+		Self::compile(n_vars, expr)
+	}
+
+	/// Compiles an expression over a query of `n_vars` variables, failing if the expression
+	/// references a variable out of range.
+	///
+	/// This is how a circuit is given the arity of a [`MixComposition`](super::MixComposition)
+	/// that it is included in.
+	pub fn with_n_vars(n_vars: usize, expr: ArithExpr<F>) -> Result<Self, Error> {
+		if expr.n_vars() > n_vars {
+			return Err(Error::IncorrectNumberOfVariables {
+				expected: n_vars,
+				actual: expr.n_vars(),
+			});
+		}
+		Ok(Self::compile(n_vars, expr))
+	}
+
+	fn compile(n_vars: usize, expr: ArithExpr<F>) -> Self {
+		let mut steps = Vec::new();
+		let retval = compile_expr(&expr, &mut steps);
+		Self {
+			n_vars,
+			degree: expr.degree(),
+			tower_level: expr.binary_tower_level(),
+			steps,
+			retval,
+			expr,
+		}
+	}
+
+	pub fn expr(&self) -> &ArithExpr<F> {
+		&self.expr
+	}
+
+	/// The number of steps in the compiled circuit.
+	pub fn n_steps(&self) -> usize {
+		self.steps.len()
+	}
+
+	fn check_query_len(&self, len: usize) -> Result<(), Error> {
+		if len != self.n_vars {
+			return Err(Error::IncorrectQuerySize {
+				expected: self.n_vars,
+			});
+		}
+		Ok(())
+	}
+
+	fn evaluate_with<T>(&self, query: &[T], constant: impl Fn(F) -> T) -> T
+	where
+		T: Copy + Default + Add<Output = T> + Mul<Output = T>,
+	{
+		if self.steps.len() <= MAX_STACK_STEPS {
+			let mut step_evals = [T::default(); MAX_STACK_STEPS];
+			self.evaluate_steps(query, &constant, &mut step_evals)
+		} else {
+			let mut step_evals = vec![T::default(); self.steps.len()];
+			self.evaluate_steps(query, &constant, &mut step_evals)
+		}
+	}
+
+	fn evaluate_steps<T>(&self, query: &[T], constant: &impl Fn(F) -> T, step_evals: &mut [T]) -> T
+	where
+		T: Copy + Add<Output = T> + Mul<Output = T>,
+	{
+		for (i, step) in self.steps.iter().enumerate() {
+			let (before, after) = step_evals.split_at_mut(i);
+			let get = |arg: &CircuitStepArgument<F>| match *arg {
+				CircuitStepArgument::Var(index) => query[index],
+				CircuitStepArgument::Const(value) => constant(value),
+				CircuitStepArgument::Step(index) => before[index],
+			};
+			after[0] = match step {
+				CircuitStep::Add(left, right) => get(left) + get(right),
+				CircuitStep::Mul(left, right) => get(left) * get(right),
+				CircuitStep::Square(arg) => {
+					let value = get(arg);
+					value * value
+				}
+			};
+		}
+
+		match self.retval {
+			CircuitStepArgument::Var(index) => query[index],
+			CircuitStepArgument::Const(value) => constant(value),
+			CircuitStepArgument::Step(index) => step_evals[index],
+		}
+	}
+}
+
+fn compile_expr<F: Field>(
+	expr: &ArithExpr<F>,
+	steps: &mut Vec<CircuitStep<F>>,
+) -> CircuitStepArgument<F> {
+	match expr {
+		ArithExpr::Const(value) => CircuitStepArgument::Const(*value),
+		ArithExpr::Var(index) => CircuitStepArgument::Var(*index),
+		ArithExpr::Add(left, right) => {
+			let left = compile_expr(left, steps);
+			let right = compile_expr(right, steps);
+			push_add(left, right, steps)
+		}
+		ArithExpr::Mul(left, right) => {
+			let left = compile_expr(left, steps);
+			let right = compile_expr(right, steps);
+			push_mul(left, right, steps)
+		}
+		ArithExpr::Pow(base, exp) => {
+			let base = compile_expr(base, steps);
+			push_pow(base, *exp, steps)
+		}
+	}
+}
+
+fn push_step<F>(step: CircuitStep<F>, steps: &mut Vec<CircuitStep<F>>) -> CircuitStepArgument<F> {
+	steps.push(step);
+	CircuitStepArgument::Step(steps.len() - 1)
+}
+
+fn push_add<F: Field>(
+	left: CircuitStepArgument<F>,
+	right: CircuitStepArgument<F>,
+	steps: &mut Vec<CircuitStep<F>>,
+) -> CircuitStepArgument<F> {
+	match (left, right) {
+		(CircuitStepArgument::Const(left), CircuitStepArgument::Const(right)) => {
+			CircuitStepArgument::Const(left + right)
+		}
+		(CircuitStepArgument::Const(zero), other) | (other, CircuitStepArgument::Const(zero))
+			if zero == F::ZERO =>
+		{
+			other
+		}
+		_ => push_step(CircuitStep::Add(left, right), steps),
+	}
+}
+
+fn push_mul<F: Field>(
+	left: CircuitStepArgument<F>,
+	right: CircuitStepArgument<F>,
+	steps: &mut Vec<CircuitStep<F>>,
+) -> CircuitStepArgument<F> {
+	match (left, right) {
+		(CircuitStepArgument::Const(left), CircuitStepArgument::Const(right)) => {
+			CircuitStepArgument::Const(left * right)
+		}
+		(CircuitStepArgument::Const(zero), _) | (_, CircuitStepArgument::Const(zero))
+			if zero == F::ZERO =>
+		{
+			CircuitStepArgument::Const(F::ZERO)
+		}
+		(CircuitStepArgument::Const(one), other) | (other, CircuitStepArgument::Const(one))
+			if one == F::ONE =>
+		{
+			other
+		}
+		_ => push_step(CircuitStep::Mul(left, right), steps),
+	}
+}
+
+fn push_square<F: Field>(
+	arg: CircuitStepArgument<F>,
+	steps: &mut Vec<CircuitStep<F>>,
+) -> CircuitStepArgument<F> {
+	match arg {
+		CircuitStepArgument::Const(value) => CircuitStepArgument::Const(value.square()),
+		_ => push_step(CircuitStep::Square(arg), steps),
+	}
+}
+
+/// Expands `base^exp` by left-to-right square-and-multiply.
+fn push_pow<F: Field>(
+	base: CircuitStepArgument<F>,
+	exp: u64,
+	steps: &mut Vec<CircuitStep<F>>,
+) -> CircuitStepArgument<F> {
+	if exp == 0 {
+		return CircuitStepArgument::Const(F::ONE);
+	}
+
+	let mut acc = base;
+	for bit in (0..exp.ilog2()).rev() {
+		acc = push_square(acc, steps);
+		if (exp >> bit) & 1 == 1 {
+			acc = push_mul(acc, base, steps);
+		}
+	}
+	acc
+}
+
+impl<F, P> CompositionPoly<P> for ArithCircuitPoly<F>
+where
+	F: TowerField,
+	P: PackedField,
+	P::Scalar: ExtensionField<F>,
+{
+	fn n_vars(&self) -> usize {
+		self.n_vars
+	}
+
+	fn degree(&self) -> usize {
+		self.degree
+	}
+
+	fn evaluate(&self, query: &[P::Scalar]) -> Result<P::Scalar, Error> {
+		self.check_query_len(query.len())?;
+		Ok(self.evaluate_with(query, Into::into))
+	}
+
+	fn evaluate_packed(&self, query: &[P]) -> Result<P, Error> {
+		self.check_query_len(query.len())?;
+		Ok(self.evaluate_with(query, |value| P::broadcast(value.into())))
+	}
+
+	fn binary_tower_level(&self) -> usize {
+		self.tower_level
+	}
+
+	fn bitsliced_coeffs(&self) -> Option<Vec<P::Scalar>> {
+		// Circuits with only 0 and 1 constants are already polynomials over BinaryField1b.
+		(self.tower_level == 0).then(|| vec![P::Scalar::ONE])
+	}
+
+	fn evaluate_bitsliced(
+		&self,
+		query: &[BitslicedPoly],
+		dst: &mut Vec<BitslicedPoly>,
+	) -> Result<(), Error> {
+		self.check_query_len(query.len())?;
+		if self.tower_level != 0 {
+			return Err(Error::BitslicedEvaluationUnsupported);
+		}
+		dst.push(self.evaluate_with(query, |value| {
+			if value == F::ONE {
+				BitslicedPoly::ONE
+			} else {
+				BitslicedPoly::ZERO
+			}
+		}));
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::polynomial::composition::{empty_mix_composition, BivariateProduct, MixComposition};
+	use binius_field::{
+		BinaryField128b, BinaryField16b, BinaryField1b, BinaryField8b, PackedBinaryField8x16b,
+	};
+	use rand::{rngs::StdRng, SeedableRng};
+	use std::iter::repeat_with;
+
+	type F = BinaryField8b;
+
+	// x0 * x1^5 + 3 * (x2 + 1)^2
+	fn example_expr() -> ArithExpr<F> {
+		let x = ArithExpr::Var;
+		let c = |value| ArithExpr::Const(F::new(value));
+		x(0) * x(1).pow(5) + c(3) * (x(2) + c(1)).pow(2)
+	}
+
+	fn evaluate_tree<P: PackedField>(expr: &ArithExpr<F>, query: &[P]) -> P
+	where
+		P::Scalar: ExtensionField<F>,
+	{
+		match expr {
+			ArithExpr::Const(value) => P::broadcast((*value).into()),
+			ArithExpr::Var(index) => query[*index],
+			ArithExpr::Add(left, right) => evaluate_tree(left, query) + evaluate_tree(right, query),
+			ArithExpr::Mul(left, right) => evaluate_tree(left, query) * evaluate_tree(right, query),
+			ArithExpr::Pow(base, exp) => (0..*exp).map(|_| evaluate_tree(base, query)).product(),
+		}
+	}
+
+	#[test]
+	fn test_degree_and_arity() {
+		let expr = example_expr();
+		assert_eq!(expr.degree(), 6);
+		assert_eq!(expr.n_vars(), 3);
+		assert_eq!(expr.binary_tower_level(), 1);
+
+		let circuit = ArithCircuitPoly::new(expr.clone());
+		assert_eq!(CompositionPoly::<BinaryField128b>::degree(&circuit), 6);
+		assert_eq!(CompositionPoly::<BinaryField128b>::n_vars(&circuit), 3);
+		assert_eq!(CompositionPoly::<BinaryField128b>::binary_tower_level(&circuit), 1);
+
+		let circuit = ArithCircuitPoly::with_n_vars(5, expr.clone()).unwrap();
+		assert_eq!(CompositionPoly::<BinaryField128b>::n_vars(&circuit), 5);
+		assert!(ArithCircuitPoly::with_n_vars(2, expr).is_err());
+
+		// Powers of constants are folded and x^0 is the constant 1.
+		let x = ArithExpr::<F>::Var;
+		let folded = ArithCircuitPoly::new(ArithExpr::Const(F::new(7)).pow(9) * x(0).pow(0));
+		assert_eq!(folded.n_steps(), 0);
+
+		// Subtraction is a single addition in characteristic 2.
+		assert_eq!(ArithCircuitPoly::new(x(0) - x(1)).n_steps(), 1);
+	}
+
+	#[test]
+	fn test_evaluate_matches_expression_tree() {
+		let mut rng = StdRng::seed_from_u64(0);
+		let expr = example_expr();
+		let circuit = ArithCircuitPoly::new(expr.clone());
+
+		let query = repeat_with(|| <BinaryField128b as Field>::random(&mut rng))
+			.take(3)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			CompositionPoly::<BinaryField128b>::evaluate(&circuit, &query).unwrap(),
+			evaluate_tree(&expr, &query)
+		);
+
+		let packed_query = repeat_with(|| PackedBinaryField8x16b::random(&mut rng))
+			.take(3)
+			.collect::<Vec<_>>();
+		let packed_eval = circuit.evaluate_packed(&packed_query).unwrap();
+		assert_eq!(packed_eval, evaluate_tree(&expr, &packed_query));
+		for i in 0..PackedBinaryField8x16b::WIDTH {
+			let query = packed_query
+				.iter()
+				.map(|packed| packed.get(i))
+				.collect::<Vec<_>>();
+			assert_eq!(
+				CompositionPoly::<PackedBinaryField8x16b>::evaluate(&circuit, &query).unwrap(),
+				packed_eval.get(i)
+			);
+		}
+
+		assert!(CompositionPoly::<BinaryField128b>::evaluate(&circuit, &query[..2]).is_err());
+	}
+
+	#[test]
+	fn test_evaluate_bitsliced() {
+		let x = ArithExpr::<BinaryField1b>::Var;
+		let one = ArithExpr::Const(BinaryField1b::ONE);
+		let circuit = ArithCircuitPoly::new(x(0) * (x(1) + one).pow(2) + x(2).pow(3));
+		let query = [
+			BitslicedPoly::linear(0b0011, 0b0101),
+			BitslicedPoly::linear(0b1010, 0b0110),
+			BitslicedPoly::constant(0b1001),
+		];
+
+		let mut dst = Vec::new();
+		CompositionPoly::<BinaryField128b>::evaluate_bitsliced(&circuit, &query, &mut dst).unwrap();
+		assert_eq!(dst, vec![evaluate_tree_bitsliced(&query)]);
+		assert_eq!(
+			CompositionPoly::<BinaryField128b>::bitsliced_coeffs(&circuit),
+			Some(vec![BinaryField128b::ONE])
+		);
+
+		let circuit = ArithCircuitPoly::new(example_expr());
+		assert_eq!(CompositionPoly::<BinaryField128b>::bitsliced_coeffs(&circuit), None);
+	}
+
+	fn evaluate_tree_bitsliced(query: &[BitslicedPoly]) -> BitslicedPoly {
+		let x1_plus_one = query[1] + BitslicedPoly::ONE;
+		query[0] * x1_plus_one * x1_plus_one + query[2] * query[2] * query[2]
+	}
+
+	#[test]
+	fn test_mix_composition() {
+		type P = PackedBinaryField8x16b;
+		let mut rng = StdRng::seed_from_u64(0);
+		let challenge = <BinaryField16b as Field>::random(&mut rng);
+
+		let circuit = ArithCircuitPoly::with_n_vars(3, example_expr()).unwrap();
+		let product = ArithCircuitPoly::with_n_vars(
+			3,
+			ArithExpr::<BinaryField1b>::Var(0) * ArithExpr::Var(1),
+		)
+		.unwrap();
+		let mix: MixComposition<P, _> = empty_mix_composition(3, challenge)
+			.include([circuit.clone()])
+			.unwrap()
+			.include([product])
+			.unwrap();
+		assert_eq!(mix.degree(), 6);
+
+		let query = repeat_with(|| P::random(&mut rng))
+			.take(3)
+			.collect::<Vec<_>>();
+		let expected = evaluate_tree(&example_expr(), &query) * challenge
+			+ CompositionPoly::<P>::evaluate_packed(&BivariateProduct, &query[..2]).unwrap();
+		assert_eq!(mix.evaluate_packed(&query).unwrap(), expected);
+
+		let mismatched = ArithCircuitPoly::new(example_expr() * ArithExpr::Var(3));
+		assert!(empty_mix_composition::<P>(3, challenge)
+			.include([mismatched])
+			.is_err());
+	}
+}
@@ -1,7 +1,7 @@
 // Copyright 2024 Ulvetanna Inc.
 
-use crate::polynomial::{CompositionPoly, Error};
-use binius_field::PackedField;
+use crate::polynomial::{BitslicedPoly, CompositionPoly, Error};
+use binius_field::{Field, PackedField};
 
 #[derive(Debug, Copy, Clone)]
 pub struct BivariateProduct;
@@ -32,4 +32,20 @@ impl<P: PackedField> CompositionPoly<P> for BivariateProduct {
 	fn binary_tower_level(&self) -> usize {
 		0
 	}
+
+	fn bitsliced_coeffs(&self) -> Option<Vec<P::Scalar>> {
+		Some(vec![P::Scalar::ONE])
+	}
+
+	fn evaluate_bitsliced(
+		&self,
+		query: &[BitslicedPoly],
+		dst: &mut Vec<BitslicedPoly>,
+	) -> Result<(), Error> {
+		if query.len() != 2 {
+			return Err(Error::IncorrectQuerySize { expected: 2 });
+		}
+		dst.push(query[0] * query[1]);
+		Ok(())
+	}
 }
@@ -1,6 +1,6 @@
 // Copyright 2024 Ulvetanna Inc.
 
-use crate::polynomial::{CompositionPoly, Error};
+use crate::polynomial::{BitslicedPoly, CompositionPoly, Error};
 use binius_field::PackedField;
 use std::fmt::Debug;
 
@@ -52,6 +52,25 @@ impl<P: PackedField, C: CompositionPoly<P>, const N: usize> CompositionPoly<P>
 	fn binary_tower_level(&self) -> usize {
 		self.composition.binary_tower_level()
 	}
+
+	fn bitsliced_coeffs(&self) -> Option<Vec<P::Scalar>> {
+		self.composition.bitsliced_coeffs()
+	}
+
+	fn evaluate_bitsliced(
+		&self,
+		query: &[BitslicedPoly],
+		dst: &mut Vec<BitslicedPoly>,
+	) -> Result<(), Error> {
+		if query.len() != self.n_vars {
+			return Err(Error::IncorrectQuerySize {
+				expected: self.n_vars,
+			});
+		}
+
+		let subquery = self.indices.map(|index| query[index]);
+		self.composition.evaluate_bitsliced(&subquery, dst)
+	}
 }
 
 /// A factory helper method to create an [`IndexComposition`] by looking at
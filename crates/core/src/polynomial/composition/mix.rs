@@ -1,6 +1,6 @@
 // Copyright 2024 Ulvetanna Inc.
 
-use crate::polynomial::{CompositionPoly, Error};
+use crate::polynomial::{BitslicedPoly, CompositionPoly, Error};
 use binius_field::{Field, PackedField, TowerField};
 use std::{fmt::Debug, marker::PhantomData};
 
@@ -40,6 +40,15 @@ pub trait HornerCompositions<P: PackedField> {
 		challenge: P::Scalar,
 		inner_evals: &[P::Scalar],
 	) -> Result<P::Scalar, Error>;
+	/// Appends the evaluation of each inner composition, in the order they were included.
+	fn evaluate_each(&self, query: &[P::Scalar], evals: &mut Vec<P::Scalar>) -> Result<(), Error>;
+	/// Returns the bit-sliced decomposition coefficients of the mix, in the order they were included.
+	fn bitsliced_coeffs(&self, challenge: P::Scalar) -> Option<Vec<P::Scalar>>;
+	fn evaluate_bitsliced(
+		&self,
+		query: &[BitslicedPoly],
+		dst: &mut Vec<BitslicedPoly>,
+	) -> Result<(), Error>;
 }
 
 impl<P: PackedField> HornerCompositions<P> for () {
@@ -66,6 +75,26 @@ impl<P: PackedField> HornerCompositions<P> for () {
 
 		Ok(P::Scalar::ZERO)
 	}
+
+	fn evaluate_each(
+		&self,
+		_query: &[P::Scalar],
+		_evals: &mut Vec<P::Scalar>,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn bitsliced_coeffs(&self, _challenge: P::Scalar) -> Option<Vec<P::Scalar>> {
+		Some(Vec::new())
+	}
+
+	fn evaluate_bitsliced(
+		&self,
+		_query: &[BitslicedPoly],
+		_dst: &mut Vec<BitslicedPoly>,
+	) -> Result<(), Error> {
+		Ok(())
+	}
 }
 
 impl<P: PackedField, C, IC> HornerCompositions<P> for (Vec<C>, IC)
@@ -120,6 +149,36 @@ where
 
 		Ok(acc)
 	}
+
+	fn evaluate_each(&self, query: &[P::Scalar], evals: &mut Vec<P::Scalar>) -> Result<(), Error> {
+		self.1.evaluate_each(query, evals)?;
+		for inner_poly in &self.0 {
+			evals.push(inner_poly.evaluate(query)?);
+		}
+		Ok(())
+	}
+
+	fn bitsliced_coeffs(&self, challenge: P::Scalar) -> Option<Vec<P::Scalar>> {
+		// Horner's scheme multiplies everything included earlier by the challenge.
+		let mut coeffs = self.1.bitsliced_coeffs(challenge)?;
+		for inner_poly in &self.0 {
+			coeffs.iter_mut().for_each(|coeff| *coeff *= challenge);
+			coeffs.extend(inner_poly.bitsliced_coeffs()?);
+		}
+		Some(coeffs)
+	}
+
+	fn evaluate_bitsliced(
+		&self,
+		query: &[BitslicedPoly],
+		dst: &mut Vec<BitslicedPoly>,
+	) -> Result<(), Error> {
+		self.1.evaluate_bitsliced(query, dst)?;
+		for inner_poly in &self.0 {
+			inner_poly.evaluate_bitsliced(query, dst)?;
+		}
+		Ok(())
+	}
 }
 
 impl<P, IC> CompositionPoly<P> for MixComposition<P, IC>
@@ -153,6 +212,23 @@ where
 	fn binary_tower_level(&self) -> usize {
 		P::Scalar::TOWER_LEVEL
 	}
+
+	fn bitsliced_coeffs(&self) -> Option<Vec<P::Scalar>> {
+		self.inner_compositions.bitsliced_coeffs(self.challenge)
+	}
+
+	fn evaluate_bitsliced(
+		&self,
+		query: &[BitslicedPoly],
+		dst: &mut Vec<BitslicedPoly>,
+	) -> Result<(), Error> {
+		if query.len() != self.n_vars {
+			return Err(Error::IncorrectQuerySize {
+				expected: self.n_vars,
+			});
+		}
+		self.inner_compositions.evaluate_bitsliced(query, dst)
+	}
 }
 
 pub fn empty_mix_composition<P: PackedField>(
@@ -211,4 +287,16 @@ impl<P: PackedField, IC: HornerCompositions<P>> MixComposition<P, IC> {
 		self.inner_compositions
 			.evaluate_with_inner_evals(self.challenge, inner_evals)
 	}
+
+	/// Evaluates each mixed composition separately, in the order they were included.
+	pub fn evaluate_inner(&self, query: &[P::Scalar]) -> Result<Vec<P::Scalar>, Error> {
+		if query.len() != self.n_vars {
+			return Err(Error::IncorrectQuerySize {
+				expected: self.n_vars,
+			});
+		}
+		let mut evals = Vec::new();
+		self.inner_compositions.evaluate_each(query, &mut evals)?;
+		Ok(evals)
+	}
 }
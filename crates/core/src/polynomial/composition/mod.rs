@@ -1,9 +1,11 @@
 // Copyright 2024 Ulvetanna Inc.
 
+pub mod arith_circuit;
 pub mod bivariate_product;
 pub mod index;
 pub mod mix;
 
+pub use arith_circuit::*;
 pub use bivariate_product::*;
 pub use index::*;
 pub use mix::*;
@@ -38,6 +38,8 @@ pub enum Error {
 	HypercubeIndexOutOfRange { index: usize },
 	#[error("MultilinearQuery is full, cannot update further. Has {max_query_vars} variables")]
 	MultilinearQueryFull { max_query_vars: usize },
+	#[error("the composition does not support bit-sliced evaluation")]
+	BitslicedEvaluationUnsupported,
 	#[error("mixed polynomial was not provided")]
 	MixedMultilinearNotFound,
 	#[error("MultilinearComposite constructed with incorrect arguments: {0}")]
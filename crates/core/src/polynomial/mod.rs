@@ -1,5 +1,7 @@
 // Copyright 2023 Ulvetanna Inc.
 
+pub mod arith_expr;
+pub mod bitsliced;
 pub mod composition;
 pub mod error;
 pub mod multilinear;
@@ -10,6 +12,8 @@ pub mod transparent;
 pub mod univariate;
 pub mod util;
 
+pub use arith_expr::*;
+pub use bitsliced::*;
 pub use error::*;
 pub use multilinear::*;
 pub use multilinear_extension::*;
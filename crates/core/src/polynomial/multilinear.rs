@@ -68,6 +68,14 @@ pub trait MultilinearPoly<P: PackedField>: Debug {
 
 	/// Get a subcube of the boolean hypercube of a given size.
 	fn subcube_evals(&self, vars: usize, index: usize, dst: &mut [P]) -> Result<(), Error>;
+
+	/// Get the evaluations at 128 consecutive hypercube vertices as the bits of a `u128`.
+	///
+	/// Bit `i` of the result is the evaluation at vertex `128 * index + i`. Returns `None` if the
+	/// evaluations are not stored as packed `BinaryField1b` values, or if `index` is out of range.
+	fn evaluate_on_hypercube_bits(&self, _index: usize) -> Option<u128> {
+		None
+	}
 }
 
 impl<T, P: PackedField> MultilinearPoly<P> for T
@@ -128,4 +136,8 @@ where
 	fn subcube_evals(&self, vars: usize, index: usize, dst: &mut [P]) -> Result<(), Error> {
 		(**self).subcube_evals(vars, index, dst)
 	}
+
+	fn evaluate_on_hypercube_bits(&self, index: usize) -> Option<u128> {
+		(**self).evaluate_on_hypercube_bits(index)
+	}
 }
@@ -4,12 +4,13 @@ use super::{error::Error, multilinear::MultilinearPoly, multilinear_query::Multi
 use binius_field::{
 	packed::{get_packed_slice, iter_packed_slice, set_packed_slice},
 	util::{inner_product_par, inner_product_unchecked},
-	ExtensionField, Field, PackedField,
+	ExtensionField, Field, PackedBinaryField128x1b, PackedField,
 };
+use bytemuck::must_cast;
 use itertools::Either;
 use p3_util::log2_strict_usize;
 use rayon::prelude::*;
-use std::{borrow::Cow, fmt::Debug, marker::PhantomData, sync::Arc};
+use std::{any::Any, borrow::Cow, fmt::Debug, marker::PhantomData, sync::Arc};
 
 /// A multilinear polynomial represented by its evaluations over the boolean hypercube.
 ///
@@ -434,6 +435,13 @@ where
 		}
 		Ok(())
 	}
+
+	fn evaluate_on_hypercube_bits(&self, index: usize) -> Option<u128> {
+		let packed = self.0.evals().get(index)?;
+		(packed as &dyn Any)
+			.downcast_ref::<PackedBinaryField128x1b>()
+			.map(|&packed| must_cast(packed))
+	}
 }
 
 /// Expand the tensor product of the query values.
@@ -619,4 +627,26 @@ mod tests {
 		assert_eq!(poly.evaluate_subcube(0, &query).unwrap(), BinaryField128b::new(2));
 		assert_eq!(poly.evaluate_subcube(1, &query).unwrap(), BinaryField128b::new(9));
 	}
+
+	#[test]
+	fn test_evaluate_on_hypercube_bits() {
+		let words = [0x1234_5678_9abc_def0_u128, u128::MAX - 5];
+		let packed =
+			MultilinearExtension::from_values(words.map(PackedBinaryField128x1b::from).to_vec())
+				.unwrap()
+				.specialize::<BinaryField128b>();
+		for (index, word) in words.into_iter().enumerate() {
+			assert_eq!(packed.evaluate_on_hypercube_bits(index), Some(word));
+			for i in [0, 3, 64, 127] {
+				let bit = packed.evaluate_on_hypercube((index << 7) + i).unwrap();
+				assert_eq!(bit == BinaryField128b::ONE, (word >> i) & 1 == 1);
+			}
+		}
+		assert_eq!(packed.evaluate_on_hypercube_bits(2), None);
+
+		let unpacked = MultilinearExtension::from_values(vec![BinaryField32b::ONE; 256])
+			.unwrap()
+			.specialize::<BinaryField128b>();
+		assert_eq!(unpacked.evaluate_on_hypercube_bits(0), None);
+	}
 }
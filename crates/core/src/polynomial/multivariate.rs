@@ -1,7 +1,9 @@
 // Copyright 2023 Ulvetanna Inc.
 
-use super::{error::Error, multilinear_query::MultilinearQuery, MultilinearPoly};
-use binius_field::PackedField;
+use super::{
+	bitsliced::BitslicedPoly, error::Error, multilinear_query::MultilinearQuery, MultilinearPoly,
+};
+use binius_field::{Field, PackedField};
 use std::{borrow::Borrow, fmt::Debug, marker::PhantomData};
 
 pub trait MultivariatePoly<F>: Debug + Send + Sync {
@@ -34,6 +36,23 @@ where
 
 	/// Returns the maximum binary tower level of a constant used in the composition
 	fn binary_tower_level(&self) -> usize;
+
+	/// Returns the coefficients $c_t$ of a decomposition $\sum_t c_t g_t$ of the polynomial into
+	/// polynomials $g_t$ with `BinaryField1b` coefficients, or `None` if the composition does not
+	/// support bit-sliced evaluation.
+	fn bitsliced_coeffs(&self) -> Option<Vec<P::Scalar>> {
+		None
+	}
+
+	/// Appends the bit-sliced evaluations of the polynomials $g_t$ to `dst`, in the order of
+	/// their coefficients in [`Self::bitsliced_coeffs`].
+	fn evaluate_bitsliced(
+		&self,
+		_query: &[BitslicedPoly],
+		_dst: &mut Vec<BitslicedPoly>,
+	) -> Result<(), Error> {
+		Err(Error::BitslicedEvaluationUnsupported)
+	}
 }
 
 /// Identity composition function $g(X) = X$.
@@ -63,6 +82,22 @@ impl<P: PackedField> CompositionPoly<P> for IdentityCompositionPoly {
 	fn binary_tower_level(&self) -> usize {
 		0
 	}
+
+	fn bitsliced_coeffs(&self) -> Option<Vec<P::Scalar>> {
+		Some(vec![P::Scalar::ONE])
+	}
+
+	fn evaluate_bitsliced(
+		&self,
+		query: &[BitslicedPoly],
+		dst: &mut Vec<BitslicedPoly>,
+	) -> Result<(), Error> {
+		if query.len() != 1 {
+			return Err(Error::IncorrectQuerySize { expected: 1 });
+		}
+		dst.push(query[0]);
+		Ok(())
+	}
 }
 
 /// A polynomial defined as the composition of several multilinear polynomials.
@@ -2,6 +2,7 @@
 
 pub mod disjoint_product;
 pub mod eq_ind;
+pub mod powers;
 pub mod select_row;
 pub mod shift_ind;
 pub mod step_down;
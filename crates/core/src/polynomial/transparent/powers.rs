@@ -0,0 +1,111 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use crate::polynomial::{Error, MultilinearExtension, MultivariatePoly};
+use binius_field::{Field, PackedField};
+
+/// Represents the multilinear extension of the powers of a field element $g$, whose evaluation
+/// at the $i$-th vertex of the hypercube is $g^i$.
+///
+/// As $i = \sum_k 2^k i_k$, the multilinear extension factors as
+/// $\prod_k (1 - X_k + X_k g^{2^k})$. When $g$ generates a multiplicative group of order larger
+/// than $2^{n}$, the evaluations are distinct, which makes this a compact column of step indices.
+#[derive(Debug, Clone)]
+pub struct Powers<F: Field> {
+	n_vars: usize,
+	base: F,
+}
+
+impl<F: Field> Powers<F> {
+	pub fn new(n_vars: usize, base: F) -> Self {
+		Self { n_vars, base }
+	}
+
+	pub fn multilinear_extension<P: PackedField<Scalar = F>>(
+		&self,
+	) -> Result<MultilinearExtension<'static, P>, Error> {
+		if self.n_vars < P::LOG_WIDTH {
+			return Err(Error::PackedFieldNotFilled {
+				length: 1 << self.n_vars,
+				packed_width: 1 << P::LOG_WIDTH,
+			});
+		}
+		let mut power = F::ONE;
+		let values = (0..1 << (self.n_vars - P::LOG_WIDTH))
+			.map(|_| {
+				let mut packed_value = P::default();
+				for i in 0..P::WIDTH {
+					packed_value.set(i, power);
+					power *= self.base;
+				}
+				packed_value
+			})
+			.collect();
+		MultilinearExtension::from_values(values)
+	}
+}
+
+impl<F: Field> MultivariatePoly<F> for Powers<F> {
+	fn n_vars(&self) -> usize {
+		self.n_vars
+	}
+
+	fn degree(&self) -> usize {
+		self.n_vars
+	}
+
+	fn evaluate(&self, query: &[F]) -> Result<F, Error> {
+		if query.len() != self.n_vars {
+			return Err(Error::IncorrectQuerySize {
+				expected: self.n_vars,
+			});
+		}
+
+		let mut result = F::ONE;
+		let mut base_power = self.base;
+		for &query_i in query {
+			result *= F::ONE - query_i + query_i * base_power;
+			base_power = base_power.square();
+		}
+		Ok(result)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::polynomial::multilinear_query::MultilinearQuery;
+	use binius_field::{BinaryField, BinaryField128b, BinaryField32b, PackedBinaryField4x32b};
+	use rand::{rngs::StdRng, SeedableRng};
+	use std::iter::repeat_with;
+
+	#[test]
+	fn test_consistency() {
+		type F = BinaryField32b;
+		let mut rng = StdRng::seed_from_u64(0);
+
+		let powers = Powers::new(5, F::MULTIPLICATIVE_GENERATOR);
+		let challenge = repeat_with(|| <F as Field>::random(&mut rng))
+			.take(5)
+			.collect::<Vec<_>>();
+
+		let eval1 = powers.evaluate(&challenge).unwrap();
+		let multilin_query = MultilinearQuery::<F>::with_full_query(&challenge).unwrap();
+		let mle = powers
+			.multilinear_extension::<PackedBinaryField4x32b>()
+			.unwrap();
+		let eval2 = mle.evaluate(&multilin_query).unwrap();
+		assert_eq!(eval1, eval2);
+	}
+
+	#[test]
+	fn test_hypercube_evals() {
+		type F = BinaryField128b;
+		let base = F::new(0x1234);
+		let mle = Powers::new(3, base).multilinear_extension::<F>().unwrap();
+		let mut power = F::ONE;
+		for i in 0..8 {
+			assert_eq!(mle.evaluate_on_hypercube(i).unwrap(), power);
+			power *= base;
+		}
+	}
+}
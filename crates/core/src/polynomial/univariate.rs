@@ -109,6 +109,32 @@ impl<F: Field> EvaluationDomain<F> {
 
 		Ok(result)
 	}
+
+	/// Evaluates every Lagrange basis polynomial of the domain at `x`.
+	///
+	/// The inner product of the result with the values of a polynomial on the domain equals
+	/// [`Self::extrapolate`] at `x`.
+	pub fn lagrange_evals<FE: ExtensionField<F>>(&self, x: FE) -> Vec<FE> {
+		let n = self.size();
+
+		// suffix_products[i] is the product of (x - x_j) for j >= i
+		let mut suffix_products = vec![FE::ONE; n + 1];
+		for (i, &x_i) in self.points.iter().enumerate().rev() {
+			suffix_products[i] = suffix_products[i + 1] * (x - x_i);
+		}
+
+		let mut prefix_product = FE::ONE;
+		self.points
+			.iter()
+			.zip(self.weights.iter())
+			.zip(suffix_products.iter().skip(1))
+			.map(|((&x_i, &weight), &suffix_product)| {
+				let eval = prefix_product * suffix_product * weight;
+				prefix_product *= x - x_i;
+				eval
+			})
+			.collect()
+	}
 }
 
 #[inline]
@@ -258,4 +284,31 @@ mod tests {
 		let interpolated = domain.interpolate(&values).unwrap();
 		assert_eq!(interpolated, coeffs);
 	}
+
+	#[test]
+	fn test_lagrange_evals_match_extrapolate() {
+		let mut rng = StdRng::seed_from_u64(0);
+		let domain = <EvaluationDomain<BinaryField8b>>::new(8).unwrap();
+
+		let values = repeat_with(|| <BinaryField32b as Field>::random(&mut rng))
+			.take(8)
+			.collect::<Vec<_>>();
+		let x = <BinaryField32b as Field>::random(&mut rng);
+
+		let lagrange_evals = domain.lagrange_evals(x);
+		let expected_y = domain.extrapolate(&values, x).unwrap();
+		assert_eq!(
+			lagrange_evals
+				.iter()
+				.zip(values.iter())
+				.map(|(&l, &v)| l * v)
+				.sum::<BinaryField32b>(),
+			expected_y
+		);
+
+		// On the domain, the Lagrange basis is the unit vectors
+		let lagrange_evals = domain.lagrange_evals(BinaryField32b::from(domain.points()[3]));
+		assert_eq!(lagrange_evals[3], BinaryField32b::ONE);
+		assert_eq!(lagrange_evals.iter().sum::<BinaryField32b>(), BinaryField32b::ONE);
+	}
 }
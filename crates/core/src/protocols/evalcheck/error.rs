@@ -3,6 +3,7 @@
 use crate::{
 	oracle::{BatchId, CommittedId, Error as OracleError, OracleId},
 	polynomial::Error as PolynomialError,
+	serialization::Error as SerializationError,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -25,6 +26,8 @@ pub enum Error {
 	Polynomial(#[from] PolynomialError),
 	#[error("verification failure: {0}")]
 	Verification(#[from] VerificationError),
+	#[error("transcript error: {0}")]
+	Transcript(#[from] SerializationError),
 }
 
 #[derive(Debug, thiserror::Error)]
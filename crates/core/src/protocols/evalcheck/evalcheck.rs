@@ -2,7 +2,7 @@
 
 use super::error::Error;
 use crate::oracle::{
-	BatchId, CommittedBatch, CommittedId, CompositePolyOracle, MultilinearPolyOracle,
+	BatchId, CommittedBatch, CommittedId, CompositePolyOracle, MultilinearPolyOracle, RoundId,
 };
 use binius_field::Field;
 use tracing::instrument;
@@ -31,7 +31,7 @@ pub struct EvalcheckMultilinearClaim<F: Field> {
 	pub is_random_point: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EvalcheckProof<F: Field> {
 	Transparent,
 	Committed,
@@ -81,6 +81,8 @@ pub struct SameQueryPcsClaim<F: Field> {
 pub struct BatchCommittedEvalClaims<F: Field> {
 	/// Number of polynomials in each batch
 	batch_lengths: Vec<usize>,
+	/// Commitment round of each batch
+	round_ids: Vec<RoundId>,
 	/// Claims accumulated for each batch
 	claims_by_batch: Vec<Vec<CommittedEvalClaim<F>>>,
 }
@@ -92,10 +94,12 @@ impl<F: Field> BatchCommittedEvalClaims<F> {
 	/// within batch is important.
 	pub fn new(batches: &[CommittedBatch]) -> Self {
 		let batch_lengths = batches.iter().map(|batch| batch.n_polys).collect();
+		let round_ids = batches.iter().map(|batch| batch.round_id).collect();
 		let claims_by_batch = vec![vec![]; batches.len()];
 
 		Self {
 			batch_lengths,
+			round_ids,
 			claims_by_batch,
 		}
 	}
@@ -109,6 +113,24 @@ impl<F: Field> BatchCommittedEvalClaims<F> {
 		self.claims_by_batch.len()
 	}
 
+	/// The commitment round in which a batch is committed.
+	pub fn round_id(&self, batch_id: BatchId) -> Result<RoundId, Error> {
+		self.round_ids
+			.get(batch_id)
+			.copied()
+			.ok_or(Error::UnknownBatchId(batch_id))
+	}
+
+	/// Returns the IDs of all batches, ordered by commitment round and then by batch ID.
+	///
+	/// Batch openings must be proven in this order so that the opening proofs are bound to the
+	/// transcript in the same order as the commitments.
+	pub fn batch_ids_in_round_order(&self) -> Vec<BatchId> {
+		let mut batch_ids = (0..self.n_batches()).collect::<Vec<_>>();
+		batch_ids.sort_by_key(|&batch_id| self.round_ids[batch_id]);
+		batch_ids
+	}
+
 	/// Extract a same query claim, if possible (hence the Option in happy path)
 	// #[instrument(skip_all, name = "evalcheck::extract_pcs_claim")]
 	pub fn try_extract_same_query_pcs_claim(
@@ -12,7 +12,10 @@ use super::{
 	},
 };
 use crate::{
+	challenger::CanObserve,
 	oracle::{MultilinearOracleSet, MultilinearPolyOracle, ProjectionVariant},
+	serialization::SerializeCanonical,
+	transcript::ProverTranscript,
 	witness::MultilinearWitnessIndex,
 };
 use binius_field::{PackedField, PackedFieldIndexable, TowerField};
@@ -99,6 +102,24 @@ where
 		self.prove_composite(composite.inner_polys().into_iter(), eval_point, is_random_point)
 	}
 
+	/// Prove an evalcheck claim, writing the proof to a transcript.
+	///
+	/// The subclaim evaluations in the proof are observed by the transcript, so they are bound
+	/// before any challenge of the subsequent sumchecks is sampled.
+	pub fn prove_with_transcript<CH>(
+		&mut self,
+		evalcheck_claim: EvalcheckClaim<F>,
+		transcript: &mut ProverTranscript<CH>,
+	) -> Result<(), Error>
+	where
+		F: SerializeCanonical,
+		CH: CanObserve<F>,
+	{
+		let proof = self.prove(evalcheck_claim)?;
+		transcript.write_message(&proof);
+		Ok(())
+	}
+
 	fn prove_composite(
 		&mut self,
 		multilin_oracles: impl Iterator<Item = MultilinearPolyOracle<F>>,
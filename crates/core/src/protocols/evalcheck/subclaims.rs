@@ -13,6 +13,7 @@
 use super::{
 	error::Error,
 	evalcheck::{BatchCommittedEvalClaims, CommittedEvalClaim},
+	EvalcheckProver, EvalcheckVerifier,
 };
 use crate::{
 	oracle::{
@@ -264,6 +265,59 @@ where
 	)
 }
 
+/// Create sumcheck claims for committed evalcheck claims on differing eval points.
+///
+/// Verifier counterpart of [`make_non_same_query_pcs_sumchecks`].
+pub fn make_non_same_query_pcs_sumcheck_claims<F: TowerField>(
+	verifier: &mut EvalcheckVerifier<F>,
+	committed_eval_claims: &[CommittedEvalClaim<F>],
+) -> Result<Vec<SumcheckClaim<F>>, Error> {
+	let metas = non_same_query_pcs_sumcheck_metas(
+		verifier.oracles,
+		committed_eval_claims,
+		&mut verifier.batch_committed_eval_claims,
+	)?;
+
+	metas
+		.into_iter()
+		.map(|meta| non_same_query_pcs_sumcheck_claim(verifier.oracles, meta))
+		.collect()
+}
+
+/// Create sumcheck claims and witnesses for committed evalcheck claims on differing eval points.
+///
+/// See [`non_same_query_pcs_sumcheck_metas`] for details.
+pub fn make_non_same_query_pcs_sumchecks<'a, 'b, F, PW>(
+	prover: &mut EvalcheckProver<'a, 'b, F, PW>,
+	committed_eval_claims: &[CommittedEvalClaim<F>],
+) -> Result<Vec<BivariateSumcheck<'b, F, PW>>, Error>
+where
+	F: TowerField + From<PW::Scalar>,
+	PW: PackedField,
+	PW::Scalar: From<F>,
+{
+	let metas = non_same_query_pcs_sumcheck_metas(
+		prover.oracles,
+		committed_eval_claims,
+		&mut prover.batch_committed_eval_claims,
+	)?;
+
+	let mut memoized_queries = MemoizedQueries::new();
+
+	metas
+		.into_iter()
+		.map(|meta| {
+			let claim = non_same_query_pcs_sumcheck_claim(prover.oracles, meta.clone())?;
+			let witness = non_same_query_pcs_sumcheck_witness(
+				prover.witness_index,
+				&mut memoized_queries,
+				meta,
+			)?;
+			Ok((claim, witness))
+		})
+		.collect()
+}
+
 #[derive(Clone, Copy)]
 pub struct ProjectedBivariateMeta {
 	inner_id: OracleId,
@@ -9,9 +9,12 @@ use super::{
 	subclaims::{packed_sumcheck_meta, projected_bivariate_claim, shifted_sumcheck_meta},
 };
 use crate::{
+	challenger::CanObserve,
 	oracle::{MultilinearOracleSet, MultilinearPolyOracle, ProjectionVariant},
 	polynomial::extrapolate_line,
 	protocols::sumcheck::SumcheckClaim,
+	serialization::DeserializeCanonical,
+	transcript::VerifierTranscript,
 };
 use binius_field::{util::inner_product_unchecked, TowerField};
 use getset::{Getters, MutGetters};
@@ -102,6 +105,23 @@ impl<'a, F: TowerField> EvalcheckVerifier<'a, F> {
 		Ok(())
 	}
 
+	/// Verify an evalcheck claim, reading the proof from a transcript.
+	///
+	/// Counterpart of
+	/// [`EvalcheckProver::prove_with_transcript`](`super::prove::EvalcheckProver::prove_with_transcript`).
+	pub fn verify_with_transcript<CH>(
+		&mut self,
+		evalcheck_claim: EvalcheckClaim<F>,
+		transcript: &mut VerifierTranscript<'_, CH>,
+	) -> Result<(), Error>
+	where
+		F: DeserializeCanonical,
+		CH: CanObserve<F>,
+	{
+		let evalcheck_proof = transcript.read_message::<F, EvalcheckProof<F>>()?;
+		self.verify(evalcheck_claim, evalcheck_proof)
+	}
+
 	fn verify_multilinear(
 		&mut self,
 		evalcheck_claim: EvalcheckMultilinearClaim<F>,
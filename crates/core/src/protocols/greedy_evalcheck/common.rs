@@ -9,7 +9,7 @@ use crate::{
 };
 use binius_field::Field;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct GreedyEvalcheckProof<F: Field> {
 	pub initial_evalcheck_proofs: Vec<EvalcheckProof<F>>,
 	pub virtual_opening_proofs: Vec<(SumcheckBatchProof<F>, Vec<EvalcheckProof<F>>)>,
@@ -22,3 +22,15 @@ pub struct GreedyEvalcheckProveOutput<F: Field> {
 	pub same_query_claims: Vec<(BatchId, SameQueryPcsClaim<F>)>,
 	pub proof: GreedyEvalcheckProof<F>,
 }
+
+/// Step of the greedy evalcheck protocol that a proof message belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum GreedyEvalcheckStep {
+	/// Proofs of the initial evalcheck claims.
+	Initial,
+	/// Reduction of the pending virtual polynomial openings.
+	VirtualOpening,
+	/// Reduction of the committed evaluation claims of a batch to a single query. Carries the
+	/// position of the batch in commitment round order.
+	BatchOpening(usize),
+}
@@ -1,6 +1,9 @@
 // Copyright 2024 Ulvetanna Inc.
 
-use crate::protocols::{evalcheck, sumcheck};
+use crate::{
+	protocols::{evalcheck, sumcheck},
+	serialization::Error as SerializationError,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -20,4 +23,6 @@ pub enum Error {
 	Evalcheck(#[from] evalcheck::Error),
 	#[error("sumcheck error: {0}")]
 	Sumcheck(#[from] sumcheck::Error),
+	#[error("transcript error: {0}")]
+	Transcript(#[from] SerializationError),
 }
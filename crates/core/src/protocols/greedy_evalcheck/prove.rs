@@ -1,20 +1,27 @@
 // Copyright 2024 Ulvetanna Inc.
 
 use super::{
-	common::{GreedyEvalcheckProof, GreedyEvalcheckProveOutput},
+	common::{GreedyEvalcheckProof, GreedyEvalcheckProveOutput, GreedyEvalcheckStep},
 	error::Error,
 };
 use crate::{
-	oracle::MultilinearOracleSet,
+	oracle::{BatchId, MultilinearOracleSet},
+	polynomial::EvaluationDomain,
 	protocols::{
-		evalcheck::{EvalcheckClaim, EvalcheckProver},
-		test_utils::{
-			make_non_same_query_pcs_sumchecks, prove_bivariate_sumchecks_with_switchover,
+		evalcheck::{
+			subclaims::{make_non_same_query_pcs_sumchecks, BivariateSumcheck},
+			EvalcheckClaim, EvalcheckProof, EvalcheckProver, SameQueryPcsClaim,
+		},
+		sumcheck::{
+			batch_prove, batch_prove_with_transcript, Error as SumcheckError, SumcheckBatchProof,
+			SumcheckBatchProveOutput, SumcheckProver,
 		},
 	},
+	serialization::SerializeCanonical,
+	transcript::ProverTranscript,
 	witness::MultilinearWitnessIndex,
 };
-use binius_field::{PackedFieldIndexable, TowerField};
+use binius_field::{Field, PackedField, PackedFieldIndexable, TowerField};
 use p3_challenger::{CanObserve, CanSample};
 use std::iter::Step;
 
@@ -23,22 +30,115 @@ pub fn prove<F, PW, Challenger>(
 	witness_index: &mut MultilinearWitnessIndex<PW>,
 	claims: impl IntoIterator<Item = EvalcheckClaim<F>>,
 	switchover_fn: impl Fn(usize) -> usize + Clone,
-	mut challenger: Challenger,
+	challenger: Challenger,
 ) -> Result<GreedyEvalcheckProveOutput<F>, Error>
 where
 	F: TowerField + Step + From<PW::Scalar>,
 	PW: PackedFieldIndexable<Scalar: TowerField + From<F>>,
 	Challenger: CanObserve<F> + CanSample<F>,
 {
-	let committed_batches = oracles.committed_batches();
-	let mut proof = GreedyEvalcheckProof::default();
+	let mut writer = ProofWriter {
+		challenger,
+		proof: GreedyEvalcheckProof::default(),
+	};
+
+	let same_query_claims = prove_steps(
+		oracles,
+		witness_index,
+		claims,
+		&mut writer,
+		|writer, step, sumchecks| {
+			let (sumcheck_proof, evalcheck_claims) = prove_bivariate_sumchecks(
+				sumchecks,
+				&mut writer.challenger,
+				switchover_fn.clone(),
+			)?;
+			writer.push_sumcheck_proof(step, sumcheck_proof);
+			Ok(evalcheck_claims)
+		},
+		|writer, step, evalcheck_proof| writer.push_evalcheck_proof(step, evalcheck_proof),
+	)?;
+
+	let mut proof = writer.proof;
+	proof
+		.batch_opening_proof
+		.resize(same_query_claims.len(), None);
+
+	Ok(GreedyEvalcheckProveOutput {
+		proof,
+		same_query_claims,
+	})
+}
+
+/// Prove a greedy evalcheck instance, writing the proof to a transcript.
+///
+/// Every evalcheck proof is written as a message as soon as it is produced, so that its
+/// evaluations are observed before the challenges of the next sumcheck batch are sampled. Unlike
+/// [`GreedyEvalcheckProof`], the transcript does not record which batches needed a sumcheck
+/// reduction, as the verifier can tell from the claims.
+///
+/// Counterpart of [`verify_with_transcript`](super::verify_with_transcript).
+pub fn prove_with_transcript<F, PW, CH>(
+	oracles: &mut MultilinearOracleSet<F>,
+	witness_index: &mut MultilinearWitnessIndex<PW>,
+	claims: impl IntoIterator<Item = EvalcheckClaim<F>>,
+	switchover_fn: impl Fn(usize) -> usize + Clone,
+	transcript: &mut ProverTranscript<CH>,
+) -> Result<Vec<(BatchId, SameQueryPcsClaim<F>)>, Error>
+where
+	F: TowerField + Step + From<PW::Scalar> + SerializeCanonical,
+	PW: PackedFieldIndexable<Scalar: TowerField + From<F>>,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	prove_steps(
+		oracles,
+		witness_index,
+		claims,
+		transcript,
+		|transcript, _, sumchecks| {
+			let evalcheck_claims = prove_bivariate_sumchecks_with_transcript(
+				sumchecks,
+				transcript,
+				switchover_fn.clone(),
+			)?;
+			Ok(evalcheck_claims)
+		},
+		|transcript, _, evalcheck_proof| transcript.write_message::<F, _>(&evalcheck_proof),
+	)
+}
+
+/// Runs the greedy evalcheck reduction, handing the proof messages to the callbacks.
+///
+/// Every batch of bivariate sumchecks is proven by `prove_sumchecks`, and every evalcheck proof
+/// is passed to `send_evalcheck_proof` as soon as it is produced. Both callbacks are told the step
+/// the message belongs to. Batches of committed claims that already share a single query send no
+/// messages.
+fn prove_steps<'b, F, PW, C>(
+	oracles: &mut MultilinearOracleSet<F>,
+	witness_index: &mut MultilinearWitnessIndex<'b, PW>,
+	claims: impl IntoIterator<Item = EvalcheckClaim<F>>,
+	channel: &mut C,
+	mut prove_sumchecks: impl FnMut(
+		&mut C,
+		GreedyEvalcheckStep,
+		Vec<BivariateSumcheck<'b, F, PW>>,
+	) -> Result<Vec<EvalcheckClaim<F>>, Error>,
+	mut send_evalcheck_proof: impl FnMut(&mut C, GreedyEvalcheckStep, EvalcheckProof<F>),
+) -> Result<Vec<(BatchId, SameQueryPcsClaim<F>)>, Error>
+where
+	F: TowerField + From<PW::Scalar>,
+	PW: PackedFieldIndexable<Scalar: TowerField + From<F>>,
+{
 	let mut evalcheck_prover = EvalcheckProver::new(oracles, witness_index);
 
 	// Prove the initial evalcheck claims
-	proof.initial_evalcheck_proofs = claims
-		.into_iter()
-		.map(|claim| evalcheck_prover.prove(claim))
-		.collect::<Result<Vec<_>, _>>()?;
+	prove_evalchecks(
+		&mut evalcheck_prover,
+		channel,
+		GreedyEvalcheckStep::Initial,
+		claims,
+		&mut send_evalcheck_proof,
+	)?;
 
 	loop {
 		let new_sumchecks = evalcheck_prover.take_new_sumchecks();
@@ -47,76 +147,190 @@ where
 		}
 
 		// Reduce the new sumcheck claims for virtual polynomial openings to new evalcheck claims.
-		let (batch_sumcheck_proof, new_evalcheck_claims) =
-			prove_bivariate_sumchecks_with_switchover(
-				new_sumchecks,
-				&mut challenger,
-				switchover_fn.clone(),
-			)?;
-
-		let new_evalcheck_proofs = new_evalcheck_claims
-			.into_iter()
-			.map(|claim| evalcheck_prover.prove(claim))
-			.collect::<Result<Vec<_>, _>>()?;
-
-		proof
-			.virtual_opening_proofs
-			.push((batch_sumcheck_proof, new_evalcheck_proofs));
+		let step = GreedyEvalcheckStep::VirtualOpening;
+		let new_evalcheck_claims = prove_sumchecks(channel, step, new_sumchecks)?;
+		prove_evalchecks(
+			&mut evalcheck_prover,
+			channel,
+			step,
+			new_evalcheck_claims,
+			&mut send_evalcheck_proof,
+		)?;
 	}
 
 	// Now all remaining evalcheck claims are for committed polynomials.
 	// Batch together all committed polynomial evaluation claims to one point per batch.
-	let same_query_claims = committed_batches
+	// Batches are visited in commitment round order, which is the order of the PCS openings.
+	let batch_ids = evalcheck_prover
+		.batch_committed_eval_claims()
+		.batch_ids_in_round_order();
+	let same_query_claims = batch_ids
 		.into_iter()
-		.map(|batch| {
+		.enumerate()
+		.map(|(index, batch_id)| {
 			let maybe_same_query_claim = evalcheck_prover
 				.batch_committed_eval_claims()
-				.try_extract_same_query_pcs_claim(batch.id)?;
-			let same_query_claim = if let Some(same_query_claim) = maybe_same_query_claim {
-				proof.batch_opening_proof.push(None);
-				same_query_claim
-			} else {
-				let non_sqpcs_claims = evalcheck_prover
-					.batch_committed_eval_claims_mut()
-					.take_claims(batch.id)?;
-
-				let non_sqpcs_sumchecks =
-					make_non_same_query_pcs_sumchecks(&mut evalcheck_prover, &non_sqpcs_claims)?;
-
-				let (sumcheck_proof, new_evalcheck_claims) =
-					prove_bivariate_sumchecks_with_switchover(
-						non_sqpcs_sumchecks,
-						&mut challenger,
-						switchover_fn.clone(),
-					)?;
-
-				let new_evalcheck_proofs = new_evalcheck_claims
-					.into_iter()
-					.map(|claim| evalcheck_prover.prove(claim))
-					.collect::<Result<Vec<_>, _>>()?;
-
-				proof
-					.batch_opening_proof
-					.push(Some((sumcheck_proof, new_evalcheck_proofs)));
-
-				evalcheck_prover
-					.batch_committed_eval_claims_mut()
-					.try_extract_same_query_pcs_claim(batch.id)?
-					.expect(
-						"by construction, we must be left with a same query eval claim for the \
-						batch",
-					)
-			};
-
-			Ok((batch.id, same_query_claim))
+				.try_extract_same_query_pcs_claim(batch_id)?;
+			if let Some(same_query_claim) = maybe_same_query_claim {
+				return Ok((batch_id, same_query_claim));
+			}
+
+			let non_sqpcs_claims = evalcheck_prover
+				.batch_committed_eval_claims_mut()
+				.take_claims(batch_id)?;
+
+			let non_sqpcs_sumchecks =
+				make_non_same_query_pcs_sumchecks(&mut evalcheck_prover, &non_sqpcs_claims)?;
+
+			let step = GreedyEvalcheckStep::BatchOpening(index);
+			let new_evalcheck_claims = prove_sumchecks(channel, step, non_sqpcs_sumchecks)?;
+			prove_evalchecks(
+				&mut evalcheck_prover,
+				channel,
+				step,
+				new_evalcheck_claims,
+				&mut send_evalcheck_proof,
+			)?;
+
+			let same_query_claim = evalcheck_prover
+				.batch_committed_eval_claims_mut()
+				.try_extract_same_query_pcs_claim(batch_id)?
+				.expect(
+					"by construction, we must be left with a same query eval claim for the batch",
+				);
+			Ok((batch_id, same_query_claim))
 		})
 		.collect::<Result<_, Error>>()?;
 
 	// The batch committed reduction must not result in any new sumcheck claims.
 	assert!(evalcheck_prover.take_new_sumchecks().is_empty());
 
-	Ok(GreedyEvalcheckProveOutput {
+	Ok(same_query_claims)
+}
+
+/// Prove evalcheck claims, sending each proof before the next claim is proven.
+fn prove_evalchecks<F, PW, C>(
+	evalcheck_prover: &mut EvalcheckProver<'_, '_, F, PW>,
+	channel: &mut C,
+	step: GreedyEvalcheckStep,
+	claims: impl IntoIterator<Item = EvalcheckClaim<F>>,
+	send_evalcheck_proof: &mut impl FnMut(&mut C, GreedyEvalcheckStep, EvalcheckProof<F>),
+) -> Result<(), Error>
+where
+	F: TowerField + From<PW::Scalar>,
+	PW: PackedFieldIndexable<Scalar: TowerField + From<F>>,
+{
+	for claim in claims {
+		let proof = evalcheck_prover.prove(claim)?;
+		send_evalcheck_proof(channel, step, proof);
+	}
+	Ok(())
+}
+
+/// Collects the messages of [`prove_steps`] into a [`GreedyEvalcheckProof`].
+struct ProofWriter<F: Field, Challenger> {
+	challenger: Challenger,
+	proof: GreedyEvalcheckProof<F>,
+}
+
+impl<F: Field, Challenger> ProofWriter<F, Challenger> {
+	fn push_sumcheck_proof(
+		&mut self,
+		step: GreedyEvalcheckStep,
+		sumcheck_proof: SumcheckBatchProof<F>,
+	) {
+		match step {
+			GreedyEvalcheckStep::Initial => {
+				unreachable!("initial evalcheck claims are not reduced with a sumcheck")
+			}
+			GreedyEvalcheckStep::VirtualOpening => self
+				.proof
+				.virtual_opening_proofs
+				.push((sumcheck_proof, Vec::new())),
+			GreedyEvalcheckStep::BatchOpening(index) => {
+				// Batches before this one that were skipped already shared a single query.
+				let batch_opening_proof = &mut self.proof.batch_opening_proof;
+				batch_opening_proof.resize(index, None);
+				batch_opening_proof.push(Some((sumcheck_proof, Vec::new())));
+			}
+		}
+	}
+
+	fn push_evalcheck_proof(
+		&mut self,
+		step: GreedyEvalcheckStep,
+		evalcheck_proof: EvalcheckProof<F>,
+	) {
+		let evalcheck_proofs = match step {
+			GreedyEvalcheckStep::Initial => &mut self.proof.initial_evalcheck_proofs,
+			GreedyEvalcheckStep::VirtualOpening => self
+				.proof
+				.virtual_opening_proofs
+				.last_mut()
+				.map(|(_, evalcheck_proofs)| evalcheck_proofs)
+				.expect("virtual opening evalcheck claims are preceded by their sumcheck proof"),
+			GreedyEvalcheckStep::BatchOpening(_) => self
+				.proof
+				.batch_opening_proof
+				.last_mut()
+				.and_then(Option::as_mut)
+				.map(|(_, evalcheck_proofs)| evalcheck_proofs)
+				.expect("batch opening evalcheck claims are preceded by their sumcheck proof"),
+		};
+		evalcheck_proofs.push(evalcheck_proof);
+	}
+}
+
+/// Prove a batch of bivariate product sumchecks, reducing them to new evalcheck claims.
+fn prove_bivariate_sumchecks<'a, F, PW, CH>(
+	sumchecks: impl IntoIterator<Item = BivariateSumcheck<'a, F, PW>>,
+	challenger: &mut CH,
+	switchover_fn: impl Fn(usize) -> usize + Clone,
+) -> Result<(SumcheckBatchProof<F>, Vec<EvalcheckClaim<F>>), SumcheckError>
+where
+	F: Field + Step + From<PW::Scalar>,
+	PW: PackedField,
+	PW::Scalar: From<F>,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let bivariate_domain = EvaluationDomain::new_isomorphic::<F>(3)?;
+
+	let provers = sumchecks
+		.into_iter()
+		.map(|(claim, witness)| {
+			SumcheckProver::new(&bivariate_domain, claim, witness, switchover_fn.clone())
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let SumcheckBatchProveOutput {
 		proof,
-		same_query_claims,
-	})
+		evalcheck_claims,
+	} = batch_prove(provers, challenger)?;
+
+	Ok((proof, evalcheck_claims))
+}
+
+/// Prove a batch of bivariate product sumchecks like [`prove_bivariate_sumchecks`], writing the
+/// batch proof to the transcript.
+fn prove_bivariate_sumchecks_with_transcript<'a, F, PW, CH>(
+	sumchecks: impl IntoIterator<Item = BivariateSumcheck<'a, F, PW>>,
+	transcript: &mut ProverTranscript<CH>,
+	switchover_fn: impl Fn(usize) -> usize + Clone,
+) -> Result<Vec<EvalcheckClaim<F>>, SumcheckError>
+where
+	F: Field + Step + From<PW::Scalar> + SerializeCanonical,
+	PW: PackedField,
+	PW::Scalar: From<F>,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let bivariate_domain = EvaluationDomain::new_isomorphic::<F>(3)?;
+
+	let provers = sumchecks
+		.into_iter()
+		.map(|(claim, witness)| {
+			SumcheckProver::new(&bivariate_domain, claim, witness, switchover_fn.clone())
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	batch_prove_with_transcript(provers, transcript)
 }
@@ -1,126 +1,309 @@
 // Copyright 2024 Ulvetanna Inc.
 
-use super::{common::GreedyEvalcheckProof, error::Error};
+use super::{
+	common::{GreedyEvalcheckProof, GreedyEvalcheckStep},
+	error::Error,
+};
 use crate::{
 	oracle::{BatchId, MultilinearOracleSet},
 	protocols::{
-		evalcheck::{EvalcheckClaim, EvalcheckVerifier, SameQueryPcsClaim},
-		sumcheck::batch_verify,
-		test_utils::make_non_same_query_pcs_sumcheck_claims,
+		evalcheck::{
+			subclaims::make_non_same_query_pcs_sumcheck_claims, EvalcheckClaim, EvalcheckProof,
+			EvalcheckVerifier, SameQueryPcsClaim,
+		},
+		sumcheck::{batch_verify, batch_verify_with_transcript, SumcheckBatchProof, SumcheckClaim},
 	},
+	serialization::DeserializeCanonical,
+	transcript::VerifierTranscript,
 };
-use binius_field::TowerField;
+use binius_field::{Field, TowerField};
 use p3_challenger::{CanObserve, CanSample};
-use std::iter;
+use std::{iter, mem, vec};
 
 pub fn verify<F, Challenger>(
 	oracles: &mut MultilinearOracleSet<F>,
 	claims: impl IntoIterator<Item = EvalcheckClaim<F>>,
 	proof: GreedyEvalcheckProof<F>,
-	mut challenger: Challenger,
+	challenger: Challenger,
 ) -> Result<Vec<(BatchId, SameQueryPcsClaim<F>)>, Error>
 where
 	F: TowerField,
 	Challenger: CanObserve<F> + CanSample<F>,
 {
-	let committed_batches = oracles.committed_batches();
-	let mut evalcheck_verifier = EvalcheckVerifier::new(oracles);
+	let GreedyEvalcheckProof {
+		initial_evalcheck_proofs,
+		virtual_opening_proofs,
+		batch_opening_proof,
+	} = proof;
 
-	// Verify the initial evalcheck claims
-	let claims = claims.into_iter().collect::<Vec<_>>();
-	if claims.len() < proof.initial_evalcheck_proofs.len() {
-		return Err(Error::ExtraInitialEvalcheckProof);
+	let n_batch_opening_proofs = batch_opening_proof.len();
+	let mut reader = ProofReader {
+		challenger,
+		initial_evalcheck_proofs,
+		virtual_opening_proofs: virtual_opening_proofs.into_iter(),
+		batch_opening_proofs: batch_opening_proof.into_iter().enumerate(),
+		evalcheck_proofs: Vec::new(),
+	};
+
+	let same_query_claims = verify_steps(
+		oracles,
+		claims,
+		&mut reader,
+		|reader, step, sumcheck_claims| {
+			let sumcheck_proof = reader.take_sumcheck_proof(step)?;
+			let evalcheck_claims =
+				batch_verify(sumcheck_claims, sumcheck_proof, &mut reader.challenger)?;
+			Ok(evalcheck_claims)
+		},
+		|reader, step, n_claims| reader.take_evalcheck_proofs(step, n_claims),
+	)?;
+
+	if reader.virtual_opening_proofs.next().is_some() {
+		return Err(Error::ExtraVirtualOpeningProof);
 	}
-	if claims.len() > proof.initial_evalcheck_proofs.len() {
-		return Err(Error::MissingInitialEvalcheckProof);
+	if reader
+		.batch_opening_proofs
+		.any(|(_, batch_opening_proof)| batch_opening_proof.is_some())
+	{
+		return Err(Error::ExtraBatchOpeningProof);
 	}
-	for (claim, proof) in iter::zip(claims, proof.initial_evalcheck_proofs) {
-		evalcheck_verifier.verify(claim, proof)?;
+	if same_query_claims.len() < n_batch_opening_proofs {
+		return Err(Error::ExtraBatchOpeningProof);
 	}
+	if same_query_claims.len() > n_batch_opening_proofs {
+		return Err(Error::MissingBatchOpeningProof);
+	}
+
+	Ok(same_query_claims)
+}
 
-	for (sumcheck_batch_proof, evalcheck_proofs) in proof.virtual_opening_proofs {
+/// Verify a greedy evalcheck instance, reading the proof from a transcript.
+///
+/// Counterpart of [`prove_with_transcript`](super::prove_with_transcript).
+pub fn verify_with_transcript<F, CH>(
+	oracles: &mut MultilinearOracleSet<F>,
+	claims: impl IntoIterator<Item = EvalcheckClaim<F>>,
+	transcript: &mut VerifierTranscript<'_, CH>,
+) -> Result<Vec<(BatchId, SameQueryPcsClaim<F>)>, Error>
+where
+	F: TowerField + DeserializeCanonical,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	verify_steps(
+		oracles,
+		claims,
+		transcript,
+		|transcript, _, sumcheck_claims| {
+			let evalcheck_claims = batch_verify_with_transcript(sumcheck_claims, transcript)?;
+			Ok(evalcheck_claims)
+		},
+		|transcript, _, n_claims| {
+			let evalcheck_proofs = (0..n_claims)
+				.map(|_| transcript.read_message::<F, EvalcheckProof<F>>())
+				.collect::<Result<Vec<_>, _>>()?;
+			Ok(evalcheck_proofs)
+		},
+	)
+}
+
+/// Runs the verifier side of the greedy evalcheck reduction, obtaining the proof messages from
+/// the callbacks.
+///
+/// Every batch of bivariate sumcheck claims is verified by `verify_sumchecks`, and the proofs of
+/// the resulting evalcheck claims are obtained from `receive_evalcheck_proofs`, which must return
+/// exactly the requested number of proofs. Both callbacks are told the step the messages belong
+/// to. Batches of committed claims that already share a single query take no messages.
+fn verify_steps<F, C>(
+	oracles: &mut MultilinearOracleSet<F>,
+	claims: impl IntoIterator<Item = EvalcheckClaim<F>>,
+	channel: &mut C,
+	mut verify_sumchecks: impl FnMut(
+		&mut C,
+		GreedyEvalcheckStep,
+		Vec<SumcheckClaim<F>>,
+	) -> Result<Vec<EvalcheckClaim<F>>, Error>,
+	mut receive_evalcheck_proofs: impl FnMut(
+		&mut C,
+		GreedyEvalcheckStep,
+		usize,
+	) -> Result<Vec<EvalcheckProof<F>>, Error>,
+) -> Result<Vec<(BatchId, SameQueryPcsClaim<F>)>, Error>
+where
+	F: TowerField,
+{
+	let mut evalcheck_verifier = EvalcheckVerifier::new(oracles);
+
+	// Verify the initial evalcheck claims
+	verify_evalchecks(
+		&mut evalcheck_verifier,
+		channel,
+		GreedyEvalcheckStep::Initial,
+		claims.into_iter().collect(),
+		&mut receive_evalcheck_proofs,
+	)?;
+
+	loop {
 		let new_sumchecks = evalcheck_verifier.take_new_sumchecks();
 		if new_sumchecks.is_empty() {
-			return Err(Error::ExtraVirtualOpeningProof);
+			break;
 		}
 
 		// Reduce the new sumcheck claims for virtual polynomial openings to new evalcheck claims.
-		let new_evalcheck_claims =
-			batch_verify(new_sumchecks, sumcheck_batch_proof, &mut challenger)?;
-
-		if new_evalcheck_claims.len() < evalcheck_proofs.len() {
-			return Err(Error::ExtraVirtualOpeningProof);
-		}
-		if new_evalcheck_claims.len() > evalcheck_proofs.len() {
-			return Err(Error::MissingVirtualOpeningProof);
-		}
-		for (claim, proof) in iter::zip(new_evalcheck_claims, evalcheck_proofs) {
-			evalcheck_verifier.verify(claim, proof)?;
-		}
-	}
-
-	let new_sumchecks = evalcheck_verifier.take_new_sumchecks();
-	if !new_sumchecks.is_empty() {
-		return Err(Error::MissingVirtualOpeningProof);
+		let step = GreedyEvalcheckStep::VirtualOpening;
+		let new_evalcheck_claims = verify_sumchecks(channel, step, new_sumchecks)?;
+		verify_evalchecks(
+			&mut evalcheck_verifier,
+			channel,
+			step,
+			new_evalcheck_claims,
+			&mut receive_evalcheck_proofs,
+		)?;
 	}
 
 	// Now all remaining evalcheck claims are for committed polynomials.
 	// Batch together all committed polynomial evaluation claims to one point per batch.
-	if committed_batches.len() < proof.batch_opening_proof.len() {
-		return Err(Error::ExtraBatchOpeningProof);
-	}
-	if committed_batches.len() > proof.batch_opening_proof.len() {
-		return Err(Error::MissingBatchOpeningProof);
-	}
-	let same_query_claims = iter::zip(committed_batches, proof.batch_opening_proof)
-		.map(|(batch, proof)| {
+	// Batches are visited in commitment round order, which is the order of the PCS openings.
+	let batch_ids = evalcheck_verifier
+		.batch_committed_eval_claims()
+		.batch_ids_in_round_order();
+	let same_query_claims = batch_ids
+		.into_iter()
+		.enumerate()
+		.map(|(index, batch_id)| {
 			let maybe_same_query_claim = evalcheck_verifier
 				.batch_committed_eval_claims()
-				.try_extract_same_query_pcs_claim(batch.id)?;
-			let same_query_claim = if let Some(same_query_claim) = maybe_same_query_claim {
-				if proof.is_some() {
-					return Err(Error::ExtraBatchOpeningProof);
-				}
-				same_query_claim
-			} else {
-				let (sumcheck_proof, evalcheck_proofs) =
-					proof.ok_or(Error::ExtraBatchOpeningProof)?;
+				.try_extract_same_query_pcs_claim(batch_id)?;
+			if let Some(same_query_claim) = maybe_same_query_claim {
+				return Ok((batch_id, same_query_claim));
+			}
 
-				let non_sqpcs_claims = evalcheck_verifier
-					.batch_committed_eval_claims_mut()
-					.take_claims(batch.id)?;
+			let non_sqpcs_claims = evalcheck_verifier
+				.batch_committed_eval_claims_mut()
+				.take_claims(batch_id)?;
 
-				let non_sqpcs_sumchecks = make_non_same_query_pcs_sumcheck_claims(
-					&mut evalcheck_verifier,
-					&non_sqpcs_claims,
-				)?;
+			let non_sqpcs_sumchecks = make_non_same_query_pcs_sumcheck_claims(
+				&mut evalcheck_verifier,
+				&non_sqpcs_claims,
+			)?;
 
-				let evalcheck_claims =
-					batch_verify(non_sqpcs_sumchecks, sumcheck_proof, &mut challenger)?;
-
-				if evalcheck_claims.len() < evalcheck_proofs.len() {
-					return Err(Error::ExtraBatchOpeningProof);
-				}
-				if evalcheck_claims.len() > evalcheck_proofs.len() {
-					return Err(Error::MissingBatchOpeningProof);
-				}
-				for (claim, proof) in iter::zip(evalcheck_claims, evalcheck_proofs) {
-					evalcheck_verifier.verify(claim, proof)?;
-				}
+			let step = GreedyEvalcheckStep::BatchOpening(index);
+			let evalcheck_claims = verify_sumchecks(channel, step, non_sqpcs_sumchecks)?;
+			verify_evalchecks(
+				&mut evalcheck_verifier,
+				channel,
+				step,
+				evalcheck_claims,
+				&mut receive_evalcheck_proofs,
+			)?;
 
-				evalcheck_verifier
-					.batch_committed_eval_claims_mut()
-					.try_extract_same_query_pcs_claim(batch.id)?
-					.expect(
-						"by construction, we must be left with a same query eval claim for the batch"
-					)
-			};
-			Ok((batch.id, same_query_claim))
+			let same_query_claim = evalcheck_verifier
+				.batch_committed_eval_claims_mut()
+				.try_extract_same_query_pcs_claim(batch_id)?
+				.expect(
+					"by construction, we must be left with a same query eval claim for the batch",
+				);
+			Ok((batch_id, same_query_claim))
 		})
-		.collect::<Result<_, _>>()?;
+		.collect::<Result<_, Error>>()?;
 
 	// The batch committed reduction must not result in any new sumcheck claims.
 	assert!(evalcheck_verifier.take_new_sumchecks().is_empty());
 
 	Ok(same_query_claims)
 }
+
+/// Verify evalcheck claims against the proofs received for them.
+fn verify_evalchecks<F, C>(
+	evalcheck_verifier: &mut EvalcheckVerifier<'_, F>,
+	channel: &mut C,
+	step: GreedyEvalcheckStep,
+	claims: Vec<EvalcheckClaim<F>>,
+	receive_evalcheck_proofs: &mut impl FnMut(
+		&mut C,
+		GreedyEvalcheckStep,
+		usize,
+	) -> Result<Vec<EvalcheckProof<F>>, Error>,
+) -> Result<(), Error>
+where
+	F: TowerField,
+{
+	let proofs = receive_evalcheck_proofs(channel, step, claims.len())?;
+	debug_assert_eq!(proofs.len(), claims.len());
+	for (claim, proof) in iter::zip(claims, proofs) {
+		evalcheck_verifier.verify(claim, proof)?;
+	}
+	Ok(())
+}
+
+/// Hands out the parts of a [`GreedyEvalcheckProof`] in the order [`verify_steps`] asks for them.
+struct ProofReader<F: Field, Challenger> {
+	challenger: Challenger,
+	initial_evalcheck_proofs: Vec<EvalcheckProof<F>>,
+	virtual_opening_proofs: vec::IntoIter<(SumcheckBatchProof<F>, Vec<EvalcheckProof<F>>)>,
+	#[allow(clippy::type_complexity)]
+	batch_opening_proofs:
+		iter::Enumerate<vec::IntoIter<Option<(SumcheckBatchProof<F>, Vec<EvalcheckProof<F>>)>>>,
+	/// Evalcheck proofs accompanying the last sumcheck proof taken.
+	evalcheck_proofs: Vec<EvalcheckProof<F>>,
+}
+
+impl<F: Field, Challenger> ProofReader<F, Challenger> {
+	fn take_sumcheck_proof(
+		&mut self,
+		step: GreedyEvalcheckStep,
+	) -> Result<SumcheckBatchProof<F>, Error> {
+		let (sumcheck_proof, evalcheck_proofs) = match step {
+			GreedyEvalcheckStep::Initial => {
+				unreachable!("initial evalcheck claims are not reduced with a sumcheck")
+			}
+			GreedyEvalcheckStep::VirtualOpening => self
+				.virtual_opening_proofs
+				.next()
+				.ok_or(Error::MissingVirtualOpeningProof)?,
+			GreedyEvalcheckStep::BatchOpening(index) => loop {
+				// Batches before this one that were skipped already shared a single query.
+				match self.batch_opening_proofs.next() {
+					Some((i, Some(_))) if i < index => return Err(Error::ExtraBatchOpeningProof),
+					Some((i, None)) if i < index => continue,
+					Some((_, batch_opening_proof)) => {
+						break batch_opening_proof.ok_or(Error::MissingBatchOpeningProof)?
+					}
+					None => return Err(Error::MissingBatchOpeningProof),
+				}
+			},
+		};
+		self.evalcheck_proofs = evalcheck_proofs;
+		Ok(sumcheck_proof)
+	}
+
+	fn take_evalcheck_proofs(
+		&mut self,
+		step: GreedyEvalcheckStep,
+		n_claims: usize,
+	) -> Result<Vec<EvalcheckProof<F>>, Error> {
+		let (evalcheck_proofs, extra_error, missing_error) = match step {
+			GreedyEvalcheckStep::Initial => (
+				mem::take(&mut self.initial_evalcheck_proofs),
+				Error::ExtraInitialEvalcheckProof,
+				Error::MissingInitialEvalcheckProof,
+			),
+			GreedyEvalcheckStep::VirtualOpening => (
+				mem::take(&mut self.evalcheck_proofs),
+				Error::ExtraVirtualOpeningProof,
+				Error::MissingVirtualOpeningProof,
+			),
+			GreedyEvalcheckStep::BatchOpening(_) => (
+				mem::take(&mut self.evalcheck_proofs),
+				Error::ExtraBatchOpeningProof,
+				Error::MissingBatchOpeningProof,
+			),
+		};
+		if evalcheck_proofs.len() > n_claims {
+			return Err(extra_error);
+		}
+		if evalcheck_proofs.len() < n_claims {
+			return Err(missing_error);
+		}
+		Ok(evalcheck_proofs)
+	}
+}
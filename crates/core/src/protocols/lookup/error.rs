@@ -0,0 +1,11 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use crate::protocols::{msetcheck::Error as MsetcheckError, prodcheck::Error as ProdcheckError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("multiset check error: {0}")]
+	Msetcheck(#[from] MsetcheckError),
+	#[error("product check error: {0}")]
+	Prodcheck(#[from] ProdcheckError),
+}
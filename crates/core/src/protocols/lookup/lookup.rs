@@ -0,0 +1,124 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::error::Error;
+use crate::{
+	oracle::MultilinearPolyOracle,
+	polynomial::MultilinearExtension,
+	protocols::{
+		msetcheck::{Error as MsetcheckError, WeightedMsetcheckClaim, WeightedMsetcheckWitness},
+		prodcheck::{ReducedProductCheckClaims, SimpleMultGateComposition},
+		zerocheck::{ZerocheckClaim, ZerocheckWitness},
+	},
+	witness::MultilinearWitness,
+};
+use binius_field::{BinaryField32b, Field};
+use getset::Getters;
+
+/// A lookup claim, expressed as the weighted multiset check of the lookups against the table.
+///
+/// The lookups U are the T relation of the weighted multiset check, and the table T is its
+/// weighted U relation, so that the final counts of the table rows are the multiplicities.
+#[derive(Debug, Getters)]
+pub struct LookupClaim<F: Field> {
+	/// Oracle to the table T
+	#[get = "pub"]
+	t_oracle: MultilinearPolyOracle<F>,
+	/// Oracle to the looked up values U
+	#[get = "pub"]
+	u_oracle: MultilinearPolyOracle<F>,
+	pub(super) msetcheck_claim: WeightedMsetcheckClaim<F>,
+}
+
+impl<F: Field> LookupClaim<F> {
+	/// Claim constructor
+	///
+	/// The counts read by the lookups have the variables of the lookup column, the final counts
+	/// have the variables of the table.
+	pub fn new(
+		t_oracle: MultilinearPolyOracle<F>,
+		u_oracle: MultilinearPolyOracle<F>,
+		u_counts_oracle: MultilinearPolyOracle<F>,
+		u_counts_inverse_oracle: MultilinearPolyOracle<F>,
+		t_final_counts_oracle: MultilinearPolyOracle<F>,
+	) -> Result<Self, Error> {
+		let msetcheck_claim = WeightedMsetcheckClaim::new(
+			[u_oracle.clone()],
+			[t_oracle.clone()],
+			t_final_counts_oracle,
+			u_counts_oracle,
+			u_counts_inverse_oracle,
+		)
+		.map_err(MsetcheckError::from)?;
+		Ok(Self {
+			t_oracle,
+			u_oracle,
+			msetcheck_claim,
+		})
+	}
+
+	/// Oracle to the counts read by each lookup
+	pub fn u_counts_oracle(&self) -> &MultilinearPolyOracle<F> {
+		self.msetcheck_claim.t_counts_oracle()
+	}
+
+	/// Oracle to the multiplicative inverses of the counts read by each lookup
+	pub fn u_counts_inverse_oracle(&self) -> &MultilinearPolyOracle<F> {
+		self.msetcheck_claim.t_counts_inverse_oracle()
+	}
+
+	/// Oracle to the final counts of the table rows
+	pub fn t_final_counts_oracle(&self) -> &MultilinearPolyOracle<F> {
+		self.msetcheck_claim.u_multiplicities_oracle()
+	}
+}
+
+#[derive(Debug)]
+pub struct LookupWitness<'a, FW: Field> {
+	pub(super) msetcheck_witness: WeightedMsetcheckWitness<'a, FW>,
+}
+
+impl<'a, FW: Field> LookupWitness<'a, FW> {
+	/// Witness constructor
+	///
+	/// Computes the counts from the table row read by each lookup. The counts must be committed
+	/// before the multiset check challenges are sampled.
+	pub fn new(
+		t_polynomial: MultilinearWitness<'a, FW>,
+		u_polynomial: MultilinearWitness<'a, FW>,
+		u_to_t_mapping: Vec<usize>,
+	) -> Result<Self, Error> {
+		let msetcheck_witness =
+			WeightedMsetcheckWitness::new([u_polynomial], [t_polynomial], u_to_t_mapping)?;
+		Ok(Self { msetcheck_witness })
+	}
+
+	/// Counts read by each lookup
+	pub fn u_counts(&self) -> &MultilinearExtension<'static, BinaryField32b> {
+		self.msetcheck_witness.t_counts()
+	}
+
+	/// Inverses of the counts read by each lookup
+	pub fn u_counts_inverse(&self) -> &MultilinearExtension<'static, BinaryField32b> {
+		self.msetcheck_witness.t_counts_inverse()
+	}
+
+	/// Final counts of the table rows
+	pub fn t_final_counts(&self) -> &MultilinearExtension<'static, BinaryField32b> {
+		self.msetcheck_witness.u_multiplicities()
+	}
+}
+
+#[derive(Debug)]
+pub struct ReducedLookupClaims<F: Field> {
+	/// Claims of the product check the multiset equality is reduced to
+	pub reduced_product_check_claims: ReducedProductCheckClaims<F>,
+	/// Zerocheck claim on $1 - c \cdot c^{-1}$, showing that no read count is zero
+	pub counts_nonzero_claim: ZerocheckClaim<F>,
+}
+
+#[derive(Debug)]
+pub struct LookupProveOutput<'a, F: Field, FW: Field> {
+	pub reduced_lookup_claims: ReducedLookupClaims<F>,
+	pub t_prime_witness: ZerocheckWitness<'a, FW, SimpleMultGateComposition>,
+	pub counts_nonzero_witness: ZerocheckWitness<'a, FW, SimpleMultGateComposition>,
+}
@@ -0,0 +1,33 @@
+// Copyright 2024 Ulvetanna Inc.
+
+//! The lookup polynomial protocol.
+//!
+//! A lookup claim states that every value of a lookup column $U$ is contained in a table column
+//! $T$. The reduction follows the offline memory checking approach of Lasso: every table row is a
+//! memory cell that is initialized with count $1$, and every lookup reads a row and writes it back
+//! with its count multiplied by a fixed generator $g$ of the multiplicative group of
+//! `BinaryField32b`. Writing $c_i$ for the count read by the $i$-th lookup and $f_j$ for the final
+//! count of the $j$-th row, the lookup is valid iff the multisets
+//!
+//! $$\{(T_j, 1)\} \cup \{(U_i, g \cdot c_i)\} = \{(U_i, c_i)\} \cup \{(T_j, f_j)\}$$
+//!
+//! are equal and no $c_i$ is zero. This is exactly a weighted multiset check of the lookups
+//! against the table with the final counts as multiplicities, see
+//! [`WeightedMsetcheckClaim`](super::msetcheck::WeightedMsetcheckClaim), which is further reduced
+//! to a product check.
+//!
+//! Multiplicative counts make the argument sound as long as no table row is looked up $2^{32} - 1$
+//! or more times.
+
+mod error;
+#[allow(clippy::module_inception)]
+mod lookup;
+mod prove;
+#[cfg(test)]
+mod tests;
+mod verify;
+
+pub use error::*;
+pub use lookup::{LookupClaim, LookupProveOutput, LookupWitness, ReducedLookupClaims};
+pub use prove::*;
+pub use verify::*;
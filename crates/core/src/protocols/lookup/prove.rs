@@ -0,0 +1,77 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	error::Error,
+	lookup::{LookupClaim, LookupProveOutput, LookupWitness, ReducedLookupClaims},
+};
+use crate::{
+	oracle::{CommittedId, MultilinearOracleSet},
+	protocols::{
+		msetcheck::{self, ReducedWeightedMsetcheckClaims, WeightedMsetcheckProveOutput},
+		prodcheck::{self, ProdcheckProveOutput},
+	},
+	witness::MultilinearWitnessIndex,
+};
+use binius_field::{BinaryField32b, ExtensionField, TowerField};
+
+/// Prove a lookup instance reduction.
+///
+/// Given a table $T$ and a lookup column $U$ of any sizes, along with the committed counts of the
+/// lookup witness, this reduces the claim that every value of $U$ is in $T$ to a product check of
+/// the multiset equality
+///
+/// $$\{(T_j, 1)\} \cup \{(U_i, g \cdot c_i)\} = \{(U_i, c_i)\} \cup \{(T_j, f_j)\}$$
+///
+/// with multiset check challenges $\gamma$ and $\alpha$, and to a zerocheck that all read counts
+/// $c_i$ are invertible. The grand product polynomial of the product check is committed as
+/// `f_prime_committed_id` and its witness is added to the witness index.
+pub fn prove<'a, F, FW>(
+	oracles: &mut MultilinearOracleSet<F>,
+	witness_index: &mut MultilinearWitnessIndex<'a, FW>,
+	lookup_claim: &LookupClaim<F>,
+	lookup_witness: LookupWitness<'a, FW>,
+	gamma: F,
+	alpha: F,
+	f_prime_committed_id: CommittedId,
+) -> Result<LookupProveOutput<'a, F, FW>, Error>
+where
+	F: TowerField + ExtensionField<BinaryField32b> + From<FW>,
+	FW: TowerField + ExtensionField<BinaryField32b> + From<F>,
+{
+	let WeightedMsetcheckProveOutput {
+		reduced_claims:
+			ReducedWeightedMsetcheckClaims {
+				prodcheck_claim,
+				counts_nonzero_claim,
+			},
+		prodcheck_witness,
+		counts_nonzero_witness,
+	} = msetcheck::prove_weighted(
+		oracles,
+		witness_index,
+		&lookup_claim.msetcheck_claim,
+		lookup_witness.msetcheck_witness,
+		gamma,
+		alpha,
+	)?;
+
+	let ProdcheckProveOutput {
+		reduced_product_check_claims,
+		t_prime_witness,
+	} = prodcheck::prove(
+		oracles,
+		witness_index,
+		&prodcheck_claim,
+		prodcheck_witness,
+		f_prime_committed_id,
+	)?;
+
+	Ok(LookupProveOutput {
+		reduced_lookup_claims: ReducedLookupClaims {
+			reduced_product_check_claims,
+			counts_nonzero_claim,
+		},
+		t_prime_witness,
+		counts_nonzero_witness,
+	})
+}
@@ -0,0 +1,244 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::*;
+use crate::{
+	oracle::{CommittedBatchSpec, CommittedId, MultilinearOracleSet},
+	polynomial::MultilinearExtension,
+	protocols::{msetcheck::Error as MsetcheckError, zerocheck::check_zerocheck_claim},
+	witness::{MultilinearWitness, MultilinearWitnessIndex},
+};
+use assert_matches::assert_matches;
+use binius_field::{BinaryField128b, BinaryField32b, BinaryField8b, Field, TowerField};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+type F = BinaryField128b;
+
+const T_N_VARS: usize = 4;
+const U_N_VARS: usize = 6;
+
+struct LookupInstance {
+	oracles: MultilinearOracleSet<F>,
+	claim: LookupClaim<F>,
+	f_prime_committed_id: CommittedId,
+}
+
+fn make_instance(t_n_vars: usize, u_n_vars: usize) -> LookupInstance {
+	let mut oracles = MultilinearOracleSet::<F>::new();
+	let t_batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 1,
+		n_vars: t_n_vars,
+		n_polys: 1,
+		tower_level: BinaryField8b::TOWER_LEVEL,
+	});
+	let u_batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 1,
+		n_vars: u_n_vars,
+		n_polys: 1,
+		tower_level: BinaryField8b::TOWER_LEVEL,
+	});
+	let u_counts_batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 1,
+		n_vars: u_n_vars,
+		n_polys: 2,
+		tower_level: BinaryField32b::TOWER_LEVEL,
+	});
+	let t_counts_batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 1,
+		n_vars: t_n_vars,
+		n_polys: 1,
+		tower_level: BinaryField32b::TOWER_LEVEL,
+	});
+	// The multiset check relations merge two columns, and the grand product adds one variable more
+	let f_prime_batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 2,
+		n_vars: t_n_vars.max(u_n_vars) + 2,
+		n_polys: 1,
+		tower_level: F::TOWER_LEVEL,
+	});
+
+	let oracle = |batch_id, index| oracles.committed_oracle(CommittedId { batch_id, index });
+	let claim = LookupClaim::new(
+		oracle(t_batch_id, 0),
+		oracle(u_batch_id, 0),
+		oracle(u_counts_batch_id, 0),
+		oracle(u_counts_batch_id, 1),
+		oracle(t_counts_batch_id, 0),
+	)
+	.unwrap();
+
+	LookupInstance {
+		oracles,
+		claim,
+		f_prime_committed_id: CommittedId {
+			batch_id: f_prime_batch_id,
+			index: 0,
+		},
+	}
+}
+
+fn make_table(t_n_vars: usize) -> Vec<F> {
+	(0..1 << t_n_vars)
+		.map(|i| F::from(BinaryField8b::new((i * 13 + 7) as u8)))
+		.collect()
+}
+
+fn make_witness(
+	table: &[F],
+	mapping: Vec<usize>,
+	lookup_values: Vec<F>,
+) -> Result<LookupWitness<'static, F>, Error> {
+	let to_witness = |values: Vec<F>| -> MultilinearWitness<'static, F> {
+		MultilinearExtension::from_values(values)
+			.unwrap()
+			.specialize_arc_dyn()
+	};
+	LookupWitness::new(to_witness(table.to_vec()), to_witness(lookup_values), mapping)
+}
+
+fn random_mapping(rng: &mut StdRng, t_n_vars: usize, u_n_vars: usize) -> Vec<usize> {
+	(0..1 << u_n_vars)
+		.map(|_| rng.gen_range(0..1 << t_n_vars))
+		.collect()
+}
+
+fn test_prove_verify_interaction(t_n_vars: usize, u_n_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(0);
+	let LookupInstance {
+		oracles,
+		claim,
+		f_prime_committed_id,
+	} = make_instance(t_n_vars, u_n_vars);
+
+	let table = make_table(t_n_vars);
+	let mapping = random_mapping(&mut rng, t_n_vars, u_n_vars);
+	let lookup_values = mapping.iter().map(|&row| table[row]).collect();
+	let witness = make_witness(&table, mapping, lookup_values).unwrap();
+
+	// challenges
+	let gamma = F::new(0x123);
+	let alpha = F::new(0x346);
+
+	// PROVER
+	let mut witness_index = MultilinearWitnessIndex::new();
+	let prove_output = prove(
+		&mut oracles.clone(),
+		&mut witness_index,
+		&claim,
+		witness,
+		gamma,
+		alpha,
+		f_prime_committed_id,
+	)
+	.unwrap();
+
+	// VERIFIER
+	let f_prime_oracle = oracles.committed_oracle(f_prime_committed_id);
+	let verified_claims =
+		verify(&mut oracles.clone(), &claim, gamma, alpha, f_prime_oracle.clone()).unwrap();
+
+	// The grand product of the multiset check is one
+	let f_prime_n_vars = f_prime_oracle.n_vars();
+	let f_prime_poly = witness_index.get(f_prime_oracle.id()).unwrap();
+	assert_eq!(
+		f_prime_poly
+			.evaluate_on_hypercube((1 << f_prime_n_vars) - 2)
+			.unwrap(),
+		F::ONE
+	);
+
+	// Both zerocheck witnesses vanish on the hypercube
+	let t_prime_witness = &prove_output.t_prime_witness;
+	for i in 0..1 << t_prime_witness.n_vars() {
+		assert_eq!(t_prime_witness.evaluate_on_hypercube(i).unwrap(), F::ZERO);
+	}
+	let counts_nonzero_claim = &verified_claims.counts_nonzero_claim;
+	assert!(check_zerocheck_claim(counts_nonzero_claim, &witness_index)
+		.unwrap()
+		.is_empty());
+
+	let reduced_claims = prove_output.reduced_lookup_claims;
+	assert_eq!(
+		reduced_claims
+			.reduced_product_check_claims
+			.t_prime_claim
+			.poly
+			.n_vars(),
+		f_prime_n_vars
+	);
+	assert_eq!(
+		verified_claims
+			.reduced_product_check_claims
+			.t_prime_claim
+			.poly
+			.n_vars(),
+		f_prime_n_vars
+	);
+	assert_eq!(
+		verified_claims
+			.reduced_product_check_claims
+			.grand_product_poly_claim
+			.eval,
+		F::ONE
+	);
+	assert_eq!(reduced_claims.counts_nonzero_claim.poly.n_vars(), u_n_vars);
+}
+
+#[test]
+fn test_prove_verify_small_table() {
+	test_prove_verify_interaction(4, 6);
+}
+
+#[test]
+fn test_prove_verify_large_table() {
+	test_prove_verify_interaction(5, 3);
+}
+
+#[test]
+fn test_value_missing_from_table() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let LookupInstance {
+		oracles,
+		claim,
+		f_prime_committed_id,
+	} = make_instance(T_N_VARS, U_N_VARS);
+
+	let table = make_table(T_N_VARS);
+	let mapping = random_mapping(&mut rng, T_N_VARS, U_N_VARS);
+	let mut lookup_values = mapping.iter().map(|&row| table[row]).collect::<Vec<_>>();
+	lookup_values[5] = F::new(0x1234);
+	let witness = make_witness(&table, mapping, lookup_values).unwrap();
+
+	let mut witness_index = MultilinearWitnessIndex::new();
+	prove(
+		&mut oracles.clone(),
+		&mut witness_index,
+		&claim,
+		witness,
+		F::new(0x123),
+		F::new(0x346),
+		f_prime_committed_id,
+	)
+	.unwrap();
+
+	let f_prime_oracle = oracles.committed_oracle(f_prime_committed_id);
+	let f_prime_poly = witness_index.get(f_prime_oracle.id()).unwrap();
+	assert_ne!(
+		f_prime_poly
+			.evaluate_on_hypercube((1 << f_prime_oracle.n_vars()) - 2)
+			.unwrap(),
+		F::ONE
+	);
+}
+
+#[test]
+fn test_witness_mapping_out_of_range() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let table = make_table(T_N_VARS);
+	let mut mapping = random_mapping(&mut rng, T_N_VARS, U_N_VARS);
+	let lookup_values = mapping.iter().map(|&row| table[row]).collect();
+	mapping[3] = 1 << T_N_VARS;
+	assert_matches!(
+		make_witness(&table, mapping, lookup_values),
+		Err(Error::Msetcheck(MsetcheckError::MappingOutOfRange { index: 3 }))
+	);
+}
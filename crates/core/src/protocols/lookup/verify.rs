@@ -0,0 +1,39 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	error::Error,
+	lookup::{LookupClaim, ReducedLookupClaims},
+};
+use crate::{
+	oracle::{MultilinearOracleSet, MultilinearPolyOracle},
+	protocols::{
+		msetcheck::{self, Error as MsetcheckError, ReducedWeightedMsetcheckClaims},
+		prodcheck,
+	},
+};
+use binius_field::{BinaryField32b, ExtensionField, TowerField};
+
+/// Verify a lookup instance reduction.
+pub fn verify<F>(
+	oracles: &mut MultilinearOracleSet<F>,
+	claim: &LookupClaim<F>,
+	gamma: F,
+	alpha: F,
+	grand_prod_oracle: MultilinearPolyOracle<F>,
+) -> Result<ReducedLookupClaims<F>, Error>
+where
+	F: TowerField + ExtensionField<BinaryField32b>,
+{
+	let ReducedWeightedMsetcheckClaims {
+		prodcheck_claim,
+		counts_nonzero_claim,
+	} = msetcheck::verify_weighted(oracles, &claim.msetcheck_claim, gamma, alpha)
+		.map_err(MsetcheckError::from)?;
+	let reduced_product_check_claims =
+		prodcheck::verify(oracles, &prodcheck_claim, grand_prod_oracle)?;
+
+	Ok(ReducedLookupClaims {
+		reduced_product_check_claims,
+		counts_nonzero_claim,
+	})
+}
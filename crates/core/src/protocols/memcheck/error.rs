@@ -0,0 +1,47 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use crate::{
+	oracle::Error as IOPolynomialError,
+	polynomial::Error as PolynomialError,
+	protocols::{
+		lookup::Error as LookupError,
+		msetcheck::{Error as MsetcheckError, VerificationError as MsetcheckVerificationError},
+	},
+	witness::Error as WitnessError,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("the number of variables in some witness multilinear does not match the claim")]
+	WitnessNumVariablesMismatch,
+	#[error("the mapping must have one memory cell per access, expected {expected}")]
+	IncorrectMappingLength { expected: usize },
+	#[error("access {index} maps to a cell outside of the memory")]
+	MappingOutOfRange { index: usize },
+	#[error("polynomial error: {0}")]
+	Polynomial(#[from] PolynomialError),
+	#[error("witness error: {0}")]
+	Witness(#[from] WitnessError),
+	#[error("multiset check error: {0}")]
+	Msetcheck(#[from] MsetcheckError),
+	#[error("lookup error: {0}")]
+	Lookup(#[from] LookupError),
+	#[error("verification failure: {0}")]
+	Verification(#[from] VerificationError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerificationError {
+	#[error("the memory addresses must be a transparent oracle")]
+	NonTransparentMemoryAddresses,
+	#[error("the access oracles must have at most {max_n_vars} variables")]
+	TooManyAccesses { max_n_vars: usize },
+	#[error("the number of variables of the memory oracles are not all equal")]
+	MemoryNumVariablesMismatch,
+	#[error("the number of variables of the access oracles are not all equal")]
+	AccessNumVariablesMismatch,
+	#[error("multiset check verification failure: {0}")]
+	Msetcheck(#[from] MsetcheckVerificationError),
+	#[error("iopolynomial error: {0}")]
+	IOPolynomial(#[from] IOPolynomialError),
+}
@@ -0,0 +1,543 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::error::{Error, VerificationError};
+use crate::{
+	oracle::{CompositePolyOracle, MultilinearOracleSet, MultilinearPolyOracle, OracleId},
+	polynomial::{transparent::powers::Powers, MultilinearExtension},
+	protocols::{
+		lookup::{Error as LookupError, LookupClaim, LookupWitness},
+		msetcheck::{MsetcheckClaim, MULTIPLICITY_GENERATOR},
+		prodcheck::{ProdcheckClaim, ProdcheckWitness, SimpleMultGateComposition},
+		zerocheck::{ZerocheckClaim, ZerocheckWitness},
+	},
+	witness::MultilinearWitness,
+};
+use binius_field::{BinaryField32b, ExtensionField, Field, TowerField};
+use getset::Getters;
+use std::{iter, sync::Arc};
+
+/// The generator of the multiplicative group of `BinaryField32b` that timestamps are powers of.
+pub const TIMESTAMP_GENERATOR: BinaryField32b = MULTIPLICITY_GENERATOR;
+
+/// The maximum number of variables of the access oracles.
+///
+/// Timestamps are powers of [`TIMESTAMP_GENERATOR`], which has order $2^{32} - 1$. Bounding the
+/// number of accesses below $2^{31}$ guarantees that the difference of two timestamps never wraps
+/// around.
+pub const MAX_ACCESS_N_VARS: usize = 30;
+
+/// Oracles to the memory cells, all with the same number of variables.
+#[derive(Debug, Clone)]
+pub struct MemoryOracles<F: Field> {
+	/// Address of each cell, as a transparent oracle with distinct evaluations on the hypercube
+	pub addresses: MultilinearPolyOracle<F>,
+	/// Value of each cell before the first access
+	pub init_values: MultilinearPolyOracle<F>,
+	/// Value of each cell after the last access
+	pub final_values: MultilinearPolyOracle<F>,
+	/// Timestamp of each cell after the last access
+	pub final_timestamps: MultilinearPolyOracle<F>,
+}
+
+/// Oracles to the memory accesses, all with the same number of variables.
+///
+/// Every access reads a cell and writes it back, so a read is an access that writes the value it
+/// has read.
+#[derive(Debug, Clone)]
+pub struct AccessOracles<F: Field> {
+	/// Address of the accessed cell
+	pub addresses: MultilinearPolyOracle<F>,
+	/// Value read from the cell
+	pub read_values: MultilinearPolyOracle<F>,
+	/// Value written to the cell
+	pub write_values: MultilinearPolyOracle<F>,
+	/// Timestamp read from the cell
+	pub read_timestamps: MultilinearPolyOracle<F>,
+	/// Write timestamp divided by the read timestamp, which is $g^d$ for the number of accesses
+	/// $d$ since the read value was written
+	pub timestamp_deltas: MultilinearPolyOracle<F>,
+	/// Counts read by the lookups of the timestamp deltas into the write timestamps
+	pub delta_counts: MultilinearPolyOracle<F>,
+	/// Multiplicative inverses of the counts read by the lookups of the timestamp deltas
+	pub delta_counts_inverse: MultilinearPolyOracle<F>,
+	/// Final counts of the write timestamps looked up by the timestamp deltas
+	pub delta_final_counts: MultilinearPolyOracle<F>,
+}
+
+/// A memory checking claim, stating that a sequence of accesses is consistent with a memory.
+///
+/// The claim is that every access reads the value last written to its cell, or the initial value
+/// of the cell if it has not been written yet, and that the final values are the values last
+/// written. Timestamps are global: the $i$-th access writes its cell at timestamp $g^{i+1}$, where
+/// $g$ = [`TIMESTAMP_GENERATOR`], and the initial cells have timestamp $1$. Consistency then holds
+/// iff the multisets
+///
+/// $$\{(A_j, V^{init}_j, 1)\} \cup \{(a_i, w_i, g^{i+1})\} =
+///   \{(a_i, r_i, t_i)\} \cup \{(A_j, V^{final}_j, t^{final}_j)\}$$
+///
+/// are equal and every access reads a timestamp older than the one it writes. The latter is
+/// shown by the timestamp deltas $\delta_i$, with a zerocheck that $g^{i+1} = t_i \cdot \delta_i$
+/// and a lookup of the deltas into the write timestamps $\{g, \ldots, g^N\}$ of the $N$
+/// accesses.
+///
+/// The memory addresses must be a transparent oracle with distinct evaluations, as a cell could
+/// otherwise be replayed under the address of another. The memory and the accesses may have
+/// different numbers of variables, and there are at most $2^{30}$ accesses, see
+/// [`MAX_ACCESS_N_VARS`].
+#[derive(Debug, Getters)]
+pub struct MemcheckClaim<F: Field> {
+	#[get = "pub"]
+	memory: MemoryOracles<F>,
+	#[get = "pub"]
+	accesses: AccessOracles<F>,
+}
+
+impl<F: Field> MemcheckClaim<F> {
+	/// Claim constructor
+	pub fn new(
+		memory: MemoryOracles<F>,
+		accesses: AccessOracles<F>,
+	) -> Result<Self, VerificationError> {
+		if !matches!(memory.addresses, MultilinearPolyOracle::Transparent(..)) {
+			return Err(VerificationError::NonTransparentMemoryAddresses);
+		}
+
+		let memory_n_vars = memory.addresses.n_vars();
+		if [
+			&memory.init_values,
+			&memory.final_values,
+			&memory.final_timestamps,
+		]
+		.iter()
+		.any(|oracle| oracle.n_vars() != memory_n_vars)
+		{
+			return Err(VerificationError::MemoryNumVariablesMismatch);
+		}
+
+		let access_n_vars = accesses.addresses.n_vars();
+		if access_n_vars > MAX_ACCESS_N_VARS {
+			return Err(VerificationError::TooManyAccesses {
+				max_n_vars: MAX_ACCESS_N_VARS,
+			});
+		}
+		if [
+			&accesses.read_values,
+			&accesses.write_values,
+			&accesses.read_timestamps,
+			&accesses.timestamp_deltas,
+			&accesses.delta_counts,
+			&accesses.delta_counts_inverse,
+			&accesses.delta_final_counts,
+		]
+		.iter()
+		.any(|oracle| oracle.n_vars() != access_n_vars)
+		{
+			return Err(VerificationError::AccessNumVariablesMismatch);
+		}
+
+		Ok(Self { memory, accesses })
+	}
+
+	/// Number of variables of the memory oracles.
+	pub fn memory_n_vars(&self) -> usize {
+		self.memory.addresses.n_vars()
+	}
+
+	/// Number of variables of the access oracles.
+	pub fn access_n_vars(&self) -> usize {
+		self.accesses.addresses.n_vars()
+	}
+}
+
+#[derive(Debug, Getters)]
+pub struct MemcheckWitness<'a, FW: Field> {
+	/// Witness to the cell addresses
+	#[get = "pub"]
+	memory_addresses: MultilinearWitness<'a, FW>,
+	/// Witness to the initial cell values
+	#[get = "pub"]
+	init_values: MultilinearWitness<'a, FW>,
+	/// Witness to the accessed addresses
+	#[get = "pub"]
+	access_addresses: MultilinearWitness<'a, FW>,
+	/// Witness to the written values
+	#[get = "pub"]
+	write_values: MultilinearWitness<'a, FW>,
+	/// The cell accessed by each access
+	#[get = "pub"]
+	access_to_cell_mapping: Vec<usize>,
+	/// Values read by the accesses
+	#[get = "pub"]
+	read_values: MultilinearExtension<'static, FW>,
+	/// Final cell values
+	#[get = "pub"]
+	final_values: MultilinearExtension<'static, FW>,
+	/// Timestamps read by the accesses
+	#[get = "pub"]
+	read_timestamps: MultilinearExtension<'static, BinaryField32b>,
+	/// Write timestamps divided by the read timestamps
+	#[get = "pub"]
+	timestamp_deltas: MultilinearExtension<'static, BinaryField32b>,
+	/// Final cell timestamps
+	#[get = "pub"]
+	final_timestamps: MultilinearExtension<'static, BinaryField32b>,
+	/// Witness to the lookup of the timestamp deltas into the write timestamps
+	#[get = "pub"]
+	pub(super) timestamps_lookup_witness: LookupWitness<'a, FW>,
+}
+
+impl<'a, FW> MemcheckWitness<'a, FW>
+where
+	FW: Field + ExtensionField<BinaryField32b>,
+{
+	/// Witness constructor
+	///
+	/// Replays the accesses in order against the initial memory to compute the read values, the
+	/// final values, all timestamps and the counts of the timestamp lookup. These must be
+	/// committed before the multiset check challenges are sampled.
+	pub fn new(
+		memory_addresses: MultilinearWitness<'a, FW>,
+		init_values: MultilinearWitness<'a, FW>,
+		access_addresses: MultilinearWitness<'a, FW>,
+		write_values: MultilinearWitness<'a, FW>,
+		access_to_cell_mapping: Vec<usize>,
+	) -> Result<Self, Error> {
+		if init_values.n_vars() != memory_addresses.n_vars()
+			|| write_values.n_vars() != access_addresses.n_vars()
+		{
+			return Err(Error::WitnessNumVariablesMismatch);
+		}
+
+		let access_size = 1 << access_addresses.n_vars();
+		if access_to_cell_mapping.len() != access_size {
+			return Err(Error::IncorrectMappingLength {
+				expected: access_size,
+			});
+		}
+
+		let memory_size = 1 << memory_addresses.n_vars();
+		if let Some(index) = access_to_cell_mapping
+			.iter()
+			.position(|&cell| cell >= memory_size)
+		{
+			return Err(Error::MappingOutOfRange { index });
+		}
+
+		// The step of the last write to each cell, where step 0 is the initial memory
+		let mut final_steps = vec![0; memory_size];
+		let read_steps = access_to_cell_mapping
+			.iter()
+			.enumerate()
+			.map(|(index, &cell)| std::mem::replace(&mut final_steps[cell], index + 1))
+			.collect();
+
+		Self::from_steps(
+			memory_addresses,
+			init_values,
+			access_addresses,
+			write_values,
+			access_to_cell_mapping,
+			read_steps,
+			final_steps,
+		)
+	}
+
+	/// Builds the witness from the step of the write read by each access and of the final write
+	/// to each cell.
+	///
+	/// Step 0 stands for the initial memory, and step $i + 1$ for the write of the $i$-th access.
+	/// Steps must be in range, but are not checked for consistency with the accesses.
+	pub(super) fn from_steps(
+		memory_addresses: MultilinearWitness<'a, FW>,
+		init_values: MultilinearWitness<'a, FW>,
+		access_addresses: MultilinearWitness<'a, FW>,
+		write_values: MultilinearWitness<'a, FW>,
+		access_to_cell_mapping: Vec<usize>,
+		read_steps: Vec<usize>,
+		final_steps: Vec<usize>,
+	) -> Result<Self, Error> {
+		let access_n_vars = access_addresses.n_vars();
+		let step_value = |cell: usize, step: usize| match step {
+			0 => init_values.evaluate_on_hypercube(cell),
+			_ => write_values.evaluate_on_hypercube(step - 1),
+		};
+
+		let timestamps = iter::successors(Some(BinaryField32b::ONE), |&timestamp| {
+			Some(timestamp * TIMESTAMP_GENERATOR)
+		})
+		.take((1 << access_n_vars) + 1)
+		.collect::<Vec<_>>();
+
+		let read_values = iter::zip(&access_to_cell_mapping, &read_steps)
+			.map(|(&cell, &step)| step_value(cell, step))
+			.collect::<Result<Vec<_>, _>>()?;
+		let final_values = final_steps
+			.iter()
+			.enumerate()
+			.map(|(cell, &step)| step_value(cell, step))
+			.collect::<Result<Vec<_>, _>>()?;
+		let read_timestamps = read_steps
+			.iter()
+			.map(|&step| timestamps[step])
+			.collect::<Vec<_>>();
+		let timestamp_deltas = read_timestamps
+			.iter()
+			.enumerate()
+			.map(|(index, read_timestamp)| {
+				timestamps[index + 1]
+					* read_timestamp
+						.invert()
+						.expect("timestamps are powers of the generator")
+			})
+			.collect();
+		let final_timestamps = final_steps.iter().map(|&step| timestamps[step]).collect();
+
+		// The delta of the i-th access is the write timestamp of access i - step, which is out of
+		// the table if the step is not older than the access.
+		let write_timestamps = MultilinearExtension::from_values(timestamps[1..].to_vec())?;
+		let timestamp_deltas = MultilinearExtension::from_values(timestamp_deltas)?;
+		let delta_mapping = read_steps
+			.iter()
+			.enumerate()
+			.map(|(index, &step)| index.saturating_sub(step))
+			.collect();
+		let timestamps_lookup_witness = LookupWitness::new(
+			write_timestamps.specialize_arc_dyn(),
+			timestamp_deltas.clone().specialize_arc_dyn(),
+			delta_mapping,
+		)?;
+
+		Ok(Self {
+			memory_addresses,
+			init_values,
+			access_addresses,
+			write_values,
+			access_to_cell_mapping,
+			read_values: MultilinearExtension::from_values(read_values)?,
+			final_values: MultilinearExtension::from_values(final_values)?,
+			read_timestamps: MultilinearExtension::from_values(read_timestamps)?,
+			timestamp_deltas,
+			final_timestamps: MultilinearExtension::from_values(final_timestamps)?,
+			timestamps_lookup_witness,
+		})
+	}
+}
+
+/// The timestamp oracles of a memcheck claim that are not committed.
+#[derive(Debug, Clone, Copy)]
+pub struct TimestampOracles {
+	/// Constant one timestamps of the cells before the first access
+	pub init_timestamps: OracleId,
+	/// Transparent powers $g^i$ of the generator at the index $i$ of each access
+	pub step_powers: OracleId,
+	/// Timestamps $g^{i+1}$ written by the accesses
+	pub write_timestamps: OracleId,
+}
+
+/// Adds the initial and written timestamp oracles of a memcheck claim to the oracle set.
+pub fn add_timestamp_oracles<F>(
+	oracles: &mut MultilinearOracleSet<F>,
+	claim: &MemcheckClaim<F>,
+) -> Result<TimestampOracles, VerificationError>
+where
+	F: TowerField + ExtensionField<BinaryField32b>,
+{
+	let generator = F::from(TIMESTAMP_GENERATOR);
+	let init_timestamps =
+		oracles.add_linear_combination(claim.memory_n_vars(), F::ONE, iter::empty())?;
+	let step_powers = oracles.add_transparent(
+		Arc::new(Powers::new(claim.access_n_vars(), generator)),
+		BinaryField32b::TOWER_LEVEL,
+	)?;
+	let write_timestamps = oracles.add_linear_combination(
+		claim.access_n_vars(),
+		F::ZERO,
+		[(step_powers, generator)],
+	)?;
+	Ok(TimestampOracles {
+		init_timestamps,
+		step_powers,
+		write_timestamps,
+	})
+}
+
+/// The lookup of the timestamp deltas of a memcheck claim into the write timestamps.
+pub(super) fn timestamps_lookup_claim<F: Field>(
+	claim: &MemcheckClaim<F>,
+	write_timestamps: MultilinearPolyOracle<F>,
+) -> Result<LookupClaim<F>, LookupError> {
+	let accesses = &claim.accesses;
+	LookupClaim::new(
+		write_timestamps,
+		accesses.timestamp_deltas.clone(),
+		accesses.delta_counts.clone(),
+		accesses.delta_counts_inverse.clone(),
+		accesses.delta_final_counts.clone(),
+	)
+}
+
+#[derive(Debug)]
+pub struct ReducedMemcheckClaims<F: Field> {
+	pub prodcheck_claim: ProdcheckClaim<F>,
+	/// Zerocheck claim on $g^{i+1} - t \cdot \delta$, showing that the deltas divide the write
+	/// timestamps by the read timestamps
+	pub timestamps_order_claim: ZerocheckClaim<F>,
+	/// Lookup of the deltas into the write timestamps, showing that every access reads an older
+	/// timestamp than the one it writes
+	pub timestamps_lookup_claim: LookupClaim<F>,
+}
+
+#[derive(Debug)]
+pub struct MemcheckProveOutput<'a, F: Field, FW: Field> {
+	pub reduced_claims: ReducedMemcheckClaims<F>,
+	pub prodcheck_witness: ProdcheckWitness<'a, FW>,
+	pub timestamps_order_witness: ZerocheckWitness<'a, FW, SimpleMultGateComposition>,
+	pub timestamps_lookup_witness: LookupWitness<'a, FW>,
+}
+
+/// The result of reducing a memcheck claim to a multiset check claim.
+#[derive(Debug)]
+pub(super) struct MemcheckReduction<F: Field> {
+	pub msetcheck_claim: MsetcheckClaim<F>,
+	pub timestamps_order_claim: ZerocheckClaim<F>,
+	pub timestamp_oracles: TimestampOracles,
+}
+
+/// Reduces a memcheck claim to a three-dimensional multiset check over (address, value, timestamp)
+/// and a zerocheck of the timestamp deltas.
+///
+/// Both sides are brought to $\nu = \max(\nu_{mem}, \nu_{acc})$ variables by repeating the
+/// smaller of the memory and the accesses. The repeated rows are the initial cells for the memory
+/// and the writes for the accesses, which are added to both sides of the multiset check alike.
+pub(super) fn reduce_memcheck_claim<F>(
+	oracles: &mut MultilinearOracleSet<F>,
+	claim: &MemcheckClaim<F>,
+) -> Result<MemcheckReduction<F>, VerificationError>
+where
+	F: TowerField + ExtensionField<BinaryField32b>,
+{
+	let access_n_vars = claim.access_n_vars();
+	let n_vars = claim.memory_n_vars().max(access_n_vars);
+	let MemoryOracles {
+		addresses: memory_addresses,
+		init_values,
+		final_values,
+		final_timestamps,
+	} = &claim.memory;
+	let AccessOracles {
+		addresses: access_addresses,
+		read_values,
+		write_values,
+		read_timestamps,
+		timestamp_deltas,
+		..
+	} = &claim.accesses;
+
+	let timestamp_oracles = add_timestamp_oracles(oracles, claim)?;
+	let TimestampOracles {
+		init_timestamps,
+		write_timestamps,
+		..
+	} = timestamp_oracles;
+	let access_ones_id = oracles.add_linear_combination(access_n_vars, F::ONE, iter::empty())?;
+
+	let addresses = merge_padded(
+		oracles,
+		n_vars,
+		(memory_addresses.id(), memory_addresses.id()),
+		(access_addresses.id(), access_addresses.id()),
+	)?;
+
+	// Initial cells followed by the writes
+	let lhs_values = merge_padded(
+		oracles,
+		n_vars,
+		(init_values.id(), init_values.id()),
+		(write_values.id(), write_values.id()),
+	)?;
+	let lhs_timestamps = merge_padded(
+		oracles,
+		n_vars,
+		(init_timestamps, init_timestamps),
+		(write_timestamps, access_ones_id),
+	)?;
+
+	// Final cells followed by the reads, padded with the same rows as the left hand side
+	let rhs_values = merge_padded(
+		oracles,
+		n_vars,
+		(final_values.id(), init_values.id()),
+		(read_values.id(), write_values.id()),
+	)?;
+	let rhs_timestamps = merge_padded(
+		oracles,
+		n_vars,
+		(final_timestamps.id(), init_timestamps),
+		(read_timestamps.id(), access_ones_id),
+	)?;
+
+	let msetcheck_claim = MsetcheckClaim::new(
+		[addresses.clone(), lhs_values, lhs_timestamps],
+		[addresses, rhs_values, rhs_timestamps],
+	)?;
+
+	let timestamps_order_oracle = CompositePolyOracle::new(
+		access_n_vars,
+		vec![
+			oracles.oracle(write_timestamps),
+			read_timestamps.clone(),
+			timestamp_deltas.clone(),
+		],
+		SimpleMultGateComposition,
+	)?;
+
+	Ok(MemcheckReduction {
+		msetcheck_claim,
+		timestamps_order_claim: ZerocheckClaim {
+			poly: timestamps_order_oracle,
+		},
+		timestamp_oracles,
+	})
+}
+
+/// Pads a memory and an access column, each given with its filler, to `n_vars` variables and
+/// merges them.
+fn merge_padded<F: TowerField>(
+	oracles: &mut MultilinearOracleSet<F>,
+	n_vars: usize,
+	(memory_id, memory_filler_id): (OracleId, OracleId),
+	(access_id, access_filler_id): (OracleId, OracleId),
+) -> Result<MultilinearPolyOracle<F>, VerificationError> {
+	let memory_id = pad_with(oracles, memory_id, memory_filler_id, n_vars)?;
+	let access_id = pad_with(oracles, access_id, access_filler_id, n_vars)?;
+	let merged_id = oracles.add_merged(memory_id, access_id)?;
+	Ok(oracles.oracle(merged_id))
+}
+
+/// Extends an oracle up to `n_vars` variables with copies of `filler_id` in all added hypercube
+/// rows, where the filler has the same number of variables as the oracle.
+fn pad_with<F: TowerField>(
+	oracles: &mut MultilinearOracleSet<F>,
+	mut id: OracleId,
+	filler_id: OracleId,
+	n_vars: usize,
+) -> Result<OracleId, VerificationError> {
+	let inner_n_vars = oracles.n_vars(id);
+	if inner_n_vars == n_vars {
+		return Ok(id);
+	}
+	if id == filler_id {
+		return Ok(oracles.add_repeating(id, n_vars - inner_n_vars)?);
+	}
+
+	while oracles.n_vars(id) < n_vars {
+		let level = oracles.n_vars(id);
+		let filler_id = if level == inner_n_vars {
+			filler_id
+		} else {
+			oracles.add_repeating(filler_id, level - inner_n_vars)?
+		};
+		id = oracles.add_merged(id, filler_id)?;
+	}
+	Ok(id)
+}
@@ -0,0 +1,25 @@
+// Copyright 2024 Ulvetanna Inc.
+
+//! The offline memory checking polynomial protocol.
+//!
+//! Memory checking proves that a sequence of reads and writes is consistent with a read-write
+//! memory. Every access is a read of a cell followed by a write of the same cell, timestamped
+//! with the index of the access. The read and write tuples of (address, value, timestamp),
+//! together with the initial and final state of the memory, are reduced to a multiset check, and
+//! a lookup shows that every access reads a timestamp older than its own, see [`MemcheckClaim`].
+
+mod error;
+#[allow(clippy::module_inception)]
+mod memcheck;
+mod prove;
+#[cfg(test)]
+mod tests;
+mod verify;
+
+pub use error::*;
+pub use memcheck::{
+	add_timestamp_oracles, AccessOracles, MemcheckClaim, MemcheckProveOutput, MemcheckWitness,
+	MemoryOracles, ReducedMemcheckClaims, TimestampOracles, MAX_ACCESS_N_VARS, TIMESTAMP_GENERATOR,
+};
+pub use prove::*;
+pub use verify::*;
@@ -0,0 +1,143 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	error::Error,
+	memcheck::{
+		reduce_memcheck_claim, timestamps_lookup_claim, AccessOracles, MemcheckClaim,
+		MemcheckProveOutput, MemcheckReduction, MemcheckWitness, MemoryOracles,
+		ReducedMemcheckClaims, TimestampOracles, TIMESTAMP_GENERATOR,
+	},
+};
+use crate::{
+	oracle::{MultilinearOracleSet, MultilinearPolyOracle},
+	polynomial::{transparent::powers::Powers, MultilinearComposite},
+	protocols::{
+		msetcheck::{self, MsetcheckProveOutput, MsetcheckWitness},
+		prodcheck::SimpleMultGateComposition,
+	},
+	witness::{derive_witnesses_of, MultilinearWitnessIndex},
+};
+use binius_field::{BinaryField32b, ExtensionField, TowerField};
+
+/// Prove a memcheck instance reduction.
+///
+/// The witnesses of the memory and access oracles are added to the witness index, and the claim
+/// is reduced to a product check of the multiset equality described in [`MemcheckClaim`], with
+/// multiset check challenges $\gamma$ and $\alpha$, to a zerocheck that the timestamp deltas
+/// divide the write timestamps by the read timestamps, and to the lookup of the deltas into the
+/// write timestamps, which must be proven with [`crate::protocols::lookup::prove`].
+pub fn prove<'a, F, FW>(
+	oracles: &mut MultilinearOracleSet<F>,
+	witness_index: &mut MultilinearWitnessIndex<'a, FW>,
+	claim: &MemcheckClaim<F>,
+	witness: MemcheckWitness<'a, FW>,
+	gamma: F,
+	alpha: F,
+) -> Result<MemcheckProveOutput<'a, F, FW>, Error>
+where
+	F: TowerField + ExtensionField<BinaryField32b> + From<FW>,
+	FW: TowerField + ExtensionField<BinaryField32b> + From<F>,
+{
+	if witness.memory_addresses().n_vars() != claim.memory_n_vars()
+		|| witness.access_addresses().n_vars() != claim.access_n_vars()
+	{
+		return Err(Error::WitnessNumVariablesMismatch);
+	}
+
+	let MemoryOracles {
+		addresses: memory_addresses,
+		init_values,
+		final_values,
+		final_timestamps,
+	} = claim.memory();
+	let AccessOracles {
+		addresses: access_addresses,
+		read_values,
+		write_values,
+		read_timestamps,
+		timestamp_deltas,
+		..
+	} = claim.accesses();
+
+	let polynomials = [
+		(memory_addresses, witness.memory_addresses().clone()),
+		(init_values, witness.init_values().clone()),
+		(access_addresses, witness.access_addresses().clone()),
+		(write_values, witness.write_values().clone()),
+		(read_values, witness.read_values().clone().specialize_arc_dyn()),
+		(final_values, witness.final_values().clone().specialize_arc_dyn()),
+		(read_timestamps, witness.read_timestamps().clone().specialize_arc_dyn()),
+		(timestamp_deltas, witness.timestamp_deltas().clone().specialize_arc_dyn()),
+		(final_timestamps, witness.final_timestamps().clone().specialize_arc_dyn()),
+	];
+	for (oracle, polynomial) in polynomials {
+		witness_index.set(oracle.id(), polynomial);
+	}
+
+	let first_reduction_id = oracles.size();
+	let MemcheckReduction {
+		msetcheck_claim,
+		timestamps_order_claim,
+		timestamp_oracles,
+	} = reduce_memcheck_claim(oracles, claim)?;
+	let TimestampOracles {
+		step_powers,
+		write_timestamps,
+		..
+	} = timestamp_oracles;
+
+	let step_powers_witness = Powers::new(claim.access_n_vars(), TIMESTAMP_GENERATOR)
+		.multilinear_extension::<BinaryField32b>()?;
+	witness_index.set(step_powers, step_powers_witness.specialize_arc_dyn());
+	derive_witnesses_of(
+		oracles,
+		(first_reduction_id..oracles.size()).filter(|&id| id != step_powers),
+		witness_index,
+	)?;
+
+	let relation_witnesses = |relation_oracles: &[MultilinearPolyOracle<F>]| {
+		relation_oracles
+			.iter()
+			.map(|oracle| witness_index.get_required(oracle.id()))
+			.collect::<Result<Vec<_>, _>>()
+	};
+	let msetcheck_witness = MsetcheckWitness::new(
+		relation_witnesses(msetcheck_claim.t_oracles())?,
+		relation_witnesses(msetcheck_claim.u_oracles())?,
+	)
+	.map_err(msetcheck::Error::from)?;
+
+	let timestamps_order_witness = MultilinearComposite::new(
+		claim.access_n_vars(),
+		SimpleMultGateComposition,
+		vec![
+			witness_index.get_required(write_timestamps)?,
+			witness_index.get_required(read_timestamps.id())?,
+			witness_index.get_required(timestamp_deltas.id())?,
+		],
+	)?;
+	let timestamps_lookup_claim = timestamps_lookup_claim(claim, oracles.oracle(write_timestamps))?;
+
+	let MsetcheckProveOutput {
+		prodcheck_claim,
+		prodcheck_witness,
+	} = msetcheck::prove(
+		oracles,
+		witness_index,
+		&msetcheck_claim,
+		msetcheck_witness,
+		gamma,
+		Some(alpha),
+	)?;
+
+	Ok(MemcheckProveOutput {
+		reduced_claims: ReducedMemcheckClaims {
+			prodcheck_claim,
+			timestamps_order_claim,
+			timestamps_lookup_claim,
+		},
+		prodcheck_witness,
+		timestamps_order_witness,
+		timestamps_lookup_witness: witness.timestamps_lookup_witness,
+	})
+}
@@ -0,0 +1,323 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::*;
+use crate::{
+	challenger::HashChallenger,
+	oracle::{CommittedBatchSpec, CommittedId, MultilinearOracleSet},
+	polynomial::{transparent::tower_basis::TowerBasis, EvaluationDomain, MultilinearExtension},
+	protocols::{
+		lookup,
+		prodcheck::{
+			prove_gkr, verify_gkr, Error as ProdcheckError, GkrProdcheckProveOutput,
+			ReducedGkrProdcheckClaims, VerificationError as ProdcheckVerificationError,
+		},
+		zerocheck::{check_zerocheck_claim, ZerocheckClaim},
+	},
+	witness::{MultilinearWitness, MultilinearWitnessIndex},
+};
+use assert_matches::assert_matches;
+use binius_field::{BinaryField128b, BinaryField32b, Field, TowerField};
+use binius_hash::GroestlHasher;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::sync::Arc;
+
+type F = BinaryField128b;
+
+struct MemcheckInstance {
+	oracles: MultilinearOracleSet<F>,
+	claim: MemcheckClaim<F>,
+	/// The grand product polynomial of the timestamp lookup
+	f_prime_committed_id: CommittedId,
+}
+
+/// The distinct addresses of the memory cells, which are the tower basis elements.
+fn memory_addresses(memory_n_vars: usize) -> TowerBasis<F> {
+	TowerBasis::new(memory_n_vars, 0).unwrap()
+}
+
+fn make_instance(memory_n_vars: usize, access_n_vars: usize) -> MemcheckInstance {
+	let mut oracles = MultilinearOracleSet::<F>::new();
+	let memory_addresses_id = oracles
+		.add_transparent(Arc::new(memory_addresses(memory_n_vars)), memory_n_vars)
+		.unwrap();
+	let mut add_batch = |round_id, n_vars, n_polys, tower_level| {
+		oracles.add_committed_batch(CommittedBatchSpec {
+			round_id,
+			n_vars,
+			n_polys,
+			tower_level,
+		})
+	};
+	let memory_batch_id = add_batch(1, memory_n_vars, 2, F::TOWER_LEVEL);
+	let memory_timestamps_batch_id = add_batch(1, memory_n_vars, 1, BinaryField32b::TOWER_LEVEL);
+	let access_batch_id = add_batch(1, access_n_vars, 3, F::TOWER_LEVEL);
+	let access_timestamps_batch_id = add_batch(1, access_n_vars, 5, BinaryField32b::TOWER_LEVEL);
+	// The lookup relations merge two columns, and the grand product adds one variable more
+	let f_prime_batch_id = add_batch(2, access_n_vars + 2, 1, F::TOWER_LEVEL);
+
+	let oracle = |batch_id, index| oracles.committed_oracle(CommittedId { batch_id, index });
+	let memory = MemoryOracles {
+		addresses: oracles.oracle(memory_addresses_id),
+		init_values: oracle(memory_batch_id, 0),
+		final_values: oracle(memory_batch_id, 1),
+		final_timestamps: oracle(memory_timestamps_batch_id, 0),
+	};
+	let accesses = AccessOracles {
+		addresses: oracle(access_batch_id, 0),
+		read_values: oracle(access_batch_id, 1),
+		write_values: oracle(access_batch_id, 2),
+		read_timestamps: oracle(access_timestamps_batch_id, 0),
+		timestamp_deltas: oracle(access_timestamps_batch_id, 1),
+		delta_counts: oracle(access_timestamps_batch_id, 2),
+		delta_counts_inverse: oracle(access_timestamps_batch_id, 3),
+		delta_final_counts: oracle(access_timestamps_batch_id, 4),
+	};
+	let claim = MemcheckClaim::new(memory, accesses).unwrap();
+
+	MemcheckInstance {
+		oracles,
+		claim,
+		f_prime_committed_id: CommittedId {
+			batch_id: f_prime_batch_id,
+			index: 0,
+		},
+	}
+}
+
+fn to_witness(values: Vec<F>) -> MultilinearWitness<'static, F> {
+	MultilinearExtension::from_values(values)
+		.unwrap()
+		.specialize_arc_dyn()
+}
+
+fn memory_addresses_witness(memory_n_vars: usize) -> MultilinearWitness<'static, F> {
+	memory_addresses(memory_n_vars)
+		.multilinear_extension::<F>()
+		.unwrap()
+		.specialize_arc_dyn()
+}
+
+/// A toy RAM trace where every other access is a read and the rest write random values.
+///
+/// If `wrong_address` is set, that access reports a different address than the one of the
+/// cell it accesses.
+fn make_witness(
+	memory_n_vars: usize,
+	access_n_vars: usize,
+	wrong_address: Option<usize>,
+) -> MemcheckWitness<'static, F> {
+	let mut rng = StdRng::seed_from_u64(0);
+
+	let memory_addresses = memory_addresses_witness(memory_n_vars);
+	let init_values = (0..1 << memory_n_vars)
+		.map(|_| <F as Field>::random(&mut rng))
+		.collect::<Vec<_>>();
+
+	let mut memory = init_values.clone();
+	let mut mapping = Vec::with_capacity(1 << access_n_vars);
+	let mut access_addresses = Vec::with_capacity(1 << access_n_vars);
+	let mut write_values = Vec::with_capacity(1 << access_n_vars);
+	for index in 0..1 << access_n_vars {
+		let cell = rng.gen_range(0..1 << memory_n_vars);
+		if index % 2 == 1 {
+			memory[cell] = <F as Field>::random(&mut rng);
+		}
+		mapping.push(cell);
+		access_addresses.push(memory_addresses.evaluate_on_hypercube(cell).unwrap());
+		write_values.push(memory[cell]);
+	}
+	if let Some(index) = wrong_address {
+		access_addresses[index] += F::ONE;
+	}
+
+	MemcheckWitness::new(
+		memory_addresses,
+		to_witness(init_values),
+		to_witness(access_addresses),
+		to_witness(write_values),
+		mapping,
+	)
+	.unwrap()
+}
+
+/// Four writes to the first cell, where the second access reads the value written by the third.
+///
+/// Every written value is read exactly once or is final, so the multisets are equal, but the
+/// second access reads a timestamp newer than its own.
+fn make_reordered_witness(memory_n_vars: usize) -> MemcheckWitness<'static, F> {
+	let mut rng = StdRng::seed_from_u64(0);
+	let memory_addresses = memory_addresses_witness(memory_n_vars);
+	let init_values = (0..1 << memory_n_vars)
+		.map(|_| <F as Field>::random(&mut rng))
+		.collect::<Vec<_>>();
+	let write_values = (0..4)
+		.map(|_| <F as Field>::random(&mut rng))
+		.collect::<Vec<_>>();
+	let access_addresses = vec![memory_addresses.evaluate_on_hypercube(0).unwrap(); 4];
+
+	let mut final_steps = vec![0; 1 << memory_n_vars];
+	final_steps[0] = 4;
+	MemcheckWitness::from_steps(
+		memory_addresses,
+		to_witness(init_values),
+		to_witness(access_addresses),
+		to_witness(write_values),
+		vec![0; 4],
+		vec![0, 3, 1, 2],
+		final_steps,
+	)
+	.unwrap()
+}
+
+struct MemcheckOutcome {
+	/// Result of the GKR product check of the multiset equality
+	multiset_check: Result<ReducedGkrProdcheckClaims<F>, ProdcheckError>,
+	/// Whether the timestamp zerocheck and lookup hold on the witness
+	timestamps_ordered: bool,
+}
+
+/// Proves and verifies a toy RAM trace down to evaluation claims, using the GKR product check
+/// for the multiset equality and checking the timestamp claims directly on the witness.
+fn run_memcheck(
+	instance: MemcheckInstance,
+	witness: MemcheckWitness<'static, F>,
+) -> MemcheckOutcome {
+	let MemcheckInstance {
+		oracles,
+		claim,
+		f_prime_committed_id,
+	} = instance;
+	let memory_n_vars = claim.memory_n_vars();
+	let access_n_vars = claim.access_n_vars();
+
+	// challenges
+	let gamma = F::new(0x123);
+	let alpha = F::new(0x346);
+	let lookup_gamma = F::new(0x567);
+	let lookup_alpha = F::new(0x789);
+
+	// PROVER
+	let mut prover_oracles = oracles.clone();
+	let mut witness_index = MultilinearWitnessIndex::new();
+	let MemcheckProveOutput {
+		reduced_claims: prover_claims,
+		prodcheck_witness,
+		timestamps_lookup_witness,
+		..
+	} = prove(&mut prover_oracles, &mut witness_index, &claim, witness, gamma, alpha).unwrap();
+	lookup::prove(
+		&mut prover_oracles,
+		&mut witness_index,
+		&prover_claims.timestamps_lookup_claim,
+		timestamps_lookup_witness,
+		lookup_gamma,
+		lookup_alpha,
+		f_prime_committed_id,
+	)
+	.unwrap();
+
+	// VERIFIER
+	let mut verifier_oracles = oracles.clone();
+	let verified_claims = verify(&mut verifier_oracles, &claim, gamma, alpha).unwrap();
+	let f_prime_oracle = oracles.committed_oracle(f_prime_committed_id);
+	let verified_lookup_claims = lookup::verify(
+		&mut verifier_oracles,
+		&verified_claims.timestamps_lookup_claim,
+		lookup_gamma,
+		lookup_alpha,
+		f_prime_oracle.clone(),
+	)
+	.unwrap();
+
+	// Consistency checks
+	let n_vars = memory_n_vars.max(access_n_vars) + 1;
+	assert_eq!(verified_claims.prodcheck_claim.n_vars(), Some(n_vars));
+	assert_eq!(prover_claims.prodcheck_claim.n_vars(), Some(n_vars));
+
+	// Timestamps
+	let f_prime_poly = witness_index.get(f_prime_oracle.id()).unwrap();
+	let lookup_product = f_prime_poly
+		.evaluate_on_hypercube((1 << f_prime_oracle.n_vars()) - 2)
+		.unwrap();
+	let zerocheck_holds = |claim: &ZerocheckClaim<F>| {
+		check_zerocheck_claim(claim, &witness_index)
+			.unwrap()
+			.is_empty()
+	};
+	let timestamps_ordered = zerocheck_holds(&verified_claims.timestamps_order_claim)
+		&& zerocheck_holds(&verified_lookup_claims.counts_nonzero_claim)
+		&& lookup_product == F::ONE;
+
+	// Product check
+	let domain = EvaluationDomain::<F>::new(4).unwrap();
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let multiset_check = prove_gkr(
+		&prover_claims.prodcheck_claim,
+		prodcheck_witness,
+		&domain,
+		challenger.clone(),
+		|_| 1,
+	)
+	.and_then(|GkrProdcheckProveOutput { proof, .. }| {
+		verify_gkr(&verified_claims.prodcheck_claim, proof, challenger)
+	});
+
+	MemcheckOutcome {
+		multiset_check,
+		timestamps_ordered,
+	}
+}
+
+#[test]
+fn test_prove_verify_ram_trace_more_accesses() {
+	let outcome = run_memcheck(make_instance(3, 6), make_witness(3, 6, None));
+	outcome.multiset_check.unwrap();
+	assert!(outcome.timestamps_ordered);
+}
+
+#[test]
+fn test_prove_verify_ram_trace_larger_memory() {
+	let outcome = run_memcheck(make_instance(5, 3), make_witness(5, 3, None));
+	outcome.multiset_check.unwrap();
+	assert!(outcome.timestamps_ordered);
+}
+
+#[test]
+fn test_ram_trace_wrong_address() {
+	let outcome = run_memcheck(make_instance(3, 6), make_witness(3, 6, Some(21)));
+	assert_matches!(
+		outcome.multiset_check,
+		Err(ProdcheckError::Verification(ProdcheckVerificationError::ProductsMismatch))
+	);
+}
+
+#[test]
+fn test_ram_trace_reordered_reads() {
+	// The multiset check alone accepts a read of a value from the future
+	let outcome = run_memcheck(make_instance(2, 2), make_reordered_witness(2));
+	outcome.multiset_check.unwrap();
+	assert!(!outcome.timestamps_ordered);
+}
+
+#[test]
+fn test_claim_rejects_committed_memory_addresses() {
+	// Committed addresses may repeat, so that two cells share an address
+	let MemcheckInstance { claim, .. } = make_instance(3, 6);
+	let memory = MemoryOracles {
+		addresses: claim.memory().init_values.clone(),
+		..claim.memory().clone()
+	};
+	assert_matches!(
+		MemcheckClaim::new(memory, claim.accesses().clone()),
+		Err(VerificationError::NonTransparentMemoryAddresses)
+	);
+}
+
+#[test]
+fn test_witness_mapping_out_of_range() {
+	let values = to_witness(vec![F::ZERO; 16]);
+	assert_matches!(
+		MemcheckWitness::new(values.clone(), values.clone(), values.clone(), values, vec![16; 16]),
+		Err(Error::MappingOutOfRange { index: 0 })
+	);
+}
@@ -0,0 +1,41 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	error::{Error, VerificationError},
+	memcheck::{
+		reduce_memcheck_claim, timestamps_lookup_claim, MemcheckClaim, MemcheckReduction,
+		ReducedMemcheckClaims,
+	},
+};
+use crate::{oracle::MultilinearOracleSet, protocols::msetcheck};
+use binius_field::{BinaryField32b, ExtensionField, TowerField};
+
+/// Verify a memcheck instance reduction.
+///
+/// Besides the product check and the zerocheck, the reduced claims include the lookup of the
+/// timestamp deltas, which must be reduced with [`crate::protocols::lookup::verify`].
+pub fn verify<F>(
+	oracles: &mut MultilinearOracleSet<F>,
+	claim: &MemcheckClaim<F>,
+	gamma: F,
+	alpha: F,
+) -> Result<ReducedMemcheckClaims<F>, Error>
+where
+	F: TowerField + ExtensionField<BinaryField32b>,
+{
+	let MemcheckReduction {
+		msetcheck_claim,
+		timestamps_order_claim,
+		timestamp_oracles,
+	} = reduce_memcheck_claim(oracles, claim)?;
+	let prodcheck_claim = msetcheck::verify(oracles, &msetcheck_claim, gamma, Some(alpha))
+		.map_err(VerificationError::from)?;
+	let timestamps_lookup_claim =
+		timestamps_lookup_claim(claim, oracles.oracle(timestamp_oracles.write_timestamps))?;
+
+	Ok(ReducedMemcheckClaims {
+		prodcheck_claim,
+		timestamps_order_claim,
+		timestamps_lookup_claim,
+	})
+}
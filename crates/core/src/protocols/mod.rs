@@ -29,6 +29,8 @@
 
 pub mod evalcheck;
 pub mod greedy_evalcheck;
+pub mod lookup;
+pub mod memcheck;
 pub mod msetcheck;
 pub mod prodcheck;
 pub mod sumcheck;
@@ -1,6 +1,9 @@
 // Copyright 2024 Ulvetanna Inc.
 
-use crate::{oracle::Error as IOPolynomialError, polynomial::Error as PolynomialError};
+use crate::{
+	oracle::Error as IOPolynomialError, polynomial::Error as PolynomialError,
+	witness::Error as WitnessError,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -8,8 +11,14 @@ pub enum Error {
 	WitnessDimensionalityMismatch,
 	#[error("the number of variables in some witness multilinear does not match the claim")]
 	WitnessNumVariablesMismatch,
+	#[error("the mapping must have one U row per T row, expected {expected}")]
+	IncorrectMappingLength { expected: usize },
+	#[error("T row {index} maps to a row outside of U")]
+	MappingOutOfRange { index: usize },
 	#[error("polynomial error: {0}")]
 	Polynomial(#[from] PolynomialError),
+	#[error("witness error: {0}")]
+	Witness(#[from] WitnessError),
 	#[error("verification failure: {0}")]
 	Verification(#[from] VerificationError),
 }
@@ -24,6 +33,8 @@ pub enum VerificationError {
 	IncorrectAlpha,
 	#[error("the number of variables in some multilinear oracle is not equal to the rest")]
 	NumVariablesMismatch,
+	#[error("the number of variables of some counts oracle does not match its relation")]
+	CountsNumVariablesMismatch,
 	#[error("the challenge vector length does not equal multiset dimensionality")]
 	IncorrectChallengeLength,
 	#[error("iopolynomial error: {0}")]
@@ -2,7 +2,9 @@
 
 //! The multiset check polynomial protocol.
 //!
-//! Multiset check provides a deterministic reduction of multiset equality claims to product check claims
+//! Multiset check provides a deterministic reduction of multiset equality claims to product check claims.
+//! Weighted multiset checks, where every row of U carries a multiplicity, additionally produce a
+//! zerocheck claim.
 
 mod error;
 #[allow(clippy::module_inception)]
@@ -11,8 +13,13 @@ mod prove;
 #[cfg(test)]
 mod tests;
 mod verify;
+mod weighted;
 
 pub use error::*;
 pub use msetcheck::{MsetcheckClaim, MsetcheckProveOutput, MsetcheckWitness};
 pub use prove::*;
 pub use verify::*;
+pub use weighted::{
+	ReducedWeightedMsetcheckClaims, WeightedMsetcheckClaim, WeightedMsetcheckProveOutput,
+	WeightedMsetcheckWitness, MULTIPLICITY_GENERATOR,
+};
@@ -127,7 +127,7 @@ pub fn reduce_msetcheck_claim<F: TowerField>(
 	Ok(prodcheck_claim)
 }
 
-fn relation_sanity_checks<Column>(
+pub(super) fn relation_sanity_checks<Column>(
 	t: &[Column],
 	u: &[Column],
 	n_vars: impl Fn(&Column) -> usize,
@@ -3,15 +3,20 @@
 use super::{
 	error::Error,
 	msetcheck::{reduce_msetcheck_claim, MsetcheckClaim, MsetcheckProveOutput, MsetcheckWitness},
+	weighted::{
+		reduce_weighted_msetcheck_claim, ReducedWeightedMsetcheckClaims, WeightedMsetcheckClaim,
+		WeightedMsetcheckProveOutput, WeightedMsetcheckReduction, WeightedMsetcheckWitness,
+	},
 };
 use crate::{
-	oracle::MultilinearOracleSet,
-	polynomial::{Error as PolynomialError, MultilinearExtension},
-	protocols::prodcheck::ProdcheckWitness,
-	witness::{MultilinearWitness, MultilinearWitnessIndex},
+	oracle::{MultilinearOracleSet, MultilinearPolyOracle},
+	polynomial::{Error as PolynomialError, MultilinearComposite, MultilinearExtension},
+	protocols::prodcheck::{ProdcheckWitness, SimpleMultGateComposition},
+	witness::{derive_witnesses_of, MultilinearWitness, MultilinearWitnessIndex},
 };
-use binius_field::TowerField;
+use binius_field::{BinaryField32b, ExtensionField, TowerField};
 use rayon::prelude::*;
+use std::iter;
 
 /// Prove a multiset check instance reduction.
 ///
@@ -102,3 +107,90 @@ where
 		prodcheck_witness,
 	})
 }
+
+/// Prove a weighted multiset check instance reduction.
+///
+/// The witnesses of the T and U polynomials, of the multiplicities and counts, and of all oracles
+/// added by the reduction are set in the witness index. The reduced multiset check is then proven
+/// as above with challenges $\gamma$ and $\alpha$, see [`WeightedMsetcheckClaim`] for the
+/// reduction itself.
+pub fn prove_weighted<'a, F, FW>(
+	oracles: &mut MultilinearOracleSet<F>,
+	witness_index: &mut MultilinearWitnessIndex<'a, FW>,
+	claim: &WeightedMsetcheckClaim<F>,
+	witness: WeightedMsetcheckWitness<'a, FW>,
+	gamma: F,
+	alpha: F,
+) -> Result<WeightedMsetcheckProveOutput<'a, F, FW>, Error>
+where
+	F: TowerField + ExtensionField<BinaryField32b> + From<FW>,
+	FW: TowerField + ExtensionField<BinaryField32b> + From<F>,
+{
+	if witness.dimensions() != claim.dimensions() {
+		return Err(Error::WitnessDimensionalityMismatch);
+	}
+
+	if witness.t_polynomials()[0].n_vars() != claim.t_n_vars()
+		|| witness.u_polynomials()[0].n_vars() != claim.u_n_vars()
+	{
+		return Err(Error::WitnessNumVariablesMismatch);
+	}
+
+	let relations = iter::zip(claim.t_oracles(), witness.t_polynomials())
+		.chain(iter::zip(claim.u_oracles(), witness.u_polynomials()));
+	for (oracle, polynomial) in relations {
+		witness_index.set(oracle.id(), polynomial.clone());
+	}
+
+	let counts = [
+		(claim.u_multiplicities_oracle(), witness.u_multiplicities()),
+		(claim.t_counts_oracle(), witness.t_counts()),
+		(claim.t_counts_inverse_oracle(), witness.t_counts_inverse()),
+	];
+	for (oracle, values) in counts {
+		witness_index.set(oracle.id(), values.clone().specialize_arc_dyn());
+	}
+
+	let first_reduction_id = oracles.size();
+	let WeightedMsetcheckReduction {
+		msetcheck_claim,
+		counts_nonzero_claim,
+		t_ones_id,
+	} = reduce_weighted_msetcheck_claim(oracles, claim)?;
+	derive_witnesses_of(oracles, first_reduction_id..oracles.size(), witness_index)?;
+
+	let relation_witnesses = |relation_oracles: &[MultilinearPolyOracle<F>]| {
+		relation_oracles
+			.iter()
+			.map(|oracle| witness_index.get_required(oracle.id()))
+			.collect::<Result<Vec<_>, _>>()
+	};
+	let msetcheck_witness = MsetcheckWitness::new(
+		relation_witnesses(msetcheck_claim.t_oracles())?,
+		relation_witnesses(msetcheck_claim.u_oracles())?,
+	)?;
+
+	let counts_nonzero_witness = MultilinearComposite::new(
+		claim.t_n_vars(),
+		SimpleMultGateComposition,
+		vec![
+			witness_index.get_required(t_ones_id)?,
+			witness_index.get_required(claim.t_counts_oracle().id())?,
+			witness_index.get_required(claim.t_counts_inverse_oracle().id())?,
+		],
+	)?;
+
+	let MsetcheckProveOutput {
+		prodcheck_claim,
+		prodcheck_witness,
+	} = prove(oracles, witness_index, &msetcheck_claim, msetcheck_witness, gamma, Some(alpha))?;
+
+	Ok(WeightedMsetcheckProveOutput {
+		reduced_claims: ReducedWeightedMsetcheckClaims {
+			prodcheck_claim,
+			counts_nonzero_claim,
+		},
+		prodcheck_witness,
+		counts_nonzero_witness,
+	})
+}
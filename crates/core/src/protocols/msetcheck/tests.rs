@@ -1,14 +1,22 @@
 use crate::{
 	oracle::{CommittedBatchSpec, CommittedId, MultilinearOracleSet},
 	polynomial::MultilinearExtension,
-	protocols::msetcheck::{prove, verify, MsetcheckClaim, MsetcheckWitness},
+	protocols::{
+		msetcheck::{
+			prove, prove_weighted, verify, verify_weighted, Error, MsetcheckClaim,
+			MsetcheckWitness, WeightedMsetcheckClaim, WeightedMsetcheckWitness,
+		},
+		zerocheck::check_zerocheck_claim,
+	},
 	witness::{MultilinearWitness, MultilinearWitnessIndex},
 };
+use assert_matches::assert_matches;
 use binius_field::{
-	BinaryField128b, BinaryField16b, BinaryField32b, BinaryField64b, ExtensionField, Field,
-	TowerField,
+	BinaryField128b, BinaryField16b, BinaryField32b, BinaryField64b, BinaryField8b, ExtensionField,
+	Field, TowerField,
 };
-use std::iter::{successors, Step};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::iter::{repeat_with, successors, Step};
 
 fn create_polynomial<F: Field + Step, FW>(
 	n_vars: usize,
@@ -164,3 +172,139 @@ fn test_prove_verify_interaction() {
 	assert_eq!(verified_reduced_claim.t_oracle.n_vars(), n_vars);
 	assert_eq!(verified_reduced_claim.u_oracle.n_vars(), n_vars);
 }
+
+/// Runs a weighted multiset check where each row of T equals the row of U given by the mapping,
+/// and returns the products of the reduced product check polynomials over the hypercube.
+fn run_weighted_msetcheck(
+	t_n_vars: usize,
+	u_n_vars: usize,
+	corrupt_t_row: Option<usize>,
+) -> (BinaryField128b, BinaryField128b) {
+	type F = BinaryField128b;
+	let mut rng = StdRng::seed_from_u64(0);
+
+	// Setup witness
+	let u_values = repeat_with(|| {
+		repeat_with(|| F::from(<BinaryField8b as Field>::random(&mut rng)))
+			.take(1 << u_n_vars)
+			.collect::<Vec<_>>()
+	})
+	.take(2)
+	.collect::<Vec<_>>();
+	let mapping = (0..1 << t_n_vars)
+		.map(|_| rng.gen_range(0..1 << u_n_vars))
+		.collect::<Vec<usize>>();
+	let mut t_values = u_values
+		.iter()
+		.map(|values| mapping.iter().map(|&row| values[row]).collect::<Vec<_>>())
+		.collect::<Vec<_>>();
+	if let Some(row) = corrupt_t_row {
+		t_values[1][row] += F::ONE;
+	}
+
+	let to_witness = |values: &Vec<F>| -> MultilinearWitness<'static, F> {
+		MultilinearExtension::from_values(values.clone())
+			.unwrap()
+			.specialize_arc_dyn()
+	};
+	let witness = WeightedMsetcheckWitness::new(
+		t_values.iter().map(to_witness),
+		u_values.iter().map(to_witness),
+		mapping,
+	)
+	.unwrap();
+
+	// Setup claim
+	let mut oracles = MultilinearOracleSet::<F>::new();
+	let t_batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 1,
+		n_vars: t_n_vars,
+		n_polys: 2,
+		tower_level: BinaryField8b::TOWER_LEVEL,
+	});
+	let u_batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 1,
+		n_vars: u_n_vars,
+		n_polys: 2,
+		tower_level: BinaryField8b::TOWER_LEVEL,
+	});
+	let t_counts_batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 1,
+		n_vars: t_n_vars,
+		n_polys: 2,
+		tower_level: BinaryField32b::TOWER_LEVEL,
+	});
+	let u_multiplicities_batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 1,
+		n_vars: u_n_vars,
+		n_polys: 1,
+		tower_level: BinaryField32b::TOWER_LEVEL,
+	});
+	let oracle = |batch_id, index| oracles.committed_oracle(CommittedId { batch_id, index });
+
+	let claim = WeightedMsetcheckClaim::new(
+		[oracle(t_batch_id, 0), oracle(t_batch_id, 1)],
+		[oracle(u_batch_id, 0), oracle(u_batch_id, 1)],
+		oracle(u_multiplicities_batch_id, 0),
+		oracle(t_counts_batch_id, 0),
+		oracle(t_counts_batch_id, 1),
+	)
+	.unwrap();
+
+	// challenges
+	let gamma = F::new(0x123);
+	let alpha = F::new(0x346);
+
+	// PROVER
+	let mut witness_index = MultilinearWitnessIndex::new();
+	let prove_output =
+		prove_weighted(&mut oracles.clone(), &mut witness_index, &claim, witness, gamma, alpha)
+			.unwrap();
+
+	// VERIFIER
+	let verified_claims = verify_weighted(&mut oracles.clone(), &claim, gamma, alpha).unwrap();
+
+	// Consistency checks
+	let n_vars = t_n_vars.max(u_n_vars) + 1;
+	assert_eq!(verified_claims.prodcheck_claim.n_vars(), Some(n_vars));
+	assert_eq!(prove_output.reduced_claims.prodcheck_claim.n_vars(), Some(n_vars));
+	assert!(check_zerocheck_claim(&verified_claims.counts_nonzero_claim, &witness_index)
+		.unwrap()
+		.is_empty());
+
+	let product = |witness: &MultilinearWitness<F>| {
+		(0..1 << witness.n_vars())
+			.map(|i| witness.evaluate_on_hypercube(i).unwrap())
+			.product::<F>()
+	};
+	let prodcheck_witness = &prove_output.prodcheck_witness;
+	(product(&prodcheck_witness.t_polynomial), product(&prodcheck_witness.u_polynomial))
+}
+
+#[test]
+fn test_weighted_prove_verify_larger_t() {
+	let (t_product, u_product) = run_weighted_msetcheck(7, 4, None);
+	assert_eq!(t_product, u_product);
+}
+
+#[test]
+fn test_weighted_prove_verify_larger_u() {
+	let (t_product, u_product) = run_weighted_msetcheck(3, 5, None);
+	assert_eq!(t_product, u_product);
+}
+
+#[test]
+fn test_weighted_row_missing_from_u() {
+	let (t_product, u_product) = run_weighted_msetcheck(7, 4, Some(17));
+	assert_ne!(t_product, u_product);
+}
+
+#[test]
+fn test_weighted_witness_mapping_out_of_range() {
+	type F = BinaryField128b;
+	let polynomial = create_polynomial::<BinaryField16b, F>(4, 1, false);
+	assert_matches!(
+		WeightedMsetcheckWitness::new([polynomial.clone()], [polynomial], vec![16; 16]),
+		Err(Error::MappingOutOfRange { index: 0 })
+	);
+}
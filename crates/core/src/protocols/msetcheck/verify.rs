@@ -3,9 +3,13 @@
 use super::{
 	error::VerificationError,
 	msetcheck::{reduce_msetcheck_claim, MsetcheckClaim},
+	weighted::{
+		reduce_weighted_msetcheck_claim, ReducedWeightedMsetcheckClaims, WeightedMsetcheckClaim,
+		WeightedMsetcheckReduction,
+	},
 };
 use crate::{oracle::MultilinearOracleSet, protocols::prodcheck::ProdcheckClaim};
-use binius_field::TowerField;
+use binius_field::{BinaryField32b, ExtensionField, TowerField};
 
 /// Verify a multiset check instance reduction.
 pub fn verify<F: TowerField>(
@@ -16,3 +20,26 @@ pub fn verify<F: TowerField>(
 ) -> Result<ProdcheckClaim<F>, VerificationError> {
 	reduce_msetcheck_claim(oracles, claim, gamma, alpha)
 }
+
+/// Verify a weighted multiset check instance reduction.
+pub fn verify_weighted<F>(
+	oracles: &mut MultilinearOracleSet<F>,
+	claim: &WeightedMsetcheckClaim<F>,
+	gamma: F,
+	alpha: F,
+) -> Result<ReducedWeightedMsetcheckClaims<F>, VerificationError>
+where
+	F: TowerField + ExtensionField<BinaryField32b>,
+{
+	let WeightedMsetcheckReduction {
+		msetcheck_claim,
+		counts_nonzero_claim,
+		..
+	} = reduce_weighted_msetcheck_claim(oracles, claim)?;
+	let prodcheck_claim = reduce_msetcheck_claim(oracles, &msetcheck_claim, gamma, Some(alpha))?;
+
+	Ok(ReducedWeightedMsetcheckClaims {
+		prodcheck_claim,
+		counts_nonzero_claim,
+	})
+}
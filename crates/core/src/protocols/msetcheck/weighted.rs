@@ -0,0 +1,300 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	error::{Error, VerificationError},
+	msetcheck::{relation_sanity_checks, MsetcheckClaim},
+};
+use crate::{
+	oracle::{CompositePolyOracle, MultilinearOracleSet, MultilinearPolyOracle, OracleId},
+	polynomial::MultilinearExtension,
+	protocols::{
+		prodcheck::{ProdcheckClaim, ProdcheckWitness, SimpleMultGateComposition},
+		zerocheck::{ZerocheckClaim, ZerocheckWitness},
+	},
+	witness::MultilinearWitness,
+};
+use binius_field::{BinaryField, BinaryField32b, ExtensionField, Field, TowerField};
+use getset::Getters;
+use std::iter;
+
+/// The generator of the multiplicative group of `BinaryField32b` that counts are powers of.
+pub const MULTIPLICITY_GENERATOR: BinaryField32b = BinaryField32b::MULTIPLICATIVE_GENERATOR;
+
+/// A multiset check claim where every row of U is counted with a multiplicity.
+///
+/// The claim is that the rows of T, each counted once, are the rows of U, each counted as many
+/// times as its multiplicity. The multiplicity $m_j$ of the $j$-th row of U is committed as
+/// $g^{m_j}$, where $g$ is [`MULTIPLICITY_GENERATOR`]. T and U may have different numbers of
+/// variables.
+///
+/// The reduction is an offline memory check. Each row of U is a memory cell initialized to count
+/// $1$, and each row of T reads a cell and writes it back with its count multiplied by $g$, so
+/// that the final count of a cell is its multiplicity. The prover commits the count $c_i$ read by
+/// each row of T together with its inverse, which proves that no count is zero.
+#[derive(Debug, Getters)]
+pub struct WeightedMsetcheckClaim<F: Field> {
+	/// Oracles to the T polynomials
+	#[get = "pub"]
+	t_oracles: Vec<MultilinearPolyOracle<F>>,
+	/// Oracles to the U polynomials
+	#[get = "pub"]
+	u_oracles: Vec<MultilinearPolyOracle<F>>,
+	/// Oracle to the multiplicities of the U rows, as powers of the generator
+	#[get = "pub"]
+	u_multiplicities_oracle: MultilinearPolyOracle<F>,
+	/// Oracle to the counts read by the T rows
+	#[get = "pub"]
+	t_counts_oracle: MultilinearPolyOracle<F>,
+	/// Oracle to the inverses of the counts read by the T rows
+	#[get = "pub"]
+	t_counts_inverse_oracle: MultilinearPolyOracle<F>,
+}
+
+impl<F: Field> WeightedMsetcheckClaim<F> {
+	/// Claim constructor
+	pub fn new(
+		t_oracles: impl IntoIterator<Item = MultilinearPolyOracle<F>>,
+		u_oracles: impl IntoIterator<Item = MultilinearPolyOracle<F>>,
+		u_multiplicities_oracle: MultilinearPolyOracle<F>,
+		t_counts_oracle: MultilinearPolyOracle<F>,
+		t_counts_inverse_oracle: MultilinearPolyOracle<F>,
+	) -> Result<Self, VerificationError> {
+		let t_oracles = t_oracles.into_iter().collect::<Vec<_>>();
+		let u_oracles = u_oracles.into_iter().collect::<Vec<_>>();
+
+		if t_oracles.len() != u_oracles.len() {
+			return Err(VerificationError::IncorrectDimensions);
+		}
+		relation_sanity_checks(&t_oracles, &t_oracles, |oracle| oracle.n_vars())?;
+		relation_sanity_checks(&u_oracles, &u_oracles, |oracle| oracle.n_vars())?;
+
+		let t_n_vars = t_oracles[0].n_vars();
+		if u_multiplicities_oracle.n_vars() != u_oracles[0].n_vars()
+			|| t_counts_oracle.n_vars() != t_n_vars
+			|| t_counts_inverse_oracle.n_vars() != t_n_vars
+		{
+			return Err(VerificationError::CountsNumVariablesMismatch);
+		}
+
+		Ok(Self {
+			t_oracles,
+			u_oracles,
+			u_multiplicities_oracle,
+			t_counts_oracle,
+			t_counts_inverse_oracle,
+		})
+	}
+
+	/// Dimensions of the T/U relations.
+	pub fn dimensions(&self) -> usize {
+		self.t_oracles.len()
+	}
+
+	/// Number of variables of the T oracles.
+	pub fn t_n_vars(&self) -> usize {
+		self.t_oracles[0].n_vars()
+	}
+
+	/// Number of variables of the U oracles.
+	pub fn u_n_vars(&self) -> usize {
+		self.u_oracles[0].n_vars()
+	}
+}
+
+#[derive(Debug, Getters)]
+pub struct WeightedMsetcheckWitness<'a, FW: Field> {
+	/// Witnesses to the T polynomials
+	#[get = "pub"]
+	t_polynomials: Vec<MultilinearWitness<'a, FW>>,
+	/// Witnesses to the U polynomials
+	#[get = "pub"]
+	u_polynomials: Vec<MultilinearWitness<'a, FW>>,
+	/// The U row equal to each T row
+	#[get = "pub"]
+	t_to_u_mapping: Vec<usize>,
+	/// Multiplicities of the U rows, as powers of the generator
+	#[get = "pub"]
+	u_multiplicities: MultilinearExtension<'static, BinaryField32b>,
+	/// Counts read by the T rows
+	#[get = "pub"]
+	t_counts: MultilinearExtension<'static, BinaryField32b>,
+	/// Inverses of the counts read by the T rows
+	#[get = "pub"]
+	t_counts_inverse: MultilinearExtension<'static, BinaryField32b>,
+}
+
+impl<'a, FW: Field> WeightedMsetcheckWitness<'a, FW> {
+	/// Witness constructor
+	///
+	/// Computes the multiplicities and counts from the U row that each T row equals. These
+	/// must be committed before the multiset check challenges are sampled.
+	pub fn new(
+		t_polynomials: impl IntoIterator<Item = MultilinearWitness<'a, FW>>,
+		u_polynomials: impl IntoIterator<Item = MultilinearWitness<'a, FW>>,
+		t_to_u_mapping: Vec<usize>,
+	) -> Result<Self, Error> {
+		let t_polynomials = t_polynomials.into_iter().collect::<Vec<_>>();
+		let u_polynomials = u_polynomials.into_iter().collect::<Vec<_>>();
+
+		if t_polynomials.len() != u_polynomials.len() {
+			return Err(VerificationError::IncorrectDimensions.into());
+		}
+		relation_sanity_checks(&t_polynomials, &t_polynomials, |witness| witness.n_vars())?;
+		relation_sanity_checks(&u_polynomials, &u_polynomials, |witness| witness.n_vars())?;
+
+		let t_size = 1 << t_polynomials[0].n_vars();
+		if t_to_u_mapping.len() != t_size {
+			return Err(Error::IncorrectMappingLength { expected: t_size });
+		}
+
+		let u_size = 1 << u_polynomials[0].n_vars();
+		if let Some(index) = t_to_u_mapping.iter().position(|&row| row >= u_size) {
+			return Err(Error::MappingOutOfRange { index });
+		}
+
+		let mut u_multiplicities = vec![BinaryField32b::ONE; u_size];
+		let t_counts = t_to_u_mapping
+			.iter()
+			.map(|&row| {
+				let count = u_multiplicities[row];
+				u_multiplicities[row] = count * MULTIPLICITY_GENERATOR;
+				count
+			})
+			.collect::<Vec<_>>();
+		let t_counts_inverse = t_counts
+			.iter()
+			.map(|count| count.invert().expect("counts are powers of the generator"))
+			.collect();
+
+		Ok(Self {
+			t_polynomials,
+			u_polynomials,
+			t_to_u_mapping,
+			u_multiplicities: MultilinearExtension::from_values(u_multiplicities)?,
+			t_counts: MultilinearExtension::from_values(t_counts)?,
+			t_counts_inverse: MultilinearExtension::from_values(t_counts_inverse)?,
+		})
+	}
+
+	/// Dimensions of the T/U relations.
+	pub fn dimensions(&self) -> usize {
+		self.t_polynomials.len()
+	}
+}
+
+#[derive(Debug)]
+pub struct ReducedWeightedMsetcheckClaims<F: Field> {
+	pub prodcheck_claim: ProdcheckClaim<F>,
+	/// Zerocheck claim on $1 - c \cdot c^{-1}$, showing that no read count is zero
+	pub counts_nonzero_claim: ZerocheckClaim<F>,
+}
+
+#[derive(Debug)]
+pub struct WeightedMsetcheckProveOutput<'a, F: Field, FW: Field> {
+	pub reduced_claims: ReducedWeightedMsetcheckClaims<F>,
+	pub prodcheck_witness: ProdcheckWitness<'a, FW>,
+	pub counts_nonzero_witness: ZerocheckWitness<'a, FW, SimpleMultGateComposition>,
+}
+
+/// The result of reducing a weighted multiset check claim to an unweighted one.
+#[derive(Debug)]
+pub(super) struct WeightedMsetcheckReduction<F: Field> {
+	pub msetcheck_claim: MsetcheckClaim<F>,
+	pub counts_nonzero_claim: ZerocheckClaim<F>,
+	/// The constant one oracle the nonzero check is composed with
+	pub t_ones_id: OracleId,
+}
+
+/// Reduces a weighted multiset check claim to a multiset check of one more dimension.
+///
+/// Both relations are brought to $\nu = \max(\nu_T, \nu_U)$ variables by repeating the value
+/// columns of the smaller relation and padding its count column with ones, which adds the same
+/// rows to both sides. The resulting multiset check compares
+/// $\textit{merge}(U, T)$ with counts $\textit{merge}(1, g \cdot c)$ against
+/// $\textit{merge}(U, T)$ with counts $\textit{merge}(m, c)$.
+pub(super) fn reduce_weighted_msetcheck_claim<F>(
+	oracles: &mut MultilinearOracleSet<F>,
+	claim: &WeightedMsetcheckClaim<F>,
+) -> Result<WeightedMsetcheckReduction<F>, VerificationError>
+where
+	F: TowerField + ExtensionField<BinaryField32b>,
+{
+	let t_n_vars = claim.t_n_vars();
+	let u_n_vars = claim.u_n_vars();
+	let n_vars = t_n_vars.max(u_n_vars);
+
+	let mut ones = |n_vars| oracles.add_linear_combination(n_vars, F::ONE, iter::empty());
+	let t_ones_id = ones(t_n_vars)?;
+	let ones_id = ones(n_vars)?;
+
+	let t_counts_next_id = oracles.add_linear_combination(
+		t_n_vars,
+		F::ZERO,
+		[(claim.t_counts_oracle.id(), F::from(MULTIPLICITY_GENERATOR))],
+	)?;
+
+	let t_counts_id = pad_with_ones(oracles, claim.t_counts_oracle.id(), n_vars)?;
+	let t_counts_next_id = pad_with_ones(oracles, t_counts_next_id, n_vars)?;
+	let u_multiplicities_id = pad_with_ones(oracles, claim.u_multiplicities_oracle.id(), n_vars)?;
+
+	let mut value_oracles = Vec::with_capacity(claim.dimensions() + 1);
+	for (t_oracle, u_oracle) in iter::zip(&claim.t_oracles, &claim.u_oracles) {
+		let t_id = repeat_to(oracles, t_oracle.id(), n_vars)?;
+		let u_id = repeat_to(oracles, u_oracle.id(), n_vars)?;
+		let merged_id = oracles.add_merged(u_id, t_id)?;
+		value_oracles.push(oracles.oracle(merged_id));
+	}
+
+	// Initial counts of the U rows followed by the counts written by the T rows
+	let lhs_counts_id = oracles.add_merged(ones_id, t_counts_next_id)?;
+	// Final counts of the U rows followed by the counts read by the T rows
+	let rhs_counts_id = oracles.add_merged(u_multiplicities_id, t_counts_id)?;
+
+	let t_oracles = [value_oracles.clone(), vec![oracles.oracle(lhs_counts_id)]].concat();
+	let u_oracles = [value_oracles, vec![oracles.oracle(rhs_counts_id)]].concat();
+	let msetcheck_claim = MsetcheckClaim::new(t_oracles, u_oracles)?;
+
+	let counts_nonzero_oracle = CompositePolyOracle::new(
+		t_n_vars,
+		vec![
+			oracles.oracle(t_ones_id),
+			claim.t_counts_oracle.clone(),
+			claim.t_counts_inverse_oracle.clone(),
+		],
+		SimpleMultGateComposition,
+	)?;
+
+	Ok(WeightedMsetcheckReduction {
+		msetcheck_claim,
+		counts_nonzero_claim: ZerocheckClaim {
+			poly: counts_nonzero_oracle,
+		},
+		t_ones_id,
+	})
+}
+
+/// Repeats an oracle up to `n_vars` variables.
+fn repeat_to<F: TowerField>(
+	oracles: &mut MultilinearOracleSet<F>,
+	id: OracleId,
+	n_vars: usize,
+) -> Result<OracleId, VerificationError> {
+	let inner_n_vars = oracles.n_vars(id);
+	if inner_n_vars == n_vars {
+		return Ok(id);
+	}
+	Ok(oracles.add_repeating(id, n_vars - inner_n_vars)?)
+}
+
+/// Extends an oracle up to `n_vars` variables with ones in all added hypercube rows.
+fn pad_with_ones<F: TowerField>(
+	oracles: &mut MultilinearOracleSet<F>,
+	mut id: OracleId,
+	n_vars: usize,
+) -> Result<OracleId, VerificationError> {
+	while oracles.n_vars(id) < n_vars {
+		let ones_id = oracles.add_linear_combination(oracles.n_vars(id), F::ONE, iter::empty())?;
+		id = oracles.add_merged(id, ones_id)?;
+	}
+	Ok(id)
+}
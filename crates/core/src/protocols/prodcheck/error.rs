@@ -1,6 +1,9 @@
 // Copyright 2024 Ulvetanna Inc.
 
-use crate::{oracle::Error as IOPolynomialError, polynomial::Error as PolynomialError};
+use crate::{
+	oracle::Error as IOPolynomialError, polynomial::Error as PolynomialError,
+	protocols::sumcheck::Error as SumcheckError,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -20,6 +23,8 @@ pub enum Error {
 	Polynomial(#[from] PolynomialError),
 	#[error("iopolynomial error: {0}")]
 	IOPolynomial(#[from] IOPolynomialError),
+	#[error("sumcheck error: {0}")]
+	Sumcheck(#[from] SumcheckError),
 	#[error("verification failure: {0}")]
 	Verification(#[from] VerificationError),
 }
@@ -32,4 +37,6 @@ pub enum VerificationError {
 	NumberOfCoefficients { round: usize },
 	#[error("incorrect number of coefficients")]
 	NumberOfRounds,
+	#[error("the grand products of T and U are not equal")]
+	ProductsMismatch,
 }
@@ -0,0 +1,372 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	error::{Error, VerificationError},
+	prodcheck::{ProdcheckClaim, ProdcheckWitness},
+};
+use crate::{
+	challenger::{CanObserve, CanSample},
+	oracle::{CommittedBatchSpec, CommittedId, CompositePolyOracle, MultilinearOracleSet},
+	polynomial::{
+		transparent::eq_ind::EqIndPartialEval, CompositionPoly, Error as PolynomialError,
+		EvaluationDomain, MultilinearComposite, MultilinearExtension, MultilinearPoly,
+		MultilinearQuery, MultivariatePoly,
+	},
+	protocols::{
+		evalcheck::EvalcheckClaim,
+		sumcheck::{self, SumcheckClaim, SumcheckProof, SumcheckProveOutput},
+	},
+	witness::MultilinearWitness,
+};
+use binius_field::{Field, PackedField, TowerField};
+
+/// Composition $f(E, A, B) := E \cdot A \cdot B$ of a GKR layer sumcheck.
+///
+/// Expects three variables, ordered as follows:
+/// 1) Equality indicator (E)
+/// 2) Left half of the next layer (A)
+/// 3) Right half of the next layer (B)
+#[derive(Clone, Debug)]
+pub struct GkrLayerComposition;
+
+impl<P: PackedField> CompositionPoly<P> for GkrLayerComposition {
+	fn n_vars(&self) -> usize {
+		3
+	}
+
+	fn degree(&self) -> usize {
+		3
+	}
+
+	fn evaluate(&self, query: &[P::Scalar]) -> Result<P::Scalar, PolynomialError> {
+		self.evaluate_packed(query)
+	}
+
+	fn evaluate_packed(&self, query: &[P]) -> Result<P, PolynomialError> {
+		if query.len() != 3 {
+			return Err(PolynomialError::IncorrectQuerySize { expected: 3 });
+		}
+
+		Ok(query[0] * query[1] * query[2])
+	}
+
+	fn binary_tower_level(&self) -> usize {
+		0
+	}
+}
+
+/// Reduction of a single layer of a grand product circuit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GkrLayerProof<F: Field> {
+	/// Sumcheck proof reducing the layer claim, empty for the output layer
+	pub sumcheck_proof: SumcheckProof<F>,
+	/// Evaluation of the left half $V_{k+1}(r', 0)$ of the next layer
+	pub zero_eval: F,
+	/// Evaluation of the right half $V_{k+1}(r', 1)$ of the next layer
+	pub one_eval: F,
+}
+
+/// Proof of the grand product of a single multilinear.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GkrProductProof<F: Field> {
+	/// The claimed product of all hypercube evaluations
+	pub product: F,
+	/// Layer reductions, ordered from the output layer to the input layer
+	pub layers: Vec<GkrLayerProof<F>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GkrProdcheckProof<F: Field> {
+	pub t_proof: GkrProductProof<F>,
+	pub u_proof: GkrProductProof<F>,
+}
+
+#[derive(Debug)]
+pub struct ReducedGkrProdcheckClaims<F: Field> {
+	pub t_eval_claim: EvalcheckClaim<F>,
+	pub u_eval_claim: EvalcheckClaim<F>,
+}
+
+#[derive(Debug)]
+pub struct GkrProdcheckProveOutput<F: Field> {
+	pub reduced_claims: ReducedGkrProdcheckClaims<F>,
+	pub proof: GkrProdcheckProof<F>,
+}
+
+/// Prove a prodcheck instance with a layered grand product circuit.
+///
+/// Unlike [`prove`](super::prove), this does not need a committed grand product polynomial $f'$.
+/// Let $V_\nu := T$ and $V_k(x) := V_{k+1}(x, 0) \cdot V_{k+1}(x, 1)$, so that $V_0$ is the
+/// product of all hypercube evaluations of $T$. The prover sends $V_0$, and a claim
+/// $V_k(r) = y$ is reduced to a claim on $V_{k+1}$ by a sumcheck on
+///
+/// $$y = \sum_{x \in \{0, 1\}^k} \textit{eq}(r, x) \cdot V_{k+1}(x, 0) \cdot V_{k+1}(x, 1)$$
+///
+/// ending at a point $r'$. The prover then sends $V_{k+1}(r', 0)$ and $V_{k+1}(r', 1)$, which
+/// are combined into a single claim at $(r', \mu)$ for a random $\mu$. After $\nu$ layers this
+/// leaves an evaluation claim on $T$. The same circuit is run on $U$, and the verifier checks that
+/// both products are equal.
+///
+/// The layer sumchecks have degree 3, so `domain` must have size 4.
+pub fn prove_gkr<F, FW, CH>(
+	prodcheck_claim: &ProdcheckClaim<F>,
+	prodcheck_witness: ProdcheckWitness<'_, FW>,
+	domain: &EvaluationDomain<FW>,
+	mut challenger: CH,
+	switchover_fn: impl Fn(usize) -> usize + Copy,
+) -> Result<GkrProdcheckProveOutput<F>, Error>
+where
+	F: TowerField + From<FW>,
+	FW: Field + From<F>,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let n_vars = prodcheck_claim
+		.n_vars()
+		.ok_or(Error::NumeratorDenominatorSizeMismatch)?;
+
+	let ProdcheckWitness {
+		t_polynomial,
+		u_polynomial,
+	} = prodcheck_witness;
+
+	if t_polynomial.n_vars() != n_vars || u_polynomial.n_vars() != n_vars {
+		return Err(Error::NumVariablesMismatch);
+	}
+
+	let (t_proof, t_eval_point, t_eval) =
+		prove_product(t_polynomial, domain, &mut challenger, switchover_fn)?;
+	let (u_proof, u_eval_point, u_eval) =
+		prove_product(u_polynomial, domain, &mut challenger, switchover_fn)?;
+
+	let reduced_claims = ReducedGkrProdcheckClaims {
+		t_eval_claim: EvalcheckClaim {
+			poly: prodcheck_claim.t_oracle.clone().into_composite(),
+			eval_point: t_eval_point,
+			eval: t_eval,
+			is_random_point: true,
+		},
+		u_eval_claim: EvalcheckClaim {
+			poly: prodcheck_claim.u_oracle.clone().into_composite(),
+			eval_point: u_eval_point,
+			eval: u_eval,
+			is_random_point: true,
+		},
+	};
+
+	Ok(GkrProdcheckProveOutput {
+		reduced_claims,
+		proof: GkrProdcheckProof { t_proof, u_proof },
+	})
+}
+
+/// Verify a prodcheck instance reduction with a layered grand product circuit.
+///
+/// See [`prove_gkr`] for the protocol description.
+pub fn verify_gkr<F, CH>(
+	prodcheck_claim: &ProdcheckClaim<F>,
+	proof: GkrProdcheckProof<F>,
+	mut challenger: CH,
+) -> Result<ReducedGkrProdcheckClaims<F>, Error>
+where
+	F: TowerField,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let n_vars = prodcheck_claim
+		.n_vars()
+		.ok_or(Error::NumeratorDenominatorSizeMismatch)?;
+
+	let GkrProdcheckProof { t_proof, u_proof } = proof;
+	if t_proof.product != u_proof.product {
+		return Err(VerificationError::ProductsMismatch.into());
+	}
+
+	let (t_eval_point, t_eval) = verify_product(n_vars, t_proof, &mut challenger)?;
+	let (u_eval_point, u_eval) = verify_product(n_vars, u_proof, &mut challenger)?;
+
+	Ok(ReducedGkrProdcheckClaims {
+		t_eval_claim: EvalcheckClaim {
+			poly: prodcheck_claim.t_oracle.clone().into_composite(),
+			eval_point: t_eval_point,
+			eval: t_eval,
+			is_random_point: true,
+		},
+		u_eval_claim: EvalcheckClaim {
+			poly: prodcheck_claim.u_oracle.clone().into_composite(),
+			eval_point: u_eval_point,
+			eval: u_eval,
+			is_random_point: true,
+		},
+	})
+}
+
+/// Runs the grand product circuit of a single multilinear, returning the proof and the final
+/// evaluation claim on it.
+fn prove_product<F, FW, CH>(
+	poly: MultilinearWitness<'_, FW>,
+	domain: &EvaluationDomain<FW>,
+	mut challenger: CH,
+	switchover_fn: impl Fn(usize) -> usize + Copy,
+) -> Result<(GkrProductProof<F>, Vec<F>, F), Error>
+where
+	F: TowerField + From<FW>,
+	FW: Field + From<F>,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let n_vars = poly.n_vars();
+
+	// layers[k] holds the 2^k hypercube evaluations of V_k
+	let mut layers = Vec::with_capacity(n_vars + 1);
+	let mut input = vec![FW::ZERO; 1 << n_vars];
+	poly.subcube_evals(n_vars, 0, &mut input)?;
+	layers.push(input);
+	for k in (0..n_vars).rev() {
+		let next = layers.last().expect("layers is not empty");
+		let (zeros, ones) = next.split_at(1 << k);
+		let layer = zeros.iter().zip(ones).map(|(&a, &b)| a * b).collect();
+		layers.push(layer);
+	}
+	layers.reverse();
+
+	let product = F::from(layers[0][0]);
+	challenger.observe(product);
+
+	let mut eval_point = Vec::new();
+	let mut eval = product;
+	let mut layer_proofs = Vec::with_capacity(n_vars);
+	for (k, next_layer) in layers.iter().skip(1).enumerate() {
+		let (zero_values, one_values) = next_layer.split_at(1 << k);
+		let zero_half = MultilinearExtension::from_values(zero_values.to_vec())?;
+		let one_half = MultilinearExtension::from_values(one_values.to_vec())?;
+
+		let (sumcheck_proof, sumcheck_point) = if k == 0 {
+			(SumcheckProof { rounds: Vec::new() }, Vec::new())
+		} else {
+			let claim = layer_sumcheck_claim(k, eval)?;
+			let eq_ind =
+				EqIndPartialEval::new(k, eval_point.iter().copied().map(FW::from).collect())?
+					.multilinear_extension::<FW>()?;
+			let multilinears: Vec<MultilinearWitness<'static, FW>> = vec![
+				eq_ind.specialize_arc_dyn(),
+				zero_half.clone().specialize_arc_dyn(),
+				one_half.clone().specialize_arc_dyn(),
+			];
+			let witness = MultilinearComposite::new(k, GkrLayerComposition, multilinears)?;
+
+			let SumcheckProveOutput {
+				evalcheck_claim,
+				sumcheck_proof,
+			} = sumcheck::prove(&claim, witness, domain, &mut challenger, switchover_fn)?;
+			(sumcheck_proof, evalcheck_claim.eval_point)
+		};
+
+		let query = MultilinearQuery::<FW>::with_full_query(
+			&sumcheck_point
+				.iter()
+				.copied()
+				.map(FW::from)
+				.collect::<Vec<_>>(),
+		)?;
+		let zero_eval = F::from(zero_half.evaluate::<FW, FW>(&query)?);
+		let one_eval = F::from(one_half.evaluate::<FW, FW>(&query)?);
+		challenger.observe_slice(&[zero_eval, one_eval]);
+
+		let mu = challenger.sample();
+		eval = zero_eval + mu * (one_eval - zero_eval);
+		eval_point = sumcheck_point;
+		eval_point.push(mu);
+
+		layer_proofs.push(GkrLayerProof {
+			sumcheck_proof,
+			zero_eval,
+			one_eval,
+		});
+	}
+
+	let proof = GkrProductProof {
+		product,
+		layers: layer_proofs,
+	};
+	Ok((proof, eval_point, eval))
+}
+
+/// Checks the grand product circuit of a single multilinear, returning the final evaluation
+/// claim on it.
+fn verify_product<F, CH>(
+	n_vars: usize,
+	proof: GkrProductProof<F>,
+	mut challenger: CH,
+) -> Result<(Vec<F>, F), Error>
+where
+	F: TowerField,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let GkrProductProof { product, layers } = proof;
+	if layers.len() != n_vars {
+		return Err(VerificationError::NumberOfRounds.into());
+	}
+
+	challenger.observe(product);
+
+	let mut eval_point = Vec::new();
+	let mut eval = product;
+	for (k, layer_proof) in layers.into_iter().enumerate() {
+		let GkrLayerProof {
+			sumcheck_proof,
+			zero_eval,
+			one_eval,
+		} = layer_proof;
+
+		// The output layer has no variables, so its claim is checked directly
+		let sumcheck_point = if k == 0 {
+			if !sumcheck_proof.rounds.is_empty() {
+				return Err(VerificationError::NumberOfRounds.into());
+			}
+			if zero_eval * one_eval != eval {
+				return Err(VerificationError::IncorrectEvaluation.into());
+			}
+			Vec::new()
+		} else {
+			let claim = layer_sumcheck_claim(k, eval)?;
+			let evalcheck_claim = sumcheck::verify(&claim, sumcheck_proof, &mut challenger)?;
+			let eq_ind_eval =
+				EqIndPartialEval::new(k, eval_point)?.evaluate(&evalcheck_claim.eval_point)?;
+			if eq_ind_eval * zero_eval * one_eval != evalcheck_claim.eval {
+				return Err(VerificationError::IncorrectEvaluation.into());
+			}
+			evalcheck_claim.eval_point
+		};
+
+		challenger.observe_slice(&[zero_eval, one_eval]);
+
+		let mu = challenger.sample();
+		eval = zero_eval + mu * (one_eval - zero_eval);
+		eval_point = sumcheck_point;
+		eval_point.push(mu);
+	}
+
+	Ok((eval_point, eval))
+}
+
+/// Builds the sumcheck claim of a layer with `n_vars` variables.
+///
+/// The layer multilinears are never committed, so the claim is stated over placeholder oracles
+/// that only carry the number of variables. The evaluation claim that the sumcheck reduces to is
+/// discarded and checked against the next layer instead.
+fn layer_sumcheck_claim<F: TowerField>(n_vars: usize, sum: F) -> Result<SumcheckClaim<F>, Error> {
+	let mut layer_oracles = MultilinearOracleSet::new();
+	let batch_id = layer_oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 0,
+		n_vars,
+		n_polys: 3,
+		tower_level: F::TOWER_LEVEL,
+	});
+	let inner = (0..3)
+		.map(|index| layer_oracles.committed_oracle(CommittedId { batch_id, index }))
+		.collect();
+	let poly = CompositePolyOracle::new(n_vars, inner, GkrLayerComposition)?;
+	Ok(SumcheckClaim {
+		poly,
+		sum,
+		zerocheck_challenges: None,
+	})
+}
@@ -1,14 +1,22 @@
 // Copyright 2024 Ulvetanna Inc.
 
 mod error;
+mod gkr;
 #[allow(clippy::module_inception)]
 mod prodcheck;
 mod prove;
+#[cfg(test)]
+mod tests;
 mod verify;
 
 pub use error::*;
+pub use gkr::{
+	prove_gkr, verify_gkr, GkrLayerComposition, GkrLayerProof, GkrProdcheckProof,
+	GkrProdcheckProveOutput, GkrProductProof, ReducedGkrProdcheckClaims,
+};
 pub use prodcheck::{
 	ProdcheckClaim, ProdcheckProveOutput, ProdcheckWitness, ReducedProductCheckClaims,
+	SimpleMultGateComposition,
 };
 pub use prove::*;
 pub use verify::*;
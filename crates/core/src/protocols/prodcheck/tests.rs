@@ -0,0 +1,121 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use crate::{
+	challenger::HashChallenger,
+	oracle::{CommittedBatchSpec, CommittedId, MultilinearOracleSet},
+	polynomial::{EvaluationDomain, MultilinearExtension, MultilinearPoly, MultilinearQuery},
+	protocols::prodcheck::{
+		prove_gkr, verify_gkr, Error, GkrProdcheckProveOutput, ProdcheckClaim, ProdcheckWitness,
+		VerificationError,
+	},
+	witness::MultilinearWitness,
+};
+use assert_matches::assert_matches;
+use binius_field::{BinaryField128b, Field, TowerField};
+use binius_hash::GroestlHasher;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use std::iter::repeat_with;
+
+type F = BinaryField128b;
+
+const N_VARS: usize = 5;
+
+fn make_claim() -> ProdcheckClaim<F> {
+	let mut oracles = MultilinearOracleSet::<F>::new();
+	let batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 0,
+		n_vars: N_VARS,
+		n_polys: 2,
+		tower_level: F::TOWER_LEVEL,
+	});
+	ProdcheckClaim {
+		t_oracle: oracles.committed_oracle(CommittedId { batch_id, index: 0 }),
+		u_oracle: oracles.committed_oracle(CommittedId { batch_id, index: 1 }),
+	}
+}
+
+/// Returns random T values and a permutation of them as U values.
+fn make_values(rng: &mut StdRng) -> (Vec<F>, Vec<F>) {
+	let t_values = repeat_with(|| <F as Field>::random(&mut *rng))
+		.take(1 << N_VARS)
+		.collect::<Vec<_>>();
+	let mut u_values = t_values.clone();
+	u_values.shuffle(rng);
+	(t_values, u_values)
+}
+
+fn make_witness(t_values: Vec<F>, u_values: Vec<F>) -> ProdcheckWitness<'static, F> {
+	let to_witness = |values| -> MultilinearWitness<'static, F> {
+		MultilinearExtension::from_values(values)
+			.unwrap()
+			.specialize_arc_dyn()
+	};
+	ProdcheckWitness {
+		t_polynomial: to_witness(t_values),
+		u_polynomial: to_witness(u_values),
+	}
+}
+
+#[test]
+fn test_prove_verify_gkr_prodcheck() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let claim = make_claim();
+	let (t_values, u_values) = make_values(&mut rng);
+	let witness = make_witness(t_values, u_values);
+	let t_polynomial = witness.t_polynomial.clone();
+	let domain = EvaluationDomain::<F>::new(4).unwrap();
+
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let GkrProdcheckProveOutput {
+		reduced_claims,
+		proof,
+	} = prove_gkr(&claim, witness, &domain, challenger.clone(), |_| 1).unwrap();
+	assert_eq!(proof.t_proof.layers.len(), N_VARS);
+
+	let verified_claims = verify_gkr(&claim, proof, challenger).unwrap();
+	assert_eq!(verified_claims.t_eval_claim.eval_point, reduced_claims.t_eval_claim.eval_point);
+	assert_eq!(verified_claims.t_eval_claim.eval, reduced_claims.t_eval_claim.eval);
+	assert_eq!(verified_claims.u_eval_claim.eval_point, reduced_claims.u_eval_claim.eval_point);
+	assert_eq!(verified_claims.u_eval_claim.eval, reduced_claims.u_eval_claim.eval);
+
+	// The reduced claim on T must hold for the witness
+	let t_eval_claim = verified_claims.t_eval_claim;
+	let query = MultilinearQuery::with_full_query(&t_eval_claim.eval_point).unwrap();
+	assert_eq!(t_polynomial.evaluate(&query).unwrap(), t_eval_claim.eval);
+}
+
+#[test]
+fn test_gkr_prodcheck_products_differ() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let claim = make_claim();
+	let (t_values, mut u_values) = make_values(&mut rng);
+	u_values[3] += F::ONE;
+	let witness = make_witness(t_values, u_values);
+	let domain = EvaluationDomain::<F>::new(4).unwrap();
+
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let output = prove_gkr(&claim, witness, &domain, challenger.clone(), |_| 1).unwrap();
+	assert_matches!(
+		verify_gkr(&claim, output.proof, challenger),
+		Err(Error::Verification(VerificationError::ProductsMismatch))
+	);
+}
+
+#[test]
+fn test_gkr_prodcheck_tampered_layer() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let claim = make_claim();
+	let (t_values, u_values) = make_values(&mut rng);
+	let witness = make_witness(t_values, u_values);
+	let domain = EvaluationDomain::<F>::new(4).unwrap();
+
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let mut proof = prove_gkr(&claim, witness, &domain, challenger.clone(), |_| 1)
+		.unwrap()
+		.proof;
+	proof.u_proof.layers[2].zero_eval += F::ONE;
+	assert_matches!(
+		verify_gkr(&claim, proof, challenger),
+		Err(Error::Verification(VerificationError::IncorrectEvaluation))
+	);
+}
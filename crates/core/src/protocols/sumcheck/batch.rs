@@ -8,19 +8,27 @@
 //! later round challenges. Importantly, the verifier samples mixing challenges "just-in-time".
 //! That is, the verifier samples mixing challenges for new sumcheck claims over n variables only
 //! after the last sumcheck round message has been sent by the prover.
+//!
+//! Sumcheck claims reduced from zerocheck can be batched in the same way, provided that all claims
+//! in the batch come from zerocheck and share the zerocheck challenges. A claim over n variables
+//! must use the last n - 1 challenges of the claim with the most variables. The eq-indicator factor
+//! of each round is then the same for every claim in the batch, so the batched round polynomial
+//! satisfies the same round identity as the round polynomial of a single zerocheck instance.
 
 use super::{
-	batch_verify_final, error::Error, prove::SumcheckProver, verify_round, SumcheckClaim,
-	SumcheckRound, SumcheckRoundClaim, VerificationError,
+	batch_verify_final, error::Error, prove::SumcheckProver, verify_round, verify_zerocheck_round,
+	SumcheckClaim, SumcheckRound, SumcheckRoundClaim, VerificationError,
 };
 use crate::{
 	challenger::{CanObserve, CanSample},
 	polynomial::{CompositionPoly, MultilinearPoly},
 	protocols::evalcheck::EvalcheckClaim,
+	serialization::{DeserializeCanonical, SerializeCanonical},
+	transcript::{ProverTranscript, VerifierTranscript},
 };
 use binius_field::{Field, PackedField};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SumcheckBatchProof<F> {
 	pub rounds: Vec<SumcheckRound<F>>,
 	/// Evaluations of each multivariate in the batch at the challenge point.
@@ -47,6 +55,68 @@ where
 	CW: CompositionPoly<PW>,
 	M: MultilinearPoly<PW> + Sync,
 	CH: CanObserve<F> + CanSample<F>,
+{
+	let (rounds, evalcheck_claims) =
+		batch_prove_rounds(provers, &mut challenger, |challenger, round_proof| {
+			challenger.observe_slice(&round_proof.coeffs)
+		})?;
+
+	let evals = evalcheck_claims.iter().map(|claim| claim.eval).collect();
+
+	let sumcheck_batch_proof = SumcheckBatchProof { rounds, evals };
+
+	Ok(SumcheckBatchProveOutput {
+		proof: sumcheck_batch_proof,
+		evalcheck_claims,
+	})
+}
+
+/// Prove a batched sumcheck instance, writing the round messages and the final evaluations to a
+/// transcript.
+///
+/// Counterpart of [`batch_verify_with_transcript`].
+pub fn batch_prove_with_transcript<'a, F, PW, CW, M, CH>(
+	provers: impl IntoIterator<Item = SumcheckProver<'a, F, PW, CW, M>>,
+	transcript: &mut ProverTranscript<CH>,
+) -> Result<Vec<EvalcheckClaim<F>>, Error>
+where
+	F: Field + From<PW::Scalar> + SerializeCanonical,
+	PW: PackedField,
+	PW::Scalar: From<F>,
+	CW: CompositionPoly<PW>,
+	M: MultilinearPoly<PW> + Sync,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let (_, evalcheck_claims) =
+		batch_prove_rounds(provers, transcript, |transcript, round_proof| {
+			transcript.write_scalars(&round_proof.coeffs)
+		})?;
+
+	let evals = evalcheck_claims
+		.iter()
+		.map(|claim| claim.eval)
+		.collect::<Vec<_>>();
+	transcript.write_scalars(&evals);
+
+	Ok(evalcheck_claims)
+}
+
+/// Runs the batched sumcheck rounds, handing each round message to `send_round_proof`.
+///
+/// The callback is responsible for binding the round message to the challenger state before the
+/// round challenge is sampled.
+fn batch_prove_rounds<'a, F, PW, CW, M, CH>(
+	provers: impl IntoIterator<Item = SumcheckProver<'a, F, PW, CW, M>>,
+	challenger: &mut CH,
+	mut send_round_proof: impl FnMut(&mut CH, &SumcheckRound<F>),
+) -> Result<(Vec<SumcheckRound<F>>, Vec<EvalcheckClaim<F>>), Error>
+where
+	F: Field + From<PW::Scalar>,
+	PW: PackedField,
+	PW::Scalar: From<F>,
+	CW: CompositionPoly<PW>,
+	M: MultilinearPoly<PW> + Sync,
+	CH: CanSample<F>,
 {
 	let mut provers_vec = provers.into_iter().collect::<Vec<_>>();
 	// NOTE: Important to use stable sorting for prover-verifier consistency!
@@ -55,6 +125,25 @@ where
 
 	let n_rounds = provers_vec.first().map(|claim| claim.n_vars()).unwrap_or(0);
 
+	let is_zerocheck = shared_zerocheck_challenges(
+		provers_vec
+			.iter()
+			.map(|prover| (prover.n_vars(), prover.zerocheck_challenges())),
+	)?
+	.is_some();
+
+	// Zerocheck round polynomials are mixed with all of their coefficients, because the
+	// coefficients omitted from a round proof depend on the round of the individual instance.
+	let execute_round = |prover: &mut SumcheckProver<'a, F, PW, CW, M>, prev_rd_challenge| {
+		if is_zerocheck {
+			prover
+				.execute_round_untrimmed(prev_rd_challenge)
+				.map(|coeffs| SumcheckRound { coeffs })
+		} else {
+			prover.execute_round(prev_rd_challenge)
+		}
+	};
+
 	let mut batch_coeffs = Vec::with_capacity(provers_vec.len());
 	let mut round_proofs = Vec::with_capacity(n_rounds);
 
@@ -66,7 +155,7 @@ where
 
 		// Process the reduced sumcheck instances
 		for (prover, &coeff) in provers_vec.iter_mut().zip(batch_coeffs.iter()) {
-			let proof = prover.execute_round(prev_rd_challenge)?;
+			let proof = execute_round(prover, prev_rd_challenge)?;
 			mix_round_proofs(&mut batch_round_proof, &proof, coeff);
 		}
 
@@ -79,11 +168,15 @@ where
 			let coeff = challenger.sample();
 			batch_coeffs.push(coeff);
 
-			let proof = next_prover.execute_round(None)?;
+			let proof = execute_round(next_prover, None)?;
 			mix_round_proofs(&mut batch_round_proof, &proof, coeff);
 		}
 
-		challenger.observe_slice(&batch_round_proof.coeffs);
+		if is_zerocheck {
+			trim_zerocheck_round_proof(&mut batch_round_proof, round_no);
+		}
+
+		send_round_proof(challenger, &batch_round_proof);
 		round_proofs.push(batch_round_proof);
 		prev_rd_challenge = Some(challenger.sample());
 	}
@@ -99,17 +192,7 @@ where
 		})
 		.collect::<Result<Vec<_>, _>>()?;
 
-	let evals = evalcheck_claims.iter().map(|claim| claim.eval).collect();
-
-	let sumcheck_batch_proof = SumcheckBatchProof {
-		rounds: round_proofs,
-		evals,
-	};
-
-	Ok(SumcheckBatchProveOutput {
-		proof: sumcheck_batch_proof,
-		evalcheck_claims,
-	})
+	Ok((round_proofs, evalcheck_claims))
 }
 
 /// Verify a batched sumcheck instance.
@@ -123,6 +206,59 @@ pub fn batch_verify<F, CH>(
 where
 	F: Field,
 	CH: CanSample<F> + CanObserve<F>,
+{
+	let SumcheckBatchProof { rounds, evals } = proof;
+	let mut rounds = rounds.into_iter();
+
+	let n_rounds = rounds.len();
+	let (claims_vec, batch_coeffs, rd_claim) =
+		batch_verify_rounds(claims, &mut challenger, |challenger| {
+			let round_proof = rounds.next().ok_or(VerificationError::NumberOfRounds)?;
+			challenger.observe_slice(round_proof.coeffs.as_slice());
+			Ok(round_proof)
+		})?;
+
+	if rd_claim.partial_point.len() != n_rounds {
+		return Err(VerificationError::NumberOfRounds.into());
+	}
+
+	batch_verify_final(&claims_vec, &batch_coeffs, &evals, rd_claim)
+}
+
+/// Verify a batched sumcheck instance, reading the round messages and the final evaluations from
+/// a transcript.
+///
+/// Counterpart of [`batch_prove_with_transcript`].
+pub fn batch_verify_with_transcript<F, CH>(
+	claims: impl IntoIterator<Item = SumcheckClaim<F>>,
+	transcript: &mut VerifierTranscript<'_, CH>,
+) -> Result<Vec<EvalcheckClaim<F>>, Error>
+where
+	F: Field + DeserializeCanonical,
+	CH: CanSample<F> + CanObserve<F>,
+{
+	let (claims_vec, batch_coeffs, rd_claim) =
+		batch_verify_rounds(claims, transcript, |transcript| {
+			let coeffs = transcript.read_scalars()?;
+			Ok(SumcheckRound { coeffs })
+		})?;
+
+	let evals = transcript.read_scalars()?;
+
+	batch_verify_final(&claims_vec, &batch_coeffs, &evals, rd_claim)
+}
+
+/// Runs the verifier side of the batched sumcheck rounds, obtaining each round message from
+/// `receive_round_proof` after it has been bound to the challenger state.
+#[allow(clippy::type_complexity)]
+fn batch_verify_rounds<F, CH>(
+	claims: impl IntoIterator<Item = SumcheckClaim<F>>,
+	challenger: &mut CH,
+	mut receive_round_proof: impl FnMut(&mut CH) -> Result<SumcheckRound<F>, Error>,
+) -> Result<(Vec<SumcheckClaim<F>>, Vec<F>, SumcheckRoundClaim<F>), Error>
+where
+	F: Field,
+	CH: CanSample<F>,
 {
 	let mut claims_vec = claims.into_iter().collect::<Vec<_>>();
 	// NOTE: Important to use stable sorting for prover-verifier consistency!
@@ -131,9 +267,12 @@ where
 
 	let n_rounds = claims_vec.first().map(|claim| claim.n_vars()).unwrap_or(0);
 
-	if proof.rounds.len() != n_rounds {
-		return Err(VerificationError::NumberOfRounds.into());
-	}
+	let zerocheck_challenges = shared_zerocheck_challenges(
+		claims_vec
+			.iter()
+			.map(|claim| (claim.n_vars(), claim.zerocheck_challenges.as_ref())),
+	)?
+	.map(<[F]>::to_vec);
 
 	let mut batch_coeffs = Vec::with_capacity(claims_vec.len());
 	let mut rd_claim = SumcheckRoundClaim {
@@ -141,7 +280,7 @@ where
 		current_round_sum: F::ZERO,
 	};
 
-	for (round_no, round_proof) in proof.rounds.iter().enumerate() {
+	for round_no in 0..n_rounds {
 		let n_vars = n_rounds - round_no;
 
 		// Mix in new sumcheck claims with the appropriate number of variables
@@ -156,8 +295,15 @@ where
 			rd_claim.current_round_sum += next_claim.sum * challenge;
 		}
 
-		challenger.observe_slice(round_proof.coeffs.as_slice());
-		rd_claim = verify_round(rd_claim, challenger.sample(), round_proof.clone())?;
+		let round_proof = receive_round_proof(challenger)?;
+		let challenge = challenger.sample();
+		rd_claim = match &zerocheck_challenges {
+			Some(zerocheck_challenges) => {
+				let alpha = round_no.checked_sub(1).map(|i| zerocheck_challenges[i]);
+				verify_zerocheck_round(rd_claim, challenge, round_proof, alpha)?
+			}
+			None => verify_round(rd_claim, challenge, round_proof)?,
+		};
 	}
 
 	// Mix in remaining sumcheck claims with 0 variables
@@ -170,7 +316,46 @@ where
 		rd_claim.current_round_sum += claim.sum * challenge;
 	}
 
-	batch_verify_final(&claims_vec, &batch_coeffs, &proof.evals, rd_claim)
+	Ok((claims_vec, batch_coeffs, rd_claim))
+}
+
+/// Returns the zerocheck challenges of a batch sorted in descending order by number of variables,
+/// or `None` if none of the claims in the batch came from a zerocheck reduction.
+///
+/// Fails if the batch mixes zerocheck and regular sumcheck claims, or if the challenges of some
+/// claim are not the suffix of the challenges of the first claim.
+fn shared_zerocheck_challenges<'b, F: Field>(
+	mut claim_challenges: impl Iterator<Item = (usize, Option<&'b Vec<F>>)>,
+) -> Result<Option<&'b [F]>, Error> {
+	let Some((first_n_vars, first_challenges)) = claim_challenges.next() else {
+		return Ok(None);
+	};
+
+	let Some(shared) = first_challenges else {
+		if claim_challenges.any(|(_, challenges)| challenges.is_some()) {
+			return Err(Error::MixedZerocheckBatch);
+		}
+		return Ok(None);
+	};
+
+	if shared.len() + 1 != first_n_vars {
+		return Err(Error::ZerocheckChallengesMismatch);
+	}
+	for (n_vars, challenges) in claim_challenges {
+		let challenges = challenges.ok_or(Error::MixedZerocheckBatch)?;
+		if challenges.len() + 1 != n_vars || !shared.ends_with(challenges) {
+			return Err(Error::ZerocheckChallengesMismatch);
+		}
+	}
+	Ok(Some(shared))
+}
+
+/// Drops the coefficients of a batched zerocheck round polynomial that the verifier recovers,
+/// exactly as for a single zerocheck instance in the same round.
+fn trim_zerocheck_round_proof<F: Field>(round_proof: &mut SumcheckRound<F>, round_no: usize) {
+	let n_trimmed = if round_no == 0 { 2 } else { 1 };
+	let n_trimmed = n_trimmed.min(round_proof.coeffs.len());
+	round_proof.coeffs.drain(..n_trimmed);
 }
 
 fn mix_round_proofs<F: Field>(
@@ -1,6 +1,9 @@
 // Copyright 2023-2024 Ulvetanna Inc.
 
-use crate::{oracle::Error as IOPolynomialError, polynomial::Error as PolynomialError};
+use crate::{
+	oracle::Error as IOPolynomialError, polynomial::Error as PolynomialError,
+	serialization::Error as SerializationError,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -10,8 +13,14 @@ pub enum Error {
 	ImproperInput(String),
 	#[error("oracles must be sorted in descending order by number of variables")]
 	OraclesOutOfOrder,
+	#[error("sumcheck claims reduced from zerocheck cannot be batched with other sumcheck claims")]
+	MixedZerocheckBatch,
+	#[error("batched zerocheck claims must use suffixes of the same zerocheck challenges")]
+	ZerocheckChallengesMismatch,
 	#[error("the evaluation domain does not match the expected size")]
 	EvaluationDomainMismatch,
+	#[error("folded multilinears need at least {required} bytes, exceeding the memory budget of {budget} bytes")]
+	MemoryBudgetExceeded { budget: usize, required: usize },
 	#[error("prover has mismatch between claim and witness: {0}")]
 	ProverClaimWitnessMismatch(String),
 	#[error("IOPolynomial error: {0}")]
@@ -20,6 +29,8 @@ pub enum Error {
 	Polynomial(#[from] PolynomialError),
 	#[error("verification failure: {0}")]
 	Verification(#[from] VerificationError),
+	#[error("transcript error: {0}")]
+	Transcript(#[from] SerializationError),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -4,10 +4,12 @@ use super::{
 	error::Error,
 	sumcheck::{
 		reduce_sumcheck_claim_final, reduce_sumcheck_claim_round, reduce_zerocheck_claim_round,
-		SumcheckClaim, SumcheckRound, SumcheckRoundClaim, SumcheckWitness,
+		SumcheckClaim, SumcheckProof, SumcheckProveOutput, SumcheckRound, SumcheckRoundClaim,
+		SumcheckWitness,
 	},
 };
 use crate::{
+	challenger::{CanObserve, CanSample},
 	oracle::CompositePolyOracle,
 	polynomial::{
 		extrapolate_line, transparent::eq_ind::EqIndPartialEval, CompositionPoly, EvaluationDomain,
@@ -15,9 +17,11 @@ use crate::{
 	},
 	protocols::{
 		evalcheck::EvalcheckClaim,
-		sumcheck::prove_general::{ProverState, SumcheckEvaluator},
+		sumcheck::prove_general::{BitslicedEvaluator, ProverState, SumcheckEvaluator},
 		zerocheck::{ZerocheckFirstRoundEvaluator, ZerocheckLaterRoundEvaluator},
 	},
+	serialization::SerializeCanonical,
+	transcript::ProverTranscript,
 };
 use binius_field::{Field, PackedField};
 use either::Either;
@@ -125,6 +129,37 @@ where
 		sumcheck_claim: SumcheckClaim<F>,
 		sumcheck_witness: SumcheckWitness<PW, CW, M>,
 		switchover_fn: impl Fn(usize) -> usize,
+	) -> Result<Self, Error> {
+		Self::new_with_memory_budget(domain, sumcheck_claim, sumcheck_witness, switchover_fn, None)
+	}
+
+	/// Start a new sumcheck instance in the memory-bounded streaming mode.
+	///
+	/// Switchovers are delayed past `switchover_fn` as needed to keep the folded multilinears
+	/// within `memory_budget` bytes, see [`ProverState::new_streaming`]. The proof is identical to
+	/// the one of a prover created with [`Self::new`].
+	pub fn new_streaming(
+		domain: &'a EvaluationDomain<PW::Scalar>,
+		sumcheck_claim: SumcheckClaim<F>,
+		sumcheck_witness: SumcheckWitness<PW, CW, M>,
+		switchover_fn: impl Fn(usize) -> usize,
+		memory_budget: usize,
+	) -> Result<Self, Error> {
+		Self::new_with_memory_budget(
+			domain,
+			sumcheck_claim,
+			sumcheck_witness,
+			switchover_fn,
+			Some(memory_budget),
+		)
+	}
+
+	fn new_with_memory_budget(
+		domain: &'a EvaluationDomain<PW::Scalar>,
+		sumcheck_claim: SumcheckClaim<F>,
+		sumcheck_witness: SumcheckWitness<PW, CW, M>,
+		switchover_fn: impl Fn(usize) -> usize,
+		memory_budget: Option<usize>,
 	) -> Result<Self, Error> {
 		let n_vars = sumcheck_claim.n_vars();
 
@@ -144,7 +179,13 @@ where
 
 		check_evaluation_domain(sumcheck_claim.poly.max_individual_degree(), domain)?;
 
-		let state = ProverState::new(n_vars, sumcheck_witness.multilinears, switchover_fn)?;
+		let multilinears = sumcheck_witness.multilinears;
+		let state = match memory_budget {
+			Some(memory_budget) => {
+				ProverState::new_streaming(n_vars, multilinears, switchover_fn, memory_budget)?
+			}
+			None => ProverState::new(n_vars, multilinears, switchover_fn)?,
+		};
 
 		let composition = sumcheck_witness.composition;
 
@@ -214,7 +255,7 @@ where
 		self.zerocheck_aux_state.is_some()
 	}
 
-	fn zerocheck_challenges(&self) -> Option<&Vec<F>> {
+	pub(super) fn zerocheck_challenges(&self) -> Option<&Vec<F>> {
 		self.zerocheck_aux_state.as_ref().map(|aux| &aux.challenges)
 	}
 
@@ -276,6 +317,19 @@ where
 		&mut self,
 		prev_rd_challenge: Option<F>,
 	) -> Result<SumcheckRound<F>, Error> {
+		self.execute_round_untrimmed(prev_rd_challenge)?;
+		Ok(self
+			.last_round_proof
+			.clone()
+			.expect("execute_round_untrimmed sets last_round_proof"))
+	}
+
+	/// Executes a round like [`Self::execute_round`], but returns all coefficients of the round
+	/// polynomial, including the ones that are omitted from the round proof.
+	pub(super) fn execute_round_untrimmed(
+		&mut self,
+		prev_rd_challenge: Option<F>,
+	) -> Result<Vec<F>, Error> {
 		// First round has no challenge, other rounds should have it
 		self.validate_rd_challenge(prev_rd_challenge)?;
 
@@ -323,16 +377,19 @@ where
 
 		let evals = self.state.sum_round_evals(evaluator);
 
-		let untrimmed_coeffs = self.evals_to_coeffs(evals)?;
-		let trimmed_coeffs = self.trim_coeffs(untrimmed_coeffs);
+		let untrimmed_coeffs = self
+			.evals_to_coeffs(evals)?
+			.into_iter()
+			.map(Into::into)
+			.collect::<Vec<F>>();
 		let proof_round = SumcheckRound {
-			coeffs: trimmed_coeffs.into_iter().map(Into::into).collect(),
+			coeffs: self.trim_coeffs(untrimmed_coeffs.clone()),
 		};
-		self.last_round_proof = Some(proof_round.clone());
+		self.last_round_proof = Some(proof_round);
 
 		self.round += 1;
 
-		Ok(proof_round)
+		Ok(untrimmed_coeffs)
 	}
 
 	fn validate_rd_challenge(&self, prev_rd_challenge: Option<F>) -> Result<(), Error> {
@@ -372,6 +429,149 @@ where
 	}
 }
 
+/// The default switchover round of a multilinear, as a function of its
+/// [`MultilinearPoly::extension_degree`].
+///
+/// Multilinears over 1-bit fields are kept packed for the first few rounds, all others are
+/// switched over after the first round. Can be passed as `switchover_fn` to any sumcheck prover.
+pub fn default_switchover_round(extension_degree: usize) -> usize {
+	match extension_degree {
+		128 => 5,
+		_ => 1,
+	}
+}
+
+/// Prove a sumcheck instance, running all rounds against the challenger.
+///
+/// See [`batch_prove`](super::batch_prove) for proving several claims at once.
+pub fn prove<F, PW, CW, M, CH>(
+	claim: &SumcheckClaim<F>,
+	witness: SumcheckWitness<PW, CW, M>,
+	domain: &EvaluationDomain<PW::Scalar>,
+	challenger: CH,
+	switchover_fn: impl Fn(usize) -> usize,
+) -> Result<SumcheckProveOutput<F>, Error>
+where
+	F: Field + From<PW::Scalar>,
+	PW: PackedField,
+	PW::Scalar: From<F>,
+	CW: CompositionPoly<PW>,
+	M: MultilinearPoly<PW> + Sync,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let prover = SumcheckProver::new(domain, claim.clone(), witness, switchover_fn)?;
+	prove_with_challenger(prover, challenger)
+}
+
+/// Prove a sumcheck instance, writing the round messages to a transcript.
+///
+/// Counterpart of [`verify_with_transcript`](super::verify_with_transcript).
+pub fn prove_with_transcript<F, PW, CW, M, CH>(
+	claim: &SumcheckClaim<F>,
+	witness: SumcheckWitness<PW, CW, M>,
+	domain: &EvaluationDomain<PW::Scalar>,
+	transcript: &mut ProverTranscript<CH>,
+	switchover_fn: impl Fn(usize) -> usize,
+) -> Result<EvalcheckClaim<F>, Error>
+where
+	F: Field + From<PW::Scalar> + SerializeCanonical,
+	PW: PackedField,
+	PW::Scalar: From<F>,
+	CW: CompositionPoly<PW>,
+	M: MultilinearPoly<PW> + Sync,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let prover = SumcheckProver::new(domain, claim.clone(), witness, switchover_fn)?;
+	let (_, evalcheck_claim) = prove_rounds(prover, transcript, |transcript, round_proof| {
+		transcript.write_scalars(&round_proof.coeffs)
+	})?;
+	Ok(evalcheck_claim)
+}
+
+/// Prove a sumcheck instance like [`prove`], keeping the prover within a memory budget.
+///
+/// See [`SumcheckProver::new_streaming`] for details. The proof is identical to the one output
+/// by [`prove`].
+pub fn prove_streaming<F, PW, CW, M, CH>(
+	claim: &SumcheckClaim<F>,
+	witness: SumcheckWitness<PW, CW, M>,
+	domain: &EvaluationDomain<PW::Scalar>,
+	challenger: CH,
+	switchover_fn: impl Fn(usize) -> usize,
+	memory_budget: usize,
+) -> Result<SumcheckProveOutput<F>, Error>
+where
+	F: Field + From<PW::Scalar>,
+	PW: PackedField,
+	PW::Scalar: From<F>,
+	CW: CompositionPoly<PW>,
+	M: MultilinearPoly<PW> + Sync,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let prover = SumcheckProver::new_streaming(
+		domain,
+		claim.clone(),
+		witness,
+		switchover_fn,
+		memory_budget,
+	)?;
+	prove_with_challenger(prover, challenger)
+}
+
+fn prove_with_challenger<F, PW, CW, M, CH>(
+	prover: SumcheckProver<'_, F, PW, CW, M>,
+	mut challenger: CH,
+) -> Result<SumcheckProveOutput<F>, Error>
+where
+	F: Field + From<PW::Scalar>,
+	PW: PackedField,
+	PW::Scalar: From<F>,
+	CW: CompositionPoly<PW>,
+	M: MultilinearPoly<PW> + Sync,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let (rounds, evalcheck_claim) =
+		prove_rounds(prover, &mut challenger, |challenger, round_proof| {
+			challenger.observe_slice(&round_proof.coeffs)
+		})?;
+	Ok(SumcheckProveOutput {
+		evalcheck_claim,
+		sumcheck_proof: SumcheckProof { rounds },
+	})
+}
+
+/// Runs all sumcheck rounds, handing each round message to `send_round_proof`.
+///
+/// The callback is responsible for binding the round message to the challenger state before the
+/// round challenge is sampled.
+fn prove_rounds<F, PW, CW, M, CH>(
+	mut prover: SumcheckProver<'_, F, PW, CW, M>,
+	challenger: &mut CH,
+	mut send_round_proof: impl FnMut(&mut CH, &SumcheckRound<F>),
+) -> Result<(Vec<SumcheckRound<F>>, EvalcheckClaim<F>), Error>
+where
+	F: Field + From<PW::Scalar>,
+	PW: PackedField,
+	PW::Scalar: From<F>,
+	CW: CompositionPoly<PW>,
+	M: MultilinearPoly<PW> + Sync,
+	CH: CanSample<F>,
+{
+	let n_vars = prover.n_vars();
+
+	let mut prev_rd_challenge = None;
+	let mut rounds = Vec::with_capacity(n_vars);
+	for _ in 0..n_vars {
+		let round_proof = prover.execute_round(prev_rd_challenge)?;
+		send_round_proof(challenger, &round_proof);
+		prev_rd_challenge = Some(challenger.sample());
+		rounds.push(round_proof);
+	}
+
+	let evalcheck_claim = prover.finalize(prev_rd_challenge)?;
+	Ok((rounds, evalcheck_claim))
+}
+
 /// Validate that evaluation domain starts with 0 & 1 and the size is exactly one greater than the
 /// maximum individual degree of the polynomial.
 fn check_evaluation_domain<F: Field>(
@@ -444,4 +644,8 @@ where
 				.expect("evals_z is initialized with a length of poly.composition.n_vars()");
 		}
 	}
+
+	fn bitsliced(&self) -> Option<BitslicedEvaluator<'_, F>> {
+		BitslicedEvaluator::new::<P, _>(self.composition, &self.domain[1..], None)
+	}
 }
@@ -2,15 +2,15 @@
 
 use crate::{
 	polynomial::{
-		Error as PolynomialError, MultilinearExtensionSpecialized, MultilinearPoly,
-		MultilinearQuery,
+		BitslicedPoly, CompositionPoly, Error as PolynomialError, MultilinearExtensionSpecialized,
+		MultilinearPoly, MultilinearQuery, BITSLICED_MAX_DEGREE,
 	},
 	protocols::sumcheck::Error,
 };
 use binius_field::{Field, PackedField};
 use either::Either;
 use rayon::prelude::*;
-use std::{borrow::Borrow, cmp};
+use std::{borrow::Borrow, cmp, mem};
 
 /// An individual multilinear polynomial in a multivariate composite.
 #[derive(Debug)]
@@ -73,6 +73,119 @@ pub trait SumcheckEvaluator<F>: Send + Sync {
 		evals_z: &mut [F],
 		round_evals: &mut [F],
 	);
+
+	/// Returns the bit-sliced fast path of this evaluator, if it has one.
+	///
+	/// The prover state takes the fast path instead of calling [`Self::process_vertex`] in the
+	/// first round, when every multilinear exposes its evaluations as packed bits through
+	/// [`MultilinearPoly::evaluate_on_hypercube_bits`].
+	fn bitsliced(&self) -> Option<BitslicedEvaluator<'_, F>> {
+		None
+	}
+}
+
+/// Evaluates the round polynomial sums over `BinaryField1b` multilinears with word-level operations.
+///
+/// At a hypercube vertex, each multilinear restricted to the round variable is the linear
+/// polynomial $e_0 + (e_0 + e_1) X$ over `BinaryField1b`. The composition of these polynomials is
+/// computed for 128 vertices at once as a [`BitslicedPoly`] per term of the composition's
+/// `BinaryField1b` decomposition (see [`CompositionPoly::bitsliced_coeffs`]). The prover then only
+/// needs to sum, for each term and power of $X$, the weights of the vertices where the coefficient
+/// is set, and evaluate the resulting polynomial at the round evaluation points once.
+pub struct BitslicedEvaluator<'a, F> {
+	#[allow(clippy::type_complexity)]
+	evaluate: Box<
+		dyn Fn(&[BitslicedPoly], &mut Vec<BitslicedPoly>) -> Result<(), PolynomialError>
+			+ Send
+			+ Sync
+			+ 'a,
+	>,
+	coeffs: Vec<F>,
+	degree: usize,
+	eval_points: &'a [F],
+	vertex_weights: Option<&'a [F]>,
+}
+
+impl<'a, F: Field> BitslicedEvaluator<'a, F> {
+	/// Creates a bit-sliced evaluator for a composition.
+	///
+	/// The round evaluations are the sums over vertices of the composite at `eval_points`, each
+	/// vertex weighted by `vertex_weights` if given. Returns `None` if the composition does not
+	/// support bit-sliced evaluation or its degree exceeds [`BITSLICED_MAX_DEGREE`].
+	pub fn new<P, C>(
+		composition: &'a C,
+		eval_points: &'a [F],
+		vertex_weights: Option<&'a [F]>,
+	) -> Option<Self>
+	where
+		P: PackedField<Scalar = F>,
+		C: CompositionPoly<P>,
+	{
+		let degree = composition.degree();
+		if degree > BITSLICED_MAX_DEGREE {
+			return None;
+		}
+
+		let coeffs = composition.bitsliced_coeffs()?;
+		Some(Self {
+			evaluate: Box::new(move |query: &[BitslicedPoly], dst: &mut Vec<BitslicedPoly>| {
+				composition.evaluate_bitsliced(query, dst)
+			}),
+			coeffs,
+			degree,
+			eval_points,
+			vertex_weights,
+		})
+	}
+
+	fn n_sums(&self) -> usize {
+		self.coeffs.len() * (self.degree + 1)
+	}
+
+	// Adds the weights of the set coefficient bits of each term to the per-(term, power) sums.
+	fn accumulate(&self, block: usize, terms: &[BitslicedPoly], sums: &mut [F]) {
+		let weights = self
+			.vertex_weights
+			.map(|weights| &weights[block << 7..(block + 1) << 7]);
+		let sums_by_term = sums.chunks_exact_mut(self.degree + 1);
+		for (term, term_sums) in terms.iter().zip(sums_by_term) {
+			for (&word, sum) in term.coeffs().iter().zip(term_sums.iter_mut()) {
+				match weights {
+					Some(weights) => {
+						let mut word = word;
+						while word != 0 {
+							*sum += weights[word.trailing_zeros() as usize];
+							word &= word - 1;
+						}
+					}
+					None => {
+						if word.count_ones() % 2 == 1 {
+							*sum += F::ONE;
+						}
+					}
+				}
+			}
+		}
+	}
+
+	fn round_evals(&self, sums: &[F]) -> Vec<F> {
+		let mut poly_coeffs = vec![F::ZERO; self.degree + 1];
+		for (&coeff, term_sums) in self.coeffs.iter().zip(sums.chunks_exact(self.degree + 1)) {
+			for (poly_coeff, &sum) in poly_coeffs.iter_mut().zip(term_sums) {
+				*poly_coeff += coeff * sum;
+			}
+		}
+
+		self.eval_points
+			.iter()
+			.map(|&point| {
+				poly_coeffs
+					.iter()
+					.rev()
+					.fold(F::ZERO, |acc, &coeff| acc * point + coeff)
+			})
+			.collect()
+	}
 }
 
 impl<F, L, R> SumcheckEvaluator<F> for Either<L, R>
@@ -104,6 +217,13 @@ where
 			}
 		}
 	}
+
+	fn bitsliced(&self) -> Option<BitslicedEvaluator<'_, F>> {
+		match self {
+			Either::Left(left) => left.bitsliced(),
+			Either::Right(right) => right.bitsliced(),
+		}
+	}
 }
 
 /// A prover state for a generalized sumcheck protocol.
@@ -154,10 +274,67 @@ where
 		multilinears: impl IntoIterator<Item = M>,
 		switchover_fn: impl Fn(usize) -> usize,
 	) -> Result<Self, Error> {
-		let mut max_query_vars = 1;
+		let multilinears = multilinears.into_iter().map(|small_field_multilin| {
+			let switchover = switchover_fn(small_field_multilin.extension_degree());
+			(small_field_multilin, switchover)
+		});
+		Self::new_with_switchovers(n_rounds, multilinears)
+	}
+
+	/// Creates a prover state in the memory-bounded streaming mode.
+	///
+	/// Switchovers materialize the folded multilinears in the large field, which for many small
+	/// field multilinears may take far more memory than the witness itself. In streaming mode,
+	/// switchovers are delayed past `switchover_fn` until the folded multilinears and the query
+	/// tensor fit into `memory_budget` bytes. Until then, the round evaluations are recomputed
+	/// from the original multilinears by inner products with the query tensor, at a cost that
+	/// doubles with every delayed round. Both ways of evaluating are exact, so the round
+	/// polynomials are the same as those of a state created with [`Self::new`].
+	///
+	/// The budget does not account for the scratch space of folding a single multilinear.
+	pub fn new_streaming(
+		n_rounds: usize,
+		multilinears: impl IntoIterator<Item = M>,
+		switchover_fn: impl Fn(usize) -> usize,
+		memory_budget: usize,
+	) -> Result<Self, Error> {
+		let multilinears = multilinears.into_iter().collect::<Vec<_>>();
+		let switchovers = multilinears
+			.iter()
+			.map(|multilin| cmp::min(switchover_fn(multilin.extension_degree()), n_rounds))
+			.collect::<Vec<_>>();
+
+		// Pick the earliest common switchover lower bound that fits, as later switchovers trade
+		// memory for recomputation time.
+		let peak_memory = |min_switchover: usize| {
+			let switchovers = switchovers
+				.iter()
+				.map(|&switchover| cmp::max(switchover, min_switchover))
+				.collect::<Vec<_>>();
+			peak_folded_size(n_rounds, &switchovers) * mem::size_of::<PW::Scalar>()
+		};
+		let min_switchover = (1..=n_rounds)
+			.find(|&min_switchover| peak_memory(min_switchover) <= memory_budget)
+			.ok_or_else(|| Error::MemoryBudgetExceeded {
+				budget: memory_budget,
+				required: (1..=n_rounds).map(&peak_memory).min().unwrap_or(0),
+			})?;
+
 		let multilinears = multilinears
 			.into_iter()
-			.map(|small_field_multilin| {
+			.zip(switchovers)
+			.map(|(multilin, switchover)| (multilin, cmp::max(switchover, min_switchover)));
+		Self::new_with_switchovers(n_rounds, multilinears)
+	}
+
+	fn new_with_switchovers(
+		n_rounds: usize,
+		multilinears: impl IntoIterator<Item = (M, usize)>,
+	) -> Result<Self, Error> {
+		let mut switchovers = Vec::new();
+		let multilinears = multilinears
+			.into_iter()
+			.map(|(small_field_multilin, switchover)| {
 				if small_field_multilin.n_vars() != n_rounds {
 					return Err(PolynomialError::IncorrectNumberOfVariables {
 						expected: n_rounds,
@@ -166,8 +343,7 @@ where
 					.into());
 				}
 
-				let switchover = switchover_fn(small_field_multilin.extension_degree());
-				max_query_vars = cmp::max(max_query_vars, switchover);
+				switchovers.push(switchover);
 				Ok(SumcheckMultilinear::Transparent {
 					switchover,
 					small_field_multilin,
@@ -175,7 +351,7 @@ where
 			})
 			.collect::<Result<_, Error>>()?;
 
-		let query = Some(MultilinearQuery::new(max_query_vars)?);
+		let query = Some(MultilinearQuery::new(max_query_vars(n_rounds, &switchovers))?);
 
 		Ok(Self {
 			multilinears,
@@ -281,6 +457,14 @@ where
 		match (any_transparent, any_folded) {
 			(true, false) => {
 				if round == 0 {
+					// All transparent, first round over packed bits - bit-sliced evaluation
+					if let Some(round_evals) = evaluator
+						.bitsliced()
+						.and_then(|bitsliced| self.sum_round_evals_bitsliced(&bitsliced))
+					{
+						return round_evals;
+					}
+
 					// All transparent, first round - direct sampling
 					self.sum_round_evals_helper(
 						Self::only_transparent,
@@ -379,6 +563,73 @@ where
 			)
 	}
 
+	// First round over multilinears that are all stored as packed bits. Every block of 256
+	// hypercube vertices holds 128 pairs of evaluations at 0 & 1 of the round variable, which are
+	// unzipped from two 128-bit words per multilinear. Returns `None` if some multilinear does not
+	// expose its evaluations as bits or the bitsliced composition cannot be evaluated, in which
+	// case the caller falls back to the generic path.
+	fn sum_round_evals_bitsliced(
+		&self,
+		evaluator: &BitslicedEvaluator<PW::Scalar>,
+	) -> Option<Vec<PW::Scalar>> {
+		let rd_vars = self.n_rounds - self.round;
+		if rd_vars < 8 {
+			return None;
+		}
+
+		let multilinears = self
+			.multilinears
+			.iter()
+			.map(Self::only_transparent)
+			.collect::<Vec<_>>();
+		if multilinears
+			.iter()
+			.any(|multilin| multilin.evaluate_on_hypercube_bits(0).is_none())
+		{
+			return None;
+		}
+
+		let n_sums = evaluator.n_sums();
+		let sums = (0..1 << (rd_vars - 8))
+			.into_par_iter()
+			.try_fold(
+				|| {
+					(
+						vec![BitslicedPoly::ZERO; multilinears.len()],
+						Vec::with_capacity(evaluator.coeffs.len()),
+						vec![PW::Scalar::ZERO; n_sums],
+					)
+				},
+				|(mut query, mut terms, mut sums), block: usize| {
+					for (query, multilin) in query.iter_mut().zip(&multilinears) {
+						let evals_0 = multilin.evaluate_on_hypercube_bits(block << 1)?;
+						let evals_1 = multilin.evaluate_on_hypercube_bits((block << 1) + 1)?;
+						let (evals_0, evals_1) = unzip_bits(evals_0, evals_1);
+						*query = BitslicedPoly::linear(evals_0, evals_0 ^ evals_1);
+					}
+
+					terms.clear();
+					(evaluator.evaluate)(&query, &mut terms).ok()?;
+					evaluator.accumulate(block, &terms, &mut sums);
+
+					Some((query, terms, sums))
+				},
+			)
+			.map(|state| state.map(|(_, _, sums)| sums))
+			.try_reduce(
+				|| vec![PW::Scalar::ZERO; n_sums],
+				|mut overall_sums, partial_sums| {
+					overall_sums
+						.iter_mut()
+						.zip(partial_sums.iter())
+						.for_each(|(f, s)| *f += s);
+					Some(overall_sums)
+				},
+			)?;
+
+		Some(evaluator.round_evals(&sums))
+	}
+
 	// Note the generic parameter - this method samples small field in first round and
 	// large field post-switchover.
 	#[inline]
@@ -431,3 +682,46 @@ where
 		}
 	}
 }
+
+// The query tensor is extended once per fold, so it never needs more than `n_rounds - 1` variables.
+fn max_query_vars(n_rounds: usize, switchovers: &[usize]) -> usize {
+	let max_switchover = switchovers.iter().copied().max().unwrap_or(0);
+	cmp::max(1, cmp::min(max_switchover, n_rounds.saturating_sub(1)))
+}
+
+// Upper bound on the number of large field scalars held at once by the folded multilinears and
+// the query tensor, given the switchover round of each multilinear. A multilinear folded at or
+// before `round` has `2^(n_rounds - round)` evaluations in that round.
+fn peak_folded_size(n_rounds: usize, switchovers: &[usize]) -> usize {
+	let folded_size = (1..n_rounds)
+		.map(|round| {
+			let n_folded = switchovers
+				.iter()
+				.filter(|&&switchover| switchover <= round)
+				.count();
+			n_folded << (n_rounds - round)
+		})
+		.max()
+		.unwrap_or(0);
+	folded_size + (1 << max_query_vars(n_rounds, switchovers))
+}
+
+/// Splits two consecutive words of hypercube evaluations into the evaluations at even and at odd
+/// vertices, i.e. at 0 and 1 of the lowest variable.
+fn unzip_bits(lo: u128, hi: u128) -> (u128, u128) {
+	let evals_0 = compress_even_bits(lo) as u128 | (compress_even_bits(hi) as u128) << 64;
+	let evals_1 = compress_even_bits(lo >> 1) as u128 | (compress_even_bits(hi >> 1) as u128) << 64;
+	(evals_0, evals_1)
+}
+
+// Gathers the bits at even positions into the low half of the word.
+fn compress_even_bits(word: u128) -> u64 {
+	let mut x = word & 0x5555_5555_5555_5555_5555_5555_5555_5555;
+	x = (x | x >> 1) & 0x3333_3333_3333_3333_3333_3333_3333_3333;
+	x = (x | x >> 2) & 0x0f0f_0f0f_0f0f_0f0f_0f0f_0f0f_0f0f_0f0f;
+	x = (x | x >> 4) & 0x00ff_00ff_00ff_00ff_00ff_00ff_00ff_00ff;
+	x = (x | x >> 8) & 0x0000_ffff_0000_ffff_0000_ffff_0000_ffff;
+	x = (x | x >> 16) & 0x0000_0000_ffff_ffff_0000_0000_ffff_ffff;
+	x = (x | x >> 32) & 0x0000_0000_0000_0000_ffff_ffff_ffff_ffff;
+	x as u64
+}
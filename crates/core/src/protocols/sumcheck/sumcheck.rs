@@ -8,7 +8,7 @@ use crate::{
 };
 use binius_field::Field;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SumcheckRound<F> {
 	/// Monomial-Basis Coefficients of a round polynomial sent by the prover
 	///
@@ -23,7 +23,7 @@ pub struct SumcheckRound<F> {
 	pub coeffs: Vec<F>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SumcheckProof<F> {
 	pub rounds: Vec<SumcheckRound<F>>,
 }
@@ -8,22 +8,31 @@ use crate::{
 		MultilinearExtension, MultilinearExtensionSpecialized, MultilinearQuery,
 	},
 	protocols::{
-		sumcheck::{batch_prove, batch_verify, SumcheckClaim, SumcheckProver},
+		sumcheck::{
+			batch_prove, batch_prove_with_transcript, batch_verify, batch_verify_with_transcript,
+			prove, prove_streaming, prove_with_transcript, verify, verify_with_transcript, Error,
+			SumcheckClaim, SumcheckProver,
+		},
 		test_utils::{
 			full_prove_with_switchover, full_verify, transform_poly, TestProductComposition,
 		},
 	},
-	witness::MultilinearWitnessIndex,
+	transcript::{ProverTranscript, VerifierTranscript},
+	witness::{MultilinearWitness, MultilinearWitnessIndex},
 };
+use assert_matches::assert_matches;
 use binius_field::{
-	BinaryField128b, BinaryField128bPolyval, BinaryField32b, ExtensionField, Field, PackedField,
-	TowerField,
+	packed::iter_packed_slice, BinaryField128b, BinaryField128bPolyval, BinaryField1b,
+	BinaryField32b, ExtensionField, Field, PackedBinaryField128x1b, PackedField, TowerField,
 };
 use binius_hash::GroestlHasher;
 use p3_util::log2_ceil_usize;
-use rand::{rngs::StdRng, SeedableRng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use rayon::current_num_threads;
-use std::iter::repeat_with;
+use std::{
+	iter::{self, repeat_with},
+	mem,
+};
 
 fn generate_poly_and_sum_helper<F, FE>(
 	rng: &mut StdRng,
@@ -135,6 +144,31 @@ fn test_prove_verify_interaction_helper(
 	let multilin_query = MultilinearQuery::with_full_query(eval_point).unwrap();
 	let actual = poly.evaluate(&multilin_query).unwrap();
 	assert_eq!(actual, final_verify_output.eval);
+
+	// The single instance prover and verifier follow the same transcript
+	let prove_output =
+		prove(&sumcheck_claim, poly.clone(), &domain, challenger.clone(), |_| switchover_rd)
+			.unwrap();
+	let verified_claim =
+		verify(&sumcheck_claim, prove_output.sumcheck_proof, challenger.clone()).unwrap();
+	assert_eq!(verified_claim.eval_point, final_verify_output.eval_point);
+	assert_eq!(verified_claim.eval, final_verify_output.eval);
+
+	// So do the transcript prover and verifier
+	let mut prover_transcript = ProverTranscript::new(challenger.clone());
+	let prover_claim =
+		prove_with_transcript(&sumcheck_claim, poly, &domain, &mut prover_transcript, |_| {
+			switchover_rd
+		})
+		.unwrap();
+	let proof = prover_transcript.finalize();
+
+	let mut verifier_transcript = VerifierTranscript::new(challenger, &proof).unwrap();
+	let verifier_claim = verify_with_transcript(&sumcheck_claim, &mut verifier_transcript).unwrap();
+	verifier_transcript.finalize().unwrap();
+	assert_eq!(prover_claim.eval_point, final_verify_output.eval_point);
+	assert_eq!(verifier_claim.eval_point, final_verify_output.eval_point);
+	assert_eq!(verifier_claim.eval, final_verify_output.eval);
 }
 
 fn test_prove_verify_interaction_with_monomial_basis_conversion_helper(
@@ -282,8 +316,15 @@ impl<F: Field> CompositionPoly<F> for SquareComposition {
 	}
 }
 
-#[test]
-fn test_prove_verify_batch() {
+#[allow(clippy::type_complexity)]
+fn make_batch_claims_and_witnesses() -> (
+	Vec<SumcheckClaim<BinaryField128b>>,
+	[MultilinearComposite<
+		BinaryField128b,
+		SquareComposition,
+		MultilinearWitness<'static, BinaryField128b>,
+	>; 3],
+) {
 	type F = BinaryField32b;
 	type FE = BinaryField128b;
 
@@ -363,6 +404,13 @@ fn test_prove_verify_batch() {
 		})
 		.collect::<Vec<_>>();
 
+	(sumcheck_claims, witnesses)
+}
+
+#[test]
+fn test_prove_verify_batch() {
+	let (sumcheck_claims, witnesses) = make_batch_claims_and_witnesses();
+
 	let domain = EvaluationDomain::new(3).unwrap();
 
 	let mut witness_iter = witnesses.into_iter();
@@ -398,3 +446,207 @@ fn test_prove_verify_batch() {
 	let _evalcheck_claims =
 		batch_verify(sumcheck_claims.iter().cloned(), proof, challenger.clone()).unwrap();
 }
+
+#[test]
+fn test_prove_verify_batch_with_transcript() {
+	let (sumcheck_claims, witnesses) = make_batch_claims_and_witnesses();
+
+	let domain = EvaluationDomain::new(3).unwrap();
+	let provers = iter::zip(sumcheck_claims.iter().cloned(), witnesses)
+		.map(|(claim, witness)| SumcheckProver::new(&domain, claim, witness, |_| 3).unwrap())
+		.collect::<Vec<_>>();
+
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+
+	let mut prover_transcript = ProverTranscript::new(challenger.clone());
+	let prover_evalcheck_claims =
+		batch_prove_with_transcript(provers, &mut prover_transcript).unwrap();
+	let proof = prover_transcript.finalize();
+
+	let mut verifier_transcript = VerifierTranscript::new(challenger.clone(), &proof).unwrap();
+	let verifier_evalcheck_claims =
+		batch_verify_with_transcript(sumcheck_claims.iter().cloned(), &mut verifier_transcript)
+			.unwrap();
+	verifier_transcript.finalize().unwrap();
+
+	assert_eq!(prover_evalcheck_claims.len(), verifier_evalcheck_claims.len());
+	for (prover_claim, verifier_claim) in
+		iter::zip(prover_evalcheck_claims, verifier_evalcheck_claims)
+	{
+		assert_eq!(prover_claim.eval_point, verifier_claim.eval_point);
+		assert_eq!(prover_claim.eval, verifier_claim.eval);
+	}
+
+	// A truncated proof stream is rejected
+	let mut verifier_transcript =
+		VerifierTranscript::new(challenger.clone(), &proof[..proof.len() - 1]).unwrap();
+	assert!(batch_verify_with_transcript(
+		sumcheck_claims.iter().cloned(),
+		&mut verifier_transcript
+	)
+	.is_err());
+}
+
+#[test]
+fn test_prove_streaming_matches_prove() {
+	type F = BinaryField32b;
+	type FE = BinaryField128b;
+	let n_vars = 8;
+	let n_multilinears = 3;
+	let mut rng = StdRng::seed_from_u64(0);
+
+	let (poly, sum) = generate_poly_and_sum_helper::<F, FE>(&mut rng, n_vars, n_multilinears);
+
+	let mut oracles = MultilinearOracleSet::new();
+	let batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 0,
+		n_vars,
+		n_polys: n_multilinears,
+		tower_level: F::TOWER_LEVEL,
+	});
+	let h = (0..n_multilinears)
+		.map(|i| oracles.committed_oracle(CommittedId { batch_id, index: i }))
+		.collect();
+	let composite_poly =
+		CompositePolyOracle::new(n_vars, h, TestProductComposition::new(n_multilinears)).unwrap();
+	let sumcheck_claim = SumcheckClaim {
+		sum: sum.into(),
+		poly: composite_poly,
+		zerocheck_challenges: None,
+	};
+
+	let domain = EvaluationDomain::new(n_multilinears + 1).unwrap();
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+
+	let prove_output =
+		prove(&sumcheck_claim, poly.clone(), &domain, challenger.clone(), |_| 1).unwrap();
+
+	// Folding all three multilinears in round 1 takes 3 * 2^7 scalars, while the budget of 64
+	// scalars forces the switchover to round 4, where they take 3 * 2^4 plus 2^4 for the query.
+	let memory_budget = 64 * mem::size_of::<FE>();
+	let streaming_output = prove_streaming(
+		&sumcheck_claim,
+		poly.clone(),
+		&domain,
+		challenger.clone(),
+		|_| 1,
+		memory_budget,
+	)
+	.unwrap();
+	assert_eq!(streaming_output.sumcheck_proof, prove_output.sumcheck_proof);
+	assert_eq!(streaming_output.evalcheck_claim.eval, prove_output.evalcheck_claim.eval);
+
+	verify(&sumcheck_claim, streaming_output.sumcheck_proof, challenger.clone()).unwrap();
+
+	assert_matches!(
+		prove_streaming(&sumcheck_claim, poly, &domain, challenger, |_| 1, 16),
+		Err(Error::MemoryBudgetExceeded { budget: 16, .. })
+	);
+}
+
+/// Proves a claim over three `BinaryField1b` multilinears, once stored as packed bits, which takes
+/// the bit-sliced first round, and once as unpacked scalars. Both must yield the same proof.
+fn test_bitsliced_first_round_helper(zerocheck: bool) {
+	type FE = BinaryField128b;
+	let n_vars = 10;
+	let mut rng = StdRng::seed_from_u64(0);
+
+	let mut columns = repeat_with(|| {
+		repeat_with(|| rng.gen::<u128>())
+			.take(1 << (n_vars - 7))
+			.collect::<Vec<_>>()
+	})
+	.take(3)
+	.collect::<Vec<_>>();
+	if zerocheck {
+		// The product vanishes on the hypercube
+		columns[2] = iter::zip(&columns[0], &columns[1])
+			.map(|(a, b)| !(a & b))
+			.collect();
+	}
+
+	let packed_multilins = columns
+		.iter()
+		.map(|words| {
+			let packed = words
+				.iter()
+				.map(|&word| PackedBinaryField128x1b::from(word))
+				.collect::<Vec<_>>();
+			MultilinearExtension::from_values(packed).unwrap()
+		})
+		.collect::<Vec<_>>();
+	let scalar_multilins = packed_multilins
+		.iter()
+		.map(|multilin| {
+			let values = iter_packed_slice(multilin.evals()).collect::<Vec<BinaryField1b>>();
+			MultilinearExtension::from_values(values).unwrap()
+		})
+		.collect::<Vec<_>>();
+
+	let mut oracles = MultilinearOracleSet::new();
+	let batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 0,
+		n_vars,
+		n_polys: 3,
+		tower_level: BinaryField1b::TOWER_LEVEL,
+	});
+	let inner = (0..3)
+		.map(|index| oracles.committed_oracle(CommittedId { batch_id, index }))
+		.collect();
+	let composition = TestProductComposition::new(3);
+	let poly = CompositePolyOracle::new(n_vars, inner, composition.clone()).unwrap();
+
+	let sum = (0..1 << n_vars)
+		.map(|i| {
+			scalar_multilins
+				.iter()
+				.map(|multilin| FE::from(multilin.evaluate_on_hypercube(i).unwrap()))
+				.product::<FE>()
+		})
+		.sum();
+	let zerocheck_challenges = zerocheck.then(|| {
+		repeat_with(|| <FE as Field>::random(&mut rng))
+			.take(n_vars - 1)
+			.collect()
+	});
+	let claim = SumcheckClaim {
+		poly,
+		sum,
+		zerocheck_challenges,
+	};
+
+	let domain = EvaluationDomain::new(4).unwrap();
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let prove_with = |multilins: Vec<MultilinearWitness<'static, FE>>| {
+		let witness = MultilinearComposite::new(n_vars, composition.clone(), multilins).unwrap();
+		prove(&claim, witness, &domain, challenger.clone(), |_| 3)
+			.unwrap()
+			.sumcheck_proof
+	};
+
+	let bitsliced_proof = prove_with(
+		packed_multilins
+			.into_iter()
+			.map(|multilin| multilin.specialize_arc_dyn())
+			.collect(),
+	);
+	let scalar_proof = prove_with(
+		scalar_multilins
+			.into_iter()
+			.map(|multilin| multilin.specialize_arc_dyn())
+			.collect(),
+	);
+	assert_eq!(bitsliced_proof, scalar_proof);
+
+	verify(&claim, bitsliced_proof, challenger).unwrap();
+}
+
+#[test]
+fn test_bitsliced_first_round_sumcheck() {
+	test_bitsliced_first_round_helper(false);
+}
+
+#[test]
+fn test_bitsliced_first_round_zerocheck() {
+	test_bitsliced_first_round_helper(true);
+}
@@ -4,11 +4,17 @@ use super::{
 	error::Error,
 	sumcheck::{
 		reduce_sumcheck_claim_final, reduce_sumcheck_claim_round, reduce_zerocheck_claim_round,
-		SumcheckClaim, SumcheckRound, SumcheckRoundClaim,
+		SumcheckClaim, SumcheckProof, SumcheckRound, SumcheckRoundClaim,
 	},
 	VerificationError,
 };
-use crate::{oracle::CompositePolyOracle, protocols::evalcheck::EvalcheckClaim};
+use crate::{
+	challenger::{CanObserve, CanSample},
+	oracle::CompositePolyOracle,
+	protocols::evalcheck::EvalcheckClaim,
+	serialization::DeserializeCanonical,
+	transcript::VerifierTranscript,
+};
 use binius_field::Field;
 
 /// Verifies a sumcheck round reduction proof.
@@ -110,3 +116,71 @@ pub fn setup_first_round_claim<F: Field>(claim: &SumcheckClaim<F>) -> SumcheckRo
 		current_round_sum: claim.sum,
 	}
 }
+
+/// Verify a sumcheck instance, reducing it to an evaluation claim at the sampled challenge point.
+///
+/// Counterpart of [`prove`](super::prove).
+pub fn verify<F, CH>(
+	claim: &SumcheckClaim<F>,
+	proof: SumcheckProof<F>,
+	mut challenger: CH,
+) -> Result<EvalcheckClaim<F>, Error>
+where
+	F: Field,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	if proof.rounds.len() != claim.n_vars() {
+		return Err(VerificationError::NumberOfRounds.into());
+	}
+
+	let mut rounds = proof.rounds.into_iter();
+	verify_rounds(claim, &mut challenger, |challenger| {
+		let round_proof = rounds.next().ok_or(VerificationError::NumberOfRounds)?;
+		challenger.observe_slice(&round_proof.coeffs);
+		Ok(round_proof)
+	})
+}
+
+/// Verify a sumcheck instance, reading the round messages from a transcript.
+///
+/// Counterpart of [`prove_with_transcript`](super::prove_with_transcript).
+pub fn verify_with_transcript<F, CH>(
+	claim: &SumcheckClaim<F>,
+	transcript: &mut VerifierTranscript<'_, CH>,
+) -> Result<EvalcheckClaim<F>, Error>
+where
+	F: Field + DeserializeCanonical,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	verify_rounds(claim, transcript, |transcript| {
+		let coeffs = transcript.read_scalars()?;
+		Ok(SumcheckRound { coeffs })
+	})
+}
+
+/// Runs the verifier side of all sumcheck rounds, obtaining each round message from
+/// `receive_round_proof` after it has been bound to the challenger state.
+fn verify_rounds<F, CH>(
+	claim: &SumcheckClaim<F>,
+	challenger: &mut CH,
+	mut receive_round_proof: impl FnMut(&mut CH) -> Result<SumcheckRound<F>, Error>,
+) -> Result<EvalcheckClaim<F>, Error>
+where
+	F: Field,
+	CH: CanSample<F>,
+{
+	let mut rd_claim = setup_first_round_claim(claim);
+	for round in 0..claim.n_vars() {
+		let round_proof = receive_round_proof(challenger)?;
+		let challenge = challenger.sample();
+		rd_claim = match &claim.zerocheck_challenges {
+			Some(zerocheck_challenges) => {
+				let alpha = round.checked_sub(1).map(|i| zerocheck_challenges[i]);
+				verify_zerocheck_round(rd_claim, challenge, round_proof, alpha)?
+			}
+			None => verify_round(rd_claim, challenge, round_proof)?,
+		};
+	}
+
+	verify_final(&claim.poly, rd_claim)
+}
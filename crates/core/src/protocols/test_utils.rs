@@ -2,29 +2,20 @@
 
 use crate::{
 	polynomial::{
-		CompositionPoly, Error as PolynomialError, EvaluationDomain, MultilinearExtension,
-		MultilinearPoly, MultivariatePoly,
+		BitslicedPoly, CompositionPoly, Error as PolynomialError, EvaluationDomain,
+		MultilinearExtension, MultilinearPoly, MultivariatePoly,
 	},
 	protocols::{
-		evalcheck::{
-			subclaims::{
-				non_same_query_pcs_sumcheck_claim, non_same_query_pcs_sumcheck_metas,
-				non_same_query_pcs_sumcheck_witness, BivariateSumcheck, MemoizedQueries,
-			},
-			CommittedEvalClaim, Error as EvalcheckError, EvalcheckClaim, EvalcheckProver,
-			EvalcheckVerifier,
-		},
+		evalcheck::EvalcheckClaim,
 		sumcheck::{
-			batch_prove, setup_first_round_claim, verify_final, verify_round,
-			verify_zerocheck_round, Error as SumcheckError, SumcheckBatchProof,
-			SumcheckBatchProveOutput, SumcheckClaim, SumcheckProof, SumcheckProveOutput,
-			SumcheckProver, SumcheckRoundClaim, SumcheckWitness,
+			setup_first_round_claim, verify_final, verify_round, verify_zerocheck_round,
+			SumcheckClaim, SumcheckProof, SumcheckProveOutput, SumcheckProver, SumcheckRoundClaim,
+			SumcheckWitness,
 		},
 	},
 };
-use binius_field::{packed::set_packed_slice, BinaryField1b, Field, PackedField, TowerField};
+use binius_field::{packed::set_packed_slice, BinaryField1b, Field, PackedField};
 use p3_challenger::{CanObserve, CanSample};
-use std::iter::Step;
 use tracing::instrument;
 
 // If the macro is not used in the same module, rustc thinks it is unused for some reason
@@ -128,6 +119,28 @@ where
 	fn binary_tower_level(&self) -> usize {
 		0
 	}
+
+	fn bitsliced_coeffs(&self) -> Option<Vec<F>> {
+		Some(vec![F::ONE])
+	}
+
+	fn evaluate_bitsliced(
+		&self,
+		query: &[BitslicedPoly],
+		dst: &mut Vec<BitslicedPoly>,
+	) -> Result<(), PolynomialError> {
+		if query.len() != self.arity {
+			return Err(PolynomialError::IncorrectQuerySize {
+				expected: self.arity,
+			});
+		}
+		dst.push(
+			query
+				.iter()
+				.fold(BitslicedPoly::ONE, |product, &value| product * value),
+		);
+		Ok(())
+	}
 }
 
 pub fn transform_poly<F, OF>(
@@ -247,91 +260,3 @@ where
 	let prover_state = SumcheckProver::new(domain, claim.clone(), witness, switchover_fn).unwrap();
 	full_prove_with_switchover_impl(n_vars, prover_state, challenger)
 }
-
-// #[instrument(
-// 	skip_all,
-// 	name = "test_utils::prove_bivariate_sumchecks_with_switchover"
-// )]
-pub fn prove_bivariate_sumchecks_with_switchover<'a, F, PW, CH>(
-	sumchecks: impl IntoIterator<Item = BivariateSumcheck<'a, F, PW>>,
-	challenger: &mut CH,
-	switchover_fn: impl Fn(usize) -> usize + Clone,
-) -> Result<(SumcheckBatchProof<F>, impl IntoIterator<Item = EvalcheckClaim<F>>), SumcheckError>
-where
-	F: Field + Step + From<PW::Scalar>,
-	PW: PackedField,
-	PW::Scalar: From<F>,
-	CH: CanObserve<F> + CanSample<F>,
-{
-	let bivariate_domain = EvaluationDomain::new_isomorphic::<F>(3)?;
-
-	let (claims, witnesses) = sumchecks.into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
-
-	let prover_states = witnesses
-		.into_iter()
-		.zip(&claims)
-		.map(|(witness, claim)| {
-			SumcheckProver::new(&bivariate_domain, claim.clone(), witness, switchover_fn.clone())
-		})
-		.collect::<Result<Vec<_>, _>>()?;
-
-	let SumcheckBatchProveOutput {
-		proof,
-		evalcheck_claims,
-	} = batch_prove(prover_states, challenger)?;
-
-	Ok((proof, evalcheck_claims))
-}
-
-// #[instrument(skip_all, name = "test_utils::make_non_same_query_pcs_sumcheck_claims")]
-pub fn make_non_same_query_pcs_sumcheck_claims<'a, F: TowerField>(
-	verifier: &mut EvalcheckVerifier<'a, F>,
-	committed_eval_claims: &[CommittedEvalClaim<F>],
-) -> Result<Vec<SumcheckClaim<F>>, EvalcheckError> {
-	let metas = non_same_query_pcs_sumcheck_metas(
-		verifier.oracles,
-		committed_eval_claims,
-		&mut verifier.batch_committed_eval_claims,
-	)?;
-
-	let claims = metas
-		.into_iter()
-		.map(|meta| non_same_query_pcs_sumcheck_claim(verifier.oracles, meta))
-		.collect::<Result<Vec<_>, EvalcheckError>>()?;
-
-	Ok(claims)
-}
-
-// #[instrument(skip_all, name = "test_utils::make_non_same_query_pcs_sumchecks")]
-pub fn make_non_same_query_pcs_sumchecks<'a, 'b, F, PW>(
-	prover: &mut EvalcheckProver<'a, 'b, F, PW>,
-	committed_eval_claims: &[CommittedEvalClaim<F>],
-) -> Result<Vec<BivariateSumcheck<'a, F, PW>>, EvalcheckError>
-where
-	F: TowerField + From<PW::Scalar>,
-	PW: PackedField,
-	PW::Scalar: From<F>,
-{
-	let metas = non_same_query_pcs_sumcheck_metas(
-		prover.oracles,
-		committed_eval_claims,
-		&mut prover.batch_committed_eval_claims,
-	)?;
-
-	let mut memoized_queries = MemoizedQueries::new();
-
-	let sumchecks = metas
-		.into_iter()
-		.map(|meta| {
-			let claim = non_same_query_pcs_sumcheck_claim(prover.oracles, meta.clone())?;
-			let witness = non_same_query_pcs_sumcheck_witness(
-				prover.witness_index,
-				&mut memoized_queries,
-				meta,
-			)?;
-			Ok((claim, witness))
-		})
-		.collect::<Result<Vec<_>, EvalcheckError>>()?;
-
-	Ok(sumchecks)
-}
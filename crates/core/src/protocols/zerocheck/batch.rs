@@ -0,0 +1,89 @@
+// Copyright 2024 Ulvetanna Inc.
+
+//! Batch reduction of zerocheck claims over polynomials with different numbers of variables.
+//!
+//! The verifier samples a single vector of zerocheck challenges, sized for the claim with the most
+//! variables, and each claim over n variables is reduced using the last n - 1 of them. The reduced
+//! sumcheck claims can then be proven together with [`sumcheck::batch_prove`], which front-loads
+//! the claims with the most variables and shares the later round challenges among all of them.
+//!
+//! [`sumcheck::batch_prove`]: crate::protocols::sumcheck::batch_prove
+
+use super::{
+	error::{Error, VerificationError},
+	prove::prove,
+	verify::verify,
+	zerocheck::{ZerocheckClaim, ZerocheckProof, ZerocheckProveOutput, ZerocheckWitness},
+};
+use crate::{polynomial::CompositionPoly, protocols::sumcheck::SumcheckClaim};
+use binius_field::{PackedField, TowerField};
+
+/// Returns the number of zerocheck challenges to sample for a batch of claims.
+pub fn batch_n_challenges<'b, F: TowerField>(
+	claims: impl IntoIterator<Item = &'b ZerocheckClaim<F>>,
+) -> usize {
+	claims
+		.into_iter()
+		.map(|claim| claim.poly.n_vars())
+		.max()
+		.unwrap_or(0)
+		.saturating_sub(1)
+}
+
+/// Prove a batched zerocheck instance reduction.
+///
+/// The outputs are in the same order as the inputs. See module documentation for details.
+pub fn batch_prove<'a, F, PW, CW>(
+	claims_and_witnesses: impl IntoIterator<Item = (ZerocheckClaim<F>, ZerocheckWitness<'a, PW, CW>)>,
+	challenge: Vec<F>,
+) -> Result<Vec<ZerocheckProveOutput<'a, F, PW, CW>>, Error>
+where
+	F: TowerField + From<PW::Scalar>,
+	PW: PackedField,
+	PW::Scalar: TowerField + From<F>,
+	CW: CompositionPoly<PW>,
+{
+	let claims_and_witnesses = claims_and_witnesses.into_iter().collect::<Vec<_>>();
+	if challenge.len() != batch_n_challenges(claims_and_witnesses.iter().map(|(claim, _)| claim)) {
+		return Err(Error::ChallengeVectorMismatch);
+	}
+
+	claims_and_witnesses
+		.into_iter()
+		.map(|(claim, witness)| {
+			let challenge = challenge_suffix(&challenge, claim.poly.n_vars())?;
+			prove(&claim, witness, challenge)
+		})
+		.collect()
+}
+
+/// Verify a batched zerocheck instance reduction.
+///
+/// The reduced sumcheck claims are in the same order as the input claims.
+pub fn batch_verify<'b, F: TowerField>(
+	claims: impl IntoIterator<Item = &'b ZerocheckClaim<F>>,
+	challenge: Vec<F>,
+) -> Result<Vec<SumcheckClaim<F>>, VerificationError> {
+	let claims = claims.into_iter().collect::<Vec<_>>();
+	if challenge.len() != batch_n_challenges(claims.iter().copied()) {
+		return Err(VerificationError::ChallengeVectorMismatch);
+	}
+
+	claims
+		.into_iter()
+		.map(|claim| {
+			let challenge = challenge_suffix(&challenge, claim.poly.n_vars())?;
+			verify(claim, ZerocheckProof, challenge)
+		})
+		.collect()
+}
+
+fn challenge_suffix<F: TowerField>(
+	challenge: &[F],
+	n_vars: usize,
+) -> Result<Vec<F>, VerificationError> {
+	let n_challenges = n_vars
+		.checked_sub(1)
+		.ok_or(VerificationError::ChallengeVectorMismatch)?;
+	Ok(challenge[challenge.len() - n_challenges..].to_vec())
+}
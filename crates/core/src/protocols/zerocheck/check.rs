@@ -0,0 +1,207 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{error::Error, zerocheck::ZerocheckClaim};
+use crate::{
+	oracle::OracleId,
+	polynomial::{
+		composition::{HornerCompositions, MixComposition},
+		CompositionPoly, Error as PolynomialError,
+	},
+	witness::MultilinearWitnessIndex,
+};
+use binius_field::{Field, PackedField, TowerField};
+use rayon::prelude::*;
+use std::fmt;
+
+/// The maximum number of failing rows recorded for each unsatisfied constraint.
+pub const MAX_REPORTED_ROWS: usize = 16;
+
+const CHECK_CHUNK_LOG_SIZE: usize = 12;
+
+/// A hypercube row on which a constraint does not vanish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailingRow<F: Field> {
+	/// Index of the row in the hypercube, in lexicographic order
+	pub row: usize,
+	/// Value of the constraint polynomial on the row
+	pub eval: F,
+	/// Values of the constrained columns on the row
+	pub values: Vec<(OracleId, F)>,
+}
+
+/// A constraint that does not vanish on every row of the hypercube.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsatisfiedConstraint<F: Field> {
+	/// Index of the constraint, e.g. its position in a mix composition
+	pub index: usize,
+	pub name: Option<String>,
+	/// Total number of failing rows
+	pub n_failing_rows: usize,
+	/// The first [`MAX_REPORTED_ROWS`] failing rows
+	pub rows: Vec<FailingRow<F>>,
+}
+
+impl<F: Field> fmt::Display for UnsatisfiedConstraint<F> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.name {
+			Some(name) => write!(f, "constraint {} ({name})", self.index)?,
+			None => write!(f, "constraint {}", self.index)?,
+		}
+		writeln!(f, " fails on {} rows", self.n_failing_rows)?;
+		for row in self.rows.iter() {
+			write!(f, "  row {}: eval = {:?};", row.row, row.eval)?;
+			for (id, value) in row.values.iter() {
+				write!(f, " oracle {id} = {value:?}")?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
+
+/// Checks that the composition of a zerocheck claim vanishes on every row of the witness.
+///
+/// This evaluates the claim on the whole hypercube and is meant for debugging, before running
+/// the zerocheck prover. Returns an empty list if the claim is satisfied.
+pub fn check_zerocheck_claim<F, PW>(
+	claim: &ZerocheckClaim<F>,
+	witness_index: &MultilinearWitnessIndex<PW>,
+) -> Result<Vec<UnsatisfiedConstraint<F>>, Error>
+where
+	F: TowerField + From<PW::Scalar>,
+	PW: PackedField,
+{
+	let oracle_ids = claim
+		.poly
+		.inner_polys()
+		.iter()
+		.map(|oracle| oracle.id())
+		.collect::<Vec<_>>();
+	let composition = claim.poly.composition();
+	check_compositions(
+		claim.poly.n_vars(),
+		&oracle_ids,
+		&[(None, composition.as_ref())],
+		witness_index,
+	)
+}
+
+/// Checks each composition of a mix separately on every row of the witness.
+///
+/// `oracle_ids` are the oracles whose witnesses form the query of the mix composition.
+/// Unsatisfied constraints are identified by the order in which they were included in the mix.
+pub fn check_mix_composition<F, PW, IC>(
+	n_vars: usize,
+	oracle_ids: &[OracleId],
+	mix: &MixComposition<F, IC>,
+	witness_index: &MultilinearWitnessIndex<PW>,
+) -> Result<Vec<UnsatisfiedConstraint<F>>, Error>
+where
+	F: TowerField + From<PW::Scalar>,
+	PW: PackedField,
+	IC: HornerCompositions<F> + Sync,
+{
+	check_rows(n_vars, oracle_ids, witness_index, |_| None, |query| mix.evaluate_inner(query))
+}
+
+/// Checks a list of optionally named compositions on every row of the witness.
+pub fn check_compositions<F, PW>(
+	n_vars: usize,
+	oracle_ids: &[OracleId],
+	compositions: &[(Option<&str>, &dyn CompositionPoly<F>)],
+	witness_index: &MultilinearWitnessIndex<PW>,
+) -> Result<Vec<UnsatisfiedConstraint<F>>, Error>
+where
+	F: TowerField + From<PW::Scalar>,
+	PW: PackedField,
+{
+	check_rows(
+		n_vars,
+		oracle_ids,
+		witness_index,
+		|index| compositions[index].0.map(str::to_string),
+		|query| {
+			compositions
+				.iter()
+				.map(|(_, composition)| composition.evaluate(query))
+				.collect()
+		},
+	)
+}
+
+fn check_rows<F, PW>(
+	n_vars: usize,
+	oracle_ids: &[OracleId],
+	witness_index: &MultilinearWitnessIndex<PW>,
+	name: impl Fn(usize) -> Option<String>,
+	evaluate: impl Fn(&[F]) -> Result<Vec<F>, PolynomialError> + Sync,
+) -> Result<Vec<UnsatisfiedConstraint<F>>, Error>
+where
+	F: TowerField + From<PW::Scalar>,
+	PW: PackedField,
+{
+	let witnesses = oracle_ids
+		.iter()
+		.map(|&id| witness_index.get(id).ok_or(Error::MissingWitness(id)))
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let mut unsatisfied = Vec::<UnsatisfiedConstraint<F>>::new();
+
+	// Rows are checked in chunks so that memory is bounded when many rows fail
+	let chunk_size = 1 << CHECK_CHUNK_LOG_SIZE.min(n_vars);
+	for chunk_start in (0..1 << n_vars).step_by(chunk_size) {
+		let failing = (chunk_start..chunk_start + chunk_size)
+			.into_par_iter()
+			.map(|row| {
+				let query = witnesses
+					.iter()
+					.map(|witness| Ok(F::from(witness.evaluate_on_hypercube(row)?)))
+					.collect::<Result<Vec<_>, PolynomialError>>()?;
+				let evals = evaluate(&query)?;
+				let failed = evals.iter().any(|&eval| eval != F::ZERO);
+				Ok(failed.then_some((row, query, evals)))
+			})
+			.filter_map(Result::transpose)
+			.collect::<Result<Vec<_>, PolynomialError>>()?;
+
+		for (row, query, evals) in failing {
+			for (index, eval) in evals.into_iter().enumerate() {
+				if eval == F::ZERO {
+					continue;
+				}
+
+				let position = match unsatisfied
+					.iter()
+					.position(|constraint| constraint.index == index)
+				{
+					Some(position) => position,
+					None => {
+						unsatisfied.push(UnsatisfiedConstraint {
+							index,
+							name: name(index),
+							n_failing_rows: 0,
+							rows: Vec::new(),
+						});
+						unsatisfied.len() - 1
+					}
+				};
+				let constraint = &mut unsatisfied[position];
+				constraint.n_failing_rows += 1;
+				if constraint.rows.len() < MAX_REPORTED_ROWS {
+					constraint.rows.push(FailingRow {
+						row,
+						eval,
+						values: oracle_ids
+							.iter()
+							.copied()
+							.zip(query.iter().copied())
+							.collect(),
+					});
+				}
+			}
+		}
+	}
+
+	unsatisfied.sort_by_key(|constraint| constraint.index);
+	Ok(unsatisfied)
+}
@@ -1,6 +1,10 @@
 // Copyright 2023 Ulvetanna Inc.
 
-use crate::{oracle::Error as IOPolynomialError, polynomial::Error as PolynomialError};
+use crate::{
+	oracle::{Error as IOPolynomialError, OracleId},
+	polynomial::Error as PolynomialError,
+	protocols::sumcheck::Error as SumcheckError,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -12,6 +16,16 @@ pub enum Error {
 	Polynomial(#[from] PolynomialError),
 	#[error("verification failure: {0}")]
 	Verification(#[from] VerificationError),
+	#[error("no witness is available for oracle {0}")]
+	MissingWitness(OracleId),
+	#[error("the number of skipped rounds must be positive and less than the number of variables")]
+	InvalidSkipRounds,
+	#[error("the witness multilinears do not match the claim")]
+	ClaimWitnessMismatch,
+	#[error("sumcheck error: {0}")]
+	Sumcheck(#[from] SumcheckError),
+	#[error("NTT error: {0}")]
+	NTT(#[from] binius_ntt::Error),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -20,6 +34,12 @@ pub enum VerificationError {
 	ChallengeVectorMismatch,
 	#[error("IOPolynomial error: {0}")]
 	IOPolynomial(#[from] IOPolynomialError),
+	#[error("incorrect number of univariate skip round evaluations")]
+	NumberOfSkipRoundEvals,
+	#[error("incorrect number of partial evaluations over the skipped variables")]
+	NumberOfPartialEvals,
+	#[error("the partial evaluations do not match the final sumcheck evaluation")]
+	IncorrectEvaluation,
 	#[error("polynomial error: {0}")]
 	Polynomial(#[from] PolynomialError),
 }
@@ -1,14 +1,27 @@
 // Copyright 2023-2024 Ulvetanna Inc.
 
+mod batch;
+mod check;
 mod error;
 mod prove;
 #[cfg(test)]
 mod tests;
+mod univariate;
 mod verify;
 #[allow(clippy::module_inception)]
 mod zerocheck;
 
+pub use batch::{batch_n_challenges, batch_prove, batch_verify};
+pub use check::{
+	check_compositions, check_mix_composition, check_zerocheck_claim, FailingRow,
+	UnsatisfiedConstraint, MAX_REPORTED_ROWS,
+};
 pub use error::*;
-pub use prove::{prove, ZerocheckFirstRoundEvaluator, ZerocheckLaterRoundEvaluator};
-pub use verify::verify;
+pub use prove::{
+	prove, prove_with_transcript, ZerocheckFirstRoundEvaluator, ZerocheckLaterRoundEvaluator,
+};
+pub use univariate::{
+	prove_univariate_skip, verify_univariate_skip, UnivariateSkipProof, UnivariateSkipProveOutput,
+};
+pub use verify::{verify, verify_with_transcript};
 pub use zerocheck::{ZerocheckClaim, ZerocheckProof, ZerocheckProveOutput, ZerocheckWitness};
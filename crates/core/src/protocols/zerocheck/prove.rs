@@ -5,10 +5,13 @@ use super::{
 	zerocheck::{ZerocheckClaim, ZerocheckProof, ZerocheckProveOutput, ZerocheckWitness},
 };
 use crate::{
+	challenger::CanSample,
 	polynomial::{extrapolate_line, CompositionPoly, MultilinearExtension},
 	protocols::{
-		sumcheck::prove_general::SumcheckEvaluator, zerocheck::zerocheck::reduce_zerocheck_claim,
+		sumcheck::prove_general::{BitslicedEvaluator, SumcheckEvaluator},
+		zerocheck::zerocheck::reduce_zerocheck_claim,
 	},
+	transcript::ProverTranscript,
 };
 use binius_field::{Field, PackedField, TowerField};
 use tracing::instrument;
@@ -42,6 +45,26 @@ where
 	})
 }
 
+/// Prove a zerocheck instance reduction, sampling the zerocheck challenges from a transcript.
+///
+/// The zerocheck reduction has no prover messages, so nothing is written to the transcript.
+pub fn prove_with_transcript<'a, F, PW, CW, CH>(
+	zerocheck_claim: &ZerocheckClaim<F>,
+	zerocheck_witness: ZerocheckWitness<'a, PW, CW>,
+	transcript: &mut ProverTranscript<CH>,
+) -> Result<ZerocheckProveOutput<'a, F, PW, CW>, Error>
+where
+	F: TowerField + From<PW::Scalar>,
+	PW: PackedField,
+	PW::Scalar: TowerField + From<F>,
+	CW: CompositionPoly<PW>,
+	CH: CanSample<F>,
+{
+	let n_challenges = zerocheck_witness.n_vars().saturating_sub(1);
+	let challenge = transcript.sample_vec(n_challenges);
+	prove(zerocheck_claim, zerocheck_witness, challenge)
+}
+
 /// Evaluator for the first round of the zerocheck protocol.
 ///
 /// In the first round, we do not need to evaluate at the point F::ONE, because the value is known
@@ -103,6 +126,14 @@ where
 			round_evals[d - 2] += composite_value * eq_ind_factor;
 		}
 	}
+
+	fn bitsliced(&self) -> Option<BitslicedEvaluator<'_, F>> {
+		BitslicedEvaluator::new::<P, _>(
+			self.composition,
+			&self.domain[2..],
+			Some(self.eq_ind.evals()),
+		)
+	}
 }
 
 /// Evaluator for the later rounds of the zerocheck protocol.
@@ -1,20 +1,34 @@
 // Copyright 2024 Ulvetanna Inc.
 
 use crate::{
-	challenger::HashChallenger,
+	challenger::{CanObserve, CanSample, HashChallenger},
 	oracle::{CommittedBatchSpec, CommittedId, CompositePolyOracle, MultilinearOracleSet},
-	polynomial::{EvaluationDomain, MultilinearComposite, MultilinearExtension, MultilinearQuery},
+	polynomial::{
+		composition::{empty_mix_composition, index_composition, BivariateProduct},
+		EvaluationDomain, MultilinearComposite, MultilinearExtension, MultilinearQuery,
+	},
 	protocols::{
+		evalcheck::EvalcheckClaim,
+		sumcheck::{self, Error as SumcheckError, SumcheckProver},
 		test_utils::{full_prove_with_switchover, full_verify, TestProductComposition},
-		zerocheck::{prove, verify, ZerocheckClaim, ZerocheckProveOutput},
+		zerocheck::{
+			batch_n_challenges, batch_prove, batch_verify, check_mix_composition,
+			check_zerocheck_claim, prove, prove_univariate_skip, verify, verify_univariate_skip,
+			Error, UnivariateSkipProveOutput, VerificationError, ZerocheckClaim,
+			ZerocheckProveOutput, MAX_REPORTED_ROWS,
+		},
 	},
+	witness::MultilinearWitnessIndex,
+};
+use assert_matches::assert_matches;
+use binius_field::{
+	BinaryField128b, BinaryField1b, BinaryField32b, BinaryField8b, Field, TowerField,
 };
-use binius_field::{BinaryField128b, BinaryField32b, Field, TowerField};
 use binius_hash::GroestlHasher;
 use p3_util::log2_ceil_usize;
-use rand::{rngs::StdRng, SeedableRng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use rayon::current_num_threads;
-use std::iter::repeat_with;
+use std::{iter, iter::repeat_with};
 
 fn generate_poly_helper<F>(
 	rng: &mut StdRng,
@@ -143,6 +157,17 @@ fn test_prove_verify_interaction_helper(
 	let multilin_query = MultilinearQuery::with_full_query(eval_point).unwrap();
 	let actual = zc_witness.evaluate(&multilin_query).unwrap();
 	assert_eq!(actual, final_verify_output.eval);
+
+	// The single instance sumcheck prover and verifier support claims reduced from zerocheck
+	let prove_output =
+		sumcheck::prove(&sumcheck_claim, zc_witness, &domain, challenger.clone(), |_| {
+			switchover_rd
+		})
+		.unwrap();
+	let verified_claim =
+		sumcheck::verify(&sumcheck_claim, prove_output.sumcheck_proof, challenger).unwrap();
+	assert_eq!(verified_claim.eval_point, final_verify_output.eval_point);
+	assert_eq!(verified_claim.eval, actual);
 }
 
 #[test]
@@ -166,3 +191,381 @@ fn test_zerocheck_prove_verify_interaction_pigeonhole_cores() {
 		}
 	}
 }
+
+#[test]
+fn test_check_reports_failing_rows() {
+	type F = BinaryField32b;
+	type FE = BinaryField128b;
+	let mut rng = StdRng::seed_from_u64(0);
+	let n_vars = 5;
+	let n_multilinears = 3;
+
+	let mut multilins = generate_poly_helper::<F>(&mut rng, n_vars, n_multilinears)
+		.into_iter()
+		.map(|m| m.evals().to_vec())
+		.collect::<Vec<_>>();
+
+	let mut oracles = MultilinearOracleSet::<FE>::new();
+	let batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 0,
+		n_vars,
+		n_polys: n_multilinears,
+		tower_level: F::TOWER_LEVEL,
+	});
+	let h = (0..n_multilinears)
+		.map(|i| oracles.committed_oracle(CommittedId { batch_id, index: i }))
+		.collect::<Vec<_>>();
+	let oracle_ids = h.iter().map(|oracle| oracle.id()).collect::<Vec<_>>();
+
+	let witness_index = |multilins: &[Vec<F>]| {
+		let mut witness_index = MultilinearWitnessIndex::<F>::new();
+		for (&id, values) in oracle_ids.iter().zip(multilins) {
+			witness_index.set(
+				id,
+				MultilinearExtension::from_values(values.clone())
+					.unwrap()
+					.specialize_arc_dyn(),
+			);
+		}
+		witness_index
+	};
+
+	let zc_claim = ZerocheckClaim {
+		poly: CompositePolyOracle::new(
+			n_vars,
+			h.clone(),
+			TestProductComposition::new(n_multilinears),
+		)
+		.unwrap(),
+	};
+	assert!(check_zerocheck_claim(&zc_claim, &witness_index(&multilins))
+		.unwrap()
+		.is_empty());
+
+	// The product of the first two multilinears only vanishes where one of them is zero
+	let mix = empty_mix_composition::<FE>(n_multilinears, Field::random(&mut rng))
+		.include([TestProductComposition::new(n_multilinears)])
+		.unwrap()
+		.include([
+			index_composition(&oracle_ids, [oracle_ids[0], oracle_ids[1]], BivariateProduct)
+				.unwrap(),
+		])
+		.unwrap();
+	let failing_rows = (0..1 << n_vars)
+		.filter(|&i| multilins[0][i] * multilins[1][i] != F::ZERO)
+		.collect::<Vec<_>>();
+	let unsatisfied =
+		check_mix_composition(n_vars, &oracle_ids, &mix, &witness_index(&multilins)).unwrap();
+	assert_eq!(unsatisfied.len(), 1);
+	assert_eq!(unsatisfied[0].index, 1);
+	assert_eq!(unsatisfied[0].name, None);
+	assert_eq!(unsatisfied[0].n_failing_rows, failing_rows.len());
+	assert_eq!(
+		unsatisfied[0]
+			.rows
+			.iter()
+			.map(|row| row.row)
+			.collect::<Vec<_>>(),
+		failing_rows[..failing_rows.len().min(MAX_REPORTED_ROWS)]
+	);
+
+	// Break the claim on the first row, where only the first multilinear is zero
+	multilins[0][0] = F::ONE;
+	let unsatisfied = check_zerocheck_claim(&zc_claim, &witness_index(&multilins)).unwrap();
+	assert_eq!(unsatisfied.len(), 1);
+	assert_eq!(unsatisfied[0].n_failing_rows, 1);
+	let row = &unsatisfied[0].rows[0];
+	assert_eq!(row.row, 0);
+	assert_eq!(row.eval, FE::from(multilins[1][0] * multilins[2][0]));
+	assert_eq!(
+		row.values,
+		oracle_ids
+			.iter()
+			.zip(multilins.iter())
+			.map(|(&id, values)| (id, FE::from(values[0])))
+			.collect::<Vec<_>>()
+	);
+
+	let empty_index = MultilinearWitnessIndex::<F>::new();
+	assert_matches!(
+		check_zerocheck_claim(&zc_claim, &empty_index),
+		Err(Error::MissingWitness(id)) if id == oracle_ids[0]
+	);
+}
+
+#[test]
+fn test_prove_verify_batch_different_n_vars() {
+	type F = BinaryField32b;
+	type FE = BinaryField128b;
+	let mut rng = StdRng::seed_from_u64(0);
+	let n_multilinears = 3;
+
+	let mut oracles = MultilinearOracleSet::<FE>::new();
+	let claims_and_witnesses = [6, 4, 6, 3]
+		.into_iter()
+		.map(|n_vars| {
+			let multilins = generate_poly_helper::<F>(&mut rng, n_vars, n_multilinears)
+				.into_iter()
+				.map(|m| m.specialize_arc_dyn())
+				.collect();
+			let witness = MultilinearComposite::<FE, _, _>::new(
+				n_vars,
+				TestProductComposition::new(n_multilinears),
+				multilins,
+			)
+			.unwrap();
+
+			let batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+				round_id: 0,
+				n_vars,
+				n_polys: n_multilinears,
+				tower_level: F::TOWER_LEVEL,
+			});
+			let h = (0..n_multilinears)
+				.map(|i| oracles.committed_oracle(CommittedId { batch_id, index: i }))
+				.collect();
+			let claim = ZerocheckClaim {
+				poly: CompositePolyOracle::new(
+					n_vars,
+					h,
+					TestProductComposition::new(n_multilinears),
+				)
+				.unwrap(),
+			};
+			(claim, witness)
+		})
+		.collect::<Vec<_>>();
+	let (claims, witnesses): (Vec<_>, Vec<_>) = claims_and_witnesses.iter().cloned().unzip();
+
+	let challenge = repeat_with(|| <FE as Field>::random(&mut rng))
+		.take(batch_n_challenges(&claims))
+		.collect::<Vec<_>>();
+	assert_eq!(challenge.len(), 5);
+	assert_matches!(
+		batch_prove(claims_and_witnesses.clone(), challenge[1..].to_vec()),
+		Err(Error::ChallengeVectorMismatch)
+	);
+
+	let prove_outputs = batch_prove(claims_and_witnesses, challenge.clone()).unwrap();
+	let sumcheck_claims = batch_verify(&claims, challenge.clone()).unwrap();
+	for (prove_output, sumcheck_claim) in prove_outputs.iter().zip(&sumcheck_claims) {
+		let n_vars = sumcheck_claim.n_vars();
+		let expected_challenges = challenge[challenge.len() + 1 - n_vars..].to_vec();
+		assert_eq!(sumcheck_claim.zerocheck_challenges.as_ref(), Some(&expected_challenges));
+		assert_eq!(
+			prove_output.sumcheck_claim.zerocheck_challenges,
+			sumcheck_claim.zerocheck_challenges
+		);
+	}
+
+	// All reduced sumcheck claims are proven in a single batch
+	let domain = EvaluationDomain::new(n_multilinears + 1).unwrap();
+	let provers = prove_outputs.into_iter().map(|output| {
+		SumcheckProver::new(&domain, output.sumcheck_claim, output.sumcheck_witness, |_| 1).unwrap()
+	});
+	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let proof = sumcheck::batch_prove(provers, challenger.clone())
+		.unwrap()
+		.proof;
+	assert_eq!(proof.rounds.len(), 6);
+
+	// Claims that do not share the zerocheck challenges cannot be batched
+	let mut unshared_claims = sumcheck_claims.clone();
+	unshared_claims[1].zerocheck_challenges = Some(challenge[..3].to_vec());
+	assert_matches!(
+		sumcheck::batch_verify(unshared_claims, proof.clone(), challenger.clone()),
+		Err(SumcheckError::ZerocheckChallengesMismatch)
+	);
+
+	let evalcheck_claims = sumcheck::batch_verify(sumcheck_claims, proof, challenger).unwrap();
+	assert_eq!(evalcheck_claims.len(), claims.len());
+	for evalcheck_claim in evalcheck_claims {
+		let (_, witness) = claims
+			.iter()
+			.zip(&witnesses)
+			.find(|(claim, _)| claim.poly.inner_polys() == evalcheck_claim.poly.inner_polys())
+			.unwrap();
+		let query = MultilinearQuery::with_full_query(&evalcheck_claim.eval_point).unwrap();
+		assert_eq!(witness.evaluate(&query).unwrap(), evalcheck_claim.eval);
+	}
+}
+
+/// A zerocheck claim on the product of 1-bit multilinears, which vanishes everywhere except on
+/// the rows in `bad_rows`, along with its witness multilinears and zerocheck challenges.
+fn univariate_skip_instance(
+	n_vars: usize,
+	bad_rows: &[usize],
+) -> (
+	ZerocheckClaim<BinaryField128b>,
+	Vec<MultilinearExtension<'static, BinaryField1b>>,
+	Vec<BinaryField128b>,
+) {
+	type FE = BinaryField128b;
+	let mut rng = StdRng::seed_from_u64(0);
+	let n_multilinears = 3;
+
+	let multilins = (0..n_multilinears)
+		.map(|j| {
+			let values = (0..1 << n_vars)
+				.map(|i| {
+					if bad_rows.contains(&i) {
+						BinaryField1b::ONE
+					} else if i % n_multilinears == j || rng.gen_bool(0.5) {
+						BinaryField1b::ZERO
+					} else {
+						BinaryField1b::ONE
+					}
+				})
+				.collect::<Vec<_>>();
+			MultilinearExtension::from_values(values).unwrap()
+		})
+		.collect::<Vec<_>>();
+
+	let mut oracles = MultilinearOracleSet::<FE>::new();
+	let batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 0,
+		n_vars,
+		n_polys: n_multilinears,
+		tower_level: BinaryField1b::TOWER_LEVEL,
+	});
+	let h = (0..n_multilinears)
+		.map(|i| oracles.committed_oracle(CommittedId { batch_id, index: i }))
+		.collect();
+	let zc_claim = ZerocheckClaim {
+		poly: CompositePolyOracle::new(n_vars, h, TestProductComposition::new(n_multilinears))
+			.unwrap(),
+	};
+
+	let zc_challenges = repeat_with(|| <FE as Field>::random(&mut rng))
+		.take(n_vars - 1)
+		.collect::<Vec<_>>();
+	(zc_claim, multilins, zc_challenges)
+}
+
+fn prove_univariate_skip_instance(
+	zc_claim: &ZerocheckClaim<BinaryField128b>,
+	multilins: &[MultilinearExtension<'static, BinaryField1b>],
+	skip_rounds: usize,
+	zc_challenges: Vec<BinaryField128b>,
+) -> Result<UnivariateSkipProveOutput<BinaryField128b>, Error> {
+	let n_multilinears = multilins.len();
+	let domain = EvaluationDomain::new(n_multilinears + 2).unwrap();
+	prove_univariate_skip::<_, BinaryField8b, _, _, _>(
+		zc_claim,
+		&TestProductComposition::new(n_multilinears),
+		multilins,
+		skip_rounds,
+		zc_challenges,
+		&domain,
+		<HashChallenger<_, GroestlHasher<_>>>::new(),
+		|_| 1,
+	)
+}
+
+/// Checks that the evaluation claims of a univariate skip hold for the witness multilinears.
+fn univariate_skip_claims_hold(
+	evalcheck_claims: &[EvalcheckClaim<BinaryField128b>],
+	multilins: &[MultilinearExtension<'static, BinaryField1b>],
+) -> bool {
+	evalcheck_claims.len() == multilins.len()
+		&& iter::zip(evalcheck_claims, multilins).all(|(evalcheck_claim, multilin)| {
+			let query =
+				MultilinearQuery::<BinaryField128b>::with_full_query(&evalcheck_claim.eval_point)
+					.unwrap();
+			multilin.evaluate(&query).unwrap() == evalcheck_claim.eval
+		})
+}
+
+/// Runs the univariate skip zerocheck on 1-bit multilinears whose product vanishes everywhere,
+/// except on the rows in `bad_rows`.
+fn run_univariate_skip(n_vars: usize, skip_rounds: usize, bad_rows: &[usize]) -> Result<(), Error> {
+	let (zc_claim, multilins, zc_challenges) = univariate_skip_instance(n_vars, bad_rows);
+	let n_multilinears = multilins.len();
+	let prove_output =
+		prove_univariate_skip_instance(&zc_claim, &multilins, skip_rounds, zc_challenges.clone())?;
+	assert_eq!(prove_output.proof.skip_round_evals.len(), (n_multilinears - 1) << skip_rounds);
+
+	let evalcheck_claims = verify_univariate_skip::<_, BinaryField8b, _>(
+		&zc_claim,
+		skip_rounds,
+		zc_challenges,
+		prove_output.proof,
+		<HashChallenger<_, GroestlHasher<_>>>::new(),
+	)?;
+	assert_eq!(evalcheck_claims.len(), n_multilinears);
+	for (evalcheck_claim, prover_claim) in
+		iter::zip(&evalcheck_claims, &prove_output.evalcheck_claims)
+	{
+		assert_eq!(evalcheck_claim.eval_point, prover_claim.eval_point);
+		assert_eq!(evalcheck_claim.eval, prover_claim.eval);
+	}
+
+	// The reduced claims must hold for the witness
+	assert!(univariate_skip_claims_hold(&evalcheck_claims, &multilins));
+	Ok(())
+}
+
+#[test]
+fn test_prove_verify_univariate_skip() {
+	for skip_rounds in 1..5 {
+		run_univariate_skip(7, skip_rounds, &[]).unwrap();
+	}
+}
+
+#[test]
+fn test_univariate_skip_unsatisfied_claim() {
+	// The verifier assumes that the skipped round polynomial vanishes on the skip domain, so the
+	// sumcheck reduces to a false evaluation of the composition
+	assert_matches!(
+		run_univariate_skip(7, 3, &[21]),
+		Err(Error::Verification(VerificationError::IncorrectEvaluation))
+	);
+}
+
+#[test]
+fn test_univariate_skip_invalid_skip_rounds() {
+	assert_matches!(run_univariate_skip(5, 0, &[]), Err(Error::InvalidSkipRounds));
+	assert_matches!(run_univariate_skip(5, 5, &[]), Err(Error::InvalidSkipRounds));
+}
+
+#[test]
+fn test_univariate_skip_tampered_partial_evals() {
+	let n_vars = 7;
+	let skip_rounds = 3;
+	let (zc_claim, multilins, zc_challenges) = univariate_skip_instance(n_vars, &[]);
+	let proof =
+		prove_univariate_skip_instance(&zc_claim, &multilins, skip_rounds, zc_challenges.clone())
+			.unwrap()
+			.proof;
+	let verify_tampered = |proof| {
+		verify_univariate_skip::<_, BinaryField8b, _>(
+			&zc_claim,
+			skip_rounds,
+			zc_challenges.clone(),
+			proof,
+			<HashChallenger<_, GroestlHasher<_>>>::new(),
+		)
+	};
+
+	// A partial evaluation that changes the folded multilinear fails the sumcheck
+	let mut tampered_proof = proof.clone();
+	tampered_proof.partial_evals[0][1] += BinaryField128b::ONE;
+	assert_matches!(
+		verify_tampered(tampered_proof),
+		Err(Error::Verification(VerificationError::IncorrectEvaluation))
+	);
+
+	// A change orthogonal to the Lagrange basis at the skip challenge leaves the folded
+	// multilinears unchanged, and is only caught by the evaluation claim on the multilinear
+	let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	challenger.observe_slice(&proof.skip_round_evals);
+	let z: BinaryField128b = challenger.sample();
+	let lagrange_evals = EvaluationDomain::<BinaryField8b>::new(1 << skip_rounds)
+		.unwrap()
+		.lagrange_evals(z);
+	let mut tampered_proof = proof;
+	tampered_proof.partial_evals[0][0] += lagrange_evals[1];
+	tampered_proof.partial_evals[0][1] += lagrange_evals[0];
+	let evalcheck_claims = verify_tampered(tampered_proof).unwrap();
+	assert!(!univariate_skip_claims_hold(&evalcheck_claims, &multilins));
+}
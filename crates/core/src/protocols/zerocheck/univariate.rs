@@ -0,0 +1,476 @@
+// Copyright 2024 Ulvetanna Inc.
+
+//! Zerocheck with a univariate skip over the first rounds.
+//!
+//! The first sumcheck rounds of a zerocheck over small field multilinears are dominated by
+//! evaluating the composition over extension field points. Following section 5 of [Gruen24], the
+//! prover can instead treat the first $k$ variables as a single univariate variable over a
+//! subspace $D$ of a small field, of size $2^k$. For every assignment $x$ of the remaining
+//! variables, the hypercube values $f_j(i, x)$ for $i \in \{0, 1\}^k$ are read as the values of a
+//! univariate polynomial $\hat{f}_j(X, x)$ of degree less than $2^k$ on $D$, and the round
+//! polynomial
+//!
+//! $$R(X) = \sum_{x} \textit{eq}(x, \alpha) \cdot C(\hat{f}_1(X, x), \ldots, \hat{f}_m(X, x))$$
+//!
+//! vanishes on $D$ if the claim holds. The prover computes $R$ on the cosets of $D$ with the
+//! additive NTT, so this round only involves small field arithmetic, apart from the
+//! multiplication by the equality indicator.
+//!
+//! After the verifier samples $z$, the claim $R(z)$ is reduced with a regular sumcheck over the
+//! remaining variables, on the multilinears $\hat{f}_j(z, \cdot)$ and the equality indicator.
+//! Finally, the prover sends the evaluations of every $f_j$ on the skipped hypercube at the
+//! sumcheck point, which the verifier checks against the last sumcheck claim. After sampling a
+//! random point $\rho$ of the skipped variables, these are combined into one evaluation claim
+//! per multilinear, on $f_j(\rho, r)$ where $r$ is the sumcheck point. The claims must be on the
+//! individual multilinears, as the composition alone does not bind the partial evaluations.
+//!
+//! [Gruen24]: https://eprint.iacr.org/2024/108
+
+use super::{
+	error::{Error, VerificationError},
+	zerocheck::ZerocheckClaim,
+};
+use crate::{
+	challenger::{CanObserve, CanSample},
+	oracle::{CommittedBatchSpec, CommittedId, CompositePolyOracle, MultilinearOracleSet},
+	polynomial::{
+		transparent::eq_ind::EqIndPartialEval, CompositionPoly, Error as PolynomialError,
+		EvaluationDomain, MultilinearComposite, MultilinearExtension, MultilinearQuery,
+		MultivariatePoly,
+	},
+	protocols::{
+		evalcheck::EvalcheckClaim,
+		sumcheck::{self, SumcheckClaim, SumcheckProof, SumcheckProveOutput},
+	},
+	witness::MultilinearWitness,
+};
+use binius_field::{BinaryField, ExtensionField, Field, PackedField, TowerField};
+use binius_ntt::{AdditiveNTT, AdditiveNTTWithOTFCompute};
+use p3_util::log2_ceil_usize;
+use rayon::prelude::*;
+use std::{iter, iter::Step, sync::Arc};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnivariateSkipProof<F: Field> {
+	/// Values of the skipped round polynomial on the cosets of the skip domain, excluding the
+	/// skip domain itself where it vanishes
+	pub skip_round_evals: Vec<F>,
+	/// Sumcheck proof over the remaining variables
+	pub sumcheck_proof: SumcheckProof<F>,
+	/// Values of each multilinear on the skipped hypercube, with the remaining variables set to
+	/// the sumcheck challenges
+	pub partial_evals: Vec<Vec<F>>,
+}
+
+#[derive(Debug)]
+pub struct UnivariateSkipProveOutput<F: Field> {
+	/// Evaluation claims on the multilinears of the zerocheck claim, in order
+	pub evalcheck_claims: Vec<EvalcheckClaim<F>>,
+	pub proof: UnivariateSkipProof<F>,
+}
+
+/// Composition $E \cdot C(X_1, \ldots, X_m)$ of the sumcheck over the variables after the skip.
+///
+/// The equality indicator $E$ is the last variable.
+#[derive(Debug, Clone)]
+struct EqIndFactorComposition<F: Field> {
+	inner: Arc<dyn CompositionPoly<F>>,
+}
+
+impl<F: Field> CompositionPoly<F> for EqIndFactorComposition<F> {
+	fn n_vars(&self) -> usize {
+		self.inner.n_vars() + 1
+	}
+
+	fn degree(&self) -> usize {
+		self.inner.degree() + 1
+	}
+
+	fn evaluate(&self, query: &[F]) -> Result<F, PolynomialError> {
+		self.evaluate_packed(query)
+	}
+
+	fn evaluate_packed(&self, query: &[F]) -> Result<F, PolynomialError> {
+		let (eq_ind, inner_query) =
+			query
+				.split_last()
+				.ok_or(PolynomialError::IncorrectQuerySize {
+					expected: self.n_vars(),
+				})?;
+		Ok(*eq_ind * self.inner.evaluate(inner_query)?)
+	}
+
+	fn binary_tower_level(&self) -> usize {
+		self.inner.binary_tower_level()
+	}
+}
+
+/// Prove a zerocheck instance, handling the first `skip_rounds` variables in a single univariate
+/// round over the small field `FDomain`.
+///
+/// `challenge` holds the same $n - 1$ zerocheck challenges as for [`prove`](super::prove), of
+/// which the skip only uses the last $n - k$. `composition` must be the composition of the claim,
+/// evaluated over `FDomain`, and `multilinears` its inputs over a subfield of `FDomain`. The
+/// sumcheck over the remaining variables has degree one more than the composition, so `domain`
+/// must have size two more than the composition degree.
+///
+/// See module documentation for details.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_univariate_skip<F, FDomain, PBase, CW, CH>(
+	claim: &ZerocheckClaim<F>,
+	composition: &CW,
+	multilinears: &[MultilinearExtension<'_, PBase>],
+	skip_rounds: usize,
+	challenge: Vec<F>,
+	domain: &EvaluationDomain<F>,
+	mut challenger: CH,
+	switchover_fn: impl Fn(usize) -> usize,
+) -> Result<UnivariateSkipProveOutput<F>, Error>
+where
+	F: TowerField + ExtensionField<FDomain> + ExtensionField<PBase::Scalar>,
+	FDomain: BinaryField + Step + ExtensionField<PBase::Scalar>,
+	PBase: PackedField,
+	CW: CompositionPoly<FDomain>,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let n_vars = claim.poly.n_vars();
+	check_skip_rounds(n_vars, skip_rounds, &challenge)?;
+	if composition.n_vars() != claim.poly.n_multilinears()
+		|| multilinears.len() != claim.poly.n_multilinears()
+		|| multilinears
+			.iter()
+			.any(|multilinear| multilinear.n_vars() != n_vars)
+	{
+		return Err(Error::ClaimWitnessMismatch);
+	}
+
+	let n_rest_vars = n_vars - skip_rounds;
+	let degree = claim.poly.max_individual_degree();
+	let eq_ind = EqIndPartialEval::new(n_rest_vars, challenge[skip_rounds - 1..].to_vec())?
+		.multilinear_extension::<F>()?;
+
+	let skip_round_evals =
+		compute_skip_round_evals(composition, multilinears, &eq_ind, skip_rounds, degree)?;
+	challenger.observe_slice(&skip_round_evals);
+	let z = challenger.sample();
+	let sum = extrapolate_skip_round::<F, FDomain>(skip_rounds, degree, &skip_round_evals, z)?;
+
+	// Fold the skipped variables of each multilinear at z
+	let lagrange_evals = EvaluationDomain::<FDomain>::new(1 << skip_rounds)?.lagrange_evals(z);
+	let folded_multilinears = multilinears
+		.iter()
+		.map(|multilinear| -> Result<MultilinearWitness<'static, F>, Error> {
+			let values = (0..1 << n_rest_vars)
+				.into_par_iter()
+				.map(|index| {
+					lagrange_evals
+						.iter()
+						.enumerate()
+						.map(|(i, &lagrange_eval)| -> Result<F, PolynomialError> {
+							let value =
+								multilinear.evaluate_on_hypercube(index << skip_rounds | i)?;
+							Ok(lagrange_eval * FDomain::from(value))
+						})
+						.sum::<Result<F, PolynomialError>>()
+				})
+				.collect::<Result<Vec<_>, _>>()?;
+			Ok(MultilinearExtension::from_values(values)?.specialize_arc_dyn())
+		})
+		.chain(iter::once(Ok(eq_ind.specialize_arc_dyn())))
+		.collect::<Result<Vec<_>, Error>>()?;
+
+	let sumcheck_claim = rest_sumcheck_claim(claim, n_rest_vars, sum)?;
+	let witness = MultilinearComposite::new(
+		n_rest_vars,
+		EqIndFactorComposition {
+			inner: claim.poly.composition(),
+		},
+		folded_multilinears,
+	)?;
+	let SumcheckProveOutput {
+		evalcheck_claim,
+		sumcheck_proof,
+	} = sumcheck::prove(&sumcheck_claim, witness, domain, &mut challenger, switchover_fn)?;
+
+	let query = MultilinearQuery::<F>::with_full_query(&evalcheck_claim.eval_point)?;
+	let partial_evals = multilinears
+		.iter()
+		.map(|multilinear| -> Result<Vec<F>, Error> {
+			Ok(multilinear.evaluate_partial_high(&query)?.evals().to_vec())
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let evalcheck_claims = skip_evalcheck_claims(
+		claim,
+		skip_rounds,
+		&partial_evals,
+		evalcheck_claim.eval_point,
+		&mut challenger,
+	)?;
+
+	Ok(UnivariateSkipProveOutput {
+		evalcheck_claims,
+		proof: UnivariateSkipProof {
+			skip_round_evals,
+			sumcheck_proof,
+			partial_evals,
+		},
+	})
+}
+
+/// Verify a zerocheck instance proven with [`prove_univariate_skip`].
+///
+/// Returns one evaluation claim per multilinear of the zerocheck claim, in order.
+pub fn verify_univariate_skip<F, FDomain, CH>(
+	claim: &ZerocheckClaim<F>,
+	skip_rounds: usize,
+	challenge: Vec<F>,
+	proof: UnivariateSkipProof<F>,
+	mut challenger: CH,
+) -> Result<Vec<EvalcheckClaim<F>>, Error>
+where
+	F: TowerField + ExtensionField<FDomain>,
+	FDomain: Field + Step,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	let n_vars = claim.poly.n_vars();
+	check_skip_rounds(n_vars, skip_rounds, &challenge)?;
+
+	let UnivariateSkipProof {
+		skip_round_evals,
+		sumcheck_proof,
+		partial_evals,
+	} = proof;
+
+	let n_rest_vars = n_vars - skip_rounds;
+	let degree = claim.poly.max_individual_degree();
+	if skip_round_evals.len() != degree.saturating_sub(1) << skip_rounds {
+		return Err(VerificationError::NumberOfSkipRoundEvals.into());
+	}
+	if partial_evals.len() != claim.poly.n_multilinears()
+		|| partial_evals
+			.iter()
+			.any(|evals| evals.len() != 1 << skip_rounds)
+	{
+		return Err(VerificationError::NumberOfPartialEvals.into());
+	}
+
+	challenger.observe_slice(&skip_round_evals);
+	let z = challenger.sample();
+	let sum = extrapolate_skip_round::<F, FDomain>(skip_rounds, degree, &skip_round_evals, z)?;
+
+	let sumcheck_claim = rest_sumcheck_claim(claim, n_rest_vars, sum)?;
+	let evalcheck_claim = sumcheck::verify(&sumcheck_claim, sumcheck_proof, &mut challenger)?;
+
+	// The folded multilinears at the sumcheck point follow from the partial evaluations
+	let lagrange_evals = EvaluationDomain::<FDomain>::new(1 << skip_rounds)?.lagrange_evals(z);
+	let folded_evals = partial_evals
+		.iter()
+		.map(|evals| inner_product(&lagrange_evals, evals))
+		.collect::<Vec<_>>();
+	let eq_ind_eval = EqIndPartialEval::new(n_rest_vars, challenge[skip_rounds - 1..].to_vec())?
+		.evaluate(&evalcheck_claim.eval_point)?;
+	if eq_ind_eval * claim.poly.composition().evaluate(&folded_evals)? != evalcheck_claim.eval {
+		return Err(VerificationError::IncorrectEvaluation.into());
+	}
+
+	skip_evalcheck_claims(
+		claim,
+		skip_rounds,
+		&partial_evals,
+		evalcheck_claim.eval_point,
+		&mut challenger,
+	)
+}
+
+fn check_skip_rounds<F: Field>(
+	n_vars: usize,
+	skip_rounds: usize,
+	challenge: &[F],
+) -> Result<(), Error> {
+	if skip_rounds == 0 || skip_rounds >= n_vars {
+		return Err(Error::InvalidSkipRounds);
+	}
+	if challenge.len() + 1 != n_vars {
+		return Err(Error::ChallengeVectorMismatch);
+	}
+	Ok(())
+}
+
+/// Computes the skipped round polynomial on the cosets $1, \ldots, d - 1$ of the skip domain.
+///
+/// The values on coset 0, which is the skip domain itself, are zero and omitted.
+fn compute_skip_round_evals<F, FDomain, PBase, CW>(
+	composition: &CW,
+	multilinears: &[MultilinearExtension<'_, PBase>],
+	eq_ind: &MultilinearExtension<'_, F>,
+	skip_rounds: usize,
+	degree: usize,
+) -> Result<Vec<F>, Error>
+where
+	F: Field + ExtensionField<FDomain>,
+	FDomain: BinaryField + ExtensionField<PBase::Scalar>,
+	PBase: PackedField,
+	CW: CompositionPoly<FDomain>,
+{
+	let n_round_evals = degree.saturating_sub(1) << skip_rounds;
+	if n_round_evals == 0 {
+		return Ok(Vec::new());
+	}
+
+	let ntt = AdditiveNTTWithOTFCompute::<FDomain>::new(skip_rounds + log2_ceil_usize(degree))?;
+
+	(0..eq_ind.size())
+		.into_par_iter()
+		.try_fold(
+			|| vec![F::ZERO; n_round_evals],
+			|mut round_evals, index| -> Result<_, Error> {
+				let eq_ind_factor = eq_ind.evaluate_on_hypercube(index)?;
+
+				// Novel basis coefficients of the univariate interpolant of each multilinear
+				let coeffs = multilinears
+					.iter()
+					.map(|multilinear| -> Result<Vec<FDomain>, Error> {
+						let mut coeffs = (0..1 << skip_rounds)
+							.map(|i| {
+								multilinear
+									.evaluate_on_hypercube(index << skip_rounds | i)
+									.map(FDomain::from)
+							})
+							.collect::<Result<Vec<_>, _>>()?;
+						AdditiveNTT::<FDomain>::inverse_transform(&ntt, &mut coeffs, 0, 0)?;
+						Ok(coeffs)
+					})
+					.collect::<Result<Vec<_>, _>>()?;
+
+				let mut query = vec![FDomain::ZERO; multilinears.len()];
+				for (coset, coset_round_evals) in
+					(1..degree).zip(round_evals.chunks_exact_mut(1 << skip_rounds))
+				{
+					let coset_evals = coeffs
+						.iter()
+						.map(|coeffs| -> Result<Vec<FDomain>, Error> {
+							let mut evals = coeffs.clone();
+							AdditiveNTT::<FDomain>::forward_transform(
+								&ntt,
+								&mut evals,
+								coset as u32,
+								0,
+							)?;
+							Ok(evals)
+						})
+						.collect::<Result<Vec<_>, Error>>()?;
+
+					for (i, round_eval) in coset_round_evals.iter_mut().enumerate() {
+						for (query_j, evals) in query.iter_mut().zip(coset_evals.iter()) {
+							*query_j = evals[i];
+						}
+						*round_eval += eq_ind_factor * composition.evaluate(&query)?;
+					}
+				}
+
+				Ok(round_evals)
+			},
+		)
+		.try_reduce(
+			|| vec![F::ZERO; n_round_evals],
+			|mut round_evals, other| {
+				for (round_eval, other_eval) in round_evals.iter_mut().zip(other) {
+					*round_eval += other_eval;
+				}
+				Ok(round_evals)
+			},
+		)
+}
+
+/// Evaluates the skipped round polynomial at `z` from its values on the cosets of the skip domain.
+fn extrapolate_skip_round<F, FDomain>(
+	skip_rounds: usize,
+	degree: usize,
+	skip_round_evals: &[F],
+	z: F,
+) -> Result<F, Error>
+where
+	F: Field + ExtensionField<FDomain>,
+	FDomain: Field + Step,
+{
+	if degree <= 1 {
+		// The round polynomial has degree less than the size of the skip domain, on which it
+		// vanishes
+		return Ok(F::ZERO);
+	}
+
+	let values = iter::repeat(F::ZERO)
+		.take(1 << skip_rounds)
+		.chain(skip_round_evals.iter().copied())
+		.collect::<Vec<_>>();
+	let eval = EvaluationDomain::<FDomain>::new(degree << skip_rounds)?.extrapolate(&values, z)?;
+	Ok(eval)
+}
+
+/// Builds the sumcheck claim over the variables after the skip.
+///
+/// The folded multilinears are never committed, so the claim is stated over placeholder oracles.
+/// Its final evaluation claim is checked against the partial evaluations instead.
+fn rest_sumcheck_claim<F: TowerField>(
+	claim: &ZerocheckClaim<F>,
+	n_vars: usize,
+	sum: F,
+) -> Result<SumcheckClaim<F>, Error> {
+	let n_polys = claim.poly.n_multilinears() + 1;
+	let mut rest_oracles = MultilinearOracleSet::new();
+	let batch_id = rest_oracles.add_committed_batch(CommittedBatchSpec {
+		round_id: 0,
+		n_vars,
+		n_polys,
+		tower_level: F::TOWER_LEVEL,
+	});
+	let inner = (0..n_polys)
+		.map(|index| rest_oracles.committed_oracle(CommittedId { batch_id, index }))
+		.collect();
+	let composition = EqIndFactorComposition {
+		inner: claim.poly.composition(),
+	};
+	let poly = CompositePolyOracle::new(n_vars, inner, composition)?;
+	Ok(SumcheckClaim {
+		poly,
+		sum,
+		zerocheck_challenges: None,
+	})
+}
+
+/// Combines the partial evaluations at a random point of the skipped variables into an
+/// evaluation claim on each multilinear of the zerocheck polynomial.
+fn skip_evalcheck_claims<F, CH>(
+	claim: &ZerocheckClaim<F>,
+	skip_rounds: usize,
+	partial_evals: &[Vec<F>],
+	rest_eval_point: Vec<F>,
+	mut challenger: CH,
+) -> Result<Vec<EvalcheckClaim<F>>, Error>
+where
+	F: TowerField,
+	CH: CanObserve<F> + CanSample<F>,
+{
+	for evals in partial_evals {
+		challenger.observe_slice(evals);
+	}
+
+	let mut eval_point = challenger.sample_vec(skip_rounds);
+	let query = MultilinearQuery::<F>::with_full_query(&eval_point)?;
+	eval_point.extend(rest_eval_point);
+
+	let evalcheck_claims = iter::zip(claim.poly.inner_polys(), partial_evals)
+		.map(|(multilinear, evals)| EvalcheckClaim {
+			poly: multilinear.into_composite(),
+			eval_point: eval_point.clone(),
+			eval: inner_product(query.expansion(), evals),
+			is_random_point: true,
+		})
+		.collect();
+	Ok(evalcheck_claims)
+}
+
+fn inner_product<F: Field>(a: &[F], b: &[F]) -> F {
+	a.iter().zip(b).map(|(&a_i, &b_i)| a_i * b_i).sum()
+}
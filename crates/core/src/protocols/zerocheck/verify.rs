@@ -4,7 +4,9 @@ use super::{
 	error::VerificationError,
 	zerocheck::{reduce_zerocheck_claim, ZerocheckClaim, ZerocheckProof},
 };
-use crate::protocols::sumcheck::SumcheckClaim;
+use crate::{
+	challenger::CanSample, protocols::sumcheck::SumcheckClaim, transcript::VerifierTranscript,
+};
 use binius_field::TowerField;
 
 pub fn verify<F: TowerField>(
@@ -16,3 +18,17 @@ pub fn verify<F: TowerField>(
 	let claim = reduce_zerocheck_claim(claim, challenge)?;
 	Ok(claim)
 }
+
+/// Verify a zerocheck instance reduction, sampling the zerocheck challenges from a transcript.
+pub fn verify_with_transcript<F, CH>(
+	claim: &ZerocheckClaim<F>,
+	transcript: &mut VerifierTranscript<'_, CH>,
+) -> Result<SumcheckClaim<F>, VerificationError>
+where
+	F: TowerField,
+	CH: CanSample<F>,
+{
+	let n_challenges = claim.poly.n_vars().saturating_sub(1);
+	let challenge = transcript.sample_vec(n_challenges);
+	verify(claim, ZerocheckProof, challenge)
+}
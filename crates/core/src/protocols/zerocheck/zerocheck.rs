@@ -10,7 +10,7 @@ use crate::{
 use binius_field::{Field, PackedField, TowerField};
 use std::fmt::Debug;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ZerocheckProof;
 
 #[derive(Debug)]
@@ -0,0 +1,322 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::error::Error;
+use crate::merkle_tree::MerkleCap;
+use binius_field::{
+	packed::iter_packed_slice, AESTowerField128b, AESTowerField16b, AESTowerField32b,
+	AESTowerField64b, AESTowerField8b, BinaryField128b, BinaryField128bPolyval, BinaryField16b,
+	BinaryField1b, BinaryField2b, BinaryField32b, BinaryField4b, BinaryField64b, BinaryField8b,
+	PackedField,
+};
+use binius_hash::GroestlDigest;
+use std::{fs, mem, path::Path};
+
+/// Version of the byte encoding produced by [`serialize_versioned`].
+///
+/// The version must be bumped whenever the encoding of any proof type changes.
+pub const ENCODING_VERSION: u16 = 3;
+
+/// A value with a unique byte encoding.
+///
+/// All integers are little-endian, lengths are encoded as `u64`, and field elements are encoded
+/// as their canonical integer representation in the smallest whole number of bytes.
+pub trait SerializeCanonical {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>);
+}
+
+/// A value that can be decoded from the encoding produced by [`SerializeCanonical`].
+///
+/// Decoding must reject every byte string that is not the encoding of some value, so that each
+/// value has exactly one accepted encoding.
+pub trait DeserializeCanonical: Sized {
+	/// Decodes a value from the front of `buf`, advancing it past the consumed bytes.
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error>;
+}
+
+/// Encodes a value prefixed with the [`ENCODING_VERSION`].
+pub fn serialize_versioned(value: &impl SerializeCanonical) -> Vec<u8> {
+	let mut buf = Vec::new();
+	ENCODING_VERSION.serialize_canonical(&mut buf);
+	value.serialize_canonical(&mut buf);
+	buf
+}
+
+/// Decodes a value encoded by [`serialize_versioned`].
+///
+/// Fails if the version does not match [`ENCODING_VERSION`] or if any input is left over.
+pub fn deserialize_versioned<T: DeserializeCanonical>(mut bytes: &[u8]) -> Result<T, Error> {
+	let version = u16::deserialize_canonical(&mut bytes)?;
+	if version != ENCODING_VERSION {
+		return Err(Error::UnsupportedVersion {
+			found: version,
+			expected: ENCODING_VERSION,
+		});
+	}
+
+	let value = T::deserialize_canonical(&mut bytes)?;
+	if !bytes.is_empty() {
+		return Err(Error::TrailingBytes { count: bytes.len() });
+	}
+	Ok(value)
+}
+
+/// Writes a value encoded by [`serialize_versioned`] to a file.
+pub fn save_versioned(
+	path: impl AsRef<Path>,
+	value: &impl SerializeCanonical,
+) -> Result<(), Error> {
+	fs::write(path, serialize_versioned(value))?;
+	Ok(())
+}
+
+/// Reads a value from a file written by [`save_versioned`].
+///
+/// The whole file is read into memory and then decoded into owned values, so packed matrices are
+/// copied element by element. Decoding the bytes of a memory-mapped file with
+/// [`deserialize_versioned`] saves the read, but not the copy.
+pub fn load_versioned<T: DeserializeCanonical>(path: impl AsRef<Path>) -> Result<T, Error> {
+	deserialize_versioned(&fs::read(path)?)
+}
+
+/// Splits `n` bytes off the front of `buf`.
+pub(super) fn take<'a>(buf: &mut &'a [u8], n: usize) -> Result<&'a [u8], Error> {
+	if buf.len() < n {
+		return Err(Error::Truncated {
+			needed: n,
+			remaining: buf.len(),
+		});
+	}
+	let (head, tail) = buf.split_at(n);
+	*buf = tail;
+	Ok(head)
+}
+
+/// Reads a one byte enum discriminant.
+pub(super) fn take_tag(buf: &mut &[u8]) -> Result<u8, Error> {
+	Ok(take(buf, 1)?[0])
+}
+
+/// Encodes a slice of packed field elements as its length in packed elements followed by all of
+/// the scalars.
+pub fn serialize_packed_slice<P>(values: &[P], buf: &mut Vec<u8>)
+where
+	P: PackedField,
+	P::Scalar: SerializeCanonical,
+{
+	values.len().serialize_canonical(buf);
+	for scalar in iter_packed_slice(values) {
+		scalar.serialize_canonical(buf);
+	}
+}
+
+/// Decodes a vector of packed field elements encoded by [`serialize_packed_slice`].
+pub fn deserialize_packed_vec<P>(buf: &mut &[u8]) -> Result<Vec<P>, Error>
+where
+	P: PackedField,
+	P::Scalar: DeserializeCanonical,
+{
+	let len = usize::deserialize_canonical(buf)?;
+	let mut values = Vec::with_capacity(len.min(buf.len()));
+	for _ in 0..len {
+		values.push(deserialize_packed(buf)?);
+	}
+	Ok(values)
+}
+
+fn deserialize_packed<P>(buf: &mut &[u8]) -> Result<P, Error>
+where
+	P: PackedField,
+	P::Scalar: DeserializeCanonical,
+{
+	let scalars = (0..P::WIDTH)
+		.map(|_| P::Scalar::deserialize_canonical(buf))
+		.collect::<Result<Vec<_>, _>>()?;
+	Ok(P::from_fn(|i| scalars[i]))
+}
+
+macro_rules! impl_canonical_for_uint {
+	($($typ:ty),*) => {
+		$(
+			impl SerializeCanonical for $typ {
+				fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+					buf.extend_from_slice(&self.to_le_bytes());
+				}
+			}
+
+			impl DeserializeCanonical for $typ {
+				fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+					let bytes = take(buf, mem::size_of::<$typ>())?;
+					Ok(<$typ>::from_le_bytes(bytes.try_into().expect("take returns exactly n bytes")))
+				}
+			}
+		)*
+	};
+}
+
+impl_canonical_for_uint!(u8, u16, u32, u64, u128);
+
+impl SerializeCanonical for usize {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		(*self as u64).serialize_canonical(buf);
+	}
+}
+
+impl DeserializeCanonical for usize {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let len = u64::deserialize_canonical(buf)?;
+		usize::try_from(len).map_err(|_| Error::LengthOverflow { len })
+	}
+}
+
+impl SerializeCanonical for bool {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		buf.push(*self as u8);
+	}
+}
+
+impl DeserializeCanonical for bool {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		match take_tag(buf)? {
+			0 => Ok(false),
+			1 => Ok(true),
+			_ => Err(Error::NonCanonical { type_name: "bool" }),
+		}
+	}
+}
+
+impl<T: SerializeCanonical> SerializeCanonical for Vec<T> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.len().serialize_canonical(buf);
+		for item in self {
+			item.serialize_canonical(buf);
+		}
+	}
+}
+
+impl<T: DeserializeCanonical> DeserializeCanonical for Vec<T> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let len = usize::deserialize_canonical(buf)?;
+		// Do not trust the length prefix for the allocation size
+		let mut items = Vec::with_capacity(len.min(buf.len()));
+		for _ in 0..len {
+			items.push(T::deserialize_canonical(buf)?);
+		}
+		Ok(items)
+	}
+}
+
+impl<T: SerializeCanonical> SerializeCanonical for Option<T> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		match self {
+			None => buf.push(0),
+			Some(value) => {
+				buf.push(1);
+				value.serialize_canonical(buf);
+			}
+		}
+	}
+}
+
+impl<T: DeserializeCanonical> DeserializeCanonical for Option<T> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		match take_tag(buf)? {
+			0 => Ok(None),
+			1 => Ok(Some(T::deserialize_canonical(buf)?)),
+			tag => Err(Error::InvalidTag {
+				type_name: "Option",
+				tag,
+			}),
+		}
+	}
+}
+
+impl<A: SerializeCanonical, B: SerializeCanonical> SerializeCanonical for (A, B) {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.0.serialize_canonical(buf);
+		self.1.serialize_canonical(buf);
+	}
+}
+
+impl<A: DeserializeCanonical, B: DeserializeCanonical> DeserializeCanonical for (A, B) {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let a = A::deserialize_canonical(buf)?;
+		let b = B::deserialize_canonical(buf)?;
+		Ok((a, b))
+	}
+}
+
+macro_rules! impl_canonical_for_tower_field {
+	($($name:ident($typ:ty)),*) => {
+		$(
+			impl SerializeCanonical for $name {
+				fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+					self.val().serialize_canonical(buf);
+				}
+			}
+
+			impl DeserializeCanonical for $name {
+				fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+					let value = <$typ>::deserialize_canonical(buf)?;
+					$name::new_checked(value).map_err(|_| Error::NonCanonical {
+						type_name: stringify!($name),
+					})
+				}
+			}
+		)*
+	};
+}
+
+impl_canonical_for_tower_field!(
+	BinaryField1b(u8),
+	BinaryField2b(u8),
+	BinaryField4b(u8),
+	BinaryField8b(u8),
+	BinaryField16b(u16),
+	BinaryField32b(u32),
+	BinaryField64b(u64),
+	BinaryField128b(u128),
+	AESTowerField8b(u8),
+	AESTowerField16b(u16),
+	AESTowerField32b(u32),
+	AESTowerField64b(u64),
+	AESTowerField128b(u128)
+);
+
+// Every 128-bit string is a valid POLYVAL element, so the Montgomery form is encoded as is.
+impl SerializeCanonical for BinaryField128bPolyval {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		bytemuck::must_cast::<_, u128>(*self).serialize_canonical(buf);
+	}
+}
+
+impl DeserializeCanonical for BinaryField128bPolyval {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		Ok(bytemuck::must_cast(u128::deserialize_canonical(buf)?))
+	}
+}
+
+impl SerializeCanonical for GroestlDigest {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		for scalar in self.iter() {
+			scalar.serialize_canonical(buf);
+		}
+	}
+}
+
+impl DeserializeCanonical for GroestlDigest {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		deserialize_packed(buf)
+	}
+}
+
+impl<D: SerializeCanonical> SerializeCanonical for MerkleCap<D> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.0.serialize_canonical(buf);
+	}
+}
+
+impl<D: DeserializeCanonical> DeserializeCanonical for MerkleCap<D> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		Ok(Self(Vec::deserialize_canonical(buf)?))
+	}
+}
@@ -0,0 +1,74 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	canonical::{
+		deserialize_packed_vec, serialize_packed_slice, DeserializeCanonical, SerializeCanonical,
+	},
+	error::Error,
+};
+use crate::merkle_tree::MerkleTree;
+use binius_field::PackedField;
+use p3_matrix::dense::RowMajorMatrix;
+
+impl<D: SerializeCanonical> SerializeCanonical for MerkleTree<D> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.log_len.serialize_canonical(buf);
+		self.cap_height.serialize_canonical(buf);
+		self.batch_size.serialize_canonical(buf);
+		self.inner_nodes.serialize_canonical(buf);
+	}
+}
+
+impl<D: DeserializeCanonical> DeserializeCanonical for MerkleTree<D> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let log_len = usize::deserialize_canonical(buf)?;
+		let cap_height = usize::deserialize_canonical(buf)?;
+		let batch_size = usize::deserialize_canonical(buf)?;
+		let inner_nodes = Vec::<D>::deserialize_canonical(buf)?;
+
+		// The flattened layers run from the 2^log_len leaves up to the 2^cap_height cap nodes
+		let consistent = log_len < usize::BITS as usize - 1
+			&& cap_height <= log_len
+			&& inner_nodes.len() == (2 << log_len) - (1 << cap_height);
+		if !consistent {
+			return Err(Error::InconsistentDimensions {
+				type_name: "MerkleTree",
+			});
+		}
+
+		Ok(Self {
+			log_len,
+			cap_height,
+			batch_size,
+			inner_nodes,
+		})
+	}
+}
+
+impl<P> SerializeCanonical for RowMajorMatrix<P>
+where
+	P: PackedField,
+	P::Scalar: SerializeCanonical,
+{
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.width.serialize_canonical(buf);
+		serialize_packed_slice(&self.values, buf);
+	}
+}
+
+impl<P> DeserializeCanonical for RowMajorMatrix<P>
+where
+	P: PackedField,
+	P::Scalar: DeserializeCanonical,
+{
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let width = usize::deserialize_canonical(buf)?;
+		let values = deserialize_packed_vec(buf)?;
+		if width == 0 || values.len() % width != 0 {
+			return Err(Error::InconsistentDimensions {
+				type_name: "RowMajorMatrix",
+			});
+		}
+		Ok(RowMajorMatrix::new(values, width))
+	}
+}
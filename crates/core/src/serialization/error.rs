@@ -0,0 +1,27 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use crate::polynomial::Error as PolynomialError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("unexpected end of input: needed {needed} bytes, {remaining} remaining")]
+	Truncated { needed: usize, remaining: usize },
+	#[error("encoding of {type_name} is not canonical")]
+	NonCanonical { type_name: &'static str },
+	#[error("invalid variant tag {tag} for {type_name}")]
+	InvalidTag { type_name: &'static str, tag: u8 },
+	#[error("nesting depth exceeds the limit of {limit}")]
+	NestingTooDeep { limit: usize },
+	#[error("encoded length {len} exceeds the addressable size")]
+	LengthOverflow { len: u64 },
+	#[error("unsupported encoding version {found}, expected {expected}")]
+	UnsupportedVersion { found: u16, expected: u16 },
+	#[error("{count} trailing bytes after the encoded value")]
+	TrailingBytes { count: usize },
+	#[error("decoded {type_name} has inconsistent dimensions")]
+	InconsistentDimensions { type_name: &'static str },
+	#[error("I/O error: {0}")]
+	Io(#[from] std::io::Error),
+	#[error("polynomial error: {0}")]
+	Polynomial(#[from] PolynomialError),
+}
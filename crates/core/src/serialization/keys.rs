@@ -0,0 +1,48 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	canonical::{DeserializeCanonical, SerializeCanonical},
+	error::Error,
+};
+use crate::{oracle::CommittedBatchSpec, setup::VerifyingKey};
+
+impl SerializeCanonical for CommittedBatchSpec {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.round_id.serialize_canonical(buf);
+		self.n_vars.serialize_canonical(buf);
+		self.n_polys.serialize_canonical(buf);
+		self.tower_level.serialize_canonical(buf);
+	}
+}
+
+impl DeserializeCanonical for CommittedBatchSpec {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		Ok(Self {
+			round_id: usize::deserialize_canonical(buf)?,
+			n_vars: usize::deserialize_canonical(buf)?,
+			n_polys: usize::deserialize_canonical(buf)?,
+			tower_level: usize::deserialize_canonical(buf)?,
+		})
+	}
+}
+
+impl<Commitment: SerializeCanonical> SerializeCanonical for VerifyingKey<Commitment> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.batches.serialize_canonical(buf);
+		self.fixed_batch_id.serialize_canonical(buf);
+		self.fixed_commitment.serialize_canonical(buf);
+	}
+}
+
+impl<Commitment: DeserializeCanonical> DeserializeCanonical for VerifyingKey<Commitment> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let batches = Vec::deserialize_canonical(buf)?;
+		let fixed_batch_id = usize::deserialize_canonical(buf)?;
+		let fixed_commitment = Commitment::deserialize_canonical(buf)?;
+		Ok(Self {
+			batches,
+			fixed_batch_id,
+			fixed_commitment,
+		})
+	}
+}
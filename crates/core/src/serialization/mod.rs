@@ -0,0 +1,26 @@
+// Copyright 2024 Ulvetanna Inc.
+
+//! Canonical byte encoding of proofs and verifying keys.
+//!
+//! Every proof type and the field elements inside it implement [`SerializeCanonical`] and
+//! [`DeserializeCanonical`]. The encoding is deterministic and decoding accepts exactly one byte
+//! string per value, rejecting truncated input, out-of-range field elements, unknown enum tags and
+//! non-zero/one booleans with a typed [`Error`]. Proofs sent between processes should be wrapped
+//! with [`serialize_versioned`], which prefixes the [`ENCODING_VERSION`].
+//!
+//! The prover-side state of a commitment, such as the encoded matrices and Merkle tree held in
+//! [`TensorPCS`](crate::poly_commit::tensor_pcs::TensorPCS)'s `Committed`, is encoded the same way.
+//! It can be written with [`save_versioned`] and reloaded with [`load_versioned`] by a later
+//! process that opens the commitment, without encoding the polynomials again.
+
+mod canonical;
+mod committed;
+mod error;
+mod keys;
+mod proofs;
+#[cfg(test)]
+mod tests;
+
+pub use canonical::*;
+pub use error::*;
+pub use proofs::MAX_EVALCHECK_PROOF_DEPTH;
@@ -0,0 +1,451 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	canonical::{
+		deserialize_packed_vec, serialize_packed_slice, take_tag, DeserializeCanonical,
+		SerializeCanonical,
+	},
+	error::Error,
+};
+use crate::{
+	constraint_system::Proof as ConstraintSystemProof,
+	poly_commit::{
+		fri_pcs::Proof as FRIPCSProof, ring_switch::Proof as RingSwitchProof,
+		tensor_pcs::Proof as TensorPCSProof,
+	},
+	polynomial::MultilinearExtension,
+	protocols::{
+		evalcheck::EvalcheckProof,
+		greedy_evalcheck::GreedyEvalcheckProof,
+		prodcheck::{GkrLayerProof, GkrProdcheckProof, GkrProductProof},
+		sumcheck::{SumcheckBatchProof, SumcheckProof, SumcheckRound},
+		zerocheck::{UnivariateSkipProof, ZerocheckProof},
+	},
+};
+use binius_field::{Field, PackedField};
+
+/// Maximum nesting depth accepted when decoding an [`EvalcheckProof`].
+///
+/// The depth of an evalcheck proof follows the depth of the oracle definitions, which is small
+/// in practice. The limit bounds recursion on adversarial input.
+pub const MAX_EVALCHECK_PROOF_DEPTH: usize = 256;
+
+impl<F: SerializeCanonical> SerializeCanonical for SumcheckRound<F> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.coeffs.serialize_canonical(buf);
+	}
+}
+
+impl<F: DeserializeCanonical> DeserializeCanonical for SumcheckRound<F> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		Ok(Self {
+			coeffs: Vec::deserialize_canonical(buf)?,
+		})
+	}
+}
+
+impl<F: SerializeCanonical> SerializeCanonical for SumcheckProof<F> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.rounds.serialize_canonical(buf);
+	}
+}
+
+impl<F: DeserializeCanonical> DeserializeCanonical for SumcheckProof<F> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		Ok(Self {
+			rounds: Vec::deserialize_canonical(buf)?,
+		})
+	}
+}
+
+impl<F: SerializeCanonical> SerializeCanonical for SumcheckBatchProof<F> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.rounds.serialize_canonical(buf);
+		self.evals.serialize_canonical(buf);
+	}
+}
+
+impl<F: DeserializeCanonical> DeserializeCanonical for SumcheckBatchProof<F> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let rounds = Vec::deserialize_canonical(buf)?;
+		let evals = Vec::deserialize_canonical(buf)?;
+		Ok(Self { rounds, evals })
+	}
+}
+
+impl SerializeCanonical for ZerocheckProof {
+	fn serialize_canonical(&self, _buf: &mut Vec<u8>) {}
+}
+
+impl DeserializeCanonical for ZerocheckProof {
+	fn deserialize_canonical(_buf: &mut &[u8]) -> Result<Self, Error> {
+		Ok(ZerocheckProof)
+	}
+}
+
+impl<F: Field + SerializeCanonical> SerializeCanonical for GkrLayerProof<F> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.sumcheck_proof.serialize_canonical(buf);
+		self.zero_eval.serialize_canonical(buf);
+		self.one_eval.serialize_canonical(buf);
+	}
+}
+
+impl<F: Field + DeserializeCanonical> DeserializeCanonical for GkrLayerProof<F> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let sumcheck_proof = SumcheckProof::deserialize_canonical(buf)?;
+		let zero_eval = F::deserialize_canonical(buf)?;
+		let one_eval = F::deserialize_canonical(buf)?;
+		Ok(Self {
+			sumcheck_proof,
+			zero_eval,
+			one_eval,
+		})
+	}
+}
+
+impl<F: Field + SerializeCanonical> SerializeCanonical for GkrProductProof<F> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.product.serialize_canonical(buf);
+		self.layers.serialize_canonical(buf);
+	}
+}
+
+impl<F: Field + DeserializeCanonical> DeserializeCanonical for GkrProductProof<F> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let product = F::deserialize_canonical(buf)?;
+		let layers = Vec::deserialize_canonical(buf)?;
+		Ok(Self { product, layers })
+	}
+}
+
+impl<F: Field + SerializeCanonical> SerializeCanonical for GkrProdcheckProof<F> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.t_proof.serialize_canonical(buf);
+		self.u_proof.serialize_canonical(buf);
+	}
+}
+
+impl<F: Field + DeserializeCanonical> DeserializeCanonical for GkrProdcheckProof<F> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let t_proof = GkrProductProof::deserialize_canonical(buf)?;
+		let u_proof = GkrProductProof::deserialize_canonical(buf)?;
+		Ok(Self { t_proof, u_proof })
+	}
+}
+
+impl<F: Field + SerializeCanonical> SerializeCanonical for UnivariateSkipProof<F> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.skip_round_evals.serialize_canonical(buf);
+		self.sumcheck_proof.serialize_canonical(buf);
+		self.partial_evals.serialize_canonical(buf);
+	}
+}
+
+impl<F: Field + DeserializeCanonical> DeserializeCanonical for UnivariateSkipProof<F> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let skip_round_evals = Vec::deserialize_canonical(buf)?;
+		let sumcheck_proof = SumcheckProof::deserialize_canonical(buf)?;
+		let partial_evals = Vec::<Vec<F>>::deserialize_canonical(buf)?;
+
+		// Every multilinear has one value per point of the skipped hypercube
+		if let Some(first) = partial_evals.first() {
+			if !first.len().is_power_of_two()
+				|| partial_evals.iter().any(|evals| evals.len() != first.len())
+			{
+				return Err(Error::InconsistentDimensions {
+					type_name: "UnivariateSkipProof",
+				});
+			}
+		}
+
+		Ok(Self {
+			skip_round_evals,
+			sumcheck_proof,
+			partial_evals,
+		})
+	}
+}
+
+impl<F: Field + SerializeCanonical> SerializeCanonical for EvalcheckProof<F> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		match self {
+			EvalcheckProof::Transparent => buf.push(0),
+			EvalcheckProof::Committed => buf.push(1),
+			EvalcheckProof::Shifted => buf.push(2),
+			EvalcheckProof::Packed => buf.push(3),
+			EvalcheckProof::Repeating(subproof) => {
+				buf.push(4);
+				subproof.serialize_canonical(buf);
+			}
+			EvalcheckProof::Interleaved {
+				eval1,
+				eval2,
+				subproof1,
+				subproof2,
+			} => {
+				buf.push(5);
+				eval1.serialize_canonical(buf);
+				eval2.serialize_canonical(buf);
+				subproof1.serialize_canonical(buf);
+				subproof2.serialize_canonical(buf);
+			}
+			EvalcheckProof::Merged {
+				eval1,
+				eval2,
+				subproof1,
+				subproof2,
+			} => {
+				buf.push(6);
+				eval1.serialize_canonical(buf);
+				eval2.serialize_canonical(buf);
+				subproof1.serialize_canonical(buf);
+				subproof2.serialize_canonical(buf);
+			}
+			EvalcheckProof::Composite { subproofs } => {
+				buf.push(7);
+				subproofs.len().serialize_canonical(buf);
+				for (eval, subproof) in subproofs {
+					eval.serialize_canonical(buf);
+					subproof.serialize_canonical(buf);
+				}
+			}
+		}
+	}
+}
+
+impl<F: Field + DeserializeCanonical> DeserializeCanonical for EvalcheckProof<F> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		deserialize_evalcheck_proof(buf, 0)
+	}
+}
+
+fn deserialize_evalcheck_proof<F: Field + DeserializeCanonical>(
+	buf: &mut &[u8],
+	depth: usize,
+) -> Result<EvalcheckProof<F>, Error> {
+	if depth > MAX_EVALCHECK_PROOF_DEPTH {
+		return Err(Error::NestingTooDeep {
+			limit: MAX_EVALCHECK_PROOF_DEPTH,
+		});
+	}
+
+	let subproof = |buf: &mut &[u8]| deserialize_evalcheck_proof(buf, depth + 1).map(Box::new);
+
+	let proof = match take_tag(buf)? {
+		0 => EvalcheckProof::Transparent,
+		1 => EvalcheckProof::Committed,
+		2 => EvalcheckProof::Shifted,
+		3 => EvalcheckProof::Packed,
+		4 => EvalcheckProof::Repeating(subproof(buf)?),
+		5 => EvalcheckProof::Interleaved {
+			eval1: F::deserialize_canonical(buf)?,
+			eval2: F::deserialize_canonical(buf)?,
+			subproof1: subproof(buf)?,
+			subproof2: subproof(buf)?,
+		},
+		6 => EvalcheckProof::Merged {
+			eval1: F::deserialize_canonical(buf)?,
+			eval2: F::deserialize_canonical(buf)?,
+			subproof1: subproof(buf)?,
+			subproof2: subproof(buf)?,
+		},
+		7 => {
+			let len = usize::deserialize_canonical(buf)?;
+			let mut subproofs = Vec::with_capacity(len.min(buf.len()));
+			for _ in 0..len {
+				let eval = F::deserialize_canonical(buf)?;
+				subproofs.push((eval, deserialize_evalcheck_proof(buf, depth + 1)?));
+			}
+			EvalcheckProof::Composite { subproofs }
+		}
+		tag => {
+			return Err(Error::InvalidTag {
+				type_name: "EvalcheckProof",
+				tag,
+			})
+		}
+	};
+	Ok(proof)
+}
+
+impl<F: Field + SerializeCanonical> SerializeCanonical for GreedyEvalcheckProof<F> {
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.initial_evalcheck_proofs.serialize_canonical(buf);
+		self.virtual_opening_proofs.serialize_canonical(buf);
+		self.batch_opening_proof.serialize_canonical(buf);
+	}
+}
+
+impl<F: Field + DeserializeCanonical> DeserializeCanonical for GreedyEvalcheckProof<F> {
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let initial_evalcheck_proofs = Vec::deserialize_canonical(buf)?;
+		let virtual_opening_proofs = Vec::deserialize_canonical(buf)?;
+		let batch_opening_proof = Vec::deserialize_canonical(buf)?;
+		Ok(Self {
+			initial_evalcheck_proofs,
+			virtual_opening_proofs,
+			batch_opening_proof,
+		})
+	}
+}
+
+impl<F, PCSComm, PCSProof> SerializeCanonical for ConstraintSystemProof<F, PCSComm, PCSProof>
+where
+	F: Field + SerializeCanonical,
+	PCSComm: SerializeCanonical,
+	PCSProof: SerializeCanonical,
+{
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.commitments.serialize_canonical(buf);
+		self.zerocheck_proof.serialize_canonical(buf);
+		self.sumcheck_proof.serialize_canonical(buf);
+		self.evalcheck_proof.serialize_canonical(buf);
+		self.opening_proofs.serialize_canonical(buf);
+	}
+}
+
+impl<F, PCSComm, PCSProof> DeserializeCanonical for ConstraintSystemProof<F, PCSComm, PCSProof>
+where
+	F: Field + DeserializeCanonical,
+	PCSComm: DeserializeCanonical,
+	PCSProof: DeserializeCanonical,
+{
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let commitments = Vec::deserialize_canonical(buf)?;
+		let zerocheck_proof = ZerocheckProof::deserialize_canonical(buf)?;
+		let sumcheck_proof = SumcheckProof::deserialize_canonical(buf)?;
+		let evalcheck_proof = GreedyEvalcheckProof::deserialize_canonical(buf)?;
+		let opening_proofs = Vec::deserialize_canonical(buf)?;
+		Ok(Self {
+			commitments,
+			zerocheck_proof,
+			sumcheck_proof,
+			evalcheck_proof,
+			opening_proofs,
+		})
+	}
+}
+
+impl<PI, PE, VCSProof> SerializeCanonical for TensorPCSProof<'_, PI, PE, VCSProof>
+where
+	PI: PackedField,
+	PI::Scalar: SerializeCanonical,
+	PE: PackedField,
+	PE::Scalar: SerializeCanonical,
+	VCSProof: SerializeCanonical,
+{
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.n_polys.serialize_canonical(buf);
+		serialize_packed_slice(self.mixed_t_prime.evals(), buf);
+
+		self.opened_columns.len().serialize_canonical(buf);
+		for cols in &self.opened_columns {
+			cols.len().serialize_canonical(buf);
+			for col in cols {
+				serialize_packed_slice(col, buf);
+			}
+		}
+		self.vcs_proof.serialize_canonical(buf);
+	}
+}
+
+impl<PI, PE, VCSProof> DeserializeCanonical for TensorPCSProof<'static, PI, PE, VCSProof>
+where
+	PI: PackedField,
+	PI::Scalar: DeserializeCanonical,
+	PE: PackedField,
+	PE::Scalar: DeserializeCanonical,
+	VCSProof: DeserializeCanonical,
+{
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let n_polys = usize::deserialize_canonical(buf)?;
+		let mixed_t_prime = MultilinearExtension::from_values(deserialize_packed_vec(buf)?)?;
+
+		let n_queries = usize::deserialize_canonical(buf)?;
+		let mut opened_columns = Vec::with_capacity(n_queries.min(buf.len()));
+		for _ in 0..n_queries {
+			let n_cols = usize::deserialize_canonical(buf)?;
+			let mut cols = Vec::with_capacity(n_cols.min(buf.len()));
+			for _ in 0..n_cols {
+				cols.push(deserialize_packed_vec(buf)?);
+			}
+			opened_columns.push(cols);
+		}
+		let vcs_proof = VCSProof::deserialize_canonical(buf)?;
+
+		Ok(Self {
+			n_polys,
+			mixed_t_prime,
+			opened_columns,
+			vcs_proof,
+		})
+	}
+}
+
+impl<F, Digest> SerializeCanonical for FRIPCSProof<F, Digest>
+where
+	F: SerializeCanonical,
+	Digest: SerializeCanonical,
+{
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.n_polys.serialize_canonical(buf);
+		self.sumcheck_rounds.serialize_canonical(buf);
+		self.round_commitments.serialize_canonical(buf);
+		self.final_value.serialize_canonical(buf);
+		self.query_proofs.serialize_canonical(buf);
+	}
+}
+
+impl<F, Digest> DeserializeCanonical for FRIPCSProof<F, Digest>
+where
+	F: DeserializeCanonical,
+	Digest: DeserializeCanonical,
+{
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let n_polys = usize::deserialize_canonical(buf)?;
+		let sumcheck_rounds = Vec::deserialize_canonical(buf)?;
+		let round_commitments = Vec::deserialize_canonical(buf)?;
+		let final_value = F::deserialize_canonical(buf)?;
+		let query_proofs = Vec::deserialize_canonical(buf)?;
+		Ok(Self {
+			n_polys,
+			sumcheck_rounds,
+			round_commitments,
+			final_value,
+			query_proofs,
+		})
+	}
+}
+
+impl<F, InnerProof> SerializeCanonical for RingSwitchProof<F, InnerProof>
+where
+	F: SerializeCanonical,
+	InnerProof: SerializeCanonical,
+{
+	fn serialize_canonical(&self, buf: &mut Vec<u8>) {
+		self.partial_evals.serialize_canonical(buf);
+		self.sumcheck_rounds.serialize_canonical(buf);
+		self.packed_evals.serialize_canonical(buf);
+		self.inner_proof.serialize_canonical(buf);
+	}
+}
+
+impl<F, InnerProof> DeserializeCanonical for RingSwitchProof<F, InnerProof>
+where
+	F: DeserializeCanonical,
+	InnerProof: DeserializeCanonical,
+{
+	fn deserialize_canonical(buf: &mut &[u8]) -> Result<Self, Error> {
+		let partial_evals = Vec::deserialize_canonical(buf)?;
+		let sumcheck_rounds = Vec::deserialize_canonical(buf)?;
+		let packed_evals = Vec::deserialize_canonical(buf)?;
+		let inner_proof = InnerProof::deserialize_canonical(buf)?;
+		Ok(Self {
+			partial_evals,
+			sumcheck_rounds,
+			packed_evals,
+			inner_proof,
+		})
+	}
+}
@@ -0,0 +1,510 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{
+	deserialize_versioned, load_versioned, save_versioned, serialize_versioned,
+	DeserializeCanonical, Error, SerializeCanonical, ENCODING_VERSION, MAX_EVALCHECK_PROOF_DEPTH,
+};
+use crate::{
+	constraint_system::Proof as ConstraintSystemProof,
+	merkle_tree::{MerkleCap, MerkleTree, MerkleTreeVCS, VectorCommitScheme},
+	oracle::CommittedBatchSpec,
+	poly_commit::{
+		fri_pcs::Proof as FRIPCSProof, ring_switch::Proof as RingSwitchProof,
+		tensor_pcs::Proof as TensorPCSProof,
+	},
+	polynomial::MultilinearExtension,
+	protocols::{
+		evalcheck::EvalcheckProof,
+		greedy_evalcheck::GreedyEvalcheckProof,
+		prodcheck::{GkrLayerProof, GkrProdcheckProof, GkrProductProof},
+		sumcheck::{SumcheckBatchProof, SumcheckProof, SumcheckRound},
+		zerocheck::{UnivariateSkipProof, ZerocheckProof},
+	},
+	setup::VerifyingKey,
+};
+use assert_matches::assert_matches;
+use binius_field::{
+	BinaryField128b, BinaryField128bPolyval, BinaryField1b, BinaryField8b, Field,
+	PackedBinaryField16x8b, PackedField,
+};
+use binius_hash::{GroestlDigest, GroestlDigestCompression, GroestlHasher};
+use p3_matrix::dense::RowMajorMatrix;
+use proptest::{collection::vec, prelude::*, sample::Index};
+use std::{fmt::Debug, fs, iter::repeat_with, process};
+
+type F = BinaryField128b;
+
+fn arb_field() -> impl Strategy<Value = F> {
+	any::<u128>().prop_map(F::new)
+}
+
+fn arb_sumcheck_round() -> impl Strategy<Value = SumcheckRound<F>> {
+	vec(arb_field(), 0..4).prop_map(|coeffs| SumcheckRound { coeffs })
+}
+
+fn arb_sumcheck_proof() -> impl Strategy<Value = SumcheckProof<F>> {
+	vec(arb_sumcheck_round(), 0..8).prop_map(|rounds| SumcheckProof { rounds })
+}
+
+fn arb_sumcheck_batch_proof() -> impl Strategy<Value = SumcheckBatchProof<F>> {
+	(vec(arb_sumcheck_round(), 0..8), vec(arb_field(), 0..4))
+		.prop_map(|(rounds, evals)| SumcheckBatchProof { rounds, evals })
+}
+
+fn arb_evalcheck_proof() -> impl Strategy<Value = EvalcheckProof<F>> {
+	let leaf = prop_oneof![
+		Just(EvalcheckProof::Transparent),
+		Just(EvalcheckProof::Committed),
+		Just(EvalcheckProof::Shifted),
+		Just(EvalcheckProof::Packed),
+	];
+	leaf.prop_recursive(4, 32, 4, |inner| {
+		prop_oneof![
+			inner
+				.clone()
+				.prop_map(|subproof| EvalcheckProof::Repeating(Box::new(subproof))),
+			(arb_field(), arb_field(), inner.clone(), inner.clone()).prop_map(
+				|(eval1, eval2, subproof1, subproof2)| EvalcheckProof::Interleaved {
+					eval1,
+					eval2,
+					subproof1: Box::new(subproof1),
+					subproof2: Box::new(subproof2),
+				}
+			),
+			(arb_field(), arb_field(), inner.clone(), inner.clone()).prop_map(
+				|(eval1, eval2, subproof1, subproof2)| EvalcheckProof::Merged {
+					eval1,
+					eval2,
+					subproof1: Box::new(subproof1),
+					subproof2: Box::new(subproof2),
+				}
+			),
+			vec((arb_field(), inner), 0..4)
+				.prop_map(|subproofs| EvalcheckProof::Composite { subproofs }),
+		]
+	})
+}
+
+fn arb_opening_proof() -> impl Strategy<Value = (SumcheckBatchProof<F>, Vec<EvalcheckProof<F>>)> {
+	(arb_sumcheck_batch_proof(), vec(arb_evalcheck_proof(), 0..3))
+}
+
+fn arb_greedy_evalcheck_proof() -> impl Strategy<Value = GreedyEvalcheckProof<F>> {
+	(
+		vec(arb_evalcheck_proof(), 0..3),
+		vec(arb_opening_proof(), 0..3),
+		vec(proptest::option::of(arb_opening_proof()), 0..3),
+	)
+		.prop_map(|(initial_evalcheck_proofs, virtual_opening_proofs, batch_opening_proof)| {
+			GreedyEvalcheckProof {
+				initial_evalcheck_proofs,
+				virtual_opening_proofs,
+				batch_opening_proof,
+			}
+		})
+}
+
+fn arb_gkr_product_proof() -> impl Strategy<Value = GkrProductProof<F>> {
+	let arb_layer = (arb_sumcheck_proof(), arb_field(), arb_field()).prop_map(
+		|(sumcheck_proof, zero_eval, one_eval)| GkrLayerProof {
+			sumcheck_proof,
+			zero_eval,
+			one_eval,
+		},
+	);
+	(arb_field(), vec(arb_layer, 0..4))
+		.prop_map(|(product, layers)| GkrProductProof { product, layers })
+}
+
+fn arb_gkr_prodcheck_proof() -> impl Strategy<Value = GkrProdcheckProof<F>> {
+	(arb_gkr_product_proof(), arb_gkr_product_proof())
+		.prop_map(|(t_proof, u_proof)| GkrProdcheckProof { t_proof, u_proof })
+}
+
+fn arb_univariate_skip_proof() -> impl Strategy<Value = UnivariateSkipProof<F>> {
+	(0..4usize)
+		.prop_flat_map(|skip_rounds| {
+			(
+				vec(arb_field(), 0..8),
+				arb_sumcheck_proof(),
+				vec(vec(arb_field(), 1 << skip_rounds), 0..4),
+			)
+		})
+		.prop_map(|(skip_round_evals, sumcheck_proof, partial_evals)| UnivariateSkipProof {
+			skip_round_evals,
+			sumcheck_proof,
+			partial_evals,
+		})
+}
+
+fn arb_packed_8b() -> impl Strategy<Value = PackedBinaryField16x8b> {
+	any::<[u8; 16]>()
+		.prop_map(|bytes| PackedBinaryField16x8b::from_fn(|i| BinaryField8b::new(bytes[i])))
+}
+
+fn arb_digest() -> impl Strategy<Value = GroestlDigest> {
+	any::<[u8; 32]>().prop_map(|bytes| GroestlDigest::from_fn(|i| BinaryField8b::new(bytes[i])))
+}
+
+type TestTensorPCSProof = TensorPCSProof<'static, PackedBinaryField16x8b, F, Vec<GroestlDigest>>;
+
+fn arb_tensor_pcs_proof() -> impl Strategy<Value = TestTensorPCSProof> {
+	(0..8usize, 0..4usize)
+		.prop_flat_map(|(n_polys, log_len)| {
+			(
+				Just(n_polys),
+				vec(arb_field(), 1 << log_len),
+				vec(vec(vec(arb_packed_8b(), 0..3), 0..3), 0..4),
+				vec(arb_digest(), 0..8),
+			)
+		})
+		.prop_map(|(n_polys, mixed_t_prime, opened_columns, vcs_proof)| TensorPCSProof {
+			n_polys,
+			mixed_t_prime: MultilinearExtension::from_values(mixed_t_prime).unwrap(),
+			opened_columns,
+			vcs_proof,
+		})
+}
+
+fn arb_fri_pcs_proof() -> impl Strategy<Value = FRIPCSProof<F, GroestlDigest>> {
+	(
+		0..8usize,
+		vec((arb_field(), arb_field()), 0..4),
+		vec(arb_digest(), 0..4),
+		arb_field(),
+		vec(vec((vec(arb_field(), 0..4), vec(arb_digest(), 0..4)), 0..4), 0..3),
+	)
+		.prop_map(|(n_polys, sumcheck_rounds, round_commitments, final_value, query_proofs)| {
+			FRIPCSProof {
+				n_polys,
+				sumcheck_rounds,
+				round_commitments,
+				final_value,
+				query_proofs,
+			}
+		})
+}
+
+type TestRingSwitchProof = RingSwitchProof<F, FRIPCSProof<F, GroestlDigest>>;
+
+fn arb_ring_switch_proof() -> impl Strategy<Value = TestRingSwitchProof> {
+	(
+		vec(vec(arb_field(), 0..4), 0..3),
+		vec((arb_field(), arb_field()), 0..4),
+		vec(arb_field(), 0..3),
+		arb_fri_pcs_proof(),
+	)
+		.prop_map(|(partial_evals, sumcheck_rounds, packed_evals, inner_proof)| RingSwitchProof {
+			partial_evals,
+			sumcheck_rounds,
+			packed_evals,
+			inner_proof,
+		})
+}
+
+type TestConstraintSystemProof =
+	ConstraintSystemProof<F, GroestlDigest, FRIPCSProof<F, GroestlDigest>>;
+
+fn arb_constraint_system_proof() -> impl Strategy<Value = TestConstraintSystemProof> {
+	(
+		vec(vec(arb_digest(), 0..3), 0..3),
+		arb_sumcheck_proof(),
+		arb_greedy_evalcheck_proof(),
+		vec(arb_fri_pcs_proof(), 0..3),
+	)
+		.prop_map(|(commitments, sumcheck_proof, evalcheck_proof, opening_proofs)| {
+			ConstraintSystemProof {
+				commitments,
+				zerocheck_proof: ZerocheckProof,
+				sumcheck_proof,
+				evalcheck_proof,
+				opening_proofs,
+			}
+		})
+}
+
+fn arb_verifying_key() -> impl Strategy<Value = VerifyingKey<GroestlDigest>> {
+	let arb_batch_spec = (0..4usize, 0..32usize, 0..64usize, 0..8usize).prop_map(
+		|(round_id, n_vars, n_polys, tower_level)| CommittedBatchSpec {
+			round_id,
+			n_vars,
+			n_polys,
+			tower_level,
+		},
+	);
+	(vec(arb_batch_spec, 0..4), 0..4usize, arb_digest()).prop_map(
+		|(batches, fixed_batch_id, fixed_commitment)| VerifyingKey {
+			batches,
+			fixed_batch_id,
+			fixed_commitment,
+		},
+	)
+}
+
+fn check_round_trip<T>(value: &T, cut: Index)
+where
+	T: SerializeCanonical + DeserializeCanonical + PartialEq + Debug,
+{
+	let bytes = serialize_versioned(value);
+	assert_eq!(&deserialize_versioned::<T>(&bytes).unwrap(), value);
+
+	// The encoding is prefix-free, so any strict prefix must be rejected as truncated
+	let truncated = &bytes[..cut.index(bytes.len())];
+	assert_matches!(deserialize_versioned::<T>(truncated), Err(Error::Truncated { .. }));
+
+	let mut extended = bytes.clone();
+	extended.push(0);
+	assert_matches!(deserialize_versioned::<T>(&extended), Err(Error::TrailingBytes { count: 1 }));
+}
+
+proptest! {
+	#[test]
+	fn test_sumcheck_proof_round_trip(proof in arb_sumcheck_proof(), cut in any::<Index>()) {
+		check_round_trip(&proof, cut);
+	}
+
+	#[test]
+	fn test_sumcheck_batch_proof_round_trip(
+		proof in arb_sumcheck_batch_proof(),
+		cut in any::<Index>(),
+	) {
+		check_round_trip(&proof, cut);
+	}
+
+	#[test]
+	fn test_evalcheck_proof_round_trip(proof in arb_evalcheck_proof(), cut in any::<Index>()) {
+		check_round_trip(&proof, cut);
+	}
+
+	#[test]
+	fn test_greedy_evalcheck_proof_round_trip(
+		proof in arb_greedy_evalcheck_proof(),
+		cut in any::<Index>(),
+	) {
+		check_round_trip(&proof, cut);
+	}
+
+	#[test]
+	fn test_gkr_prodcheck_proof_round_trip(
+		proof in arb_gkr_prodcheck_proof(),
+		cut in any::<Index>(),
+	) {
+		check_round_trip(&proof, cut);
+	}
+
+	#[test]
+	fn test_zerocheck_proof_round_trip(cut in any::<Index>()) {
+		check_round_trip(&ZerocheckProof, cut);
+	}
+
+	#[test]
+	fn test_univariate_skip_proof_round_trip(
+		proof in arb_univariate_skip_proof(),
+		cut in any::<Index>(),
+	) {
+		check_round_trip(&proof, cut);
+	}
+
+	#[test]
+	fn test_tensor_pcs_proof_round_trip(proof in arb_tensor_pcs_proof(), cut in any::<Index>()) {
+		check_round_trip(&proof, cut);
+	}
+
+	#[test]
+	fn test_fri_pcs_proof_round_trip(proof in arb_fri_pcs_proof(), cut in any::<Index>()) {
+		check_round_trip(&proof, cut);
+	}
+
+	#[test]
+	fn test_ring_switch_proof_round_trip(proof in arb_ring_switch_proof(), cut in any::<Index>()) {
+		check_round_trip(&proof, cut);
+	}
+
+	#[test]
+	fn test_constraint_system_proof_round_trip(
+		proof in arb_constraint_system_proof(),
+		cut in any::<Index>(),
+	) {
+		check_round_trip(&proof, cut);
+	}
+
+	#[test]
+	fn test_merkle_cap_round_trip(nodes in vec(arb_digest(), 0..8), cut in any::<Index>()) {
+		check_round_trip(&MerkleCap(nodes), cut);
+	}
+
+	#[test]
+	fn test_verifying_key_round_trip(key in arb_verifying_key(), cut in any::<Index>()) {
+		check_round_trip(&key, cut);
+	}
+
+	#[test]
+	fn test_polyval_round_trip(value in any::<u128>()) {
+		let elem = BinaryField128bPolyval::new(value);
+		let bytes = serialize_versioned(&elem);
+		assert_eq!(deserialize_versioned::<BinaryField128bPolyval>(&bytes).unwrap(), elem);
+	}
+}
+
+#[test]
+fn test_rejects_unsupported_version() {
+	let mut bytes = serialize_versioned(&ZerocheckProof);
+	bytes[0] ^= 1;
+	assert_matches!(
+		deserialize_versioned::<ZerocheckProof>(&bytes),
+		Err(Error::UnsupportedVersion { found, expected: ENCODING_VERSION }) if found != ENCODING_VERSION
+	);
+}
+
+#[test]
+fn test_rejects_non_canonical_field_element() {
+	let mut buf = Vec::new();
+	2u8.serialize_canonical(&mut buf);
+	assert_matches!(
+		BinaryField1b::deserialize_canonical(&mut buf.as_slice()),
+		Err(Error::NonCanonical { .. })
+	);
+
+	let mut buf = Vec::new();
+	BinaryField1b::new(1).serialize_canonical(&mut buf);
+	assert_eq!(
+		BinaryField1b::deserialize_canonical(&mut buf.as_slice()).unwrap(),
+		BinaryField1b::new(1)
+	);
+}
+
+#[test]
+fn test_rejects_invalid_tags() {
+	let bytes = [8u8];
+	assert_matches!(
+		EvalcheckProof::<F>::deserialize_canonical(&mut bytes.as_slice()),
+		Err(Error::InvalidTag { tag: 8, .. })
+	);
+
+	let bytes = [2u8];
+	assert_matches!(
+		bool::deserialize_canonical(&mut bytes.as_slice()),
+		Err(Error::NonCanonical { .. })
+	);
+	assert_matches!(
+		Option::<F>::deserialize_canonical(&mut bytes.as_slice()),
+		Err(Error::InvalidTag { tag: 2, .. })
+	);
+}
+
+#[test]
+fn test_rejects_deeply_nested_evalcheck_proof() {
+	// A chain of Repeating tags terminated by a Transparent leaf
+	let mut bytes = vec![4u8; MAX_EVALCHECK_PROOF_DEPTH + 1];
+	bytes.push(0);
+	assert_matches!(
+		EvalcheckProof::<F>::deserialize_canonical(&mut bytes.as_slice()),
+		Err(Error::NestingTooDeep { .. })
+	);
+
+	let mut bytes = vec![4u8; MAX_EVALCHECK_PROOF_DEPTH];
+	bytes.push(0);
+	assert!(EvalcheckProof::<F>::deserialize_canonical(&mut bytes.as_slice()).is_ok());
+}
+
+#[test]
+fn test_rejects_non_power_of_two_mixed_t_prime() {
+	let mut buf = Vec::new();
+	ENCODING_VERSION.serialize_canonical(&mut buf);
+	// n_polys
+	1usize.serialize_canonical(&mut buf);
+	// mixed_t_prime with three evaluations
+	vec![F::ONE; 3].serialize_canonical(&mut buf);
+	// no queries
+	0usize.serialize_canonical(&mut buf);
+	assert_matches!(deserialize_versioned::<TestTensorPCSProof>(&buf), Err(Error::Polynomial(_)));
+}
+
+#[test]
+fn test_rejects_inconsistent_partial_evals() {
+	let proof = |partial_evals| UnivariateSkipProof {
+		skip_round_evals: vec![F::ONE],
+		sumcheck_proof: SumcheckProof { rounds: vec![] },
+		partial_evals,
+	};
+	for partial_evals in [
+		vec![vec![F::ONE; 2], vec![F::ONE; 4]],
+		vec![vec![F::ONE; 3]],
+	] {
+		let bytes = serialize_versioned(&proof(partial_evals));
+		assert_matches!(
+			deserialize_versioned::<UnivariateSkipProof<F>>(&bytes),
+			Err(Error::InconsistentDimensions {
+				type_name: "UnivariateSkipProof"
+			})
+		);
+	}
+}
+
+fn committed_state() -> (Vec<RowMajorMatrix<PackedBinaryField16x8b>>, MerkleTree<GroestlDigest>) {
+	let matrices = (0..2u8)
+		.map(|i| {
+			let values = (0..32u8)
+				.map(|j| PackedBinaryField16x8b::broadcast(BinaryField8b::new(i ^ j)))
+				.collect();
+			RowMajorMatrix::new(values, 2)
+		})
+		.collect::<Vec<_>>();
+
+	let vcs =
+		<MerkleTreeVCS<_, _, GroestlHasher<_>, _>>::with_cap_height(4, 1, GroestlDigestCompression)
+			.unwrap();
+	let leaves = repeat_with(|| vec![GroestlDigest::default(); 16]).take(2);
+	let (_, tree) = vcs.commit_batch(leaves).unwrap();
+	(matrices, tree)
+}
+
+#[test]
+fn test_committed_state_file_round_trip() {
+	let committed = committed_state();
+
+	let path = std::env::temp_dir().join(format!("binius_committed_{}.bin", process::id()));
+	save_versioned(&path, &committed).unwrap();
+	let loaded = load_versioned::<(
+		Vec<RowMajorMatrix<PackedBinaryField16x8b>>,
+		MerkleTree<GroestlDigest>,
+	)>(&path);
+	fs::remove_file(&path).unwrap();
+
+	let (matrices, tree) = loaded.unwrap();
+	assert_eq!(tree, committed.1);
+	assert_eq!(matrices.len(), committed.0.len());
+	for (loaded, original) in matrices.iter().zip(&committed.0) {
+		assert_eq!(loaded.width, original.width);
+		assert_eq!(loaded.values, original.values);
+	}
+
+	assert_matches!(load_versioned::<MerkleTree<GroestlDigest>>(&path), Err(Error::Io(_)));
+}
+
+#[test]
+fn test_rejects_inconsistent_committed_state() {
+	let (matrices, mut tree) = committed_state();
+
+	tree.inner_nodes.pop();
+	assert_matches!(
+		deserialize_versioned::<MerkleTree<GroestlDigest>>(&serialize_versioned(&tree)),
+		Err(Error::InconsistentDimensions {
+			type_name: "MerkleTree"
+		})
+	);
+
+	let mut buf = Vec::new();
+	ENCODING_VERSION.serialize_canonical(&mut buf);
+	// width
+	3usize.serialize_canonical(&mut buf);
+	// values
+	matrices[0].values.len().serialize_canonical(&mut buf);
+	buf.extend(vec![0u8; matrices[0].values.len() * PackedBinaryField16x8b::WIDTH]);
+	assert_matches!(
+		deserialize_versioned::<RowMajorMatrix<PackedBinaryField16x8b>>(&buf),
+		Err(Error::InconsistentDimensions {
+			type_name: "RowMajorMatrix"
+		})
+	);
+}
@@ -0,0 +1,378 @@
+// Copyright 2024 Ulvetanna Inc.
+
+//! Preprocessing of the fixed columns of a constraint system.
+//!
+//! Fixed columns, like round constants and selectors, are the same for every proof of a
+//! constraint system. Instead of being derived as transparent oracles on every run, they are
+//! committed once during [`setup`] in the precommitment batch of round 0. The prover reuses the
+//! resulting [`ProvingKey`] across proofs, and the verifier only needs the [`VerifyingKey`], which
+//! holds the commitment and the shape of the committed batches and can be serialized.
+
+use crate::{
+	oracle::{BatchId, CommittedBatch, CommittedBatchSpec, MultilinearOracleSet},
+	poly_commit::{Error, PolyCommitScheme},
+	polynomial::MultilinearExtension,
+};
+use binius_field::{ExtensionField, PackedField, TowerField};
+
+/// The preprocessed data needed to verify proofs for a constraint system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyingKey<Commitment> {
+	/// Specifications of all committed batches, in batch ID order
+	pub batches: Vec<CommittedBatchSpec>,
+	/// ID of the precommitted batch holding the fixed columns
+	pub fixed_batch_id: BatchId,
+	/// Commitment to the fixed columns
+	pub fixed_commitment: Commitment,
+}
+
+impl<Commitment> VerifyingKey<Commitment> {
+	/// Checks that the committed batches of an oracle set have the shape recorded in the key.
+	pub fn check_oracles<F: TowerField>(
+		&self,
+		oracles: &MultilinearOracleSet<F>,
+	) -> Result<(), Error> {
+		let batches = oracles.committed_batches();
+		let matches = batches.len() == self.batches.len()
+			&& batches
+				.iter()
+				.zip(self.batches.iter())
+				.all(|(batch, spec)| {
+					batch.round_id == spec.round_id
+						&& batch.n_vars == spec.n_vars
+						&& batch.n_polys == spec.n_polys
+						&& batch.tower_level == spec.tower_level
+				});
+		if !matches {
+			return Err(Error::VerifyingKeyMismatch);
+		}
+		check_fixed_batch(&batches, self.fixed_batch_id)?;
+		Ok(())
+	}
+}
+
+/// The preprocessed data needed to prove statements about a constraint system.
+pub struct ProvingKey<P, FE, PCS>
+where
+	P: PackedField,
+	FE: ExtensionField<P::Scalar>,
+	PCS: PolyCommitScheme<P, FE>,
+{
+	verifying_key: VerifyingKey<PCS::Commitment>,
+	fixed_columns: Vec<MultilinearExtension<'static, P>>,
+	fixed_committed: PCS::Committed,
+}
+
+impl<P, FE, PCS> ProvingKey<P, FE, PCS>
+where
+	P: PackedField,
+	FE: ExtensionField<P::Scalar>,
+	PCS: PolyCommitScheme<P, FE>,
+{
+	pub fn verifying_key(&self) -> &VerifyingKey<PCS::Commitment> {
+		&self.verifying_key
+	}
+
+	/// The fixed columns, in the order of the fixed batch.
+	pub fn fixed_columns(&self) -> &[MultilinearExtension<'static, P>] {
+		&self.fixed_columns
+	}
+
+	pub fn fixed_committed(&self) -> &PCS::Committed {
+		&self.fixed_committed
+	}
+}
+
+/// Commits to the fixed columns of a constraint system and derives its proving and verifying keys.
+///
+/// The oracle set must already contain all committed batches of the constraint system. The batch
+/// `fixed_batch_id` must be the only batch of round 0 and hold exactly the given fixed columns.
+#[allow(clippy::type_complexity)]
+pub fn setup<F, P, FE, PCS>(
+	pcs: &PCS,
+	oracles: &MultilinearOracleSet<F>,
+	fixed_batch_id: BatchId,
+	fixed_columns: Vec<MultilinearExtension<'static, P>>,
+) -> Result<(ProvingKey<P, FE, PCS>, VerifyingKey<PCS::Commitment>), Error>
+where
+	F: TowerField,
+	P: PackedField,
+	FE: ExtensionField<P::Scalar>,
+	PCS: PolyCommitScheme<P, FE>,
+{
+	let batches = oracles.committed_batches();
+	let fixed_batch = check_fixed_batch(&batches, fixed_batch_id)?;
+	if fixed_columns.len() != fixed_batch.n_polys {
+		return Err(Error::NumBatchedMismatchError {
+			err_str: format!(
+				"In setup: fixed batch has {} polynomials, got {} fixed columns",
+				fixed_batch.n_polys,
+				fixed_columns.len()
+			),
+		});
+	}
+
+	let (fixed_commitment, fixed_committed) = pcs
+		.commit(&fixed_columns)
+		.map_err(|err| Error::PolyCommit(Box::new(err)))?;
+
+	let verifying_key = VerifyingKey {
+		batches: batches
+			.into_iter()
+			.map(|batch| CommittedBatchSpec {
+				round_id: batch.round_id,
+				n_vars: batch.n_vars,
+				n_polys: batch.n_polys,
+				tower_level: batch.tower_level,
+			})
+			.collect(),
+		fixed_batch_id,
+		fixed_commitment,
+	};
+	let proving_key = ProvingKey {
+		verifying_key: verifying_key.clone(),
+		fixed_columns,
+		fixed_committed,
+	};
+	Ok((proving_key, verifying_key))
+}
+
+/// Checks that the fixed batch exists and is the only batch of round 0.
+fn check_fixed_batch(
+	batches: &[CommittedBatch],
+	fixed_batch_id: BatchId,
+) -> Result<&CommittedBatch, Error> {
+	let fixed_batch = batches.get(fixed_batch_id).ok_or(Error::UncommittedBatch {
+		batch_id: fixed_batch_id,
+	})?;
+	if fixed_batch.round_id != 0
+		|| batches
+			.iter()
+			.any(|batch| batch.round_id == 0 && batch.id != fixed_batch_id)
+	{
+		return Err(Error::InvalidFixedBatch {
+			batch_id: fixed_batch_id,
+		});
+	}
+	Ok(fixed_batch)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		challenger::{CanSample, CanSampleBits, HashChallenger},
+		oracle::{CommittedId, CompositePolyOracle},
+		poly_commit::{MultiRoundProver, MultiRoundVerifier, FRIPCS},
+		polynomial::{composition::BivariateProduct, MultilinearQuery},
+		protocols::{evalcheck::EvalcheckClaim, greedy_evalcheck},
+		serialization::{deserialize_versioned, serialize_versioned},
+		witness::MultilinearWitnessIndex,
+	};
+	use assert_matches::assert_matches;
+	use binius_field::{BinaryField128b, BinaryField16b, PackedBinaryField1x128b};
+	use binius_hash::{GroestlDigestCompression, GroestlHasher};
+	use rand::{rngs::StdRng, SeedableRng};
+	use std::iter::repeat_with;
+
+	type F = BinaryField128b;
+	type P = PackedBinaryField1x128b;
+	type TestPCS = FRIPCS<P, BinaryField16b, F, GroestlHasher<F>, GroestlDigestCompression>;
+
+	const N_VARS: usize = 6;
+
+	/// An oracle set with a fixed batch in round 0 and a trace batch in round 1.
+	fn make_oracles() -> (MultilinearOracleSet<F>, BatchId, BatchId) {
+		let mut oracles = MultilinearOracleSet::new();
+		let fixed_batch = oracles.add_committed_batch(CommittedBatchSpec {
+			round_id: 0,
+			n_vars: N_VARS,
+			n_polys: 1,
+			tower_level: F::TOWER_LEVEL,
+		});
+		let trace_batch = oracles.add_committed_batch(CommittedBatchSpec {
+			round_id: 1,
+			n_vars: N_VARS,
+			n_polys: 1,
+			tower_level: F::TOWER_LEVEL,
+		});
+		(oracles, fixed_batch, trace_batch)
+	}
+
+	fn make_claim(
+		oracles: &MultilinearOracleSet<F>,
+		fixed_batch: BatchId,
+		trace_batch: BatchId,
+		eval_point: Vec<F>,
+		eval: F,
+	) -> EvalcheckClaim<F> {
+		let polys = [fixed_batch, trace_batch]
+			.into_iter()
+			.map(|batch_id| oracles.committed_oracle(CommittedId { batch_id, index: 0 }))
+			.collect();
+		EvalcheckClaim {
+			poly: CompositePolyOracle::new(N_VARS, polys, BivariateProduct).unwrap(),
+			eval_point,
+			eval,
+			is_random_point: true,
+		}
+	}
+
+	fn random_poly(rng: &mut StdRng) -> MultilinearExtension<'static, P> {
+		MultilinearExtension::from_values(
+			repeat_with(|| <P as PackedField>::random(&mut *rng))
+				.take(1 << N_VARS)
+				.collect(),
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn test_prove_verify_with_keys() {
+		let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+		let mut rng = StdRng::seed_from_u64(0);
+
+		let (mut oracles, fixed_batch, trace_batch) = make_oracles();
+		let fixed = random_poly(&mut rng);
+		let (proving_key, verifying_key) =
+			setup(&pcs, &oracles, fixed_batch, vec![fixed.clone()]).unwrap();
+
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		let mut prover =
+			MultiRoundProver::with_proving_key(&pcs, &oracles, &proving_key, &mut challenger)
+				.unwrap();
+		let trace = random_poly(&mut rng);
+		let trace_commitments = prover
+			.commit_round(&mut challenger, 1, vec![vec![trace.clone()]])
+			.unwrap();
+
+		let eval_point: Vec<F> = challenger.sample_vec(N_VARS);
+		let query = MultilinearQuery::<P>::with_full_query(&eval_point).unwrap();
+		let eval =
+			fixed.evaluate::<F, _>(&query).unwrap() * trace.evaluate::<F, _>(&query).unwrap();
+
+		let mut witness_index = MultilinearWitnessIndex::<F>::new();
+		for (batch_id, poly) in [(fixed_batch, fixed), (trace_batch, trace)] {
+			let oracle_id = oracles.committed_oracle_id(CommittedId { batch_id, index: 0 });
+			witness_index.set(oracle_id, poly.specialize_arc_dyn());
+		}
+
+		let claim = make_claim(&oracles, fixed_batch, trace_batch, eval_point.clone(), eval);
+		let output = greedy_evalcheck::prove(
+			&mut oracles,
+			&mut witness_index,
+			[claim],
+			|_| 1,
+			&mut challenger,
+		)
+		.unwrap();
+		let opening_proofs = prover
+			.prove_openings(&mut challenger, &output.same_query_claims)
+			.unwrap();
+		let prover_sample = CanSampleBits::<usize>::sample_bits(&mut challenger, 32);
+
+		// The verifier only receives the serialized verifying key
+		let verifying_key_bytes = serialize_versioned(&verifying_key);
+		let verifying_key = deserialize_versioned(&verifying_key_bytes).unwrap();
+
+		let (mut oracles, fixed_batch, trace_batch) = make_oracles();
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		let mut verifier =
+			MultiRoundVerifier::with_verifying_key(&pcs, &oracles, &verifying_key, &mut challenger)
+				.unwrap();
+		verifier
+			.observe_round(&mut challenger, 1, trace_commitments)
+			.unwrap();
+
+		let verifier_eval_point: Vec<F> = challenger.sample_vec(N_VARS);
+		assert_eq!(verifier_eval_point, eval_point);
+
+		let claim = make_claim(&oracles, fixed_batch, trace_batch, verifier_eval_point, eval);
+		let same_query_claims =
+			greedy_evalcheck::verify(&mut oracles, [claim], output.proof, &mut challenger).unwrap();
+		verifier
+			.verify_openings(&mut challenger, &same_query_claims, opening_proofs)
+			.unwrap();
+		assert_eq!(CanSampleBits::<usize>::sample_bits(&mut challenger, 32), prover_sample);
+	}
+
+	#[test]
+	fn test_setup_rejects_invalid_fixed_batch() {
+		let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+		let (oracles, fixed_batch, trace_batch) = make_oracles();
+		let poly = MultilinearExtension::<P>::zeros(N_VARS).unwrap();
+
+		assert_matches!(
+			setup(&pcs, &oracles, trace_batch, vec![poly.clone()]),
+			Err(Error::InvalidFixedBatch { batch_id }) if batch_id == trace_batch
+		);
+		assert_matches!(
+			setup(&pcs, &oracles, fixed_batch, vec![poly.clone(), poly]),
+			Err(Error::NumBatchedMismatchError { .. })
+		);
+	}
+
+	#[test]
+	fn test_verifying_key_mismatch() {
+		let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+		let (oracles, fixed_batch, _) = make_oracles();
+		let poly = MultilinearExtension::<P>::zeros(N_VARS).unwrap();
+		let (_, verifying_key) = setup(&pcs, &oracles, fixed_batch, vec![poly]).unwrap();
+
+		let mut other_oracles = MultilinearOracleSet::<F>::new();
+		other_oracles.add_committed_batch(CommittedBatchSpec {
+			round_id: 0,
+			n_vars: N_VARS - 1,
+			n_polys: 1,
+			tower_level: F::TOWER_LEVEL,
+		});
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		assert_matches!(
+			MultiRoundVerifier::<P, F, _>::with_verifying_key(
+				&pcs,
+				&other_oracles,
+				&verifying_key,
+				&mut challenger
+			),
+			Err(Error::VerifyingKeyMismatch)
+		);
+	}
+
+	#[test]
+	fn test_bogus_verifying_key_fixed_batch() {
+		let pcs = TestPCS::new_using_groestl_merkle_tree(N_VARS, 1, 16).unwrap();
+		let (oracles, fixed_batch, trace_batch) = make_oracles();
+		let poly = MultilinearExtension::<P>::zeros(N_VARS).unwrap();
+		let (_, verifying_key) = setup(&pcs, &oracles, fixed_batch, vec![poly]).unwrap();
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+
+		let bogus_key = VerifyingKey {
+			fixed_batch_id: trace_batch,
+			..verifying_key.clone()
+		};
+		assert_matches!(
+			MultiRoundVerifier::<P, F, _>::with_verifying_key(
+				&pcs,
+				&oracles,
+				&bogus_key,
+				&mut challenger
+			),
+			Err(Error::InvalidFixedBatch { batch_id }) if batch_id == trace_batch
+		);
+
+		let n_batches = oracles.committed_batches().len();
+		let bogus_key = VerifyingKey {
+			fixed_batch_id: n_batches,
+			..verifying_key
+		};
+		assert_matches!(
+			MultiRoundVerifier::<P, F, _>::with_verifying_key(
+				&pcs,
+				&oracles,
+				&bogus_key,
+				&mut challenger
+			),
+			Err(Error::UncommittedBatch { batch_id }) if batch_id == n_batches
+		);
+	}
+}
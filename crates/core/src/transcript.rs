@@ -0,0 +1,358 @@
+// Copyright 2024 Ulvetanna Inc.
+
+//! Fiat-Shamir transcripts that carry the proof as a byte stream.
+//!
+//! The prover writes every message into a [`ProverTranscript`], which observes it with the
+//! wrapped challenger and appends its canonical encoding to a buffer. The verifier reads the same
+//! messages back from a [`VerifierTranscript`], which observes them in the same order, so prover
+//! and verifier challenger states cannot drift apart.
+//!
+//! Data that only needs to be authenticated against an earlier commitment, like Merkle openings,
+//! is written as a *decommitment*. Decommitments are appended to the stream without being
+//! observed by the challenger.
+
+use crate::{
+	challenger::{CanObserve, CanSample, CanSampleBits},
+	protocols::evalcheck::EvalcheckProof,
+	serialization::{DeserializeCanonical, Error, SerializeCanonical, ENCODING_VERSION},
+};
+use binius_field::Field;
+
+/// A structured prover message whose field elements are bound into the Fiat-Shamir state.
+pub trait TranscriptMessage<F>: SerializeCanonical {
+	/// Observes every field element of the message, in encoding order.
+	fn observe<CH: CanObserve<F>>(&self, challenger: &mut CH);
+}
+
+impl<F: Field + SerializeCanonical> TranscriptMessage<F> for EvalcheckProof<F> {
+	fn observe<CH: CanObserve<F>>(&self, challenger: &mut CH) {
+		match self {
+			EvalcheckProof::Transparent
+			| EvalcheckProof::Committed
+			| EvalcheckProof::Shifted
+			| EvalcheckProof::Packed => {}
+			EvalcheckProof::Repeating(subproof) => subproof.observe(challenger),
+			EvalcheckProof::Interleaved {
+				eval1,
+				eval2,
+				subproof1,
+				subproof2,
+			}
+			| EvalcheckProof::Merged {
+				eval1,
+				eval2,
+				subproof1,
+				subproof2,
+			} => {
+				challenger.observe(*eval1);
+				challenger.observe(*eval2);
+				subproof1.observe(challenger);
+				subproof2.observe(challenger);
+			}
+			EvalcheckProof::Composite { subproofs } => {
+				for (eval, subproof) in subproofs {
+					challenger.observe(*eval);
+					subproof.observe(challenger);
+				}
+			}
+		}
+	}
+}
+
+/// Prover side of a Fiat-Shamir transcript.
+///
+/// Wraps a challenger, typically a [`HashChallenger`](crate::challenger::HashChallenger), and
+/// records the proof stream. Challenges are sampled from the wrapped challenger.
+#[derive(Debug, Clone)]
+pub struct ProverTranscript<Challenger> {
+	challenger: Challenger,
+	buffer: Vec<u8>,
+}
+
+impl<Challenger> ProverTranscript<Challenger> {
+	pub fn new(challenger: Challenger) -> Self {
+		let mut buffer = Vec::new();
+		ENCODING_VERSION.serialize_canonical(&mut buffer);
+		Self { challenger, buffer }
+	}
+
+	/// Writes a single field element, observing it.
+	pub fn write_scalar<F>(&mut self, value: F)
+	where
+		F: Copy + SerializeCanonical,
+		Challenger: CanObserve<F>,
+	{
+		value.serialize_canonical(&mut self.buffer);
+		self.challenger.observe(value);
+	}
+
+	/// Writes a length-prefixed slice of field elements, observing each of them.
+	pub fn write_scalars<F>(&mut self, values: &[F])
+	where
+		F: Copy + SerializeCanonical,
+		Challenger: CanObserve<F>,
+	{
+		values.len().serialize_canonical(&mut self.buffer);
+		for &value in values {
+			self.write_scalar(value);
+		}
+	}
+
+	/// Writes a structured message, observing all of its field elements.
+	pub fn write_message<F, M>(&mut self, message: &M)
+	where
+		M: TranscriptMessage<F>,
+		Challenger: CanObserve<F>,
+	{
+		message.serialize_canonical(&mut self.buffer);
+		message.observe(&mut self.challenger);
+	}
+
+	/// Writes a commitment, observing it.
+	pub fn write_commitment<T>(&mut self, commitment: &T)
+	where
+		T: Clone + SerializeCanonical,
+		Challenger: CanObserve<T>,
+	{
+		commitment.serialize_canonical(&mut self.buffer);
+		self.challenger.observe(commitment.clone());
+	}
+
+	/// Appends a value to the proof stream without observing it.
+	pub fn write_decommitment<T: SerializeCanonical>(&mut self, value: &T) {
+		value.serialize_canonical(&mut self.buffer);
+	}
+
+	/// Returns the encoded proof stream.
+	pub fn finalize(self) -> Vec<u8> {
+		self.buffer
+	}
+}
+
+impl<Challenger, T> CanObserve<T> for ProverTranscript<Challenger>
+where
+	Challenger: CanObserve<T>,
+{
+	/// Observes a value known to both parties, without writing it to the proof stream.
+	fn observe(&mut self, value: T) {
+		self.challenger.observe(value);
+	}
+
+	fn observe_slice(&mut self, values: &[T])
+	where
+		T: Clone,
+	{
+		self.challenger.observe_slice(values);
+	}
+}
+
+impl<Challenger, T> CanSample<T> for ProverTranscript<Challenger>
+where
+	Challenger: CanSample<T>,
+{
+	fn sample(&mut self) -> T {
+		self.challenger.sample()
+	}
+}
+
+impl<Challenger, T> CanSampleBits<T> for ProverTranscript<Challenger>
+where
+	Challenger: CanSampleBits<T>,
+{
+	fn sample_bits(&mut self, bits: usize) -> T {
+		self.challenger.sample_bits(bits)
+	}
+}
+
+/// Verifier side of a Fiat-Shamir transcript.
+///
+/// Reads back the messages written by a [`ProverTranscript`] initialized with an identical
+/// challenger, observing them in the same order.
+#[derive(Debug, Clone)]
+pub struct VerifierTranscript<'a, Challenger> {
+	challenger: Challenger,
+	buffer: &'a [u8],
+}
+
+impl<'a, Challenger> VerifierTranscript<'a, Challenger> {
+	/// Creates a transcript over a proof stream produced by [`ProverTranscript::finalize`].
+	pub fn new(challenger: Challenger, mut proof: &'a [u8]) -> Result<Self, Error> {
+		let version = u16::deserialize_canonical(&mut proof)?;
+		if version != ENCODING_VERSION {
+			return Err(Error::UnsupportedVersion {
+				found: version,
+				expected: ENCODING_VERSION,
+			});
+		}
+		Ok(Self {
+			challenger,
+			buffer: proof,
+		})
+	}
+
+	/// Reads a single field element, observing it.
+	pub fn read_scalar<F>(&mut self) -> Result<F, Error>
+	where
+		F: Copy + DeserializeCanonical,
+		Challenger: CanObserve<F>,
+	{
+		let value = F::deserialize_canonical(&mut self.buffer)?;
+		self.challenger.observe(value);
+		Ok(value)
+	}
+
+	/// Reads a length-prefixed vector of field elements, observing each of them.
+	pub fn read_scalars<F>(&mut self) -> Result<Vec<F>, Error>
+	where
+		F: Copy + DeserializeCanonical,
+		Challenger: CanObserve<F>,
+	{
+		let len = usize::deserialize_canonical(&mut self.buffer)?;
+		let mut values = Vec::with_capacity(len.min(self.buffer.len()));
+		for _ in 0..len {
+			values.push(self.read_scalar()?);
+		}
+		Ok(values)
+	}
+
+	/// Reads a structured message, observing all of its field elements.
+	pub fn read_message<F, M>(&mut self) -> Result<M, Error>
+	where
+		M: TranscriptMessage<F> + DeserializeCanonical,
+		Challenger: CanObserve<F>,
+	{
+		let message = M::deserialize_canonical(&mut self.buffer)?;
+		message.observe(&mut self.challenger);
+		Ok(message)
+	}
+
+	/// Reads a commitment, observing it.
+	pub fn read_commitment<T>(&mut self) -> Result<T, Error>
+	where
+		T: Clone + DeserializeCanonical,
+		Challenger: CanObserve<T>,
+	{
+		let commitment = T::deserialize_canonical(&mut self.buffer)?;
+		self.challenger.observe(commitment.clone());
+		Ok(commitment)
+	}
+
+	/// Reads a value that was appended with [`ProverTranscript::write_decommitment`].
+	pub fn read_decommitment<T: DeserializeCanonical>(&mut self) -> Result<T, Error> {
+		T::deserialize_canonical(&mut self.buffer)
+	}
+
+	/// Checks that the whole proof stream has been consumed.
+	pub fn finalize(self) -> Result<(), Error> {
+		if !self.buffer.is_empty() {
+			return Err(Error::TrailingBytes {
+				count: self.buffer.len(),
+			});
+		}
+		Ok(())
+	}
+}
+
+impl<Challenger, T> CanObserve<T> for VerifierTranscript<'_, Challenger>
+where
+	Challenger: CanObserve<T>,
+{
+	/// Observes a value known to both parties, without reading it from the proof stream.
+	fn observe(&mut self, value: T) {
+		self.challenger.observe(value);
+	}
+
+	fn observe_slice(&mut self, values: &[T])
+	where
+		T: Clone,
+	{
+		self.challenger.observe_slice(values);
+	}
+}
+
+impl<Challenger, T> CanSample<T> for VerifierTranscript<'_, Challenger>
+where
+	Challenger: CanSample<T>,
+{
+	fn sample(&mut self) -> T {
+		self.challenger.sample()
+	}
+}
+
+impl<Challenger, T> CanSampleBits<T> for VerifierTranscript<'_, Challenger>
+where
+	Challenger: CanSampleBits<T>,
+{
+	fn sample_bits(&mut self, bits: usize) -> T {
+		self.challenger.sample_bits(bits)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::challenger::HashChallenger;
+	use assert_matches::assert_matches;
+	use binius_field::{BinaryField128b, BinaryField8b};
+	use binius_hash::{GroestlDigest, GroestlHasher};
+
+	type F = BinaryField128b;
+	type Challenger = HashChallenger<BinaryField8b, GroestlHasher<BinaryField8b>>;
+
+	#[test]
+	fn test_transcript_round_trip() {
+		let eval_proof = EvalcheckProof::Interleaved {
+			eval1: F::new(3),
+			eval2: F::new(4),
+			subproof1: Box::new(EvalcheckProof::Committed),
+			subproof2: Box::new(EvalcheckProof::Repeating(Box::new(EvalcheckProof::Shifted))),
+		};
+		let commitment = GroestlDigest::default();
+		let decommitment = vec![GroestlDigest::default(); 2];
+
+		let mut prover = ProverTranscript::new(Challenger::new());
+		prover.write_commitment(&commitment);
+		prover.write_scalars(&[F::new(1), F::new(2)]);
+		let prover_challenge: F = prover.sample();
+		prover.write_message(&eval_proof);
+		let prover_index: usize = prover.sample_bits(10);
+		prover.write_decommitment(&decommitment);
+		prover.write_scalar(F::new(5));
+		let prover_final: F = prover.sample();
+		let proof = prover.finalize();
+
+		let mut verifier = VerifierTranscript::new(Challenger::new(), &proof).unwrap();
+		assert_eq!(verifier.read_commitment::<GroestlDigest>().unwrap(), commitment);
+		assert_eq!(verifier.read_scalars::<F>().unwrap(), vec![F::new(1), F::new(2)]);
+		assert_eq!(CanSample::<F>::sample(&mut verifier), prover_challenge);
+		assert_eq!(verifier.read_message::<F, EvalcheckProof<F>>().unwrap(), eval_proof);
+		assert_eq!(CanSampleBits::<usize>::sample_bits(&mut verifier, 10), prover_index);
+		assert_eq!(verifier.read_decommitment::<Vec<GroestlDigest>>().unwrap(), decommitment);
+		assert_eq!(verifier.read_scalar::<F>().unwrap(), F::new(5));
+		assert_eq!(CanSample::<F>::sample(&mut verifier), prover_final);
+		verifier.finalize().unwrap();
+	}
+
+	#[test]
+	fn test_verifier_transcript_detects_tampering() {
+		let mut prover = ProverTranscript::new(Challenger::new());
+		prover.write_scalar(F::new(7));
+		let prover_challenge: F = prover.sample();
+		let mut proof = prover.finalize();
+
+		// A modified message changes the sampled challenge
+		*proof.last_mut().unwrap() ^= 1;
+		let mut verifier = VerifierTranscript::new(Challenger::new(), &proof).unwrap();
+		verifier.read_scalar::<F>().unwrap();
+		assert_ne!(CanSample::<F>::sample(&mut verifier), prover_challenge);
+
+		// Unread data is rejected
+		proof.push(0);
+		let verifier = VerifierTranscript::new(Challenger::new(), &proof).unwrap();
+		assert_matches!(verifier.finalize(), Err(Error::TrailingBytes { .. }));
+
+		// Reading past the end of the stream is rejected
+		let mut verifier = VerifierTranscript::new(Challenger::new(), &proof[..4]).unwrap();
+		assert_matches!(verifier.read_scalar::<F>(), Err(Error::Truncated { .. }));
+	}
+}
@@ -0,0 +1,518 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use super::{Error, MultilinearWitness, MultilinearWitnessIndex};
+use crate::{
+	oracle::{
+		MultilinearOracleSet, MultilinearPolyOracle, OracleId, ProjectionVariant, ShiftVariant,
+	},
+	polynomial::{MultilinearExtension, MultilinearPoly, MultilinearQuery},
+};
+use binius_field::{packed::get_packed_slice, Field, PackedField, TowerField};
+use rayon::prelude::*;
+
+/// Base-2 logarithm of the number of evaluations that [`hypercube_evals`] copies per task.
+const LOG_SUBCUBE_SIZE: usize = 12;
+
+/// Computes the witnesses of all derived oracles that are missing from the witness index.
+///
+/// The witnesses of committed and transparent oracles must be set by the caller, as well as
+/// those of any derived oracle that should not be recomputed. Derived oracles are processed by
+/// their depth in the oracle graph, computing all oracles of the same depth in parallel.
+pub fn derive_witnesses<F, PW>(
+	oracles: &MultilinearOracleSet<F>,
+	witness_index: &mut MultilinearWitnessIndex<'_, PW>,
+) -> Result<(), Error>
+where
+	F: TowerField,
+	PW: PackedField,
+	PW::Scalar: TowerField + From<F>,
+{
+	let mut depths = Vec::with_capacity(oracles.size());
+	let mut levels = Vec::<Vec<MultilinearPolyOracle<F>>>::new();
+	for id in 0..oracles.size() {
+		let oracle = oracles.oracle(id);
+		let depth = inner_oracles(&oracle)
+			.map(|inner| depths[inner.id()] + 1)
+			.max()
+			.unwrap_or(0);
+		depths.push(depth);
+
+		if depth > 0 && witness_index.get(id).is_none() {
+			if levels.len() < depth {
+				levels.resize_with(depth, Vec::new);
+			}
+			levels[depth - 1].push(oracle);
+		}
+	}
+
+	for level in levels {
+		let index = &*witness_index;
+		let witnesses = level
+			.into_par_iter()
+			.map(|oracle| {
+				let witness = derive_witness(&oracle, index)?;
+				Ok((oracle.id(), witness))
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+
+		for (id, witness) in witnesses {
+			witness_index.set(id, witness);
+		}
+	}
+
+	Ok(())
+}
+
+/// Computes the witnesses of the given derived oracles, one after the other.
+///
+/// Unlike [`derive_witnesses`], this leaves all other oracles of the set untouched, which lets
+/// protocols fill in the witnesses of the oracles they add without requiring the witnesses of
+/// unrelated oracles. The witness of every inner oracle must either be set or be derived earlier
+/// in `ids`.
+pub fn derive_witnesses_of<F, PW>(
+	oracles: &MultilinearOracleSet<F>,
+	ids: impl IntoIterator<Item = OracleId>,
+	witness_index: &mut MultilinearWitnessIndex<'_, PW>,
+) -> Result<(), Error>
+where
+	F: TowerField,
+	PW: PackedField,
+	PW::Scalar: TowerField + From<F>,
+{
+	for id in ids {
+		let witness = derive_witness(&oracles.oracle(id), witness_index)?;
+		witness_index.set(id, witness);
+	}
+	Ok(())
+}
+
+fn inner_oracles<F: Field>(
+	oracle: &MultilinearPolyOracle<F>,
+) -> Box<dyn Iterator<Item = &MultilinearPolyOracle<F>> + '_> {
+	use MultilinearPolyOracle::*;
+	match oracle {
+		Transparent(..) | Committed { .. } => Box::new(std::iter::empty()),
+		Repeating { inner, .. } => Box::new(std::iter::once(inner.as_ref())),
+		Interleaved(_, poly0, poly1) | Merged(_, poly0, poly1) => {
+			Box::new([poly0.as_ref(), poly1.as_ref()].into_iter())
+		}
+		Projected(_, projected) => Box::new(std::iter::once(projected.inner().as_ref())),
+		Shifted(_, shifted) => Box::new(std::iter::once(shifted.inner())),
+		Packed(_, packed) => Box::new(std::iter::once(packed.inner().as_ref())),
+		LinearCombination(_, lin_com) => Box::new(lin_com.polys()),
+	}
+}
+
+fn derive_witness<F, PW>(
+	oracle: &MultilinearPolyOracle<F>,
+	witness_index: &MultilinearWitnessIndex<'_, PW>,
+) -> Result<MultilinearWitness<'static, PW>, Error>
+where
+	F: TowerField,
+	PW: PackedField,
+	PW::Scalar: TowerField + From<F>,
+{
+	let get_witness = |inner: &MultilinearPolyOracle<F>| {
+		witness_index
+			.get(inner.id())
+			.ok_or(Error::MissingWitness(inner.id()))
+	};
+	let get_evals = |inner: &MultilinearPolyOracle<F>| hypercube_evals(get_witness(inner)?);
+
+	let id = oracle.id();
+	let n_vars = oracle.n_vars();
+
+	use MultilinearPolyOracle::*;
+	match oracle {
+		Transparent(..) | Committed { .. } => Err(Error::MissingWitness(id)),
+		Repeating { inner, .. } => {
+			let evals = get_evals(inner)?;
+			let mask = (1 << inner.n_vars()) - 1;
+			pack_witness(id, n_vars, |i| get_packed_slice(&evals, i & mask))
+		}
+		Interleaved(_, poly0, poly1) => {
+			let evals0 = get_evals(poly0)?;
+			let evals1 = get_evals(poly1)?;
+			pack_witness(id, n_vars, |i| {
+				if i & 1 == 0 {
+					get_packed_slice(&evals0, i >> 1)
+				} else {
+					get_packed_slice(&evals1, i >> 1)
+				}
+			})
+		}
+		Merged(_, poly0, poly1) => {
+			let evals0 = get_evals(poly0)?;
+			let evals1 = get_evals(poly1)?;
+			let half = 1 << poly0.n_vars();
+			pack_witness(id, n_vars, |i| {
+				if i < half {
+					get_packed_slice(&evals0, i)
+				} else {
+					get_packed_slice(&evals1, i - half)
+				}
+			})
+		}
+		Projected(_, projected) => {
+			let inner = get_witness(projected.inner())?;
+			let values = projected
+				.values()
+				.iter()
+				.map(|&value| value.into())
+				.collect::<Vec<PW::Scalar>>();
+			let query = MultilinearQuery::<PW>::with_full_query(&values)?;
+			let projected = match projected.projection_variant() {
+				ProjectionVariant::FirstVars => inner.evaluate_partial_low(&query)?,
+				ProjectionVariant::LastVars => inner.evaluate_partial_high(&query)?,
+			};
+			Ok(projected.upcast_arc_dyn())
+		}
+		Shifted(_, shifted) => {
+			let evals = get_evals(shifted.inner())?;
+			let block_mask = (1 << shifted.block_size()) - 1;
+			let offset = shifted.shift_offset();
+			let variant = shifted.shift_variant();
+			pack_witness(id, n_vars, |i| {
+				let block_index = i & block_mask;
+				let block_start = i - block_index;
+				match variant {
+					ShiftVariant::CircularLeft => get_packed_slice(
+						&evals,
+						block_start | (block_index.wrapping_sub(offset) & block_mask),
+					),
+					ShiftVariant::LogicalLeft if block_index >= offset => {
+						get_packed_slice(&evals, i - offset)
+					}
+					ShiftVariant::LogicalRight if block_index + offset <= block_mask => {
+						get_packed_slice(&evals, i + offset)
+					}
+					_ => PW::Scalar::ZERO,
+				}
+			})
+		}
+		Packed(_, packed) => {
+			let evals = get_evals(packed.inner())?;
+			let log_degree = packed.log_degree();
+			let basis = (0..1 << log_degree)
+				.map(|j| <PW::Scalar as TowerField>::basis(packed.inner().binary_tower_level(), j))
+				.collect::<Result<Vec<_>, _>>()?;
+			pack_witness(id, n_vars, |i| {
+				basis
+					.iter()
+					.enumerate()
+					.map(|(j, &basis_elem)| {
+						get_packed_slice(&evals, (i << log_degree) | j) * basis_elem
+					})
+					.sum()
+			})
+		}
+		LinearCombination(_, lin_com) => {
+			let inner_evals = lin_com
+				.polys()
+				.map(get_evals)
+				.collect::<Result<Vec<_>, _>>()?;
+			let coeffs = lin_com
+				.coefficients()
+				.map(PW::Scalar::from)
+				.collect::<Vec<_>>();
+			let offset = PW::Scalar::from(lin_com.offset());
+			pack_witness(id, n_vars, |i| {
+				inner_evals
+					.iter()
+					.zip(coeffs.iter())
+					.fold(offset, |acc, (evals, &coeff)| acc + get_packed_slice(evals, i) * coeff)
+			})
+		}
+	}
+}
+
+/// Reads all evaluations of a witness over the hypercube, packed.
+///
+/// The evaluations are copied subcube by subcube in parallel. A witness with fewer variables than
+/// the packing width fits into a single packed element, which is filled one evaluation at a time.
+fn hypercube_evals<PW: PackedField>(
+	witness: &MultilinearWitness<'_, PW>,
+) -> Result<Vec<PW>, Error> {
+	let n_vars = witness.n_vars();
+	if n_vars < PW::LOG_WIDTH {
+		let mut packed = PW::zero();
+		for i in 0..1 << n_vars {
+			packed.set(i, witness.evaluate_on_hypercube(i)?);
+		}
+		return Ok(vec![packed]);
+	}
+
+	let subcube_vars = n_vars.min(PW::LOG_WIDTH.max(LOG_SUBCUBE_SIZE));
+	let mut evals = vec![PW::zero(); 1 << (n_vars - PW::LOG_WIDTH)];
+	evals
+		.par_chunks_mut(1 << (subcube_vars - PW::LOG_WIDTH))
+		.enumerate()
+		.try_for_each(|(index, subcube)| witness.subcube_evals(subcube_vars, index, subcube))?;
+	Ok(evals)
+}
+
+/// Builds a witness on `n_vars` variables from a function giving its value at each hypercube index.
+fn pack_witness<PW: PackedField>(
+	id: OracleId,
+	n_vars: usize,
+	eval: impl Fn(usize) -> PW::Scalar + Sync,
+) -> Result<MultilinearWitness<'static, PW>, Error> {
+	if n_vars < PW::LOG_WIDTH {
+		return Err(Error::NotEnoughVarsForWitness { id, n_vars });
+	}
+	let values = (0..1 << (n_vars - PW::LOG_WIDTH))
+		.into_par_iter()
+		.map(|i| PW::from_fn(|j| eval((i << PW::LOG_WIDTH) | j)))
+		.collect();
+	Ok(MultilinearExtension::from_values(values)?.specialize_arc_dyn())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		challenger::HashChallenger,
+		oracle::{CommittedBatchSpec, CommittedId},
+		protocols::{evalcheck::EvalcheckClaim, greedy_evalcheck},
+	};
+	use assert_matches::assert_matches;
+	use binius_field::{BinaryField128b, BinaryField8b, PackedBinaryField4x128b};
+	use binius_hash::GroestlHasher;
+	use rand::{rngs::StdRng, SeedableRng};
+	use std::iter::repeat_with;
+
+	type F = BinaryField128b;
+	type P = PackedBinaryField4x128b;
+
+	const N_VARS: usize = 6;
+
+	#[test]
+	fn test_derived_witnesses_match_oracles() {
+		let mut rng = StdRng::seed_from_u64(0);
+		let mut oracles = MultilinearOracleSet::<F>::new();
+		let batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+			round_id: 1,
+			n_vars: N_VARS,
+			n_polys: 2,
+			tower_level: 3,
+		});
+		let a = oracles.committed_oracle_id(CommittedId { batch_id, index: 0 });
+		let b = oracles.committed_oracle_id(CommittedId { batch_id, index: 1 });
+
+		let lin_com = oracles
+			.add_linear_combination(N_VARS, F::new(7), [(a, F::new(3)), (b, F::new(1 << 70))])
+			.unwrap();
+		let mut derived = vec![
+			oracles.add_repeating(a, 2).unwrap(),
+			oracles.add_interleaved(a, b).unwrap(),
+			oracles.add_merged(a, b).unwrap(),
+			oracles
+				.add_projected(a, vec![F::new(5), F::new(9)], ProjectionVariant::FirstVars)
+				.unwrap(),
+			oracles
+				.add_projected(b, vec![F::new(11)], ProjectionVariant::LastVars)
+				.unwrap(),
+			oracles.add_packed(a, 2).unwrap(),
+			lin_com,
+		];
+		for variant in [
+			ShiftVariant::CircularLeft,
+			ShiftVariant::LogicalLeft,
+			ShiftVariant::LogicalRight,
+		] {
+			derived.push(oracles.add_shifted(a, 3, 4, variant).unwrap());
+		}
+		// A derived oracle of a derived oracle
+		derived.push(
+			oracles
+				.add_shifted(lin_com, 1, N_VARS, ShiftVariant::LogicalRight)
+				.unwrap(),
+		);
+
+		let committed = repeat_with(|| {
+			let values = repeat_with(|| F::from(<BinaryField8b as Field>::random(&mut rng)))
+				.take(1 << N_VARS)
+				.collect();
+			MultilinearExtension::from_values(values).unwrap()
+		})
+		.take(2)
+		.collect::<Vec<_>>();
+
+		let mut witness_index = MultilinearWitnessIndex::<F>::new();
+		witness_index.set(a, committed[0].to_ref().specialize_arc_dyn());
+		witness_index.set(b, committed[1].to_ref().specialize_arc_dyn());
+		derive_witnesses(&oracles, &mut witness_index).unwrap();
+
+		// Every derived witness must be consistent with its oracle definition, which the evalcheck
+		// protocol reduces to evaluations of the committed polynomials.
+		let claims = derived
+			.iter()
+			.map(|&id| {
+				let oracle = oracles.oracle(id);
+				let eval_point = repeat_with(|| <F as Field>::random(&mut rng))
+					.take(oracle.n_vars())
+					.collect::<Vec<_>>();
+				let query = MultilinearQuery::<F>::with_full_query(&eval_point).unwrap();
+				let eval = witness_index.get(id).unwrap().evaluate(&query).unwrap();
+				EvalcheckClaim {
+					poly: oracle.into_composite(),
+					eval_point,
+					eval,
+					is_random_point: true,
+				}
+			})
+			.collect::<Vec<_>>();
+
+		let mut prover_oracles = oracles.clone();
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		let output = greedy_evalcheck::prove(
+			&mut prover_oracles,
+			&mut witness_index,
+			claims.clone(),
+			|_| 1,
+			&mut challenger,
+		)
+		.unwrap();
+
+		let mut challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+		let same_query_claims =
+			greedy_evalcheck::verify(&mut oracles, claims, output.proof, &mut challenger).unwrap();
+		assert_eq!(same_query_claims.len(), 1);
+
+		let (_, claim) = &same_query_claims[0];
+		let query = MultilinearQuery::<F>::with_full_query(&claim.eval_point).unwrap();
+		for (poly, &eval) in committed.iter().zip(claim.evals.iter()) {
+			assert_eq!(poly.evaluate::<F, F>(&query).unwrap(), eval);
+		}
+	}
+
+	#[test]
+	fn test_derive_packed_witnesses() {
+		let mut rng = StdRng::seed_from_u64(0);
+		let mut oracles = MultilinearOracleSet::<F>::new();
+		let batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+			round_id: 1,
+			n_vars: N_VARS,
+			n_polys: 2,
+			tower_level: 3,
+		});
+		let a = oracles.committed_oracle_id(CommittedId { batch_id, index: 0 });
+		let b = oracles.committed_oracle_id(CommittedId { batch_id, index: 1 });
+		let derived = [
+			oracles.add_repeating(a, 2).unwrap(),
+			oracles.add_interleaved(a, b).unwrap(),
+			oracles.add_merged(a, b).unwrap(),
+			oracles.add_packed(a, 2).unwrap(),
+			oracles
+				.add_shifted(a, 3, 4, ShiftVariant::CircularLeft)
+				.unwrap(),
+			oracles
+				.add_linear_combination(N_VARS, F::new(7), [(a, F::new(3)), (b, F::ONE)])
+				.unwrap(),
+		];
+
+		let mut scalar_index = MultilinearWitnessIndex::<F>::new();
+		let mut packed_index = MultilinearWitnessIndex::<P>::new();
+		for id in [a, b] {
+			let values = repeat_with(|| F::from(<BinaryField8b as Field>::random(&mut rng)))
+				.take(1 << N_VARS)
+				.collect::<Vec<_>>();
+			let packed_values = values
+				.chunks_exact(P::WIDTH)
+				.map(|chunk| P::from_fn(|i| chunk[i]))
+				.collect();
+			scalar_index.set(
+				id,
+				MultilinearExtension::from_values(values)
+					.unwrap()
+					.specialize_arc_dyn(),
+			);
+			packed_index.set(
+				id,
+				MultilinearExtension::from_values(packed_values)
+					.unwrap()
+					.specialize_arc_dyn(),
+			);
+		}
+		derive_witnesses(&oracles, &mut scalar_index).unwrap();
+		derive_witnesses(&oracles, &mut packed_index).unwrap();
+
+		// Witnesses derived from packed inner witnesses have the same values
+		for id in derived {
+			let scalar_witness = scalar_index.get(id).unwrap();
+			let packed_witness = packed_index.get(id).unwrap();
+			for i in 0..1 << oracles.oracle(id).n_vars() {
+				assert_eq!(
+					packed_witness.evaluate_on_hypercube(i).unwrap(),
+					scalar_witness.evaluate_on_hypercube(i).unwrap()
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn test_missing_inner_witness() {
+		let mut oracles = MultilinearOracleSet::<F>::new();
+		let batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+			round_id: 1,
+			n_vars: N_VARS,
+			n_polys: 1,
+			tower_level: 0,
+		});
+		let a = oracles.committed_oracle_id(CommittedId { batch_id, index: 0 });
+		let shifted = oracles
+			.add_shifted(a, 1, N_VARS, ShiftVariant::CircularLeft)
+			.unwrap();
+		oracles.add_repeating(shifted, 1).unwrap();
+
+		let mut witness_index = MultilinearWitnessIndex::<F>::new();
+		assert_matches!(
+			derive_witnesses(&oracles, &mut witness_index),
+			Err(Error::MissingWitness(id)) if id == a
+		);
+	}
+
+	#[test]
+	fn test_derive_selected_witnesses() {
+		let mut oracles = MultilinearOracleSet::<F>::new();
+		let batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+			round_id: 1,
+			n_vars: N_VARS,
+			n_polys: 2,
+			tower_level: 0,
+		});
+		let a = oracles.committed_oracle_id(CommittedId { batch_id, index: 0 });
+		let b = oracles.committed_oracle_id(CommittedId { batch_id, index: 1 });
+		oracles
+			.add_shifted(b, 1, N_VARS, ShiftVariant::CircularLeft)
+			.unwrap();
+		let repeating = oracles.add_repeating(a, 1).unwrap();
+		let merged = oracles.add_merged(repeating, repeating).unwrap();
+
+		let values = (0..1 << N_VARS).map(|i| F::new(i as u128)).collect();
+		let mut witness_index = MultilinearWitnessIndex::<F>::new();
+		witness_index.set(
+			a,
+			MultilinearExtension::from_values(values)
+				.unwrap()
+				.specialize_arc_dyn(),
+		);
+		derive_witnesses_of(&oracles, [repeating, merged], &mut witness_index).unwrap();
+
+		let merged_witness = witness_index.get(merged).unwrap();
+		assert_eq!(merged_witness.n_vars(), N_VARS + 2);
+		assert_eq!(merged_witness.evaluate_on_hypercube(3).unwrap(), F::new(3));
+		assert_eq!(
+			merged_witness
+				.evaluate_on_hypercube((3 << N_VARS) | 5)
+				.unwrap(),
+			F::new(5)
+		);
+
+		// Deriving all witnesses fails on the unrelated shift of b
+		assert_matches!(
+			derive_witnesses(&oracles, &mut witness_index),
+			Err(Error::MissingWitness(id)) if id == b
+		);
+	}
+}
@@ -0,0 +1,15 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use crate::{oracle::OracleId, polynomial::Error as PolynomialError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("no witness is available for oracle {0}")]
+	MissingWitness(OracleId),
+	#[error("oracle {id} has {n_vars} variables, fewer than the log width of the packed field")]
+	NotEnoughVarsForWitness { id: OracleId, n_vars: usize },
+	#[error("polynomial error: {0}")]
+	Polynomial(#[from] PolynomialError),
+	#[error("field error: {0}")]
+	Field(#[from] binius_field::Error),
+}
@@ -1,5 +1,6 @@
 // Copyright 2024 Ulvetanna Inc.
 
+use super::error::Error;
 use crate::{oracle::OracleId, polynomial::MultilinearPoly};
 use binius_field::PackedField;
 use std::sync::Arc;
@@ -29,6 +30,11 @@ where
 		self.multilinears.get(id)?.as_ref()
 	}
 
+	/// Returns a handle to the witness of an oracle, failing if the witness is missing.
+	pub fn get_required(&self, id: OracleId) -> Result<MultilinearWitness<'a, P>, Error> {
+		self.get(id).cloned().ok_or(Error::MissingWitness(id))
+	}
+
 	pub fn set(&mut self, id: OracleId, witness: MultilinearWitness<'a, P>) {
 		if id >= self.multilinears.len() {
 			self.multilinears.resize(id + 1, None);
@@ -0,0 +1,9 @@
+// Copyright 2024 Ulvetanna Inc.
+
+mod derive;
+mod error;
+mod index;
+
+pub use derive::*;
+pub use error::Error;
+pub use index::*;
@@ -1,27 +1,137 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{bracketed, parse::Parse, parse_macro_input, parse_quote, Token};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use std::collections::HashMap;
+use syn::{
+	braced, bracketed, parse::Parse, parse_macro_input, parse_quote, spanned::Spanned, Token,
+};
 
+/// Defines a composition polynomial from an arithmetic expression over its variables.
+///
+/// ```
+/// use binius_field::BinaryField8b;
+/// use binius_macros::composition_poly;
+///
+/// composition_poly!(BitwiseAnd[a, b, c] = a * b - c);
+/// composition_poly!(Weighted { alpha: BinaryField8b }[a, b] = a + alpha * b.pow(3));
+/// ```
+///
+/// The expression may use the variables, the literals `0` and `1`, the typed constant parameters
+/// declared in braces, `+`, `-`, `*` and `.pow(n)`. Constant parameters become fields of the
+/// generated struct and the binary tower level of the composition is the highest tower level of
+/// their types. Repeated subexpressions are evaluated once.
+///
+/// Every identifier must be a variable or a parameter:
+///
+/// ```compile_fail
+/// use binius_macros::composition_poly;
+///
+/// composition_poly!(Unknown[a, b] = a * c);
+/// ```
+///
+/// and exponents must be integer literals:
+///
+/// ```compile_fail
+/// use binius_macros::composition_poly;
+///
+/// const N: u64 = 3;
+/// composition_poly!(NonLiteralExponent[a] = a.pow(N));
+/// ```
 #[proc_macro]
 pub fn composition_poly(input: TokenStream) -> TokenStream {
+	let item = parse_macro_input!(input as CompositionPolyItem);
+	match expand(item) {
+		Ok(tokens) => tokens.into(),
+		Err(err) => err.to_compile_error().into(),
+	}
+}
+
+fn expand(item: CompositionPolyItem) -> syn::Result<TokenStream2> {
 	let CompositionPolyItem {
 		name,
+		params,
 		vars,
-		mut poly,
-	} = parse_macro_input!(input);
+		poly,
+	} = item;
+
+	let params = params
+		.iter()
+		.map(|(name, ty)| (name, ty))
+		.collect::<Vec<_>>();
+	let param_names = params.iter().map(|&(name, _)| name).collect::<Vec<_>>();
+	let param_types = params.iter().map(|&(_, ty)| ty).collect::<Vec<_>>();
+
+	let mut circuit = Circuit::default();
+	let root = circuit.lower(&poly, &vars, &param_names)?;
 
 	let n_vars = vars.len();
-	let i = 0..n_vars;
-	let degree = poly_degree(&poly);
-	rewrite_literals(&mut poly);
+	let degree = circuit.degree(root);
+
+	let scalar_body = circuit.emit(
+		root,
+		&Target {
+			zero: parse_quote!(<P::Scalar as binius_field::Field>::ZERO),
+			one: parse_quote!(<P::Scalar as binius_field::Field>::ONE),
+			param: &|index| {
+				let name = param_names[index];
+				parse_quote!(Into::<P::Scalar>::into(self.#name))
+			},
+			square: &|arg| parse_quote!(binius_field::Field::square(&#arg)),
+		},
+	);
+	let packed_body = circuit.emit(
+		root,
+		&Target {
+			zero: parse_quote!(<P as binius_field::PackedField>::zero()),
+			one: parse_quote!(<P as binius_field::PackedField>::one()),
+			param: &|index| {
+				let name = param_names[index];
+				parse_quote!(<P as binius_field::PackedField>::broadcast(self.#name.into()))
+			},
+			square: &|arg| parse_quote!(binius_field::PackedField::square(#arg)),
+		},
+	);
+	// Bit-sliced evaluation is only reachable when every parameter is a BinaryField1b.
+	let bitsliced_body = circuit.emit(
+		root,
+		&Target {
+			zero: parse_quote!(binius_core::polynomial::BitslicedPoly::ZERO),
+			one: parse_quote!(binius_core::polynomial::BitslicedPoly::ONE),
+			param: &|index| {
+				let (name, ty) = params[index];
+				parse_quote!(if self.#name == <#ty as binius_field::Field>::ONE {
+					binius_core::polynomial::BitslicedPoly::ONE
+				} else {
+					binius_core::polynomial::BitslicedPoly::ZERO
+				})
+			},
+			square: &|arg| parse_quote!(#arg * #arg),
+		},
+	);
+
+	let definition = if params.is_empty() {
+		quote! {
+			#[derive(Debug, Clone)]
+			struct #name;
+		}
+	} else {
+		quote! {
+			#[derive(Debug, Clone)]
+			struct #name {
+				#( #param_names: #param_types, )*
+			}
+		}
+	};
 
-	quote! {
-		#[derive(Debug, Clone)]
-		struct #name;
+	Ok(quote! {
+		#definition
 
-		impl<F: binius_field::Field> binius_core::polynomial::multivariate::CompositionPoly<F> for #name {
+		impl<P: binius_field::PackedField> binius_core::polynomial::multivariate::CompositionPoly<P> for #name
+		where
+			#( P::Scalar: binius_field::ExtensionField<#param_types>, )*
+		{
 			fn n_vars(&self) -> usize {
 				#n_vars
 			}
@@ -30,85 +140,305 @@ pub fn composition_poly(input: TokenStream) -> TokenStream {
 				#degree
 			}
 
-			fn evaluate(&self, query: &[F]) -> Result<F, binius_core::polynomial::Error> {
-				self.evaluate_packed(query)
+			fn evaluate(&self, query: &[P::Scalar]) -> Result<P::Scalar, binius_core::polynomial::Error> {
+				if query.len() != #n_vars {
+					return Err(binius_core::polynomial::Error::IncorrectQuerySize { expected: #n_vars });
+				}
+				Ok(#scalar_body)
 			}
 
-			fn evaluate_packed(&self, query: &[F]) -> Result<F, binius_core::polynomial::Error> {
+			fn evaluate_packed(&self, query: &[P]) -> Result<P, binius_core::polynomial::Error> {
 				if query.len() != #n_vars {
 					return Err(binius_core::polynomial::Error::IncorrectQuerySize { expected: #n_vars });
 				}
-				#( let #vars = query[#i]; )*
-				Ok(#poly)
+				Ok(#packed_body)
 			}
 
 			fn binary_tower_level(&self) -> usize {
-				0
+				0usize #( .max(<#param_types as binius_field::TowerField>::TOWER_LEVEL) )*
+			}
+
+			fn bitsliced_coeffs(&self) -> Option<Vec<P::Scalar>> {
+				let tower_level = <Self as binius_core::polynomial::multivariate::CompositionPoly<P>>::binary_tower_level(self);
+				(tower_level == 0).then(|| vec![<P::Scalar as binius_field::Field>::ONE])
+			}
+
+			fn evaluate_bitsliced(
+				&self,
+				query: &[binius_core::polynomial::BitslicedPoly],
+				dst: &mut Vec<binius_core::polynomial::BitslicedPoly>,
+			) -> Result<(), binius_core::polynomial::Error> {
+				if query.len() != #n_vars {
+					return Err(binius_core::polynomial::Error::IncorrectQuerySize { expected: #n_vars });
+				}
+				let tower_level = <Self as binius_core::polynomial::multivariate::CompositionPoly<P>>::binary_tower_level(self);
+				if tower_level != 0 {
+					return Err(binius_core::polynomial::Error::BitslicedEvaluationUnsupported);
+				}
+				dst.push(#bitsliced_body);
+				Ok(())
 			}
 		}
-	}
-	.into()
+	})
 }
 
-/// Make sure to run this before rewrite_literals as it will rewrite Lit to Path,
-/// which will mess up the degree
-fn poly_degree(expr: &syn::Expr) -> usize {
-	match expr.clone() {
-		syn::Expr::Lit(_) => 0,
-		syn::Expr::Path(_) => 1,
-		syn::Expr::Paren(paren) => poly_degree(&paren.expr),
-		syn::Expr::Binary(binary) => {
-			let op = binary.op;
-			let left = poly_degree(&binary.left);
-			let right = poly_degree(&binary.right);
-			match op {
-				syn::BinOp::Add(_) | syn::BinOp::Sub(_) => std::cmp::max(left, right),
-				syn::BinOp::Mul(_) => left + right,
-				_ => panic!("Binary operation is not supported: {}", quote! { #op }),
+/// A node of the arithmetic circuit; operands are indices of earlier nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Node {
+	Zero,
+	One,
+	Var(usize),
+	Param(usize),
+	Add(usize, usize),
+	Sub(usize, usize),
+	Mul(usize, usize),
+}
+
+/// A hash-consed arithmetic circuit, so that structurally equal subexpressions share a node.
+#[derive(Debug, Default)]
+struct Circuit {
+	nodes: Vec<Node>,
+	node_ids: HashMap<Node, usize>,
+}
+
+/// How the leaves and squares of a circuit are written for one evaluation target.
+struct Target<'a> {
+	zero: syn::Expr,
+	one: syn::Expr,
+	param: &'a dyn Fn(usize) -> syn::Expr,
+	square: &'a dyn Fn(&syn::Ident) -> syn::Expr,
+}
+
+impl Circuit {
+	fn intern(&mut self, node: Node) -> usize {
+		// Addition and multiplication are commutative, so operands are ordered for sharing.
+		let node = match node {
+			Node::Add(left, right) => Node::Add(left.min(right), left.max(right)),
+			Node::Mul(left, right) => Node::Mul(left.min(right), left.max(right)),
+			node => node,
+		};
+		*self.node_ids.entry(node).or_insert_with(|| {
+			self.nodes.push(node);
+			self.nodes.len() - 1
+		})
+	}
+
+	fn add(&mut self, left: usize, right: usize) -> usize {
+		match (self.nodes[left], self.nodes[right]) {
+			(Node::Zero, _) => right,
+			(_, Node::Zero) => left,
+			_ => self.intern(Node::Add(left, right)),
+		}
+	}
+
+	fn sub(&mut self, left: usize, right: usize) -> usize {
+		match self.nodes[right] {
+			Node::Zero => left,
+			_ => self.intern(Node::Sub(left, right)),
+		}
+	}
+
+	fn mul(&mut self, left: usize, right: usize) -> usize {
+		match (self.nodes[left], self.nodes[right]) {
+			(Node::Zero, _) | (_, Node::Zero) => self.intern(Node::Zero),
+			(Node::One, _) => right,
+			(_, Node::One) => left,
+			_ => self.intern(Node::Mul(left, right)),
+		}
+	}
+
+	/// Expands `base^exp` by left-to-right square-and-multiply.
+	fn pow(&mut self, base: usize, exp: u64) -> usize {
+		if exp == 0 {
+			return self.intern(Node::One);
+		}
+		let mut acc = base;
+		for bit in (0..exp.ilog2()).rev() {
+			acc = self.mul(acc, acc);
+			if (exp >> bit) & 1 == 1 {
+				acc = self.mul(acc, base);
 			}
 		}
-		_ => panic!("Unsupported expression: `{}`", quote! { #expr }),
+		acc
 	}
-}
 
-/// Rewrites 0 => F::ZERO, 1 => F::ONE
-fn rewrite_literals(expr: &mut syn::Expr) {
-	match expr {
-		syn::Expr::Lit(exprlit) => {
-			if let syn::Lit::Int(int) = &exprlit.lit {
-				match &*int.to_string() {
-					"0" => {
-						*expr = parse_quote!(F::ZERO);
-					}
-					"1" => {
-						*expr = parse_quote!(F::ONE);
-					}
-					int => panic!("Value not supported: {int}"),
+	/// Adds the nodes of an expression over the given variables and constant parameters.
+	fn lower(
+		&mut self,
+		expr: &syn::Expr,
+		vars: &[syn::Ident],
+		params: &[&syn::Ident],
+	) -> syn::Result<usize> {
+		match expr {
+			syn::Expr::Lit(syn::ExprLit {
+				lit: syn::Lit::Int(int),
+				..
+			}) => match int.base10_digits() {
+				"0" => Ok(self.intern(Node::Zero)),
+				"1" => Ok(self.intern(Node::One)),
+				_ => Err(syn::Error::new(
+					int.span(),
+					"only the literals 0 and 1 are supported, declare other constants as typed parameters",
+				)),
+			},
+			syn::Expr::Path(path) => {
+				let ident = path
+					.path
+					.get_ident()
+					.ok_or_else(|| syn::Error::new(path.span(), "expected a variable or parameter"))?;
+				if let Some(index) = vars.iter().position(|var| var == ident) {
+					Ok(self.intern(Node::Var(index)))
+				} else if let Some(index) = params.iter().position(|&name| name == ident) {
+					Ok(self.intern(Node::Param(index)))
+				} else {
+					Err(syn::Error::new(ident.span(), format!("unknown variable `{ident}`")))
+				}
+			}
+			syn::Expr::Paren(paren) => self.lower(&paren.expr, vars, params),
+			syn::Expr::Group(group) => self.lower(&group.expr, vars, params),
+			syn::Expr::Binary(binary) => {
+				let left = self.lower(&binary.left, vars, params)?;
+				let right = self.lower(&binary.right, vars, params)?;
+				match binary.op {
+					syn::BinOp::Add(_) => Ok(self.add(left, right)),
+					syn::BinOp::Sub(_) => Ok(self.sub(left, right)),
+					syn::BinOp::Mul(_) => Ok(self.mul(left, right)),
+					op => Err(syn::Error::new(
+						op.span(),
+						format!("binary operation is not supported: {}", quote! { #op }),
+					)),
 				}
 			}
+			syn::Expr::MethodCall(call) if call.method == "pow" && call.args.len() == 1 => {
+				let exp = match &call.args[0] {
+					syn::Expr::Lit(syn::ExprLit {
+						lit: syn::Lit::Int(int),
+						..
+					}) => int.base10_parse::<u64>()?,
+					arg => {
+						return Err(syn::Error::new(
+							arg.span(),
+							"the exponent must be an integer literal",
+						))
+					}
+				};
+				let base = self.lower(&call.receiver, vars, params)?;
+				Ok(self.pow(base, exp))
+			}
+			_ => Err(syn::Error::new(
+				expr.span(),
+				format!("unsupported expression: `{}`", quote! { #expr }),
+			)),
 		}
-		syn::Expr::Paren(paren) => {
-			rewrite_literals(&mut paren.expr);
+	}
+
+	/// Total degree of a node as a polynomial in the variables.
+	fn degree(&self, id: usize) -> usize {
+		let mut degrees = Vec::with_capacity(self.nodes.len());
+		for node in &self.nodes {
+			let degree = match *node {
+				Node::Zero | Node::One | Node::Param(_) => 0,
+				Node::Var(_) => 1,
+				Node::Add(left, right) | Node::Sub(left, right) => {
+					std::cmp::max(degrees[left], degrees[right])
+				}
+				Node::Mul(left, right) => degrees[left] + degrees[right],
+			};
+			degrees.push(degree);
 		}
-		syn::Expr::Binary(binary) => {
-			rewrite_literals(&mut binary.left);
-			rewrite_literals(&mut binary.right);
+		degrees[id]
+	}
+
+	/// Emits a block expression that evaluates the node, binding each node it depends on once.
+	fn emit(&self, root: usize, target: &Target) -> syn::Expr {
+		let mut live = vec![false; self.nodes.len()];
+		live[root] = true;
+		for id in (0..self.nodes.len()).rev() {
+			if !live[id] {
+				continue;
+			}
+			match self.nodes[id] {
+				Node::Add(left, right) | Node::Sub(left, right) | Node::Mul(left, right) => {
+					live[left] = true;
+					live[right] = true;
+				}
+				_ => {}
+			}
 		}
-		_ => {}
+
+		let temp = |id: usize| format_ident!("t{id}");
+		let statements = (0..self.nodes.len())
+			.filter(|&id| live[id])
+			.map(|id| {
+				let value: syn::Expr = match self.nodes[id] {
+					Node::Zero => target.zero.clone(),
+					Node::One => target.one.clone(),
+					Node::Var(index) => parse_quote!(query[#index]),
+					Node::Param(index) => (target.param)(index),
+					Node::Add(left, right) => {
+						let (left, right) = (temp(left), temp(right));
+						parse_quote!(#left + #right)
+					}
+					Node::Sub(left, right) => {
+						let (left, right) = (temp(left), temp(right));
+						parse_quote!(#left - #right)
+					}
+					Node::Mul(left, right) if left == right => (target.square)(&temp(left)),
+					Node::Mul(left, right) => {
+						let (left, right) = (temp(left), temp(right));
+						parse_quote!(#left * #right)
+					}
+				};
+				let name = temp(id);
+				quote! { let #name = #value; }
+			})
+			.collect::<Vec<_>>();
+
+		let root = temp(root);
+		parse_quote!({
+			#( #statements )*
+			#root
+		})
 	}
 }
 
 #[derive(Debug)]
 struct CompositionPolyItem {
 	name: syn::Ident,
+	params: Vec<(syn::Ident, syn::Type)>,
 	vars: Vec<syn::Ident>,
 	poly: syn::Expr,
 }
 
+struct TypedParam {
+	name: syn::Ident,
+	ty: syn::Type,
+}
+
+impl Parse for TypedParam {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let name = input.parse()?;
+		input.parse::<Token![:]>()?;
+		let ty = input.parse()?;
+		Ok(Self { name, ty })
+	}
+}
+
 impl Parse for CompositionPolyItem {
 	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
 		Ok(Self {
 			name: input.parse::<syn::Ident>()?,
+			params: if input.peek(syn::token::Brace) {
+				let content;
+				braced!(content in input);
+				let params = content.parse_terminated(TypedParam::parse, Token![,])?;
+				params
+					.into_iter()
+					.map(|param| (param.name, param.ty))
+					.collect()
+			} else {
+				Vec::new()
+			},
 			vars: {
 				let content;
 				bracketed!(content in input);
@@ -120,3 +450,69 @@ impl Parse for CompositionPolyItem {
 		})
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn lower(expr: syn::Expr, params: &[&str]) -> syn::Result<(Circuit, usize)> {
+		let vars = ["a", "b", "c"].map(|var| format_ident!("{}", var));
+		let params = params
+			.iter()
+			.map(|param| format_ident!("{}", param))
+			.collect::<Vec<_>>();
+		let params = params.iter().collect::<Vec<_>>();
+		let mut circuit = Circuit::default();
+		let root = circuit.lower(&expr, &vars, &params)?;
+		Ok((circuit, root))
+	}
+
+	#[test]
+	fn test_degree() {
+		let degree = |expr: syn::Expr| {
+			let (circuit, root) = lower(expr, &["alpha"]).unwrap();
+			circuit.degree(root)
+		};
+		assert_eq!(degree(parse_quote!(a * b - c)), 2);
+		assert_eq!(degree(parse_quote!(a * b.pow(3) + c)), 4);
+		assert_eq!(degree(parse_quote!(alpha * (a - 1) * 1 + 0)), 1);
+		assert_eq!(degree(parse_quote!((a * b).pow(0) + alpha)), 0);
+		assert_eq!(degree(parse_quote!(a * 0 + b)), 1);
+	}
+
+	#[test]
+	fn test_common_subexpressions() {
+		// a, b, a * b, c, a * b + c and its square; b * a is shared with a * b
+		let (circuit, root) = lower(parse_quote!((a * b + c) * (b * a + c)), &[]).unwrap();
+		assert_eq!(circuit.nodes.len(), 6);
+		assert_eq!(circuit.nodes[root], Node::Mul(4, 4));
+		assert_eq!(circuit.degree(root), 4);
+
+		// a, a^2, a^4 and a^5 by square-and-multiply
+		let (circuit, root) = lower(parse_quote!(a.pow(5)), &[]).unwrap();
+		assert_eq!(
+			circuit.nodes,
+			vec![
+				Node::Var(0),
+				Node::Mul(0, 0),
+				Node::Mul(1, 1),
+				Node::Mul(0, 2)
+			]
+		);
+		assert_eq!(circuit.degree(root), 5);
+
+		// Subtraction is not commutative, so its operands are not reordered
+		let (circuit, _) = lower(parse_quote!((a - b) * (b - a)), &[]).unwrap();
+		assert_eq!(circuit.nodes.len(), 5);
+	}
+
+	#[test]
+	fn test_lower_errors() {
+		let message = |expr: syn::Expr| lower(expr, &["alpha"]).unwrap_err().to_string();
+		assert_eq!(message(parse_quote!(a * d)), "unknown variable `d`");
+		assert_eq!(message(parse_quote!(a.pow(n))), "the exponent must be an integer literal");
+		assert!(message(parse_quote!(a + 2)).starts_with("only the literals 0 and 1"));
+		assert!(message(parse_quote!(a / b)).starts_with("binary operation is not supported"));
+		assert!(message(parse_quote!(a.square())).starts_with("unsupported expression"));
+	}
+}
@@ -0,0 +1,117 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use binius_core::polynomial::CompositionPoly;
+use binius_field::{
+	BinaryField128b, BinaryField16b, BinaryField1b, BinaryField32b, BinaryField8b, Field,
+	PackedBinaryField4x32b, PackedField,
+};
+use binius_macros::composition_poly;
+use rand::{rngs::StdRng, SeedableRng};
+use std::iter::repeat_with;
+
+type F = BinaryField128b;
+
+composition_poly!(Product[a, b, c] = a * b * c);
+composition_poly!(Powers[a, b] = a.pow(5) + (a * b + 1).pow(2) - b.pow(0));
+composition_poly!(Shared { alpha: BinaryField8b }[a, b, c] = (a * b + alpha * c) * (b * a + alpha * c) - c.pow(3));
+composition_poly!(Mixed { alpha: BinaryField8b, beta: BinaryField32b }[a, b] = alpha * a + beta * b);
+composition_poly!(Bit { bit: BinaryField1b }[a, b] = bit * a * b + a);
+
+fn random_query<P: PackedField>(n_vars: usize) -> Vec<P> {
+	let mut rng = StdRng::seed_from_u64(0);
+	repeat_with(|| P::random(&mut rng)).take(n_vars).collect()
+}
+
+#[test]
+fn test_degree_and_arity() {
+	assert_eq!(CompositionPoly::<F>::degree(&Product), 3);
+	assert_eq!(CompositionPoly::<F>::degree(&Powers), 5);
+	let shared = Shared {
+		alpha: BinaryField8b::new(0x2a),
+	};
+	assert_eq!(CompositionPoly::<F>::degree(&shared), 4);
+	let mixed = Mixed {
+		alpha: BinaryField8b::new(0x2a),
+		beta: BinaryField32b::new(0x1234567),
+	};
+	assert_eq!(CompositionPoly::<F>::degree(&mixed), 1);
+
+	assert_eq!(CompositionPoly::<F>::n_vars(&Product), 3);
+	assert_eq!(CompositionPoly::<F>::n_vars(&Powers), 2);
+	assert!(CompositionPoly::<F>::evaluate(&Product, &random_query::<F>(2)).is_err());
+}
+
+#[test]
+fn test_tower_level() {
+	assert_eq!(CompositionPoly::<F>::binary_tower_level(&Product), 0);
+	let shared = Shared {
+		alpha: BinaryField8b::ONE,
+	};
+	assert_eq!(CompositionPoly::<F>::binary_tower_level(&shared), 3);
+	let mixed = Mixed {
+		alpha: BinaryField8b::ONE,
+		beta: BinaryField32b::ONE,
+	};
+	assert_eq!(CompositionPoly::<F>::binary_tower_level(&mixed), 5);
+
+	let bit = Bit {
+		bit: BinaryField1b::ONE,
+	};
+	assert_eq!(CompositionPoly::<F>::binary_tower_level(&bit), 0);
+	assert_eq!(CompositionPoly::<F>::bitsliced_coeffs(&bit), Some(vec![F::ONE]));
+	assert_eq!(CompositionPoly::<F>::bitsliced_coeffs(&shared), None);
+}
+
+#[test]
+fn test_evaluate_matches_naive_expression() {
+	let query = random_query::<F>(3);
+	let [a, b, c] = [query[0], query[1], query[2]];
+
+	assert_eq!(CompositionPoly::<F>::evaluate(&Product, &query).unwrap(), a * b * c);
+
+	let a_squared = a * a;
+	let ab_plus_one = a * b + F::ONE;
+	assert_eq!(
+		CompositionPoly::<F>::evaluate(&Powers, &query[..2]).unwrap(),
+		a_squared * a_squared * a + ab_plus_one * ab_plus_one - F::ONE
+	);
+
+	let alpha = BinaryField8b::new(0x2a);
+	let shared = Shared { alpha };
+	let alpha = F::from(alpha);
+	assert_eq!(
+		CompositionPoly::<F>::evaluate(&shared, &query).unwrap(),
+		(a * b + alpha * c) * (b * a + alpha * c) - c * c * c
+	);
+}
+
+#[test]
+fn test_evaluate_packed() {
+	let mixed = Mixed {
+		alpha: BinaryField8b::new(0x2a),
+		beta: BinaryField32b::new(0x1234567),
+	};
+	let shared = Shared {
+		alpha: BinaryField8b::new(0x2a),
+	};
+
+	let query = random_query::<PackedBinaryField4x32b>(3);
+	let mixed_eval = mixed.evaluate_packed(&query[..2]).unwrap();
+	let shared_eval = shared.evaluate_packed(&query).unwrap();
+	for i in 0..PackedBinaryField4x32b::WIDTH {
+		let scalar_query = query.iter().map(|packed| packed.get(i)).collect::<Vec<_>>();
+		assert_eq!(
+			CompositionPoly::<PackedBinaryField4x32b>::evaluate(&mixed, &scalar_query[..2])
+				.unwrap(),
+			mixed_eval.get(i)
+		);
+		assert_eq!(
+			CompositionPoly::<PackedBinaryField4x32b>::evaluate(&shared, &scalar_query).unwrap(),
+			shared_eval.get(i)
+		);
+	}
+
+	// The parameters only need to embed in the scalar field of the query
+	let query = random_query::<BinaryField16b>(3);
+	assert!(CompositionPoly::<BinaryField16b>::evaluate(&shared, &query).is_ok());
+}
@@ -2,12 +2,11 @@ use anyhow::Result;
 use binius_core::{
 	challenger::HashChallenger,
 	oracle::{CommittedBatchSpec, CommittedId, CompositePolyOracle, MultilinearOracleSet},
-	poly_commit::{tensor_pcs, PolyCommitScheme},
+	poly_commit::PolyCommitScheme,
 	polynomial::{EvaluationDomain, MultilinearComposite, MultilinearExtension},
 	protocols::{
 		greedy_evalcheck::{self, GreedyEvalcheckProof, GreedyEvalcheckProveOutput},
-		sumcheck::{SumcheckProof, SumcheckProveOutput},
-		test_utils::{full_prove_with_switchover, full_verify},
+		sumcheck::{self, SumcheckProof, SumcheckProveOutput},
 		zerocheck::{
 			prove as prove_zerocheck, verify as verify_zerocheck, ZerocheckClaim, ZerocheckProof,
 			ZerocheckProveOutput,
@@ -16,8 +15,8 @@ use binius_core::{
 	witness::MultilinearWitnessIndex,
 };
 use binius_field::{
-	BinaryField128b, BinaryField128bPolyval, ExtensionField, PackedBinaryField128x1b,
-	PackedBinaryField1x128b, PackedBinaryField8x16b, PackedField, TowerField,
+	BinaryField128b, BinaryField128bPolyval, BinaryField8b, ExtensionField,
+	PackedBinaryField128x1b, PackedField, TowerField,
 };
 use binius_hash::GroestlHasher;
 use binius_macros::composition_poly;
@@ -29,6 +28,17 @@ use std::{env, fmt::Debug};
 use tracing_profile::{CsvLayer, PrintTreeConfig, PrintTreeLayer};
 use tracing_subscriber::prelude::*;
 
+#[cfg(not(feature = "fri_pcs"))]
+use binius_core::poly_commit::tensor_pcs;
+#[cfg(feature = "fri_pcs")]
+use binius_core::poly_commit::{fri_pcs, FRIPCS};
+#[cfg(feature = "fri_pcs")]
+use binius_field::BinaryField16b;
+#[cfg(not(feature = "fri_pcs"))]
+use binius_field::{PackedBinaryField1x128b, PackedBinaryField8x16b};
+#[cfg(feature = "fri_pcs")]
+use binius_hash::GroestlDigestCompression;
+
 composition_poly!(BitwiseAndConstraint[a, b, c] = a * b - c);
 
 fn prove<PCS, CH>(
@@ -96,25 +106,17 @@ where
 	>(sumcheck_claim.poly.max_individual_degree() + 1)
 	.unwrap();
 
-	let switchover_fn = |extension_degree| match extension_degree {
-		128 => 5,
-		_ => 1,
-	};
-
-	// full_prove_with_switchover is instrumented
 	tracing::debug!("Proving sumcheck");
-	let (_, output) = full_prove_with_switchover(
+	let SumcheckProveOutput {
+		evalcheck_claim,
+		sumcheck_proof,
+	} = sumcheck::prove(
 		&sumcheck_claim,
 		sumcheck_witness,
 		&sumcheck_domain,
 		&mut challenger,
-		switchover_fn,
-	);
-
-	let SumcheckProveOutput {
-		evalcheck_claim,
-		sumcheck_proof,
-	} = output;
+		sumcheck::default_switchover_round,
+	)?;
 
 	// Prove evaluation claims
 	let GreedyEvalcheckProveOutput {
@@ -124,7 +126,7 @@ where
 		trace,
 		&mut witness_index,
 		[evalcheck_claim],
-		switchover_fn,
+		sumcheck::default_switchover_round,
 		&mut challenger,
 	)?;
 
@@ -204,7 +206,7 @@ where
 		verify_zerocheck(&zerocheck_claim, zerocheck_proof, zerocheck_challenge).unwrap();
 
 	// Run sumcheck protocol
-	let (_, evalcheck_claim) = full_verify(&sumcheck_claim, sumcheck_proof, &mut challenger);
+	let evalcheck_claim = sumcheck::verify(&sumcheck_claim, sumcheck_proof, &mut challenger)?;
 
 	// Verify evaluation claims
 	let same_query_claims =
@@ -346,25 +348,66 @@ fn main() {
 	let log_inv_rate = 1;
 
 	// Set up the public parameters
-	let pcs = tensor_pcs::find_proof_size_optimal_pcs::<
-		_,
-		PackedBinaryField128x1b,
-		_,
-		PackedBinaryField8x16b,
-		_,
-		PackedBinaryField8x16b,
-		_,
-		PackedBinaryField1x128b,
-	>(SECURITY_BITS, log_size, 3, log_inv_rate, false)
-	.unwrap();
+	#[cfg(not(feature = "fri_pcs"))]
+	let pcs = {
+		let pcs = tensor_pcs::find_proof_size_optimal_pcs::<
+			_,
+			PackedBinaryField128x1b,
+			_,
+			PackedBinaryField8x16b,
+			_,
+			PackedBinaryField8x16b,
+			_,
+			PackedBinaryField1x128b,
+		>(SECURITY_BITS, log_size, 3, log_inv_rate, false)
+		.unwrap();
 
-	tracing::debug!(
-		"Using BlockTensorPCS with log_rows = {}, log_cols = {}, proof_size = {}",
-		pcs.log_rows(),
-		pcs.log_cols(),
-		pcs.proof_size(3),
-	);
+		tracing::debug!(
+			"Using BlockTensorPCS with log_rows = {}, log_cols = {}, proof_size = {}",
+			pcs.log_rows(),
+			pcs.log_cols(),
+			pcs.proof_size(3),
+		);
+		pcs
+	};
 
+	#[cfg(feature = "fri_pcs")]
+	let pcs = {
+		let n_test_queries = fri_pcs::calculate_n_test_queries::<BinaryField128b>(
+			SECURITY_BITS,
+			log_size,
+			log_inv_rate,
+		)
+		.unwrap();
+		let pcs = FRIPCS::<
+			PackedBinaryField128x1b,
+			BinaryField16b,
+			BinaryField128b,
+			GroestlHasher<BinaryField128b>,
+			GroestlDigestCompression,
+		>::new_using_groestl_merkle_tree(log_size, log_inv_rate, n_test_queries)
+		.unwrap();
+
+		tracing::debug!(
+			"Using FRIPCS with log_inv_rate = {}, n_test_queries = {}, proof_size = {}",
+			pcs.log_inv_rate(),
+			pcs.n_test_queries(),
+			pcs.proof_size(3),
+		);
+		pcs
+	};
+
+	run(log_size, &pcs);
+}
+
+/// Proves and verifies a random bitwise AND trace with the given polynomial commitment scheme.
+fn run<PCS>(log_size: usize, pcs: &PCS)
+where
+	PCS: PolyCommitScheme<PackedBinaryField128x1b, BinaryField128b>,
+	PCS::Error: Debug,
+	PCS::Proof: 'static,
+	HashChallenger<BinaryField8b, GroestlHasher<BinaryField8b>>: CanObserve<PCS::Commitment>,
+{
 	let mut trace_oracle = MultilinearOracleSet::new();
 
 	trace_oracle.add_committed_batch(CommittedBatchSpec {
@@ -381,40 +424,30 @@ fn main() {
 	let witness = generate_trace(log_size);
 	drop(trace_span);
 
-	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
+	let challenger = HashChallenger::<BinaryField8b, GroestlHasher<BinaryField8b>>::new();
 
 	tracing::info!("Proving");
 	let prove_span = tracing::debug_span!(
 		"prove",
-		log_rows = pcs.log_rows(),
-		log_cols = pcs.log_cols(),
 		proof_size = pcs.proof_size(3),
 		log_size = log_size,
 		n_vars = pcs.n_vars(),
 	)
 	.entered();
-	let proof = prove(
-		log_size,
-		&pcs,
-		&mut trace_oracle.clone(),
-		&constraints,
-		&witness,
-		challenger.clone(),
-	)
-	.unwrap();
+	let proof =
+		prove(log_size, pcs, &mut trace_oracle.clone(), &constraints, &witness, challenger.clone())
+			.unwrap();
 	drop(prove_span);
 
 	tracing::info!("Verifying");
 	let verify_span = tracing::debug_span!(
 		"verify",
-		log_rows = pcs.log_rows(),
-		log_cols = pcs.log_cols(),
 		proof_size = pcs.proof_size(3),
 		log_size = log_size,
 		n_vars = pcs.n_vars(),
 	)
 	.entered();
-	verify(log_size, &pcs, &mut trace_oracle.clone(), &constraints, proof, challenger.clone())
+	verify(log_size, pcs, &mut trace_oracle.clone(), &constraints, proof, challenger.clone())
 		.unwrap();
 	drop(verify_span);
 }
@@ -0,0 +1,19 @@
+// Copyright 2024 Ulvetanna Inc.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use wasm_keccak::keccakf_prover;
+
+fn keccakf_prove_verify(c: &mut Criterion) {
+	let mut group = c.benchmark_group("Keccak-f prove and verify");
+	group.sample_size(10);
+	for log_size in [14, 16, 18] {
+		let prove_and_verify = keccakf_prover(log_size);
+		group.bench_with_input(BenchmarkId::from_parameter(log_size), &log_size, |b, _| {
+			b.iter(|| prove_and_verify().unwrap())
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(keccakf, keccakf_prove_verify);
+criterion_main!(keccakf);
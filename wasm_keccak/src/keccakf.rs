@@ -11,38 +11,46 @@ use binius_core::{
 		BatchId, CommittedBatchSpec, CommittedId, CompositePolyOracle, MultilinearOracleSet,
 		MultilinearPolyOracle, OracleId, ShiftVariant,
 	},
-	poly_commit::{tensor_pcs, PolyCommitScheme},
+	poly_commit::PolyCommitScheme,
 	polynomial::{
 		composition::{empty_mix_composition, index_composition},
-		multilinear_query::MultilinearQuery,
-		transparent::step_down::StepDown,
-		CompositionPoly, Error as PolynomialError, EvaluationDomain, MultilinearComposite,
-		MultilinearExtension, MultivariatePoly,
+		BitslicedPoly, CompositionPoly, Error as PolynomialError, EvaluationDomain,
+		MultilinearComposite, MultilinearExtension,
 	},
 	protocols::{
-		greedy_evalcheck,
-		greedy_evalcheck::{GreedyEvalcheckProof, GreedyEvalcheckProveOutput},
-		sumcheck::{SumcheckProof, SumcheckProveOutput},
-		test_utils::{full_prove_with_switchover, full_verify},
-		zerocheck::{self, ZerocheckClaim, ZerocheckProof, ZerocheckProveOutput},
+		greedy_evalcheck, sumcheck,
+		zerocheck::{self, ZerocheckClaim, ZerocheckProveOutput},
 	},
+	serialization::{DeserializeCanonical, Error as SerializationError, SerializeCanonical},
+	setup::{self, ProvingKey, VerifyingKey},
+	transcript::{ProverTranscript, VerifierTranscript},
 	witness::MultilinearWitnessIndex,
 };
 use binius_field::{
-	BinaryField, BinaryField128bPolyval, BinaryField1b, ExtensionField, Field,
-	PackedBinaryField128x1b, PackedBinaryField1x128b, PackedBinaryField8x16b, PackedField,
-	TowerField,
+	BinaryField128bPolyval, BinaryField1b, ExtensionField, Field, PackedBinaryField128x1b,
+	PackedField, TowerField,
 };
 use binius_hash::GroestlHasher;
 use binius_macros::composition_poly;
 use bytemuck::{must_cast_slice_mut, Pod};
 use rand::{thread_rng, Rng};
-use std::{array, env, fmt::Debug, iter, iter::Step, slice, sync::Arc};
+use std::{array, env, fmt::Debug, iter, iter::Step};
 use tiny_keccak::keccakf;
 use tracing::instrument;
 use tracing_profile::{CsvLayer, PrintTreeConfig, PrintTreeLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+#[cfg(not(feature = "fri_pcs"))]
+use binius_core::poly_commit::tensor_pcs;
+#[cfg(feature = "fri_pcs")]
+use binius_core::poly_commit::{fri_pcs, FRIPCS};
+#[cfg(feature = "fri_pcs")]
+use binius_field::{BinaryField128b, BinaryField16b};
+#[cfg(not(feature = "fri_pcs"))]
+use binius_field::{PackedBinaryField1x128b, PackedBinaryField8x16b};
+#[cfg(feature = "fri_pcs")]
+use binius_hash::GroestlDigestCompression;
+
 const KECCAKF_RC: [u64; 32] = [
 	0x0000000000000001,
 	0x0000000000008082,
@@ -146,66 +154,79 @@ impl<F: Field> CompositionPoly<F> for SumComposition {
 	fn binary_tower_level(&self) -> usize {
 		0
 	}
-}
-
-composition_poly!(ChiComposition[a, b0, b1, b2] = a - (b0 + (1 - b1) * b2));
-composition_poly!(ChiIotaComposition[a, b0, b1, b2, rc] = a - (rc + b0 + (1 - b1) * b2));
-composition_poly!(RoundConsistency[state_out, next_state_in, select] = (state_out - next_state_in) * select);
 
-#[derive(Debug)]
-struct RoundConstant<P: PackedField<Scalar = BinaryField1b>>(MultilinearExtension<'static, P>);
-
-impl<P: PackedField<Scalar = BinaryField1b> + Pod> RoundConstant<P> {
-	fn new() -> Result<Self> {
-		let mut values = vec![P::default(); 1 << (11 - P::LOG_WIDTH)];
-		must_cast_slice_mut::<_, u64>(&mut values).copy_from_slice(KECCAKF_RC.as_slice());
-		let mle = MultilinearExtension::from_values(values)?;
-		Ok(Self(mle))
+	fn bitsliced_coeffs(&self) -> Option<Vec<F>> {
+		Some(vec![F::ONE])
 	}
-}
 
-// TODO: Implement MultivariatePoly on MultilinearExtension?
-impl<P, FE> MultivariatePoly<FE> for RoundConstant<P>
-where
-	P: PackedField<Scalar = BinaryField1b> + Debug,
-	FE: BinaryField,
-{
-	fn n_vars(&self) -> usize {
-		self.0.n_vars()
-	}
-
-	fn degree(&self) -> usize {
-		self.0.n_vars()
-	}
-
-	fn evaluate(&self, query: &[FE]) -> Result<FE, PolynomialError> {
-		self.0
-			.evaluate(&MultilinearQuery::<FE>::with_full_query(query)?)
+	fn evaluate_bitsliced(
+		&self,
+		query: &[BitslicedPoly],
+		dst: &mut Vec<BitslicedPoly>,
+	) -> Result<(), PolynomialError> {
+		if query.len() != self.n_vars {
+			return Err(PolynomialError::IncorrectQuerySize {
+				expected: self.n_vars,
+			});
+		}
+		dst.push(query.iter().fold(BitslicedPoly::ZERO, |acc, &x| acc + x));
+		Ok(())
 	}
 }
 
+composition_poly!(ChiComposition[a, b0, b1, b2] = a - (b0 + (1 - b1) * b2));
+composition_poly!(ChiIotaComposition[a, b0, b1, b2, rc] = a - (rc + b0 + (1 - b1) * b2));
+composition_poly!(RoundConsistency[state_out, next_state_in, select] = (state_out - next_state_in) * select);
+
+/// The fixed columns, committed once in the precommitted batch of round 0 during setup.
 #[derive(Debug)]
 struct FixedOracle<F: Field> {
+	batch_id: BatchId,
 	round_consts: MultilinearPolyOracle<F>,
 	selector: MultilinearPolyOracle<F>,
 }
 
 impl<F: TowerField> FixedOracle<F> {
-	pub fn new(oracles: &mut MultilinearOracleSet<F>, log_size: usize) -> Result<Self> {
-		let round_consts_single = oracles
-			.add_transparent(Arc::new(RoundConstant::<PackedBinaryField128x1b>::new()?), 0)?;
-		let round_consts = oracles.add_repeating(round_consts_single, log_size - 11)?;
-
-		let selector_single = oracles.add_transparent(Arc::new(StepDown::new(11, 24 * 64)?), 0)?;
-		let selector = oracles.add_repeating(selector_single, log_size - 11)?;
-
-		Ok(Self {
-			round_consts: oracles.oracle(round_consts),
-			selector: oracles.oracle(selector),
-		})
+	pub fn new(oracles: &mut MultilinearOracleSet<F>, log_size: usize) -> Self {
+		let batch_id = oracles.add_committed_batch(CommittedBatchSpec {
+			round_id: 0,
+			n_vars: log_size,
+			n_polys: 2,
+			tower_level: 0,
+		});
+		let oracle = |index| oracles.committed_oracle(CommittedId { batch_id, index });
+
+		Self {
+			batch_id,
+			round_consts: oracle(0),
+			selector: oracle(1),
+		}
 	}
 }
 
+/// Generates the fixed columns in the order of the fixed batch.
+///
+/// Each permutation spans 32 rows of 64 bits, of which the first 24 are the Keccak-f rounds.
+fn generate_fixed_columns<P: PackedField<Scalar = BinaryField1b> + Pod>(
+	log_size: usize,
+) -> Vec<MultilinearExtension<'static, P>> {
+	let mut round_consts = vec![P::default(); 1 << (log_size - P::LOG_WIDTH)];
+	let mut selector = vec![P::default(); 1 << (log_size - P::LOG_WIDTH)];
+
+	let round_consts_u64 = must_cast_slice_mut::<_, u64>(round_consts.as_mut_slice());
+	let selector_u64 = must_cast_slice_mut::<_, u64>(selector.as_mut_slice());
+	for (i, (rc, select)) in iter::zip(round_consts_u64, selector_u64).enumerate() {
+		let round_i = i % 32;
+		*rc = KECCAKF_RC[round_i];
+		*select = if round_i < 24 { u64::MAX } else { 0 };
+	}
+
+	[round_consts, selector]
+		.into_iter()
+		.map(|values| MultilinearExtension::from_values(values).unwrap())
+		.collect()
+}
+
 struct TraceOracle<F: Field> {
 	state_in: [MultilinearPolyOracle<F>; 25],
 	state_out: [MultilinearPolyOracle<F>; 25],
@@ -307,16 +328,15 @@ struct TraceWitness<P: PackedField> {
 	a_theta: [Vec<P>; 25],
 	b: [Vec<P>; 25],
 	next_state_in: [Vec<P>; 25],
-	round_consts: Vec<P>,
-	selector: Vec<P>,
 }
 
 impl<P: PackedField> TraceWitness<P> {
-	fn to_index<F, PE>(
-		&self,
+	fn to_index<'a, F, PE>(
+		&'a self,
 		fixed_oracle: &FixedOracle<F>,
+		fixed_columns: &'a [MultilinearExtension<'static, P>],
 		trace_oracle: &TraceOracle<F>,
-	) -> MultilinearWitnessIndex<PE>
+	) -> MultilinearWitnessIndex<'a, PE>
 	where
 		F: ExtensionField<P::Scalar>,
 		PE: PackedField,
@@ -333,8 +353,6 @@ impl<P: PackedField> TraceWitness<P> {
 			(&trace_oracle.a_theta[..], &self.a_theta[..]),
 			(&trace_oracle.b[..], &self.b[..]),
 			(&trace_oracle.next_state_in[..], &self.next_state_in[..]),
-			(slice::from_ref(&fixed_oracle.round_consts), slice::from_ref(&self.round_consts)),
-			(slice::from_ref(&fixed_oracle.selector), slice::from_ref(&self.selector)),
 		] {
 			for (oracle, witness) in oracle_arr.iter().zip(witness_arr.iter()) {
 				index.set(
@@ -346,13 +364,21 @@ impl<P: PackedField> TraceWitness<P> {
 			}
 		}
 
+		let fixed_oracles = [&fixed_oracle.round_consts, &fixed_oracle.selector];
+		for (oracle, column) in iter::zip(fixed_oracles, fixed_columns) {
+			index.set(oracle.id(), column.to_ref().specialize_arc_dyn());
+		}
+
 		index
 	}
 
-	fn all_polys(&self) -> impl Iterator<Item = MultilinearExtension<P>> {
-		iter::once(&self.round_consts)
-			.chain(iter::once(&self.selector))
-			.chain(self.state_in.iter())
+	fn all_polys<'a>(
+		&'a self,
+		fixed_columns: &'a [MultilinearExtension<'static, P>],
+	) -> impl Iterator<Item = MultilinearExtension<'a, P>> {
+		let trace_polys = self
+			.state_in
+			.iter()
 			.chain(self.state_out.iter())
 			.chain(self.c.iter())
 			.chain(self.d.iter())
@@ -360,7 +386,11 @@ impl<P: PackedField> TraceWitness<P> {
 			.chain(self.a_theta.iter())
 			.chain(self.b.iter())
 			.chain(self.next_state_in.iter())
-			.map(|values| MultilinearExtension::from_values_slice(values.as_slice()).unwrap())
+			.map(|values| MultilinearExtension::from_values_slice(values.as_slice()).unwrap());
+		fixed_columns
+			.iter()
+			.map(|column| column.to_ref())
+			.chain(trace_polys)
 	}
 
 	fn commit_polys(&self) -> impl Iterator<Item = MultilinearExtension<P>> {
@@ -391,14 +421,6 @@ fn zerocheck_verifier_oracles<F: Field>(
 		.collect()
 }
 
-struct Proof<F: Field, PCSComm, PCSProof> {
-	trace_comm: PCSComm,
-	zerocheck_proof: ZerocheckProof,
-	sumcheck_proof: SumcheckProof<F>,
-	evalcheck_proof: GreedyEvalcheckProof<F>,
-	trace_open_proof: PCSProof,
-}
-
 // #[instrument]
 #[allow(clippy::needless_range_loop)]
 fn generate_trace<P: PackedField + Pod>(log_size: usize) -> TraceWitness<P> {
@@ -412,8 +434,6 @@ fn generate_trace<P: PackedField + Pod>(log_size: usize) -> TraceWitness<P> {
 		a_theta: array::from_fn(|_xy| build_trace_column()),
 		b: array::from_fn(|_xy| build_trace_column()),
 		next_state_in: array::from_fn(|_xy| build_trace_column()),
-		round_consts: build_trace_column(),
-		selector: build_trace_column(),
 	};
 
 	fn cast_u64_cols<P: PackedField + Pod, const N: usize>(
@@ -431,8 +451,6 @@ fn generate_trace<P: PackedField + Pod>(log_size: usize) -> TraceWitness<P> {
 	let a_theta_u64 = cast_u64_cols(&mut witness.a_theta);
 	let b_u64 = cast_u64_cols(&mut witness.b);
 	let next_state_in_u64 = cast_u64_cols(&mut witness.next_state_in);
-	let round_consts_u64 = must_cast_slice_mut(witness.round_consts.as_mut_slice());
-	let selector_u64 = must_cast_slice_mut(witness.selector.as_mut_slice());
 
 	let mut rng = thread_rng();
 
@@ -486,16 +504,13 @@ fn generate_trace<P: PackedField + Pod>(log_size: usize) -> TraceWitness<P> {
 				}
 			}
 
-			round_consts_u64[i] = KECCAKF_RC[round_i];
-			state_out_u64[0][i] ^= round_consts_u64[i];
+			state_out_u64[0][i] ^= KECCAKF_RC[round_i];
 			if round_i < 31 {
 				for xy in 0..25 {
 					state_in_u64[xy][i + 1] = state_out_u64[xy][i];
 					next_state_in_u64[xy][i] = state_out_u64[xy][i];
 				}
 			}
-
-			selector_u64[i] = if round_i < 24 { u64::MAX } else { 0 };
 		}
 
 		// Assert correct output
@@ -516,22 +531,36 @@ fn prove<P, F, PW, PCS, CH>(
 	fixed_oracle: &FixedOracle<F>,
 	trace_oracle: &TraceOracle<F>,
 	pcs: &PCS,
-	mut challenger: CH,
+	proving_key: &ProvingKey<P, F, PCS>,
+	challenger: CH,
 	witness: &TraceWitness<P>,
-) -> Result<Proof<F, PCS::Commitment, PCS::Proof>>
+) -> Result<Vec<u8>>
 where
 	P: PackedField<Scalar = BinaryField1b> + Pod,
-	F: TowerField + Step + From<PW>,
+	F: TowerField + Step + From<PW> + SerializeCanonical,
 	PW: TowerField + From<F>,
-	PCS: PolyCommitScheme<P, F, Error: Debug, Proof: 'static>,
+	PCS: PolyCommitScheme<
+		P,
+		F,
+		Error: Debug,
+		Commitment: SerializeCanonical,
+		Proof: SerializeCanonical + 'static,
+	>,
 	CH: CanObserve<F> + CanObserve<PCS::Commitment> + CanSample<F> + CanSampleBits<usize> + Clone,
 {
-	let mut trace_witness = witness.to_index::<_, PW>(fixed_oracle, trace_oracle);
+	let mut trace_witness =
+		witness.to_index::<_, PW>(fixed_oracle, proving_key.fixed_columns(), trace_oracle);
+	let mut transcript = ProverTranscript::new(challenger);
+
+	// Round 0 is the fixed batch precommitted during setup
+	let verifying_key = proving_key.verifying_key();
+	verifying_key.check_oracles(oracles)?;
+	transcript.observe(verifying_key.fixed_commitment.clone());
 
 	// Round 1
 	let trace_commit_polys = witness.commit_polys().collect::<Vec<_>>();
 	let (trace_comm, trace_committed) = pcs.commit(&trace_commit_polys).unwrap();
-	challenger.observe(trace_comm.clone());
+	transcript.write_commitment(&trace_comm);
 
 	// Zerocheck mixing
 	let zerocheck_column_oracles = zerocheck_verifier_oracles(fixed_oracle, trace_oracle);
@@ -540,7 +569,7 @@ where
 		.map(|oracle| oracle.id())
 		.collect::<Vec<_>>();
 
-	let mixing_challenge = challenger.sample();
+	let mixing_challenge = transcript.sample();
 
 	let mix_composition_verifier =
 		make_constraints(fixed_oracle, trace_oracle, &zerocheck_column_ids, mixing_challenge)?;
@@ -563,19 +592,18 @@ where
 		log_size,
 		mix_composition_prover,
 		witness
-			.all_polys()
+			.all_polys(proving_key.fixed_columns())
 			.map(|mle| mle.specialize_arc_dyn())
 			.collect(),
 	)?;
 
 	// Zerocheck
-	let zerocheck_challenge = challenger.sample_vec(log_size - 1);
-
 	let ZerocheckProveOutput {
 		sumcheck_claim,
 		sumcheck_witness,
-		zerocheck_proof,
-	} = zerocheck::prove(&zerocheck_claim, zerocheck_witness, zerocheck_challenge).unwrap();
+		..
+	} = zerocheck::prove_with_transcript(&zerocheck_claim, zerocheck_witness, &mut transcript)
+		.unwrap();
 
 	// Sumcheck
 	let sumcheck_domain = EvaluationDomain::<PW>::new_isomorphic::<F>(
@@ -583,59 +611,42 @@ where
 	)
 	.unwrap();
 
-	let switchover_fn = |extension_degree| match extension_degree {
-		128 => 5,
-		_ => 1,
-	};
-
-	let (_, output) = full_prove_with_switchover(
+	let evalcheck_claim = sumcheck::prove_with_transcript(
 		&sumcheck_claim,
 		sumcheck_witness,
 		&sumcheck_domain,
-		&mut challenger,
-		switchover_fn,
-	);
-
-	let SumcheckProveOutput {
-		evalcheck_claim,
-		sumcheck_proof,
-	} = output;
+		&mut transcript,
+		sumcheck::default_switchover_round,
+	)?;
 
 	// Evalcheck
-	let GreedyEvalcheckProveOutput {
-		same_query_claims,
-		proof: evalcheck_proof,
-	} = greedy_evalcheck::prove(
+	let same_query_claims = greedy_evalcheck::prove_with_transcript(
 		oracles,
 		&mut trace_witness,
 		[evalcheck_claim],
-		switchover_fn,
-		&mut challenger,
+		sumcheck::default_switchover_round,
+		&mut transcript,
 	)?;
 
-	assert_eq!(same_query_claims.len(), 1);
-	let (batch_id, same_query_claim) = same_query_claims
-		.into_iter()
-		.next()
-		.expect("length is asserted to be 1");
-	assert_eq!(batch_id, trace_batch_id);
-
-	let trace_open_proof = pcs
-		.prove_evaluation(
-			&mut challenger,
-			&trace_committed,
-			&trace_commit_polys,
+	// The claims come in commitment round order, so the fixed batch is opened first
+	assert_eq!(same_query_claims.len(), 2);
+	for (batch_id, same_query_claim) in same_query_claims {
+		let (committed, polys) = if batch_id == fixed_oracle.batch_id {
+			(proving_key.fixed_committed(), proving_key.fixed_columns())
+		} else {
+			assert_eq!(batch_id, trace_batch_id);
+			(&trace_committed, trace_commit_polys.as_slice())
+		};
+		pcs.prove_evaluation_with_transcript(
+			&mut transcript,
+			committed,
+			polys,
 			&same_query_claim.eval_point,
 		)
 		.unwrap();
+	}
 
-	Ok(Proof {
-		trace_comm,
-		zerocheck_proof,
-		sumcheck_proof,
-		evalcheck_proof,
-		trace_open_proof,
-	})
+	Ok(transcript.finalize())
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -647,25 +658,30 @@ fn verify<P, F, PCS, CH>(
 	fixed_oracle: &FixedOracle<F>,
 	trace_oracle: &TraceOracle<F>,
 	pcs: &PCS,
-	mut challenger: CH,
-	proof: Proof<F, PCS::Commitment, PCS::Proof>,
+	verifying_key: &VerifyingKey<PCS::Commitment>,
+	challenger: CH,
+	proof: &[u8],
 ) -> Result<()>
 where
 	P: PackedField<Scalar = BinaryField1b>,
-	F: TowerField,
-	PCS: PolyCommitScheme<P, F, Error: Debug, Proof: 'static>,
+	F: TowerField + DeserializeCanonical,
+	PCS: PolyCommitScheme<
+		P,
+		F,
+		Error: Debug + From<SerializationError>,
+		Commitment: DeserializeCanonical,
+		Proof: DeserializeCanonical + 'static,
+	>,
 	CH: CanObserve<F> + CanObserve<PCS::Commitment> + CanSample<F> + CanSampleBits<usize>,
 {
-	let Proof {
-		trace_comm,
-		zerocheck_proof,
-		sumcheck_proof,
-		evalcheck_proof,
-		trace_open_proof,
-	} = proof;
+	let mut transcript = VerifierTranscript::new(challenger, proof)?;
+
+	// Round 0 is the fixed batch precommitted during setup
+	verifying_key.check_oracles(oracles)?;
+	transcript.observe(verifying_key.fixed_commitment.clone());
 
 	// Round 1
-	challenger.observe(trace_comm.clone());
+	let trace_comm: PCS::Commitment = transcript.read_commitment()?;
 
 	// Zerocheck mixing
 	let zerocheck_column_oracles = zerocheck_verifier_oracles(fixed_oracle, trace_oracle);
@@ -674,42 +690,41 @@ where
 		.map(|oracle| oracle.id())
 		.collect::<Vec<_>>();
 
-	let mixing_challenge = challenger.sample();
+	let mixing_challenge = transcript.sample();
 
 	let mix_composition =
 		make_constraints(fixed_oracle, trace_oracle, &zerocheck_column_ids, mixing_challenge)?;
 
 	// Zerocheck
-	let zerocheck_challenge = challenger.sample_vec(log_size - 1);
-
 	let zerocheck_claim = ZerocheckClaim {
 		poly: CompositePolyOracle::new(log_size, zerocheck_column_oracles, mix_composition)?,
 	};
 
-	let sumcheck_claim =
-		zerocheck::verify(&zerocheck_claim, zerocheck_proof, zerocheck_challenge).unwrap();
+	let sumcheck_claim = zerocheck::verify_with_transcript(&zerocheck_claim, &mut transcript)?;
 
 	// Sumcheck
-	let (_, evalcheck_claim) = full_verify(&sumcheck_claim, sumcheck_proof, &mut challenger);
+	let evalcheck_claim = sumcheck::verify_with_transcript(&sumcheck_claim, &mut transcript)?;
 
 	// Evalcheck
 	let same_query_claims =
-		greedy_evalcheck::verify(oracles, [evalcheck_claim], evalcheck_proof, &mut challenger)?;
-
-	assert_eq!(same_query_claims.len(), 1);
-	let (batch_id, same_query_claim) = same_query_claims
-		.into_iter()
-		.next()
-		.expect("length is asserted to be 1");
-	assert_eq!(batch_id, trace_batch_id);
-
-	pcs.verify_evaluation(
-		&mut challenger,
-		&trace_comm,
-		&same_query_claim.eval_point,
-		trace_open_proof,
-		&same_query_claim.evals,
-	)?;
+		greedy_evalcheck::verify_with_transcript(oracles, [evalcheck_claim], &mut transcript)?;
+
+	assert_eq!(same_query_claims.len(), 2);
+	for (batch_id, same_query_claim) in same_query_claims {
+		let commitment = if batch_id == verifying_key.fixed_batch_id {
+			&verifying_key.fixed_commitment
+		} else {
+			assert_eq!(batch_id, trace_batch_id);
+			&trace_comm
+		};
+		pcs.verify_evaluation_with_transcript(
+			&mut transcript,
+			commitment,
+			&same_query_claim.eval_point,
+			&same_query_claim.evals,
+		)?;
+	}
+	transcript.finalize()?;
 
 	Ok(())
 }
@@ -810,15 +825,15 @@ fn make_constraints<F: TowerField, FI: TowerField>(
 	Ok(mix)
 }
 
-pub fn main() {
+/// Sets up a Keccak-f instance with `2^log_size` rows and returns a function that proves and
+/// verifies it, so that benchmarks exclude the setup and the trace generation.
+pub fn keccakf_prover(log_size: usize) -> impl Fn() -> Result<()> {
 	const SECURITY_BITS: usize = 100;
 
-	init_tracing();
-
-	let log_size = 23;
 	let log_inv_rate = 1;
 
 	// Set up the public parameters
+	#[cfg(not(feature = "fri_pcs"))]
 	let pcs = tensor_pcs::find_proof_size_optimal_pcs::<
 		_,
 		PackedBinaryField128x1b,
@@ -831,41 +846,72 @@ pub fn main() {
 	>(SECURITY_BITS, log_size, 60, log_inv_rate, false)
 	.unwrap();
 
+	#[cfg(feature = "fri_pcs")]
+	let pcs = {
+		let n_test_queries = fri_pcs::calculate_n_test_queries::<BinaryField128b>(
+			SECURITY_BITS,
+			log_size,
+			log_inv_rate,
+		)
+		.unwrap();
+		FRIPCS::<
+			PackedBinaryField128x1b,
+			BinaryField16b,
+			BinaryField128b,
+			GroestlHasher<BinaryField128b>,
+			GroestlDigestCompression,
+		>::new_using_groestl_merkle_tree(log_size, log_inv_rate, n_test_queries)
+		.unwrap()
+	};
+
 	let mut oracles = MultilinearOracleSet::new();
-	let fixed_oracle = FixedOracle::new(&mut oracles, log_size).unwrap();
+	let fixed_oracle = FixedOracle::new(&mut oracles, log_size);
 
 	let trace_batch_id = oracles.add_committed_batch(CommittedBatchSpec {
-		round_id: 0,
+		round_id: 1,
 		n_vars: log_size,
 		n_polys: 60,
 		tower_level: 0,
 	});
 	let trace_oracle = TraceOracle::new(&mut oracles, trace_batch_id);
 
+	let (proving_key, verifying_key) =
+		setup::setup(&pcs, &oracles, fixed_oracle.batch_id, generate_fixed_columns(log_size))
+			.unwrap();
+
 	let challenger = <HashChallenger<_, GroestlHasher<_>>>::new();
 
 	let witness = generate_trace(log_size);
-	let proof = prove::<_, _, BinaryField128bPolyval, _, _>(
-		log_size,
-		&mut oracles.clone(),
-		trace_batch_id,
-		&fixed_oracle,
-		&trace_oracle,
-		&pcs,
-		challenger.clone(),
-		&witness,
-	)
-	.unwrap();
+	move || {
+		let proof = prove::<_, _, BinaryField128bPolyval, _, _>(
+			log_size,
+			&mut oracles.clone(),
+			trace_batch_id,
+			&fixed_oracle,
+			&trace_oracle,
+			&pcs,
+			&proving_key,
+			challenger.clone(),
+			&witness,
+		)?;
+
+		verify(
+			log_size,
+			&mut oracles.clone(),
+			trace_batch_id,
+			&fixed_oracle,
+			&trace_oracle,
+			&pcs,
+			&verifying_key,
+			challenger.clone(),
+			&proof,
+		)
+	}
+}
 
-	verify(
-		log_size,
-		&mut oracles.clone(),
-		trace_batch_id,
-		&fixed_oracle,
-		&trace_oracle,
-		&pcs,
-		challenger.clone(),
-		proof,
-	)
-	.unwrap();
+pub fn main() {
+	init_tracing();
+
+	let log_size = 23;
+	keccakf_prover(log_size)().unwrap();
 }
@@ -6,6 +6,8 @@ use web_sys::window;
 use console_error_panic_hook;
 mod keccakf;
 
+pub use keccakf::keccakf_prover;
+
 #[wasm_bindgen]
 pub fn run_keccak_example() {
 		keccakf::main();